@@ -3,8 +3,10 @@
 use massa_models::amount::Amount;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
+use parking_lot::RwLock;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use serde::Deserialize;
 
@@ -82,6 +84,7 @@ pub struct APIConfig {
     pub chain_id: u64,
     /// Delta to compute upper bounds when fetching deferred credits
     pub deferred_credits_delta: MassaTime,
-    /// minimal fees to include an operation in a block
-    pub minimal_fees: Amount,
+    /// minimal fees to include an operation in a block, shared with the pool so that a reload is seen by the API
+    #[serde(skip)]
+    pub minimal_fees: Arc<RwLock<Amount>>,
 }
@@ -0,0 +1,184 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, block_id::BlockId, clique::Clique, slot::Slot};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Block of a block graph export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphBlock {
+    /// id of the block
+    pub id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// address of the block creator
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// fitness of the block, only known for active blocks
+    pub fitness: Option<u64>,
+    /// true if the block is final
+    pub is_final: bool,
+    /// true if the block belongs to the blockclique
+    pub is_in_blockclique: bool,
+    /// indices in `BlockGraphDump::cliques` of the cliques containing the block
+    pub cliques: Vec<usize>,
+    /// reason of the discard, if the block was discarded
+    pub discard_reason: Option<String>,
+}
+
+/// Export of a part of the block graph, for visualization and debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockGraphDump {
+    /// number of threads
+    pub thread_count: u8,
+    /// active and discarded blocks of the exported slot range, sorted by slot
+    pub blocks: Vec<GraphBlock>,
+    /// maximal cliques of compatible blocks
+    pub cliques: Vec<Clique>,
+    /// best parent and its period in each thread
+    pub best_parents: Vec<(BlockId, u64)>,
+    /// latest final block and its period in each thread
+    pub latest_final_blocks_periods: Vec<(BlockId, u64)>,
+}
+
+impl BlockGraphDump {
+    /// Render the export as a Graphviz DOT graph.
+    ///
+    /// Blocks are grouped by thread and point to their parents.
+    /// Final blocks are filled, blockclique blocks are drawn in green and discarded blocks in dashed red.
+    /// Edges to parents that are not part of the export are omitted.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // writing to a String cannot fail
+        let _ = self.write_dot(&mut dot);
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String) -> std::fmt::Result {
+        writeln!(dot, "digraph block_graph {{")?;
+        writeln!(dot, "    rankdir=LR;")?;
+        writeln!(dot, "    node [shape=box, fontname=\"monospace\"];")?;
+
+        for thread in 0..self.thread_count {
+            writeln!(dot, "    subgraph cluster_thread_{} {{", thread)?;
+            writeln!(dot, "        label=\"thread {}\";", thread)?;
+            for block in self.blocks.iter().filter(|b| b.slot.thread == thread) {
+                writeln!(
+                    dot,
+                    "        \"{}\" [label=\"{}\", {}];",
+                    block.id,
+                    escape(&node_label(block)),
+                    node_style(block)
+                )?;
+            }
+            writeln!(dot, "    }}")?;
+        }
+
+        let exported: std::collections::HashSet<&BlockId> =
+            self.blocks.iter().map(|b| &b.id).collect();
+        for block in &self.blocks {
+            for (thread, parent) in block.parents.iter().enumerate() {
+                if !exported.contains(parent) {
+                    continue;
+                }
+                if thread == block.slot.thread as usize {
+                    writeln!(dot, "    \"{}\" -> \"{}\";", block.id, parent)?;
+                } else {
+                    writeln!(
+                        dot,
+                        "    \"{}\" -> \"{}\" [style=dotted, color=grey];",
+                        block.id, parent
+                    )?;
+                }
+            }
+        }
+        writeln!(dot, "}}")
+    }
+}
+
+fn node_label(block: &GraphBlock) -> String {
+    let id = block.id.to_string();
+    let mut label = format!(
+        "{}\n({}, {})",
+        id.get(..10).unwrap_or(&id),
+        block.slot.period,
+        block.slot.thread
+    );
+    if let Some(fitness) = block.fitness {
+        label.push_str(&format!("\nfitness {}", fitness));
+    }
+    if let Some(reason) = &block.discard_reason {
+        label.push_str(&format!("\ndiscarded: {}", reason));
+    }
+    label
+}
+
+fn node_style(block: &GraphBlock) -> &'static str {
+    if block.discard_reason.is_some() {
+        "style=dashed, color=red"
+    } else if block.is_final {
+        "style=filled, fillcolor=lightgrey"
+    } else if block.is_in_blockclique {
+        "color=green, penwidth=2"
+    } else {
+        "color=black"
+    }
+}
+
+/// Escape a string to be used as a quoted DOT identifier or label
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_signature::KeyPair;
+
+    fn block(name: &str, slot: Slot, parents: Vec<BlockId>) -> GraphBlock {
+        GraphBlock {
+            id: BlockId::generate_from_hash(Hash::compute_from(name.as_bytes())),
+            slot,
+            creator: Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()),
+            parents,
+            fitness: Some(1),
+            is_final: false,
+            is_in_blockclique: true,
+            cliques: vec![0],
+            discard_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_block_graph_to_dot() {
+        let genesis_0 = block("genesis_0", Slot::new(0, 0), vec![]);
+        let genesis_1 = block("genesis_1", Slot::new(0, 1), vec![]);
+        let mut child = block("child", Slot::new(1, 1), vec![genesis_0.id, genesis_1.id]);
+        child.discard_reason = Some("Invalid(\"bad\")".to_string());
+        let dump = BlockGraphDump {
+            thread_count: 2,
+            blocks: vec![genesis_0.clone(), genesis_1.clone(), child.clone()],
+            cliques: vec![],
+            best_parents: vec![],
+            latest_final_blocks_periods: vec![],
+        };
+
+        let dot = dump.to_dot();
+        assert!(dot.starts_with("digraph block_graph {"));
+        assert!(dot.contains("subgraph cluster_thread_0"));
+        assert!(dot.contains("subgraph cluster_thread_1"));
+        // same thread parent
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", child.id, genesis_1.id)));
+        // other thread parent
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [style=dotted, color=grey];",
+            child.id, genesis_0.id
+        )));
+        assert!(dot.contains("discarded: Invalid(\\\"bad\\\")"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
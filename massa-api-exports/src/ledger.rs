@@ -2,6 +2,7 @@
 
 use massa_models::amount::Amount;
 use massa_models::ledger::LedgerData;
+use massa_models::slot::Slot;
 
 use serde::{Deserialize, Serialize};
 
@@ -28,3 +29,44 @@ impl std::fmt::Display for LedgerInfo {
         Ok(())
     }
 }
+
+/// Aggregate data about the coins and the ledger at the last final slot
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerStatsInfo {
+    /// last final slot
+    pub slot: Slot,
+    /// all the coins: ledger balances, rolls at the roll price and deferred credits
+    pub total_supply: Amount,
+    /// coins that can be spent right away: ledger balances
+    pub circulating_supply: Amount,
+    /// number of rolls owned by all the addresses
+    pub roll_count: u64,
+    /// value of all the rolls at the roll price
+    pub rolls_value: Amount,
+    /// coins that will be credited later, for example after a roll sell
+    pub deferred_credits: Amount,
+    /// number of user addresses having a ledger entry
+    pub user_address_count: u64,
+    /// number of smart contract addresses having a ledger entry
+    pub sc_address_count: u64,
+    /// bytes used by all the datastores (keys and values)
+    pub datastore_bytes: u64,
+}
+
+impl std::fmt::Display for LedgerStatsInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Final ledger at slot {}:", self.slot)?;
+        writeln!(f, "\tTotal supply: {}", self.total_supply)?;
+        writeln!(f, "\tCirculating supply: {}", self.circulating_supply)?;
+        writeln!(
+            f,
+            "\tRolls: {} ({} coins)",
+            self.roll_count, self.rolls_value
+        )?;
+        writeln!(f, "\tDeferred credits: {}", self.deferred_credits)?;
+        writeln!(f, "\tUser addresses: {}", self.user_address_count)?;
+        writeln!(f, "\tSmart contract addresses: {}", self.sc_address_count)?;
+        writeln!(f, "\tDatastore bytes: {}", self.datastore_bytes)?;
+        Ok(())
+    }
+}
@@ -23,6 +23,8 @@ pub mod endorsement;
 pub mod error;
 /// execution
 pub mod execution;
+/// block graph export
+pub mod graph;
 /// ledger structures
 pub mod ledger;
 /// node related structure
@@ -31,6 +33,10 @@ pub mod node;
 pub mod operation;
 /// page
 pub mod page;
+/// per-client rate limiting
+pub mod rate_limit;
+/// live reload of the node configuration
+pub mod reload;
 /// rolls
 pub mod rolls;
 /// slots
@@ -7,7 +7,7 @@ use massa_models::{config::CompactConfig, slot::Slot, version::Version};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// node status
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -46,6 +46,9 @@ pub struct NodeStatus {
     pub chain_id: u64,
     /// minimal fees to include an operation in a block
     pub minimal_fees: Amount,
+    /// true while the node is resynchronizing after a desynchronization
+    #[serde(default)]
+    pub is_syncing: bool,
 }
 
 impl std::fmt::Display for NodeStatus {
@@ -68,6 +71,9 @@ impl std::fmt::Display for NodeStatus {
             writeln!(f, "Last slot: {}", self.last_slot.unwrap())?;
         }
         writeln!(f, "Next slot: {}", self.next_slot)?;
+        if self.is_syncing {
+            writeln!(f, "Resynchronizing")?;
+        }
         writeln!(f)?;
 
         writeln!(f, "{}", self.consensus_stats)?;
@@ -94,3 +100,100 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// network diagnostics of a peer
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PeerDetailsInfo {
+    /// node id of the peer
+    pub node_id: NodeId,
+    /// address of the connection, none if not connected
+    pub address: Option<SocketAddr>,
+    /// true if the connection is outgoing, false if incoming, none if not connected
+    pub is_outgoing: Option<bool>,
+    /// listeners the peer announced
+    pub listeners: Vec<SocketAddr>,
+    /// category of the connection
+    pub category: Option<String>,
+    /// time elapsed since the handshake of the current connection
+    pub connection_age: Option<MassaTime>,
+    /// bytes received on the current connection
+    pub bytes_received: u64,
+    /// bytes sent on the current connection
+    pub bytes_sent: u64,
+    /// block headers received since the connection
+    pub block_announcements: u64,
+    /// operation ids announced since the connection
+    pub operation_announcements: u64,
+    /// endorsements received since the connection
+    pub endorsement_announcements: u64,
+    /// block data requests the peer answered since the connection
+    pub asks_answered: u64,
+    /// block data requests the peer did not answer in time since the connection
+    pub asks_timed_out: u64,
+    /// state of the peer in the peer database, none if unknown
+    pub state: Option<String>,
+    /// last handshake error
+    pub last_error: Option<String>,
+    /// version the peer announced in its last handshake
+    pub version: Option<Version>,
+}
+
+impl std::fmt::Display for PeerDetailsInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Node's ID: {}", self.node_id)?;
+        match (self.address, self.is_outgoing) {
+            (Some(address), Some(is_outgoing)) => writeln!(
+                f,
+                "\tConnection: {} / {}",
+                address,
+                if is_outgoing { "Out" } else { "In" }
+            )?,
+            _ => writeln!(f, "\tNot connected")?,
+        }
+        if !self.listeners.is_empty() {
+            writeln!(
+                f,
+                "\tListeners: {}",
+                self.listeners
+                    .iter()
+                    .map(|addr| addr.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        if let Some(category) = &self.category {
+            writeln!(f, "\tCategory: {}", category)?;
+        }
+        if let Some(age) = self
+            .connection_age
+            .and_then(|age| age.format_duration().ok())
+        {
+            writeln!(f, "\tConnected for: {}", age)?;
+        }
+        writeln!(
+            f,
+            "\tBytes received / sent: {} / {}",
+            self.bytes_received, self.bytes_sent
+        )?;
+        writeln!(
+            f,
+            "\tAnnouncements received: {} blocks / {} operations / {} endorsements",
+            self.block_announcements, self.operation_announcements, self.endorsement_announcements
+        )?;
+        writeln!(
+            f,
+            "\tBlock asks answered / timed out: {} / {}",
+            self.asks_answered, self.asks_timed_out
+        )?;
+        if let Some(state) = &self.state {
+            writeln!(f, "\tState: {}", state)?;
+        }
+        if let Some(error) = &self.last_error {
+            writeln!(f, "\tLast error: {}", error)?;
+        }
+        if let Some(version) = self.version {
+            writeln!(f, "\tVersion: {}", version)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Per-client token bucket rate limiting shared by the public JSON-RPC and gRPC APIs.
+//!
+//! Every request costs a number of units, configurable per method. Each client owns a bucket
+//! that holds at most `burst_units` units and is refilled at `units_per_second`.
+//! A request is refused when the bucket of its client does not hold enough units to pay for it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use massa_metrics::MassaMetrics;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use serde::{Deserialize, Serialize};
+
+/// Quota of a client bucket
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitQuota {
+    /// maximum number of units a client can spend at once
+    pub burst_units: u64,
+    /// number of units given back to a client every second
+    pub units_per_second: u64,
+}
+
+/// Rate limiting configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct RateLimitConfig {
+    /// whether to enable per-client rate limiting
+    pub enabled: bool,
+    /// quota of clients identified by their IP address
+    pub default_quota: RateLimitQuota,
+    /// quotas of clients identified by an API key. Unknown keys are ignored
+    pub api_keys: HashMap<String, RateLimitQuota>,
+    /// HTTP header (or gRPC metadata key) carrying the API key
+    pub api_key_header: String,
+    /// whether to identify clients with the `X-Forwarded-For` / `X-Real-IP` headers set by a reverse proxy
+    pub trust_proxy_headers: bool,
+    /// cost of a method that has no entry in `method_costs`
+    pub default_method_cost: u64,
+    /// cost of specific methods, by method name
+    pub method_costs: HashMap<String, u64>,
+    /// read-only executions are charged one extra unit every `read_only_gas_per_unit` gas of `max_gas`. 0 disables it
+    pub read_only_gas_per_unit: u64,
+    /// maximum number of client buckets kept in memory
+    pub max_tracked_clients: usize,
+}
+
+/// Identity of a rate limited client
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitClient {
+    /// client presenting a known API key
+    ApiKey(String),
+    /// client identified by its IP address
+    Ip(IpAddr),
+    /// client that could not be identified
+    Anonymous,
+}
+
+impl fmt::Display for RateLimitClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitClient::ApiKey(_) => write!(f, "api key"),
+            RateLimitClient::Ip(ip) => write!(f, "{}", ip),
+            RateLimitClient::Anonymous => write!(f, "anonymous"),
+        }
+    }
+}
+
+/// Details sent back to a throttled client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RateLimitExceeded {
+    /// cost of the refused request in units
+    pub cost: u64,
+    /// units currently available to the client
+    pub available_units: u64,
+    /// delay after which the request can succeed. `None` if the cost exceeds the burst of the client
+    pub retry_after_ms: Option<u64>,
+}
+
+impl fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.retry_after_ms {
+            Some(retry_after_ms) => write!(
+                f,
+                "rate limit exceeded: request costs {} units, {} available, retry after {} ms",
+                self.cost, self.available_units, retry_after_ms
+            ),
+            None => write!(
+                f,
+                "rate limit exceeded: request costs {} units, more than the client quota allows",
+                self.cost
+            ),
+        }
+    }
+}
+
+impl RateLimitExceeded {
+    /// Delay after which the request can succeed
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after_ms.map(Duration::from_millis)
+    }
+}
+
+struct TokenBucket {
+    quota: RateLimitQuota,
+    units: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: RateLimitQuota, now: Instant) -> Self {
+        TokenBucket {
+            quota,
+            units: quota.burst_units as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.units = (self.units + elapsed * self.quota.units_per_second as f64)
+            .min(self.quota.burst_units as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self) -> bool {
+        self.units >= self.quota.burst_units as f64
+    }
+
+    fn try_consume(&mut self, cost: u64, now: Instant) -> Result<(), RateLimitExceeded> {
+        self.refill(now);
+        let cost_f = cost as f64;
+        if self.units >= cost_f {
+            self.units -= cost_f;
+            return Ok(());
+        }
+        let retry_after_ms = if cost > self.quota.burst_units || self.quota.units_per_second == 0 {
+            None
+        } else {
+            let missing = cost_f - self.units;
+            Some((missing * 1000.0 / self.quota.units_per_second as f64).ceil() as u64)
+        };
+        Err(RateLimitExceeded {
+            cost,
+            available_units: self.units as u64,
+            retry_after_ms,
+        })
+    }
+}
+
+/// Per-client rate limiter
+pub struct RateLimiter {
+    config: RwLock<RateLimitConfig>,
+    buckets: Mutex<HashMap<RateLimitClient, TokenBucket>>,
+    massa_metrics: MassaMetrics,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter
+    pub fn new(config: RateLimitConfig, massa_metrics: MassaMetrics) -> Self {
+        RateLimiter {
+            config: RwLock::new(config),
+            buckets: Mutex::new(HashMap::new()),
+            massa_metrics,
+        }
+    }
+
+    /// Rate limiting configuration
+    pub fn config(&self) -> RwLockReadGuard<'_, RateLimitConfig> {
+        self.config.read()
+    }
+
+    /// Replace the rate limiting configuration while the APIs are running.
+    /// Clients keep their bucket, capped to their new quota, except the ones of removed API keys.
+    pub fn set_config(&self, config: RateLimitConfig) {
+        let mut buckets = self.buckets.lock();
+        buckets.retain(|client, _| match client {
+            RateLimitClient::ApiKey(key) => config.api_keys.contains_key(key),
+            RateLimitClient::Ip(_) | RateLimitClient::Anonymous => true,
+        });
+        for (client, bucket) in buckets.iter_mut() {
+            bucket.quota = Self::quota(&config, client);
+            bucket.units = bucket.units.min(bucket.quota.burst_units as f64);
+        }
+        *self.config.write() = config;
+    }
+
+    /// Identify a client.
+    /// A known API key takes precedence over the forwarded IP (if proxy headers are trusted), itself over the peer IP.
+    pub fn identify(
+        &self,
+        api_key: Option<&str>,
+        forwarded_ip: Option<IpAddr>,
+        peer_ip: Option<IpAddr>,
+    ) -> RateLimitClient {
+        let config = self.config.read();
+        if let Some(key) = api_key {
+            if config.api_keys.contains_key(key) {
+                return RateLimitClient::ApiKey(key.to_string());
+            }
+        }
+        if config.trust_proxy_headers {
+            if let Some(ip) = forwarded_ip {
+                return RateLimitClient::Ip(ip);
+            }
+        }
+        match peer_ip {
+            Some(ip) => RateLimitClient::Ip(ip),
+            None => RateLimitClient::Anonymous,
+        }
+    }
+
+    /// Cost in units of a call to `method`.
+    /// `max_gas` is the total gas requested by read-only executions, if any.
+    pub fn method_cost(&self, method: &str, max_gas: Option<u64>) -> u64 {
+        let config = self.config.read();
+        let base = config
+            .method_costs
+            .get(method)
+            .copied()
+            .unwrap_or(config.default_method_cost);
+        let gas_units = match (max_gas, config.read_only_gas_per_unit) {
+            (Some(gas), per_unit) if per_unit > 0 => gas.div_ceil(per_unit),
+            _ => 0,
+        };
+        base.saturating_add(gas_units)
+    }
+
+    /// Charge `cost` units to `client` for a call to `method` of `api`
+    pub fn check(
+        &self,
+        api: &str,
+        method: &str,
+        client: &RateLimitClient,
+        cost: u64,
+    ) -> Result<(), RateLimitExceeded> {
+        let res = self.check_at(client, cost, Instant::now());
+        match res {
+            Ok(()) => self.massa_metrics.inc_api_request_units(api, method, cost),
+            Err(_) => self.massa_metrics.inc_api_throttled_requests(api, method),
+        }
+        res
+    }
+
+    /// Charge the calls of a batch to `client` at once, each call being a method of `api` and its cost.
+    /// Nothing is charged if the client can not pay for the whole batch.
+    pub fn check_batch(
+        &self,
+        api: &str,
+        calls: &[(&str, u64)],
+        client: &RateLimitClient,
+    ) -> Result<(), RateLimitExceeded> {
+        let cost = calls
+            .iter()
+            .fold(0u64, |total, (_, cost)| total.saturating_add(*cost));
+        let res = self.check_at(client, cost, Instant::now());
+        for (method, cost) in calls {
+            match res {
+                Ok(()) => self.massa_metrics.inc_api_request_units(api, method, *cost),
+                Err(_) => self.massa_metrics.inc_api_throttled_requests(api, method),
+            }
+        }
+        res
+    }
+
+    fn check_at(
+        &self,
+        client: &RateLimitClient,
+        cost: u64,
+        now: Instant,
+    ) -> Result<(), RateLimitExceeded> {
+        let mut buckets = self.buckets.lock();
+        let config = self.config.read();
+        if !buckets.contains_key(client) && buckets.len() >= config.max_tracked_clients {
+            // full buckets carry no information: forgetting them is the same as re-creating them later
+            buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                !bucket.is_full()
+            });
+        }
+        let quota = Self::quota(&config, client);
+        buckets
+            .entry(client.clone())
+            .or_insert_with(|| TokenBucket::new(quota, now))
+            .try_consume(cost, now)
+    }
+
+    fn quota(config: &RateLimitConfig, client: &RateLimitClient) -> RateLimitQuota {
+        match client {
+            RateLimitClient::ApiKey(key) => config
+                .api_keys
+                .get(key)
+                .copied()
+                .unwrap_or(config.default_quota),
+            RateLimitClient::Ip(_) | RateLimitClient::Anonymous => config.default_quota,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn limiter() -> RateLimiter {
+        let config = RateLimitConfig {
+            enabled: true,
+            default_quota: RateLimitQuota {
+                burst_units: 10,
+                units_per_second: 5,
+            },
+            api_keys: HashMap::from([(
+                "fleet".to_string(),
+                RateLimitQuota {
+                    burst_units: 100,
+                    units_per_second: 50,
+                },
+            )]),
+            api_key_header: "x-api-key".to_string(),
+            trust_proxy_headers: false,
+            default_method_cost: 1,
+            method_costs: HashMap::from([("execute_read_only_call".to_string(), 4)]),
+            read_only_gas_per_unit: 1_000_000,
+            max_tracked_clients: 2,
+        };
+        let (massa_metrics, _) = MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            32,
+            Duration::from_secs(5),
+        );
+        RateLimiter::new(config, massa_metrics)
+    }
+
+    #[test]
+    fn test_method_cost() {
+        let limiter = limiter();
+        assert_eq!(limiter.method_cost("get_status", None), 1);
+        assert_eq!(limiter.method_cost("execute_read_only_call", None), 4);
+        assert_eq!(
+            limiter.method_cost("execute_read_only_call", Some(2_500_000)),
+            7
+        );
+    }
+
+    #[test]
+    fn test_identify() {
+        let limiter = limiter();
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let proxy_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(
+            limiter.identify(Some("fleet"), None, Some(ip)),
+            RateLimitClient::ApiKey("fleet".to_string())
+        );
+        // unknown keys must not allow to bypass the limits
+        assert_eq!(
+            limiter.identify(Some("random"), None, Some(ip)),
+            RateLimitClient::Ip(ip)
+        );
+        // proxy headers are not trusted by default
+        assert_eq!(
+            limiter.identify(None, Some(proxy_ip), Some(ip)),
+            RateLimitClient::Ip(ip)
+        );
+        assert_eq!(
+            limiter.identify(None, None, None),
+            RateLimitClient::Anonymous
+        );
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = limiter();
+        let client = RateLimitClient::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let now = Instant::now();
+
+        assert!(limiter.check_at(&client, 8, now).is_ok());
+        let err = limiter.check_at(&client, 4, now).unwrap_err();
+        assert_eq!(err.available_units, 2);
+        assert_eq!(err.retry_after_ms, Some(400));
+
+        // refilled after the advertised delay
+        assert!(limiter
+            .check_at(&client, 4, now + Duration::from_millis(400))
+            .is_ok());
+
+        // a request costing more than the burst can never succeed
+        let err = limiter
+            .check_at(&client, 11, now + Duration::from_secs(10))
+            .unwrap_err();
+        assert_eq!(err.retry_after_ms, None);
+
+        // api key clients have their own quota
+        let fleet = RateLimitClient::ApiKey("fleet".to_string());
+        assert!(limiter.check_at(&fleet, 11, now).is_ok());
+    }
+
+    #[test]
+    fn test_check_batch() {
+        let limiter = limiter();
+        let client = RateLimitClient::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let batch = [("get_status", 1), ("execute_read_only_call", 4)];
+
+        assert!(limiter.check_batch("test", &batch, &client).is_ok());
+        // the whole batch is refused, without charging its first calls
+        let err = limiter
+            .check_batch("test", &[batch[1], batch[1]], &client)
+            .unwrap_err();
+        assert_eq!(err.cost, 8);
+        assert_eq!(err.available_units, 5);
+        assert!(limiter.check_at(&client, 5, Instant::now()).is_ok());
+    }
+
+    #[test]
+    fn test_set_config() {
+        let limiter = limiter();
+        let client = RateLimitClient::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let fleet = RateLimitClient::ApiKey("fleet".to_string());
+        let now = Instant::now();
+        limiter.check_at(&client, 2, now).unwrap();
+        limiter.check_at(&fleet, 2, now).unwrap();
+
+        let mut config = limiter.config().clone();
+        config.default_quota.burst_units = 4;
+        config.api_keys.clear();
+        limiter.set_config(config);
+
+        // the bucket of the client is capped to its new burst
+        let err = limiter.check_at(&client, 5, now).unwrap_err();
+        assert_eq!(err.available_units, 4);
+        assert_eq!(err.retry_after_ms, None);
+        // the API key is not known anymore
+        assert!(!limiter.buckets.lock().contains_key(&fleet));
+        assert_eq!(
+            limiter.identify(Some("fleet"), None, None),
+            RateLimitClient::Anonymous
+        );
+    }
+
+    #[test]
+    fn test_full_buckets_are_evicted() {
+        let limiter = limiter();
+        let now = Instant::now();
+        let clients: Vec<RateLimitClient> = (1..=3)
+            .map(|i| RateLimitClient::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, i))))
+            .collect();
+
+        limiter.check_at(&clients[0], 10, now).unwrap();
+        limiter
+            .check_at(&clients[1], 10, now + Duration::from_secs(1))
+            .unwrap();
+        // first client bucket is full again and can be forgotten
+        limiter
+            .check_at(&clients[2], 1, now + Duration::from_secs(2))
+            .unwrap();
+        let buckets = limiter.buckets.lock();
+        assert_eq!(buckets.len(), 2);
+        assert!(!buckets.contains_key(&clients[0]));
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a reload of the node configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConfigReloadReport {
+    /// changed keys applied to the running node
+    pub applied: Vec<String>,
+    /// changed keys that only take effect after a restart of the node
+    pub requires_restart: Vec<String>,
+    /// changed keys that could not be applied, with the reason: they are tried again on the next reload
+    pub failed: Vec<String>,
+}
+
+impl std::fmt::Display for ConfigReloadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.applied.is_empty() && self.requires_restart.is_empty() && self.failed.is_empty() {
+            return writeln!(f, "No configuration change");
+        }
+        if !self.applied.is_empty() {
+            writeln!(f, "Applied:")?;
+            for key in &self.applied {
+                writeln!(f, "\t{}", key)?;
+            }
+        }
+        if !self.requires_restart.is_empty() {
+            writeln!(f, "Requires a restart:")?;
+            for key in &self.requires_restart {
+                writeln!(f, "\t{}", key)?;
+            }
+        }
+        if !self.failed.is_empty() {
+            writeln!(f, "Failed:")?;
+            for key in &self.failed {
+                writeln!(f, "\t{}", key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reloads the configuration of the running node
+pub trait ConfigReloader: Send + Sync {
+    /// Reads the configuration files again and applies the changes that are safe to make live
+    fn reload(&self) -> Result<ConfigReloadReport, String>;
+}
@@ -1,5 +1,8 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_pos_exports::DrawInputs;
 use serde::{Deserialize, Serialize};
 
 /// Roll counts
@@ -21,3 +24,56 @@ impl std::fmt::Display for RollsInfo {
         Ok(())
     }
 }
+
+/// Inputs of the selector draws of a cycle, enough to reproduce them
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct DrawInputsInfo {
+    /// drawn cycle
+    pub cycle: u64,
+    /// hash of the roll distribution, see `massa_pos_exports::roll_distribution_hash`
+    pub roll_distribution_hash: Hash,
+    /// RNG seed at look back
+    pub lookback_seed: Hash,
+    /// roll counts at look back, in address order
+    pub rolls: Vec<(Address, u64)>,
+}
+
+impl std::fmt::Display for DrawInputsInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Draw inputs of cycle {}:", self.cycle)?;
+        writeln!(
+            f,
+            "\tRoll distribution hash: {}",
+            self.roll_distribution_hash
+        )?;
+        writeln!(f, "\tLookback seed: {}", self.lookback_seed)?;
+        writeln!(
+            f,
+            "\tRolls: {} in {} addresses",
+            self.rolls.iter().map(|(_, count)| count).sum::<u64>(),
+            self.rolls.len()
+        )?;
+        Ok(())
+    }
+}
+
+impl From<DrawInputs> for DrawInputsInfo {
+    fn from(inputs: DrawInputs) -> Self {
+        DrawInputsInfo {
+            cycle: inputs.cycle,
+            roll_distribution_hash: inputs.roll_distribution_hash(),
+            lookback_seed: inputs.lookback_seed,
+            rolls: inputs.lookback_rolls.into_iter().collect(),
+        }
+    }
+}
+
+impl From<DrawInputsInfo> for DrawInputs {
+    fn from(info: DrawInputsInfo) -> Self {
+        DrawInputs {
+            cycle: info.cycle,
+            lookback_rolls: info.rolls.into_iter().collect(),
+            lookback_seed: info.lookback_seed,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_final_state::FinalStateHashes;
+use massa_hash::Hash;
 use massa_models::{amount::Amount, slot::Slot};
 
 use serde::{Deserialize, Serialize};
@@ -12,3 +14,33 @@ pub struct SlotAmount {
     /// amount
     pub amount: Amount,
 }
+
+/// Hashes of the final state right after a final slot
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FinalStateHashesInfo {
+    /// final slot
+    pub slot: Slot,
+    /// execution trail hash, summarizing all the executed slots up to `slot`
+    pub execution_trail_hash: Hash,
+    /// fingerprint of the whole final state
+    pub fingerprint: Hash,
+}
+
+impl std::fmt::Display for FinalStateHashesInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Final state at slot {}:", self.slot)?;
+        writeln!(f, "\tExecution trail hash: {}", self.execution_trail_hash)?;
+        writeln!(f, "\tFingerprint: {}", self.fingerprint)?;
+        Ok(())
+    }
+}
+
+impl From<FinalStateHashes> for FinalStateHashesInfo {
+    fn from(hashes: FinalStateHashes) -> Self {
+        FinalStateHashesInfo {
+            slot: hashes.slot,
+            execution_trail_hash: hashes.execution_trail_hash,
+            fingerprint: hashes.fingerprint,
+        }
+    }
+}
@@ -53,7 +53,7 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(self.into_rpc(), url, api_config, None).await
     }
 }
 
@@ -149,6 +149,14 @@ impl MassaApiServer for API<ApiV2> {
     ) -> SubscriptionResult {
         broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
     }
+
+    async fn subscribe_block_status(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.consensus_broadcasts.block_status_sender.clone(),
+            pending,
+        )
+        .await
+    }
 }
 
 // Brodcast the stream(sender) content via a WebSocket
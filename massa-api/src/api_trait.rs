@@ -57,4 +57,12 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// Block status changes (active, blockclique changes, finality, discards).
+    #[subscription(
+        name = "subscribe_block_status" => "block_status",
+        unsubscribe = "unsubscribe_block_status",
+        item = BlockStatusEvent
+    )]
+    async fn subscribe_block_status(&self) -> SubscriptionResult;
 }
@@ -20,13 +20,21 @@ use massa_api_exports::{
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    graph::BlockGraphDump,
+    ledger::LedgerStatsInfo,
+    node::{NodeStatus, PeerDetailsInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    rate_limit::RateLimiter,
+    reload::{ConfigReloadReport, ConfigReloader},
+    rolls::DrawInputsInfo,
+    slot::FinalStateHashesInfo,
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::ExecutionController;
+use massa_logging::subscriber::LogFilterHandle;
+use massa_models::access_list::{AccessEntry, AccessTarget};
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
@@ -34,7 +42,7 @@ use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
+    address::Address, amount::Amount, block::Block, block_id::BlockId, endorsement::EndorsementId,
     execution::EventFilter, slot::Slot, version::Version,
 };
 use massa_pool_exports::{PoolBroadcasts, PoolController};
@@ -44,6 +52,7 @@ use massa_storage::Storage;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use rate_limit::{PeerAddrLogger, RateLimitLayer};
 use serde_json::Value;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Condvar, Mutex};
@@ -59,6 +68,7 @@ mod api;
 mod api_trait;
 mod private;
 mod public;
+mod rate_limit;
 
 #[cfg(test)]
 mod tests;
@@ -87,10 +97,14 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// per-client rate limiter, if enabled
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 /// Private API content
 pub struct Private {
+    /// link to the consensus component
+    pub consensus_controller: Box<dyn ConsensusController>,
     /// link to the protocol component
     pub protocol_controller: Box<dyn ProtocolController>,
     /// link to the execution component
@@ -102,6 +116,10 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// Handle to change the log filter of the node
+    pub log_filter: LogFilterHandle,
+    /// Applies the changes of the configuration files to the running node
+    pub config_reloader: Arc<dyn ConfigReloader>,
 }
 
 /// API v2 content
@@ -149,6 +167,7 @@ async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
+    rate_limiter: Option<Arc<RateLimiter>>,
 ) -> Result<StopHandle, JsonRpseeError> {
     let mut server_builder = ServerBuilder::new()
         .max_request_body_size(api_config.max_request_body_size)
@@ -159,13 +178,27 @@ async fn serve<T>(
         } else {
             BatchRequestConfig::Disabled
         })
-        .ping_interval(api_config.ping_interval.to_duration());
+        .ping_interval(api_config.ping_interval.to_duration())
+        .set_logger(PeerAddrLogger);
+
+    // calls sent over an open websocket never go through the middlewares and could not be
+    // charged: a rate limited server only serves HTTP
+    let enable_ws = api_config.enable_ws && rate_limiter.is_none();
+    if api_config.enable_ws && !enable_ws {
+        if !api_config.enable_http {
+            panic!("wrong server configuration, websockets can't be rate limited: enable http");
+        }
+        warn!(
+            "API | websockets are disabled on {} as its clients are rate limited",
+            url
+        );
+    }
 
-    if api_config.enable_http && !api_config.enable_ws {
+    if api_config.enable_http && !enable_ws {
         server_builder = server_builder.http_only();
-    } else if api_config.enable_ws && !api_config.enable_http {
+    } else if enable_ws && !api_config.enable_http {
         server_builder = server_builder.ws_only()
-    } else if !api_config.enable_http && !api_config.enable_ws {
+    } else if !api_config.enable_http && !enable_ws {
         panic!("wrong server configuration, you can't disable both http and ws");
     }
 
@@ -188,9 +221,15 @@ async fn serve<T>(
 
     let allowed_hosts = HostFilterLayer::new(hosts).expect("failed to build allowed hosts filter");
 
+    let rate_limit = rate_limiter.map(|rate_limiter| {
+        let method_names = api.method_names().map(str::to_string).collect();
+        RateLimitLayer::new(rate_limiter, method_names, api_config.max_request_body_size)
+    });
+
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
-        .layer(allowed_hosts);
+        .layer(allowed_hosts)
+        .option_layer(rate_limit);
 
     let server = server_builder
         .set_middleware(middleware)
@@ -262,6 +301,14 @@ pub trait MassaRpc {
     #[method(name = "get_staking_addresses")]
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
 
+    /// Export the block graph between two optional slots: `<start>` (included) and `<end>` (excluded).
+    #[method(name = "node_export_block_graph")]
+    async fn node_export_block_graph(
+        &self,
+        start: Option<Slot>,
+        end: Option<Slot>,
+    ) -> RpcResult<BlockGraphDump>;
+
     /// Bans given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_ban_by_ip")]
@@ -272,50 +319,66 @@ pub trait MassaRpc {
     #[method(name = "node_ban_by_id")]
     async fn node_ban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
-    /// Returns node peers whitelist IP address(es).
+    /// Returns node peers whitelist entries: IP ranges and node ids, with optional expiry.
     #[method(name = "node_peers_whitelist")]
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<AccessEntry>>;
 
-    /// Add IP address(es) to node peers whitelist.
+    /// Add IP ranges or node ids to node peers whitelist.
+    /// Once a whitelist exists, only the peers it matches are accepted.
     /// No confirmation to expect.
-    /// Note: If the ip was unknown it adds it to the known peers, otherwise it updates the peer type
     #[method(name = "node_add_to_peers_whitelist")]
-    async fn node_add_to_peers_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_peers_whitelist(&self, arg: Vec<AccessEntry>) -> RpcResult<()>;
 
-    /// Remove from peers whitelist given IP address(es).
-    /// keep it as standard
+    /// Remove IP ranges or node ids from peers whitelist.
+    /// Removing the last entry deletes the whitelist.
     /// No confirmation to expect.
     #[method(name = "node_remove_from_peers_whitelist")]
-    async fn node_remove_from_peers_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_peers_whitelist(&self, arg: Vec<AccessTarget>) -> RpcResult<()>;
+
+    /// Returns node peers blacklist entries: IP ranges and node ids, with optional expiry.
+    #[method(name = "node_peers_blacklist")]
+    async fn node_peers_blacklist(&self) -> RpcResult<Vec<AccessEntry>>;
+
+    /// Add IP ranges or node ids to node peers blacklist.
+    /// Matching peers are disconnected.
+    /// No confirmation to expect.
+    #[method(name = "node_add_to_peers_blacklist")]
+    async fn node_add_to_peers_blacklist(&self, arg: Vec<AccessEntry>) -> RpcResult<()>;
+
+    /// Remove IP ranges or node ids from peers blacklist.
+    /// No confirmation to expect.
+    #[method(name = "node_remove_from_peers_blacklist")]
+    async fn node_remove_from_peers_blacklist(&self, arg: Vec<AccessTarget>) -> RpcResult<()>;
 
-    /// Returns node bootstrap whitelist IP address(es).
+    /// Returns node bootstrap whitelist entries.
     #[method(name = "node_bootstrap_whitelist")]
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<AccessEntry>>;
 
     /// Allow everyone to bootstrap from the node.
     /// remove bootstrap whitelist configuration file.
     #[method(name = "node_bootstrap_whitelist_allow_all")]
     async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()>;
 
-    /// Add IP address(es) to node bootstrap whitelist.
+    /// Add IP ranges or node ids to node bootstrap whitelist.
+    /// IP ranges filter the clients, node ids filter the servers this node bootstraps from.
     #[method(name = "node_add_to_bootstrap_whitelist")]
-    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<AccessEntry>) -> RpcResult<()>;
 
-    /// Remove IP address(es) to bootstrap whitelist.
+    /// Remove IP ranges or node ids from bootstrap whitelist.
     #[method(name = "node_remove_from_bootstrap_whitelist")]
-    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<AccessTarget>) -> RpcResult<()>;
 
-    /// Returns node bootstrap blacklist IP address(es).
+    /// Returns node bootstrap blacklist entries.
     #[method(name = "node_bootstrap_blacklist")]
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<AccessEntry>>;
 
-    /// Add IP address(es) to node bootstrap blacklist.
+    /// Add IP ranges or node ids to node bootstrap blacklist.
     #[method(name = "node_add_to_bootstrap_blacklist")]
-    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<AccessEntry>) -> RpcResult<()>;
 
-    /// Remove IP address(es) to bootstrap blacklist.
+    /// Remove IP ranges or node ids from bootstrap blacklist.
     #[method(name = "node_remove_from_bootstrap_blacklist")]
-    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<AccessTarget>) -> RpcResult<()>;
 
     /// Unban given IP address(es).
     /// No confirmation to expect.
@@ -327,6 +390,26 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Replaces the log filter of the node by the given directives (e.g. `massa=info,massa_protocol_worker=debug`).
+    /// Returns the previous directives.
+    #[method(name = "node_set_log_filter")]
+    async fn node_set_log_filter(&self, arg: String) -> RpcResult<String>;
+
+    /// Returns the directives of the current log filter of the node.
+    #[method(name = "node_get_log_filter")]
+    async fn node_get_log_filter(&self) -> RpcResult<String>;
+
+    /// Reads the configuration files again and applies the changes that are safe to make live:
+    /// minimal fees of the pool, outgoing connection targets of the peer categories, API rate limits and log filter.
+    /// Returns the changed keys that were applied and the ones requiring a restart.
+    #[method(name = "node_reload_config")]
+    async fn node_reload_config(&self) -> RpcResult<ConfigReloadReport>;
+
+    /// Network diagnostics of the connected peers and of the peers known by the node:
+    /// connection, traffic, announcements received, block asks answered and timed out, state, last error and version.
+    #[method(name = "get_peers_details")]
+    async fn get_peers_details(&self) -> RpcResult<Vec<PeerDetailsInfo>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -375,6 +458,31 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Get aggregate data about the final ledger: supply, address counts and datastore size.
+    #[method(name = "get_ledger_stats")]
+    async fn get_ledger_stats(&self) -> RpcResult<LedgerStatsInfo>;
+
+    /// Get the addresses with the highest final balances, sorted by decreasing balance.
+    #[method(name = "get_top_balances")]
+    async fn get_top_balances(&self, arg: u64) -> RpcResult<Vec<(Address, Amount)>>;
+
+    /// Get the number of bytes (keys and values) used by the final datastore of addresses.
+    #[method(name = "get_datastore_sizes")]
+    async fn get_datastore_sizes(&self, arg: Vec<Address>) -> RpcResult<Vec<u64>>;
+
+    /// Get the execution trail hash and the final state fingerprint right after final slots.
+    /// None for the slots that are not final yet or are older than the state hash history of the node.
+    #[method(name = "get_final_state_hashes")]
+    async fn get_final_state_hashes(
+        &self,
+        arg: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<FinalStateHashesInfo>>>;
+
+    /// Get the inputs of the selector draws of cycles (look back rolls and seed), to reproduce the draws.
+    /// None for the cycles that are no longer in the cycle history of the node.
+    #[method(name = "get_draw_inputs")]
+    async fn get_draw_inputs(&self, arg: Vec<u64>) -> RpcResult<Vec<Option<DrawInputsInfo>>>;
+
     /// Get addresses bytecode.
     #[method(name = "get_addresses_bytecode")]
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>>;
@@ -12,27 +12,48 @@ use massa_api_exports::{
     endorsement::EndorsementInfo,
     error::ApiError,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
-    node::NodeStatus,
+    graph::{BlockGraphDump, GraphBlock},
+    ledger::LedgerStatsInfo,
+    node::{NodeStatus, PeerDetailsInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    reload::{ConfigReloadReport, ConfigReloader},
+    rolls::DrawInputsInfo,
+    slot::FinalStateHashesInfo,
     ListType, ScrudOperation, TimeInterval,
 };
+use massa_consensus_exports::{
+    block_graph_export::BlockGraphExport, block_status::DiscardReason, ConsensusController,
+};
 use massa_execution_exports::ExecutionController;
 use massa_hash::Hash;
+use massa_logging::subscriber::LogFilterHandle;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    access_list::{AccessEntry, AccessList, AccessTarget},
+    address::Address,
+    amount::Amount,
+    block::Block,
+    block_id::BlockId,
+    clique::Clique,
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::EventFilter,
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_protocol_exports::{PeerConnectionType, PeerId, PeerListType, ProtocolController};
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{collections::BTreeSet, sync::Mutex};
+use std::sync::Mutex;
 use std::{
     fs::{remove_file, OpenOptions},
     sync::Condvar,
@@ -41,18 +62,24 @@ use std::{
 impl API<Private> {
     /// generate a new private API
     pub fn new(
+        consensus_controller: Box<dyn ConsensusController>,
         protocol_controller: Box<dyn ProtocolController>,
         execution_controller: Box<dyn ExecutionController>,
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        log_filter: LogFilterHandle,
+        config_reloader: Arc<dyn ConfigReloader>,
     ) -> Self {
         API(Private {
+            consensus_controller,
             protocol_controller,
             execution_controller,
             api_settings,
             stop_cv,
             node_wallet,
+            log_filter,
+            config_reloader,
         })
     }
 }
@@ -64,7 +91,7 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(self.into_rpc(), url, settings, None).await
     }
 }
 
@@ -147,6 +174,42 @@ impl MassaRpcServer for API<Private> {
         Ok(w_wallet.get_wallet_address_list())
     }
 
+    async fn node_export_block_graph(
+        &self,
+        start: Option<Slot>,
+        end: Option<Slot>,
+    ) -> RpcResult<BlockGraphDump> {
+        // at most max_arguments periods are exported per request
+        let max_periods = self.0.api_settings.max_arguments;
+        let too_many_periods = |periods: u64| {
+            ApiError::BadRequest(format!(
+                "the exported range spans {} periods. Only a maximum of {} periods are exported per request, narrow it with start and end slots",
+                periods, max_periods
+            ))
+        };
+        if let (Some(start), Some(end)) = (start, end) {
+            let periods = end.period.saturating_sub(start.period);
+            if periods > max_periods {
+                return Err(too_many_periods(periods).into());
+            }
+        }
+
+        let graph = self
+            .0
+            .consensus_controller
+            .get_block_graph_status(start, end)
+            .map_err(|e| ApiError::ConsensusError(e.to_string()))?;
+        let dump = to_block_graph_dump(graph, self.0.api_settings.thread_count);
+        // the blocks are sorted by slot
+        if let (Some(first), Some(last)) = (dump.blocks.first(), dump.blocks.last()) {
+            let periods = last.slot.period - first.slot.period + 1;
+            if periods > max_periods {
+                return Err(too_many_periods(periods).into());
+            }
+        }
+        Ok(dump)
+    }
+
     async fn node_ban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -183,6 +246,60 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
+    async fn node_set_log_filter(&self, directives: String) -> RpcResult<String> {
+        self.0
+            .log_filter
+            .set_filter(&directives)
+            .map_err(|e| ApiError::BadRequest(e).into())
+    }
+
+    async fn node_get_log_filter(&self) -> RpcResult<String> {
+        Ok(self.0.log_filter.get_filter())
+    }
+
+    async fn node_reload_config(&self) -> RpcResult<ConfigReloadReport> {
+        self.0
+            .config_reloader
+            .reload()
+            .map_err(|e| ApiError::InternalServerError(e).into())
+    }
+
+    async fn get_peers_details(&self) -> RpcResult<Vec<PeerDetailsInfo>> {
+        let peers = self
+            .0
+            .protocol_controller
+            .get_peers_details()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        let now = MassaTime::now();
+        Ok(peers
+            .into_iter()
+            .map(|peer| PeerDetailsInfo {
+                //TODO: Change when unify node id and peer id
+                node_id: NodeId::new(peer.peer_id.get_public_key()),
+                address: peer.connection.map(|(addr, _)| addr),
+                is_outgoing: peer
+                    .connection
+                    .map(|(_, connection_type)| match connection_type {
+                        PeerConnectionType::IN => false,
+                        PeerConnectionType::OUT => true,
+                    }),
+                listeners: peer.listeners,
+                category: peer.category,
+                connection_age: peer.connected_since.map(|since| now.saturating_sub(since)),
+                bytes_received: peer.bytes_received,
+                bytes_sent: peer.bytes_sent,
+                block_announcements: peer.block_announcements,
+                operation_announcements: peer.operation_announcements,
+                endorsement_announcements: peer.endorsement_announcements,
+                asks_answered: peer.asks_answered,
+                asks_timed_out: peer.asks_timed_out,
+                state: peer.state.map(|state| state.to_string()),
+                last_error: peer.last_error,
+                version: peer.version,
+            })
+            .collect())
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -242,6 +359,29 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_ledger_stats(&self) -> RpcResult<LedgerStatsInfo> {
+        crate::wrong_api::<LedgerStatsInfo>()
+    }
+
+    async fn get_top_balances(&self, _: u64) -> RpcResult<Vec<(Address, Amount)>> {
+        crate::wrong_api::<Vec<(Address, Amount)>>()
+    }
+
+    async fn get_datastore_sizes(&self, _: Vec<Address>) -> RpcResult<Vec<u64>> {
+        crate::wrong_api::<Vec<u64>>()
+    }
+
+    async fn get_final_state_hashes(
+        &self,
+        _: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<FinalStateHashesInfo>>> {
+        crate::wrong_api::<Vec<Option<FinalStateHashesInfo>>>()
+    }
+
+    async fn get_draw_inputs(&self, _: Vec<u64>) -> RpcResult<Vec<Option<DrawInputsInfo>>> {
+        crate::wrong_api::<Vec<Option<DrawInputsInfo>>>()
+    }
+
     async fn get_addresses_bytecode(&self, _: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         crate::wrong_api::<Vec<Vec<u8>>>()
     }
@@ -254,48 +394,56 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // match network_command_sender.get_peers().await {
-        //     Ok(peers) => Ok(peers.peers.into_keys().sorted().collect::<Vec<IpAddr>>()),
-        //     Err(e) => Err(ApiError::NetworkError(e).into()),
-        // }
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
+        self.0
+            .protocol_controller
+            .get_peers_list(PeerListType::Whitelist)
+            .map(Option::unwrap_or_default)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
-    async fn node_add_to_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .add_to_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_add_to_peers_whitelist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .add_to_peers_list(PeerListType::Whitelist, entries)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
-    async fn node_remove_from_peers_whitelist(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        //TODO: Readd in network refactoring
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .remove_from_whitelist(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_remove_from_peers_whitelist(&self, targets: Vec<AccessTarget>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .remove_from_peers_list(PeerListType::Whitelist, targets)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_peers_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
+        self.0
+            .protocol_controller
+            .get_peers_list(PeerListType::Blacklist)
+            .map(Option::unwrap_or_default)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_add_to_peers_blacklist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .add_to_peers_list(PeerListType::Blacklist, entries)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        read_ips_from_jsonfile(
+    async fn node_remove_from_peers_blacklist(&self, targets: Vec<AccessTarget>) -> RpcResult<()> {
+        self.0
+            .protocol_controller
+            .remove_from_peers_list(PeerListType::Blacklist, targets)
+            .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
+    }
+
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
+        read_list_from_jsonfile(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
             &ListType::Whitelist,
         )
+        .map(|list| list.entries())
     }
 
     async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()> {
@@ -308,44 +456,51 @@ impl MassaRpcServer for API<Private> {
         })
     }
 
-    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_whitelist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
-            ips,
+            entries,
             ListType::Whitelist,
             ScrudOperation::Create,
         )
     }
 
-    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_whitelist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
-            ips,
+            targets.into_iter().map(AccessEntry::from).collect(),
             ListType::Whitelist,
             ScrudOperation::Delete,
         )
     }
 
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
-        read_ips_from_jsonfile(
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
+        read_list_from_jsonfile(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
             &ListType::Blacklist,
         )
+        .map(|list| list.entries())
     }
 
-    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_blacklist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
-            ips,
+            entries,
             ListType::Blacklist,
             ScrudOperation::Create,
         )
     }
 
-    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_blacklist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
-            ips,
+            targets.into_iter().map(AccessEntry::from).collect(),
             ListType::Blacklist,
             ScrudOperation::Delete,
         )
@@ -356,10 +511,71 @@ impl MassaRpcServer for API<Private> {
     }
 }
 
-/// Run Search, Create, Read, Update, Delete operation on bootstrap list of IP(s)
+/// Convert a consensus graph export into a self-contained dump, sorted by slot
+fn to_block_graph_dump(graph: BlockGraphExport, thread_count: u8) -> BlockGraphDump {
+    let cliques_of = |id: &BlockId| -> Vec<usize> {
+        graph
+            .max_cliques
+            .iter()
+            .enumerate()
+            .filter(|(_, clique)| clique.block_ids.contains(id))
+            .map(|(index, _)| index)
+            .collect()
+    };
+    let in_blockclique = |id: &BlockId| {
+        graph
+            .max_cliques
+            .iter()
+            .any(|clique| clique.is_blockclique && clique.block_ids.contains(id))
+    };
+
+    let mut blocks = Vec::with_capacity(graph.active_blocks.len() + graph.discarded_blocks.len());
+    for (id, block) in graph.active_blocks.iter() {
+        blocks.push(GraphBlock {
+            id: *id,
+            slot: block.header.content.slot,
+            creator: block.header.content_creator_address,
+            parents: block.header.content.parents.clone(),
+            fitness: Some(block.header.get_fitness()),
+            is_final: block.is_final,
+            is_in_blockclique: in_blockclique(id),
+            cliques: cliques_of(id),
+            discard_reason: None,
+        });
+    }
+    for (id, (reason, (slot, creator, parents))) in graph.discarded_blocks.iter() {
+        let reason = match reason {
+            DiscardReason::Invalid(msg) => format!("invalid: {}", msg),
+            DiscardReason::Stale => "stale".to_string(),
+            DiscardReason::Final => "final".to_string(),
+        };
+        blocks.push(GraphBlock {
+            id: *id,
+            slot: *slot,
+            creator: *creator,
+            parents: parents.clone(),
+            fitness: None,
+            is_final: false,
+            is_in_blockclique: false,
+            cliques: Vec::new(),
+            discard_reason: Some(reason),
+        });
+    }
+    blocks.sort_unstable_by_key(|block| (block.slot, block.id));
+
+    BlockGraphDump {
+        thread_count,
+        blocks,
+        cliques: graph.max_cliques,
+        best_parents: graph.best_parents,
+        latest_final_blocks_periods: graph.latest_final_blocks_periods,
+    }
+}
+
+/// Run Search, Create, Read, Update, Delete operation on bootstrap list of IP ranges and node ids
 fn run_scrud_operation(
     bootstrap_list_file: PathBuf,
-    ips: Vec<IpAddr>,
+    entries: Vec<AccessEntry>,
     list_type: ListType,
     scrud_operation: ScrudOperation,
 ) -> RpcResult<()> {
@@ -367,14 +583,18 @@ fn run_scrud_operation(
         ScrudOperation::Create => get_file_len(bootstrap_list_file.clone(), &list_type, true)
             .and_then(|length| {
                 if length == 0 {
-                    write_ips_to_jsonfile(bootstrap_list_file, BTreeSet::from_iter(ips), &list_type)
+                    write_list_to_jsonfile(
+                        bootstrap_list_file,
+                        AccessList::from_iter(entries),
+                        &list_type,
+                    )
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
-                            list_ips.extend(ips);
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
-                        })
+                    read_list_from_jsonfile(bootstrap_list_file.clone(), &list_type).and_then(
+                        |mut list| {
+                            entries.into_iter().for_each(|entry| list.insert(entry));
+                            write_list_to_jsonfile(bootstrap_list_file, list, &list_type)
+                        },
+                    )
                 }
             }),
         ScrudOperation::Delete => get_file_len(bootstrap_list_file.clone(), &list_type, false)
@@ -386,21 +606,21 @@ fn run_scrud_operation(
                     ))
                     .into())
                 } else {
-                    read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
-                        .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
-                            if list_ips.is_empty() {
+                    read_list_from_jsonfile(bootstrap_list_file.clone(), &list_type).and_then(
+                        |mut list| {
+                            if list.is_empty() {
                                 return Err(ApiError::InternalServerError(format!(
                                     "failed to execute delete operation, bootstrap {} is empty",
                                     list_type
                                 ))
                                 .into());
                             }
-                            ips.into_iter().for_each(|ip| {
-                                list_ips.remove(&ip);
+                            entries.iter().for_each(|entry| {
+                                list.remove(&entry.target);
                             });
-                            write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
-                        })
+                            write_list_to_jsonfile(bootstrap_list_file, list, &list_type)
+                        },
+                    )
                 }
             }),
         _ => Err(ApiError::BadRequest(format!(
@@ -439,11 +659,11 @@ fn get_file_len(
         })
 }
 
-/// Read bootstrap list IP(s) from json file
-fn read_ips_from_jsonfile(
+/// Read bootstrap list entries from json file
+fn read_list_from_jsonfile(
     bootstrap_list_file: PathBuf,
     list_type: &ListType,
-) -> RpcResult<Vec<IpAddr>> {
+) -> RpcResult<AccessList> {
     std::fs::read_to_string(bootstrap_list_file)
         .map_err(|e| {
             ApiError::InternalServerError(format!(
@@ -463,10 +683,10 @@ fn read_ips_from_jsonfile(
         })
 }
 
-/// Write bootstrap list IP(s) from json file
-fn write_ips_to_jsonfile(
+/// Write bootstrap list entries to json file
+fn write_list_to_jsonfile(
     bootstrap_list_file: PathBuf,
-    ips: BTreeSet<IpAddr>,
+    list: AccessList,
     list_type: &ListType,
 ) -> RpcResult<()> {
     OpenOptions::new()
@@ -482,7 +702,7 @@ fn write_ips_to_jsonfile(
             .into()
         })
         .and_then(|file| {
-            serde_json::to_writer_pretty(file, &ips).map_err(|e| {
+            serde_json::to_writer_pretty(file, &list).map_err(|e| {
                 ApiError::InternalServerError(format!(
                     "failed to write bootstrap {} configuration file: {}",
                     list_type, e
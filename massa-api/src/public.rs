@@ -15,10 +15,15 @@ use massa_api_exports::{
     execution::{
         ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult, Transfer,
     },
-    node::NodeStatus,
+    graph::BlockGraphDump,
+    ledger::LedgerStatsInfo,
+    node::{NodeStatus, PeerDetailsInfo},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
-    slot::SlotAmount,
+    rate_limit::RateLimiter,
+    reload::ConfigReloadReport,
+    rolls::DrawInputsInfo,
+    slot::{FinalStateHashesInfo, SlotAmount},
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
@@ -29,6 +34,7 @@ use massa_execution_exports::{
     ReadOnlyExecutionTarget,
 };
 use massa_models::{
+    access_list::{AccessEntry, AccessTarget},
     address::Address,
     amount::Amount,
     block::{Block, BlockGraphStatus},
@@ -65,6 +71,7 @@ use massa_versioning::{
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 
 impl API<Public> {
     /// generate a new public API
@@ -80,6 +87,7 @@ impl API<Public> {
         node_id: NodeId,
         storage: Storage,
         mip_store: MipStore,
+        rate_limiter: Option<Arc<RateLimiter>>,
     ) -> Self {
         API(Public {
             consensus_controller,
@@ -93,6 +101,7 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            rate_limiter,
         })
     }
 }
@@ -104,7 +113,8 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        let rate_limiter = self.0.rate_limiter.clone();
+        crate::serve(self.into_rpc(), url, api_config, rate_limiter).await
     }
 }
 
@@ -283,12 +293,13 @@ impl MassaRpcServer for API<Public> {
 
             // check if fee is enough
             if let Some(fee) = fee {
-                if fee.checked_sub(self.0.api_settings.minimal_fees).is_none() {
+                let minimal_fees = *self.0.api_settings.minimal_fees.read();
+                if fee.checked_sub(minimal_fees).is_none() {
                     let result = ExecuteReadOnlyResponse {
                         executed_at: Slot::new(0, 0),
                         result: ReadOnlyResult::Error(format!(
                             "fee is too low provided: {} , minimal_fees required: {}",
-                            fee, self.0.api_settings.minimal_fees
+                            fee, minimal_fees
                         )),
                         gas_cost: 0,
                         output_events: Default::default(),
@@ -384,12 +395,13 @@ impl MassaRpcServer for API<Public> {
             };
 
             if let Some(fee) = fee {
-                if fee.checked_sub(self.0.api_settings.minimal_fees).is_none() {
+                let minimal_fees = *self.0.api_settings.minimal_fees.read();
+                if fee.checked_sub(minimal_fees).is_none() {
                     let result = ExecuteReadOnlyResponse {
                         executed_at: Slot::new(0, 0),
                         result: ReadOnlyResult::Error(format!(
                             "fee is too low provided: {} , minimal_fees required: {}",
-                            fee, self.0.api_settings.minimal_fees
+                            fee, minimal_fees
                         )),
                         gas_cost: 0,
                         output_events: Default::default(),
@@ -434,6 +446,14 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
+    async fn node_export_block_graph(
+        &self,
+        _: Option<Slot>,
+        _: Option<Slot>,
+    ) -> RpcResult<BlockGraphDump> {
+        crate::wrong_api::<BlockGraphDump>()
+    }
+
     async fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -450,6 +470,22 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_set_log_filter(&self, _: String) -> RpcResult<String> {
+        crate::wrong_api::<String>()
+    }
+
+    async fn node_get_log_filter(&self) -> RpcResult<String> {
+        crate::wrong_api::<String>()
+    }
+
+    async fn node_reload_config(&self) -> RpcResult<ConfigReloadReport> {
+        crate::wrong_api::<ConfigReloadReport>()
+    }
+
+    async fn get_peers_details(&self) -> RpcResult<Vec<PeerDetailsInfo>> {
+        crate::wrong_api::<Vec<PeerDetailsInfo>>()
+    }
+
     /// get status
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let version = self.0.version;
@@ -555,7 +591,8 @@ impl MassaRpcServer for API<Public> {
             config,
             current_cycle,
             chain_id: self.0.api_settings.chain_id,
-            minimal_fees: self.0.api_settings.minimal_fees,
+            minimal_fees: *self.0.api_settings.minimal_fees.read(),
+            is_syncing: self.0.consensus_controller.is_syncing(),
         })
     }
 
@@ -1115,6 +1152,74 @@ impl MassaRpcServer for API<Public> {
     }
 
     /// get addresses bytecode
+    async fn get_ledger_stats(&self) -> RpcResult<LedgerStatsInfo> {
+        let stats = self.0.execution_controller.get_final_ledger_stats();
+        Ok(LedgerStatsInfo {
+            slot: stats.slot,
+            total_supply: stats.total_supply(),
+            circulating_supply: stats.circulating_supply(),
+            roll_count: stats.roll_count,
+            rolls_value: stats.rolls_value,
+            deferred_credits: stats.deferred_credits,
+            user_address_count: stats.user_address_count,
+            sc_address_count: stats.sc_address_count,
+            datastore_bytes: stats.datastore_bytes,
+        })
+    }
+
+    async fn get_top_balances(&self, count: u64) -> RpcResult<Vec<(Address, Amount)>> {
+        if count > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest(format!(
+                "too many addresses requested. Only a maximum of {} addresses are returned per request",
+                self.0.api_settings.max_arguments
+            ))
+            .into());
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_final_top_balances(count as usize))
+    }
+
+    async fn get_datastore_sizes(&self, addresses: Vec<Address>) -> RpcResult<Vec<u64>> {
+        if addresses.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_final_datastore_sizes(&addresses))
+    }
+
+    async fn get_final_state_hashes(
+        &self,
+        slots: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<FinalStateHashesInfo>>> {
+        if slots.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_final_state_hashes(&slots)
+            .into_iter()
+            .map(|hashes| hashes.map(FinalStateHashesInfo::from))
+            .collect())
+    }
+
+    async fn get_draw_inputs(&self, cycles: Vec<u64>) -> RpcResult<Vec<Option<DrawInputsInfo>>> {
+        if cycles.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_draw_inputs(&cycles)
+            .into_iter()
+            .map(|inputs| inputs.map(DrawInputsInfo::from))
+            .collect())
+    }
+
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         let queries = args
             .into_iter()
@@ -1176,20 +1281,16 @@ impl MassaRpcServer for API<Public> {
         )
         .map_err(ApiError::ModelsError)?;
 
+        let minimal_fees = *api_cfg.minimal_fees.read();
         let verified_ops = ops
             .into_iter()
             .map(|op_input| check_input_operation(op_input, api_cfg, last_slot))
             .map(|op| match op {
                 Ok(operation) => {
-                    if operation
-                        .content
-                        .fee
-                        .checked_sub(api_cfg.minimal_fees)
-                        .is_none()
-                    {
+                    if operation.content.fee.checked_sub(minimal_fees).is_none() {
                         return Err(ApiError::BadRequest(format!(
                             "fee is too low provided: {} , minimal_fees required: {}",
-                            operation.content.fee, self.0.api_settings.minimal_fees
+                            operation.content.fee, minimal_fees
                         ))
                         .into());
                     }
@@ -1206,6 +1307,12 @@ impl MassaRpcServer for API<Public> {
 
         to_send.store_operations(verified_ops.clone());
         let ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
+        // open the spans following the operations until they are final and executed,
+        // the stage lasting until they are handed to the pool and protocol
+        let _spans: Vec<_> = ids
+            .iter()
+            .map(|id| massa_logging::otel::object_span("operation", id, "api.send_operations"))
+            .collect();
         cmd_sender.add_operations(to_send.clone());
 
         tokio::task::spawn_blocking(move || protocol_sender.propagate_operations(to_send))
@@ -1236,43 +1343,55 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
-    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
+        crate::wrong_api::<Vec<AccessEntry>>()
+    }
+
+    async fn node_add_to_peers_whitelist(&self, _: Vec<AccessEntry>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
     }
 
-    async fn node_add_to_peers_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_peers_whitelist(&self, _: Vec<AccessTarget>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_peers_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_peers_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
+        crate::wrong_api::<Vec<AccessEntry>>()
+    }
+
+    async fn node_add_to_peers_blacklist(&self, _: Vec<AccessEntry>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn node_remove_from_peers_blacklist(&self, _: Vec<AccessTarget>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
+        crate::wrong_api::<Vec<AccessEntry>>()
     }
 
     async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_add_to_bootstrap_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_whitelist(&self, _: Vec<AccessEntry>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_bootstrap_whitelist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_whitelist(&self, _: Vec<AccessTarget>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
-        crate::wrong_api::<Vec<IpAddr>>()
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
+        crate::wrong_api::<Vec<AccessEntry>>()
     }
 
-    async fn node_add_to_bootstrap_blacklist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_blacklist(&self, _: Vec<AccessEntry>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
-    async fn node_remove_from_bootstrap_blacklist(&self, _: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_blacklist(&self, _: Vec<AccessTarget>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
 
@@ -1341,7 +1460,7 @@ fn check_input_operation(
     let (rest, op): (&[u8], SecureShareOperation) = operation_deserializer
         .deserialize::<DeserializeError>(&op_serialized)
         .map_err(|err| ApiError::ModelsError(ModelsError::DeserializeError(err.to_string())))?;
-    match op.content.op {
+    match op.content.op.get_emitted_op() {
         OperationType::CallSC { .. } => {
             let gas_usage =
                 op.get_gas_usage(api_cfg.base_operation_gas_cost, api_cfg.sp_compilation_cost);
@@ -1360,6 +1479,15 @@ fn check_input_operation(
                 return Err(ApiError::InconsistencyError(err_msg).into());
             }
         }
+        OperationType::BatchTransaction { transfers } => {
+            let gas_usage =
+                op.get_gas_usage(api_cfg.base_operation_gas_cost, api_cfg.sp_compilation_cost);
+            if gas_usage > api_cfg.max_gas_per_block {
+                let err_msg = format!("BatchTransaction operation with {} transfers exceeds the block gas limit ({}). Your operation will never be included in a block.",
+                    transfers.len(), api_cfg.max_gas_per_block);
+                return Err(ApiError::InconsistencyError(err_msg).into());
+            }
+        }
         _ => {}
     };
     if let Some(slot) = last_slot {
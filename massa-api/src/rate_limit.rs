@@ -0,0 +1,263 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Per-client rate limiting middleware of the public JSON-RPC API
+
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::body::HttpBody;
+use hyper::{Body, Request, Response, StatusCode};
+use jsonrpsee::server::logger::{
+    HttpRequest, Logger, MethodKind, Params, SuccessOrError, TransportProtocol,
+};
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use massa_api_exports::error::ApiError;
+use massa_api_exports::rate_limit::{RateLimitExceeded, RateLimiter};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+/// label used in metrics
+const API_LABEL: &str = "jsonrpc";
+/// label of methods that are not served by the API
+const UNKNOWN_METHOD: &str = "unknown";
+
+thread_local! {
+    /// address of the client of the request being handed to the JSON-RPC server on this thread
+    static PEER_ADDR: Cell<Option<SocketAddr>> = const { Cell::new(None) };
+}
+
+/// Logger of the JSON-RPC server reporting the address of the clients to the rate limiting middleware.
+///
+/// The server does not expose the peer address to its middlewares, but gives it to its logger
+/// synchronously when a request is handed to it, before the request is processed.
+#[derive(Clone)]
+pub(crate) struct PeerAddrLogger;
+
+impl Logger for PeerAddrLogger {
+    type Instant = ();
+
+    fn on_connect(&self, remote_addr: SocketAddr, _: &HttpRequest, _: TransportProtocol) {
+        PEER_ADDR.with(|addr| addr.set(Some(remote_addr)));
+    }
+
+    fn on_request(&self, _: TransportProtocol) -> Self::Instant {}
+
+    fn on_call(&self, _: &str, _: Params, _: MethodKind, _: TransportProtocol) {}
+
+    fn on_result(&self, _: &str, _: SuccessOrError, _: Self::Instant, _: TransportProtocol) {}
+
+    fn on_response(&self, _: &str, _: Self::Instant, _: TransportProtocol) {}
+
+    fn on_disconnect(&self, _: SocketAddr, _: TransportProtocol) {}
+}
+
+/// Hand a request to the JSON-RPC server, returning the IP of its client reported to `PeerAddrLogger`.
+/// The request is only processed once the returned future is polled: dropping it cancels the request.
+fn call_with_peer_ip<S: Service<Request<Body>>>(
+    inner: &mut S,
+    req: Request<Body>,
+) -> (Option<IpAddr>, S::Future) {
+    PEER_ADDR.with(|addr| addr.set(None));
+    let response = inner.call(req);
+    let peer_ip = PEER_ADDR.with(|addr| addr.take()).map(|addr| addr.ip());
+    (peer_ip, response)
+}
+
+/// Layer charging every JSON-RPC call to the bucket of its client
+#[derive(Clone)]
+pub(crate) struct RateLimitLayer {
+    rate_limiter: Arc<RateLimiter>,
+    /// methods served by the API, used to bound metrics labels
+    method_names: Arc<HashSet<String>>,
+    max_request_body_size: u32,
+}
+
+impl RateLimitLayer {
+    pub(crate) fn new(
+        rate_limiter: Arc<RateLimiter>,
+        method_names: HashSet<String>,
+        max_request_body_size: u32,
+    ) -> Self {
+        RateLimitLayer {
+            rate_limiter,
+            method_names: Arc::new(method_names),
+            max_request_body_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service enforcing the rate limits before handing requests to the JSON-RPC server
+#[derive(Clone)]
+pub(crate) struct RateLimitService<S> {
+    inner: S,
+    layer: RateLimitLayer,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: From<hyper::Error> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        // the service that was polled ready must be the one called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            if is_upgrade_request(&req) {
+                // the calls sent over a websocket could not be charged: rate limited servers only serve HTTP
+                return Ok(Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::empty())
+                    .expect("failed to build response"));
+            }
+
+            let rate_limiter = &layer.rate_limiter;
+            let (api_key, forwarded_ip) = client_headers(rate_limiter, &req);
+
+            let (parts, mut body) = req.into_parts();
+            let mut bytes = Vec::new();
+            while let Some(chunk) = body.data().await {
+                bytes.extend_from_slice(&chunk?);
+                if bytes.len() > layer.max_request_body_size as usize {
+                    return Ok(Response::builder()
+                        .status(StatusCode::PAYLOAD_TOO_LARGE)
+                        .body(Body::empty())
+                        .expect("failed to build response"));
+                }
+            }
+
+            // invalid payloads are charged as unknown calls and rejected by the JSON-RPC server
+            let calls = match serde_json::from_slice::<Value>(&bytes) {
+                Ok(Value::Array(batch)) => batch,
+                Ok(call) => vec![call],
+                Err(_) => vec![Value::Null],
+            };
+            let charges: Vec<(&str, u64)> = calls
+                .iter()
+                .map(|call| {
+                    let method = call
+                        .get("method")
+                        .and_then(Value::as_str)
+                        .filter(|method| layer.method_names.contains(*method))
+                        .unwrap_or(UNKNOWN_METHOD);
+                    let max_gas = call.get("params").and_then(requested_gas);
+                    (method, rate_limiter.method_cost(method, max_gas))
+                })
+                .collect();
+
+            let (peer_ip, response) =
+                call_with_peer_ip(&mut inner, Request::from_parts(parts, Body::from(bytes)));
+            let client = rate_limiter.identify(api_key.as_deref(), forwarded_ip, peer_ip);
+            // a batch is charged at once, so that a refused batch costs nothing
+            if let Err(e) = rate_limiter.check_batch(API_LABEL, &charges, &client) {
+                let id = match calls.as_slice() {
+                    [call] => call.get("id").cloned().unwrap_or(Value::Null),
+                    _ => Value::Null,
+                };
+                return Ok(throttled_response(&id, e));
+            }
+            response.await
+        })
+    }
+}
+
+/// API key and IP forwarded by a reverse proxy of the client of a request
+fn client_headers(
+    rate_limiter: &RateLimiter,
+    req: &Request<Body>,
+) -> (Option<String>, Option<IpAddr>) {
+    let headers = req.headers();
+    let api_key = headers
+        .get(rate_limiter.config().api_key_header.as_str())
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let forwarded_ip = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok())
+        })
+        .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+    (api_key, forwarded_ip)
+}
+
+fn is_upgrade_request(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(hyper::header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
+/// Total `max_gas` requested by the read-only executions in the parameters of a call.
+/// Parameters are either positional (`[[{..}, {..}]]`) or named (`{"arg": [{..}, {..}]}`).
+fn requested_gas(params: &Value) -> Option<u64> {
+    let requests: Vec<&Value> = match params {
+        Value::Array(values) => values.iter().collect(),
+        Value::Object(values) => values.values().collect(),
+        _ => return None,
+    };
+    requests
+        .into_iter()
+        .flat_map(|value| match value {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        })
+        .filter_map(|item| item.get("max_gas").and_then(Value::as_u64))
+        .fold(None, |total: Option<u64>, gas| {
+            Some(total.unwrap_or_default().saturating_add(gas))
+        })
+}
+
+fn throttled_response(id: &Value, exceeded: RateLimitExceeded) -> Response<Body> {
+    let retry_after = exceeded.retry_after();
+    let error: ErrorObjectOwned = ApiError::RateLimited(exceeded.to_string()).into();
+    let error = ErrorObject::owned(error.code(), error.message(), Some(exceeded));
+    let payload = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": error,
+        "id": id,
+    });
+    // JSON-RPC clients only parse the error object of successful HTTP responses
+    let mut builder = Response::builder()
+        .status(StatusCode::OK)
+        .header(hyper::header::CONTENT_TYPE, "application/json");
+    if let Some(retry_after) = retry_after {
+        builder = builder.header(
+            hyper::header::RETRY_AFTER,
+            retry_after.as_secs_f64().ceil().to_string(),
+        );
+    }
+    builder
+        .body(Body::from(payload.to_string()))
+        .expect("failed to build response")
+}
@@ -2,12 +2,11 @@
 //!
 //!
 
-use std::{collections::HashMap, net::SocketAddr};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf};
 
 use massa_api_exports::config::APIConfig;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{GasCosts, MockExecutionController};
-use massa_models::amount::Amount;
 use massa_models::config::CHAINID;
 use massa_models::{
     config::{
@@ -69,7 +68,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         deferred_credits_delta: MassaTime::from_millis(24 * 3600 * 2),
-        minimal_fees: Amount::zero(),
+        minimal_fees: Default::default(),
     };
 
     // let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -93,6 +92,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         block_header_sender: broadcast::channel(100).0,
         block_sender: broadcast::channel(100).0,
         filled_block_sender: broadcast::channel(100).0,
+        block_status_sender: broadcast::channel(100).0,
     };
 
     let api = API::<ApiV2>::new(
@@ -145,7 +145,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         deferred_credits_delta: MassaTime::from_millis(24 * 3600 * 2),
-        minimal_fees: Amount::zero(),
+        minimal_fees: Default::default(),
     };
 
     let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -209,6 +209,8 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            peers_whitelist_path: PathBuf::from("peers_whitelist.json"),
+            peers_blacklist_path: PathBuf::from("peers_blacklist.json"),
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -263,6 +265,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         NodeId::new(keypair.get_public_key()),
         shared_storage,
         mip_store.clone(),
+        None,
     );
 
     (api_public, api_config)
@@ -5,6 +5,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     net::{IpAddr, SocketAddr},
     str::FromStr,
+    sync::Arc,
 };
 
 use jsonrpsee::{
@@ -19,6 +20,9 @@ use massa_api_exports::{
     endorsement::EndorsementInfo,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
     operation::{OperationInfo, OperationInput},
+    rate_limit::{RateLimitConfig, RateLimitQuota, RateLimiter},
+    rolls::DrawInputsInfo,
+    slot::FinalStateHashesInfo,
     TimeInterval,
 };
 use massa_consensus_exports::{
@@ -26,13 +30,16 @@ use massa_consensus_exports::{
     MockConsensusController,
 };
 use massa_pool_exports::MockPoolController;
-use massa_pos_exports::MockSelectorController;
+use massa_pos_exports::{DrawInputs, MockSelectorController};
 
 use crate::{tests::mock::start_public_api, RpcServer};
 use massa_execution_exports::{
     ExecutionAddressInfo, ExecutionQueryResponse, ExecutionQueryResponseItem,
     MockExecutionController, ReadOnlyExecutionOutput,
 };
+use massa_final_state::FinalStateHashes;
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
 use massa_models::{
     address::Address,
     amount::Amount,
@@ -86,6 +93,7 @@ async fn get_status() {
             clique_count: 30,
         })
     });
+    consensus_ctrl.expect_is_syncing().return_const(true);
 
     let mut protocol_ctrl = MockProtocolController::new();
     protocol_ctrl.expect_get_stats().returning(|| {
@@ -128,6 +136,7 @@ async fn get_status() {
     assert_eq!(response.network_stats.in_connection_count, 10);
     assert_eq!(response.network_stats.out_connection_count, 5);
     assert_eq!(response.config.thread_count, 32);
+    assert!(response.is_syncing);
     // Chain id == 77 for Node in sandbox mode otherwise it is always greater
     assert!(response.chain_id >= 77);
 
@@ -452,10 +461,10 @@ async fn get_graph_interval() {
 #[tokio::test]
 async fn send_operations_low_fee() {
     let addr: SocketAddr = "[::]:5049".parse().unwrap();
-    let (mut api_public, mut config) = start_public_api(addr);
+    let (mut api_public, config) = start_public_api(addr);
 
-    config.minimal_fees = Amount::from_str("0.01").unwrap();
-    api_public.0.api_settings.minimal_fees = Amount::from_str("0.01").unwrap();
+    // shared with the configuration the API was built with
+    *config.minimal_fees.write() = Amount::from_str("0.01").unwrap();
 
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl.expect_clone_box().returning(|| {
@@ -833,6 +842,100 @@ async fn execute_read_only_call() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn execute_read_only_call_rate_limited() {
+    let addr: SocketAddr = "[::]:5050".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .returning(|_req| {
+            Ok(ReadOnlyExecutionOutput {
+                out: massa_execution_exports::ExecutionOutput {
+                    slot: Slot {
+                        period: 1,
+                        thread: 5,
+                    },
+                    block_info: None,
+                    state_changes: massa_final_state::StateChanges::default(),
+                    events: massa_execution_exports::EventStore::default(),
+                    #[cfg(feature = "execution-trace")]
+                    slot_trace: None,
+                    #[cfg(feature = "dump-block")]
+                    storage: None,
+                    deferred_credits_execution: vec![],
+                    cancel_async_message_execution: vec![],
+                    auto_sell_execution: vec![],
+                },
+                gas_cost: 100,
+                call_result: "toto".as_bytes().to_vec(),
+            })
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    // a client can afford a single read-only call costing 20 + 1000000 / 100000 units
+    let rate_limit_config = RateLimitConfig {
+        enabled: true,
+        default_quota: RateLimitQuota {
+            burst_units: 40,
+            units_per_second: 1,
+        },
+        api_keys: HashMap::new(),
+        api_key_header: "x-api-key".to_string(),
+        trust_proxy_headers: false,
+        default_method_cost: 1,
+        method_costs: HashMap::from([("execute_read_only_call".to_string(), 20)]),
+        read_only_gas_per_unit: 100000,
+        max_tracked_clients: 100,
+    };
+    let (massa_metrics, _) = MassaMetrics::new(
+        false,
+        "0.0.0.0:9898".parse().unwrap(),
+        32,
+        std::time::Duration::from_secs(5),
+    );
+    api_public.0.rate_limiter = Some(Arc::new(RateLimiter::new(rate_limit_config, massa_metrics)));
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![vec![ReadOnlyCall {
+        max_gas: 1000000,
+        target_address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: None,
+        fee: None,
+        coins: None,
+    }]];
+    let response: Vec<ExecuteReadOnlyResponse> = client
+        .request("execute_read_only_call", params.clone())
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 1);
+
+    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+        .request("execute_read_only_call", params.clone())
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("rate limit exceeded"));
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_addresses() {
     let addr: SocketAddr = "[::]:5010".parse().unwrap();
@@ -1048,6 +1151,17 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client
+        .request(
+            "node_export_block_graph",
+            rpc_params![Option::<Slot>::None, Option::<Slot>::None],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request("node_ban_by_ip", rpc_params![Vec::<IpAddr>::new()])
         .await;
@@ -1108,6 +1222,12 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client.request("node_peers_blacklist", params.clone()).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request(
             "node_bootstrap_whitelist",
@@ -1345,3 +1465,118 @@ async fn get_stakers() {
 
     api_public_handle.stop().await;
 }
+
+#[tokio::test]
+async fn get_final_state_hashes() {
+    let addr: SocketAddr = "[::]:5051".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_final_state_hashes()
+        .returning(|slots| {
+            slots
+                .iter()
+                .map(|slot| {
+                    (slot.period < 10).then(|| FinalStateHashes {
+                        slot: *slot,
+                        execution_trail_hash: Hash::compute_from(&slot.period.to_be_bytes()),
+                        fingerprint: Hash::compute_from(&slot.thread.to_be_bytes()),
+                    })
+                })
+                .collect()
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let params = rpc_params![vec![Slot::new(3, 1), Slot::new(12, 0)]];
+
+    let response: Vec<Option<FinalStateHashesInfo>> = client
+        .request("get_final_state_hashes", params)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response,
+        vec![
+            Some(FinalStateHashesInfo {
+                slot: Slot::new(3, 1),
+                execution_trail_hash: Hash::compute_from(&3u64.to_be_bytes()),
+                fingerprint: Hash::compute_from(&1u8.to_be_bytes()),
+            }),
+            None
+        ]
+    );
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_draw_inputs() {
+    let addr: SocketAddr = "[::]:5052".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let staker = KeyPair::generate(0).unwrap();
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+    let lookback_rolls = BTreeMap::from([(staker_address, 42)]);
+    let lookback_seed = Hash::compute_from(b"seed");
+
+    let mut exec_ctrl = MockExecutionController::new();
+    let rolls = lookback_rolls.clone();
+    exec_ctrl.expect_get_draw_inputs().returning(move |cycles| {
+        cycles
+            .iter()
+            .map(|cycle| {
+                (*cycle > 2).then(|| DrawInputs {
+                    cycle: *cycle,
+                    lookback_rolls: rolls.clone(),
+                    lookback_seed,
+                })
+            })
+            .collect()
+    });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let params = rpc_params![vec![1u64, 5u64]];
+
+    let response: Vec<Option<DrawInputsInfo>> =
+        client.request("get_draw_inputs", params).await.unwrap();
+
+    assert_eq!(
+        response,
+        vec![
+            None,
+            Some(DrawInputsInfo {
+                cycle: 5,
+                roll_distribution_hash: massa_pos_exports::roll_distribution_hash(&lookback_rolls),
+                lookback_seed,
+                rolls: vec![(staker_address, 42)],
+            })
+        ]
+    );
+
+    api_public_handle.stop().await;
+}
@@ -1,10 +1,14 @@
-use std::{net::SocketAddr, str::FromStr};
+use std::{collections::HashMap, net::SocketAddr, str::FromStr, sync::Arc};
 
 use jsonrpsee::{
     client_transport::ws::Url, core::client::ClientT, http_client::HttpClientBuilder, rpc_params,
     ws_client::WsClientBuilder,
 };
-use massa_api_exports::operation::OperationInfo;
+use massa_api_exports::{
+    operation::OperationInfo,
+    rate_limit::{RateLimitConfig, RateLimitQuota, RateLimiter},
+};
+use massa_metrics::MassaMetrics;
 use massa_models::operation::OperationId;
 
 use crate::{tests::mock::get_apiv2_server, ApiServer, RpcServer};
@@ -122,6 +126,62 @@ async fn ws_disabled() {
     api_handle.stop().await;
 }
 
+#[tokio::test]
+async fn ws_refused_when_rate_limited() {
+    let addr: SocketAddr = "[::]:5053".parse().unwrap();
+    let (mut api_public, mut api_config) = crate::tests::mock::start_public_api(addr);
+    api_config.enable_ws = true;
+
+    let rate_limit_config = RateLimitConfig {
+        enabled: true,
+        default_quota: RateLimitQuota {
+            burst_units: 100,
+            units_per_second: 10,
+        },
+        api_keys: HashMap::new(),
+        api_key_header: "x-api-key".to_string(),
+        trust_proxy_headers: false,
+        default_method_cost: 1,
+        method_costs: HashMap::new(),
+        read_only_gas_per_unit: 0,
+        max_tracked_clients: 100,
+    };
+    let (massa_metrics, _) = MassaMetrics::new(
+        false,
+        "0.0.0.0:9899".parse().unwrap(),
+        32,
+        std::time::Duration::from_secs(5),
+    );
+    api_public.0.rate_limiter = Some(Arc::new(RateLimiter::new(rate_limit_config, massa_metrics)));
+
+    let api_handle = api_public
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    // the calls sent over a websocket could not be charged
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let err = WsClientBuilder::default().build(&uri).await.unwrap_err();
+    assert!(err.to_string().contains("status code: 403"));
+
+    // HTTP is still served
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: Result<Vec<OperationInfo>, jsonrpsee::core::Error> =
+        client.request("get_operations", rpc_params![]).await;
+    assert!(response.unwrap_err().to_string().contains("Invalid params"));
+
+    api_handle.stop().await;
+}
+
 #[tokio::test]
 async fn http_disabled() {
     let addr: SocketAddr = "[::]:5040".parse().unwrap();
@@ -7,6 +7,7 @@ use crate::messages::{
     BootstrapServerMessageDeserializer,
 };
 use crate::settings::BootstrapClientConfig;
+use crate::tools::identification_hash;
 use massa_hash::Hash;
 use massa_models::config::{
     MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE_BYTES, SIGNATURE_DESER_SIZE,
@@ -14,7 +15,8 @@ use massa_models::config::{
 use massa_models::serialization::{DeserializeMinBEInt, SerializeMinBEInt};
 use massa_models::version::{Version, VersionSerializer};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
-use massa_signature::{PublicKey, Signature};
+use massa_signature::{KeyPair, PublicKey, Signature};
+use massa_time::MassaTime;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use std::time::Instant;
 use std::{net::TcpStream, time::Duration};
@@ -83,6 +85,28 @@ impl BootstrapClientBinder {
         Ok(())
     }
 
+    /// Proves the identity of our node to the server. Should be called right after the server asked for it
+    /// NOT cancel-safe
+    pub fn identify(
+        &mut self,
+        keypair: &KeyPair,
+        duration: Option<Duration>,
+    ) -> Result<(), BootstrapError> {
+        let prev_message = self.prev_message.ok_or_else(|| {
+            BootstrapError::GeneralError("identification sent before the handshake".to_string())
+        })?;
+        let timestamp = MassaTime::now();
+        let signature = keypair.sign(&identification_hash(&prev_message, timestamp))?;
+        self.send_timeout(
+            &BootstrapClientMessage::Identify {
+                public_key: keypair.get_public_key(),
+                timestamp,
+                signature,
+            },
+            duration,
+        )
+    }
+
     /// Reads the next message.
     pub fn next_timeout(
         &mut self,
@@ -90,16 +90,17 @@ impl BootstrapServerBinder {
             write_error_timeout,
         }
     }
-    /// Performs a handshake. Should be called after connection
-    /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`
+    /// Performs a handshake and returns the version of the client. Should be called after connection
+    /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`,
+    /// possibly preceded by a `BootstrapMessage::AskIdentification`
     pub fn handshake_timeout(
         &mut self,
         version: Version,
         duration: Option<Duration>,
-    ) -> Result<(), BootstrapError> {
+    ) -> Result<Version, BootstrapError> {
         let deadline = duration.map(|d| Instant::now() + d);
         // read version and random bytes, send signature
-        let msg_hash = {
+        let (msg_hash, received_version) = {
             let mut version_bytes = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_bytes)?;
@@ -113,13 +114,18 @@ impl BootstrapServerBinder {
             if !received_version.is_compatible(&version) {
                 return Err(BootstrapError::IncompatibleVersionError(format!("Received a bad incompatible version in handshake. (excepted: {}, received: {})", version, received_version)));
             }
-            Hash::compute_from(&msg_bytes)
+            (Hash::compute_from(&msg_bytes), received_version)
         };
 
         // save prev sig
         self.prev_message = Some(msg_hash);
 
-        Ok(())
+        Ok(received_version)
+    }
+
+    /// Hash of the last message exchanged, the next client message is chained to it
+    pub fn prev_message_hash(&self) -> Option<Hash> {
+        self.prev_message
     }
 
     pub fn send_msg(
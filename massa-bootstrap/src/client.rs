@@ -26,7 +26,9 @@ use crate::{
     bindings::BootstrapClientBinder,
     error::BootstrapError,
     messages::{BootstrapClientMessage, BootstrapServerMessage},
+    parallel::parallel_bootstrap,
     settings::IpType,
+    tools::load_node_keypair,
     BootstrapConfig, GlobalBootstrapState,
 };
 
@@ -65,7 +67,7 @@ impl BSConnector for DefaultConnector {
 /// This function will send the starting point to receive a stream of the ledger and will receive and process each part until receive a `BootstrapServerMessage::FinalStateFinished` message from the server.
 /// `next_bootstrap_message` passed as parameter must be `BootstrapClientMessage::AskFinalStatePart` enum variant.
 /// `next_bootstrap_message` will be updated after receiving each part so that in case of connection lost we can restart from the last message we processed.
-fn stream_final_state_and_consensus(
+pub(crate) fn stream_final_state_and_consensus(
     cfg: &BootstrapConfig,
     client: &mut BootstrapClientBinder,
     next_bootstrap_message: &mut BootstrapClientMessage,
@@ -208,6 +210,65 @@ pub(crate) fn bootstrap_from_server(
 ) -> Result<(), BootstrapError> {
     massa_trace!("bootstrap.lib.bootstrap_from_server", {});
 
+    bootstrap_handshake(cfg, client, our_version)?;
+
+    let write_timeout: std::time::Duration = cfg.write_timeout.into();
+    // Loop to ask data to the server depending on the last message we sent
+    loop {
+        match next_bootstrap_message {
+            BootstrapClientMessage::AskBootstrapPart { .. } => {
+                stream_final_state_and_consensus(
+                    cfg,
+                    client,
+                    next_bootstrap_message,
+                    global_bootstrap_state,
+                )?;
+            }
+            BootstrapClientMessage::AskBootstrapPeers => {
+                let peers = match send_client_message(
+                    next_bootstrap_message,
+                    client,
+                    write_timeout,
+                    cfg.read_timeout.into(),
+                    "ask bootstrap peers timed out",
+                )? {
+                    BootstrapServerMessage::BootstrapPeers { peers } => peers,
+                    BootstrapServerMessage::BootstrapError { error } => {
+                        return Err(BootstrapError::ReceivedError(error))
+                    }
+                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+                };
+                global_bootstrap_state.peers = Some(peers);
+                *next_bootstrap_message = BootstrapClientMessage::BootstrapSuccess;
+            }
+            BootstrapClientMessage::BootstrapSuccess => {
+                client.send_timeout(next_bootstrap_message, Some(write_timeout))?;
+                break;
+            }
+            BootstrapClientMessage::BootstrapError { error: _ } => {
+                panic!("The next message to send shouldn't be BootstrapError");
+            }
+            BootstrapClientMessage::AskStateRange { .. }
+            | BootstrapClientMessage::AskStateFingerprint { .. } => {
+                panic!("The next message to send shouldn't be a parallel bootstrap request");
+            }
+            BootstrapClientMessage::Identify { .. } => {
+                panic!("The next message to send shouldn't be an identification");
+            }
+        };
+    }
+    info!("Successful bootstrap");
+    Ok(())
+}
+
+/// Reads the error the server may send at connection, then does the handshake,
+/// identifies our node if the server asks for it and its keypair is available,
+/// and checks the ping, the clock and the version of the server.
+pub(crate) fn bootstrap_handshake(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match client.next_timeout(Some(cfg.read_error_timeout.to_duration())) {
@@ -237,9 +298,20 @@ pub(crate) fn bootstrap_from_server(
         ));
     }
 
-    // First, clock and version.
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
-    let server_time = match client.next_timeout(Some(cfg.read_timeout.into())) {
+    let mut msg = client.next_timeout(Some(cfg.read_timeout.into()));
+
+    // servers supporting it ask us to identify before admitting us: identify our node
+    // so that servers whitelisting it serve us with priority
+    if let Ok(BootstrapServerMessage::AskIdentification) = msg {
+        if let Some(keypair) = load_node_keypair(&cfg.keypair_file) {
+            client.identify(&keypair, Some(cfg.write_timeout.into()))?;
+        }
+        msg = client.next_timeout(Some(cfg.read_timeout.into()));
+    }
+
+    // First, clock and version.
+    let server_time = match msg {
         Err(e) => return Err(e),
         Ok(BootstrapServerMessage::BootstrapTime {
             server_time,
@@ -253,6 +325,9 @@ pub(crate) fn bootstrap_from_server(
             }
             server_time
         }
+        Ok(BootstrapServerMessage::ServerBusy { retry_after }) => {
+            return Err(BootstrapError::ServerBusy(retry_after))
+        }
         Ok(BootstrapServerMessage::BootstrapError { error }) => {
             return Err(BootstrapError::ReceivedError(error))
         }
@@ -286,49 +361,10 @@ pub(crate) fn bootstrap_from_server(
         return Err(BootstrapError::ClockError(message));
     }
 
-    let write_timeout: std::time::Duration = cfg.write_timeout.into();
-    // Loop to ask data to the server depending on the last message we sent
-    loop {
-        match next_bootstrap_message {
-            BootstrapClientMessage::AskBootstrapPart { .. } => {
-                stream_final_state_and_consensus(
-                    cfg,
-                    client,
-                    next_bootstrap_message,
-                    global_bootstrap_state,
-                )?;
-            }
-            BootstrapClientMessage::AskBootstrapPeers => {
-                let peers = match send_client_message(
-                    next_bootstrap_message,
-                    client,
-                    write_timeout,
-                    cfg.read_timeout.into(),
-                    "ask bootstrap peers timed out",
-                )? {
-                    BootstrapServerMessage::BootstrapPeers { peers } => peers,
-                    BootstrapServerMessage::BootstrapError { error } => {
-                        return Err(BootstrapError::ReceivedError(error))
-                    }
-                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
-                };
-                global_bootstrap_state.peers = Some(peers);
-                *next_bootstrap_message = BootstrapClientMessage::BootstrapSuccess;
-            }
-            BootstrapClientMessage::BootstrapSuccess => {
-                client.send_timeout(next_bootstrap_message, Some(write_timeout))?;
-                break;
-            }
-            BootstrapClientMessage::BootstrapError { error: _ } => {
-                panic!("The next message to send shouldn't be BootstrapError");
-            }
-        };
-    }
-    info!("Successful bootstrap");
     Ok(())
 }
 
-fn send_client_message(
+pub(crate) fn send_client_message(
     message_to_send: &BootstrapClientMessage,
     client: &mut BootstrapClientBinder,
     write_timeout: Duration,
@@ -476,18 +512,127 @@ pub fn get_state(
 
     // If the two conditions above are not verified, we need to bootstrap
     // we filter the bootstrap list to keep only the ip addresses we are compatible with
+    let mut filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
+
+    // first try to download the state from several servers at once
+    if bootstrap_config.parallel_bootstrap_servers > 1 && filtered_bootstrap_list.len() > 1 {
+        let mut blacklist = HashSet::new();
+        match parallel_bootstrap(
+            bootstrap_config,
+            &filtered_bootstrap_list,
+            final_state.clone(),
+            &mut connector,
+            version,
+            &mut blacklist,
+            &interupted,
+            &massa_metrics,
+        ) {
+            Ok(global_bootstrap_state) => return Ok(global_bootstrap_state),
+            Err(BootstrapError::Interrupted(msg)) => return Err(BootstrapError::Interrupted(msg)),
+            Err(e) => {
+                warn!(
+                    "Parallel bootstrap failed: {}. Bootstrapping from one server at a time",
+                    e
+                );
+                final_state.write().reset();
+            }
+        }
+        filtered_bootstrap_list.retain(|(_, node_id)| !blacklist.contains(node_id));
+        if filtered_bootstrap_list.is_empty() {
+            return Err(BootstrapError::GeneralError(
+                "all the bootstrap servers served inconsistent data".into(),
+            ));
+        }
+    }
+
+    let next_bootstrap_message: BootstrapClientMessage = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_state_step: StreamingStep::Started,
+        last_versioning_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+    let global_bootstrap_state = GlobalBootstrapState::new(final_state);
+
+    bootstrap_from_list(
+        bootstrap_config,
+        &filtered_bootstrap_list,
+        connector,
+        version,
+        end_timestamp,
+        next_bootstrap_message,
+        global_bootstrap_state,
+        interupted,
+        massa_metrics,
+    )
+}
+
+/// Resynchronizes a desynchronized node without dropping its final state.
+/// Only the changes that happened since the slot of the local final state are asked to the bootstrap servers,
+/// along with the final blocks of their graph.
+/// If the local final state is not covered by the change history of a server,
+/// the final state is reset and fully bootstrapped again.
+///
+/// Uses the same cond-var pattern as `get_state` to handle sig-int cancellation.
+pub fn resync_state(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    connector: impl BSConnector,
+    version: Version,
+    end_timestamp: Option<MassaTime>,
+    interupted: Arc<(Mutex<bool>, Condvar)>,
+    massa_metrics: MassaMetrics,
+) -> Result<GlobalBootstrapState, BootstrapError> {
+    massa_trace!("bootstrap.lib.resync_state", {});
+
     let filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
 
-    let mut next_bootstrap_message: BootstrapClientMessage =
-        BootstrapClientMessage::AskBootstrapPart {
-            last_slot: None,
-            last_state_step: StreamingStep::Started,
-            last_versioning_step: StreamingStep::Started,
-            last_consensus_step: StreamingStep::Started,
-            send_last_start_period: true,
-        };
-    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
+    let last_slot = final_state
+        .read()
+        .get_database()
+        .read()
+        .get_change_id()
+        .map_err(|e| BootstrapError::GeneralError(e.to_string()))?;
+    info!("Resynchronizing final state from slot {}", last_slot);
+
+    // the whole state is already there: only ask for the changes since our last slot
+    let next_bootstrap_message: BootstrapClientMessage = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(last_slot),
+        last_state_step: StreamingStep::Finished(None),
+        last_versioning_step: StreamingStep::Finished(None),
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: false,
+    };
+    let global_bootstrap_state = GlobalBootstrapState::new(final_state);
+
+    bootstrap_from_list(
+        bootstrap_config,
+        &filtered_bootstrap_list,
+        connector,
+        version,
+        end_timestamp,
+        next_bootstrap_message,
+        global_bootstrap_state,
+        interupted,
+        massa_metrics,
+    )
+}
 
+/// Tries the servers of the bootstrap list in turn until one of them completes the bootstrap.
+/// `next_bootstrap_message` is the first message to send, it is updated as the bootstrap progresses
+/// so that the next server resumes where the previous one stopped.
+#[allow(clippy::too_many_arguments)]
+fn bootstrap_from_list(
+    bootstrap_config: &BootstrapConfig,
+    filtered_bootstrap_list: &[(SocketAddr, NodeId)],
+    mut connector: impl BSConnector,
+    version: Version,
+    end_timestamp: Option<MassaTime>,
+    mut next_bootstrap_message: BootstrapClientMessage,
+    mut global_bootstrap_state: GlobalBootstrapState,
+    interupted: Arc<(Mutex<bool>, Condvar)>,
+    massa_metrics: MassaMetrics,
+) -> Result<GlobalBootstrapState, BootstrapError> {
     let limit = bootstrap_config.rate_limit;
     loop {
         // check for interuption
@@ -503,6 +648,7 @@ pub fn get_state(
                 }
             }
             info!("Start bootstrapping from {}", addr);
+            let mut retry_delay = bootstrap_config.retry_delay;
             let conn = connect_to_server(
                 &mut connector,
                 bootstrap_config,
@@ -525,6 +671,14 @@ pub fn get_state(
                         Err(BootstrapError::ReceivedError(error)) => {
                             warn!("Error received from bootstrap server: {}", error)
                         }
+                        Err(BootstrapError::ServerBusy(retry_after)) => {
+                            info!(
+                                "Bootstrap server {} is busy, its queue should let us in within {}",
+                                addr, retry_after
+                            );
+                            // come back early enough to keep our place in its queue
+                            retry_delay = retry_delay.min(retry_after);
+                        }
                         Err(e) => {
                             warn!("Error while bootstrapping: {}", &e);
                             // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
@@ -543,7 +697,7 @@ pub fn get_state(
                 }
             };
 
-            info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
+            info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(retry_delay.to_duration()).to_string());
 
             // Before, we would use a simple sleep(...), and that was fine
             // in a cancellable async context: the runtime could
@@ -568,7 +722,7 @@ pub fn get_state(
                 .expect("double-lock() on interupted signal mutex");
             let wake = interupted
                 .1
-                .wait_timeout(int_sig, bootstrap_config.retry_delay.to_duration())
+                .wait_timeout(int_sig, retry_delay.to_duration())
                 .expect("interupt signal mutex poisoned");
             if *wake.0 {
                 return Err(BootstrapError::Interrupted(
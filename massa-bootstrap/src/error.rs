@@ -67,6 +67,10 @@ pub enum BootstrapError {
     WhiteListed(String),
     /// The bootstrap process ended prematurely - e.g. too much time elapsed
     Interrupted(String),
+    /// bootstrap server busy, retry in {0}
+    ServerBusy(massa_time::MassaTime),
+    /// client identification refused: {0}
+    IdentificationError(String),
 }
 
 /// # Platform-specific behavior
@@ -23,13 +23,15 @@ mod error;
 pub use error::BootstrapError;
 mod listener;
 mod messages;
+mod parallel;
+mod scheduler;
 mod server;
 mod settings;
 mod tools;
 /// white/black list
 pub mod white_black_list;
 
-pub use client::{get_state, DefaultConnector};
+pub use client::{get_state, resync_state, DefaultConnector};
 pub use listener::BootstrapTcpListener;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
@@ -7,6 +7,7 @@ use massa_consensus_exports::bootstrapable_graph::{
 
 use massa_db_exports::StreamBatch;
 
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
 use massa_models::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
 
 use massa_models::prehash::PreHashSet;
@@ -27,6 +28,7 @@ use massa_serialization::{
     U64VarIntSerializer,
 };
 
+use massa_signature::{PublicKey, PublicKeyDeserializer, Signature, SignatureDeserializer};
 use massa_time::{MassaTime, MassaTimeDeserializer, MassaTimeSerializer};
 use nom::error::context;
 use nom::multi::{length_data, length_value, many0};
@@ -40,6 +42,10 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::convert::TryInto;
 use std::ops::Bound::{Excluded, Included};
 
+/// First minor version of the clients understanding `BootstrapServerMessage::AskIdentification`,
+/// older clients would drop the connection when receiving it
+pub(crate) const IDENTIFICATION_MIN_MINOR_VERSION: u32 = 5;
+
 /// Messages used during bootstrap by server
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
@@ -82,6 +88,20 @@ pub enum BootstrapServerMessage {
         /// Error message
         error: String,
     },
+    /// Fingerprint of the final state at the end of the slot asked by the client
+    StateFingerprint {
+        /// Slot at the end of which the fingerprint was taken
+        slot: Slot,
+        /// Fingerprint of the final state, None if the slot is not in our change history anymore
+        fingerprint: Option<Hash>,
+    },
+    /// The server has no slot available for the client right now
+    ServerBusy {
+        /// Expected time to wait before trying again
+        retry_after: MassaTime,
+    },
+    /// The server accepts an identification of the client before admitting it
+    AskIdentification,
 }
 
 #[allow(clippy::to_string_trait_impl)]
@@ -96,6 +116,11 @@ impl ToString for BootstrapServerMessage {
             BootstrapServerMessage::BootstrapError { error } => {
                 format!("BootstrapError {{ error: {} }}", error)
             }
+            BootstrapServerMessage::StateFingerprint { .. } => "StateFingerprint".to_string(),
+            BootstrapServerMessage::ServerBusy { retry_after } => {
+                format!("ServerBusy {{ retry_after: {} }}", retry_after)
+            }
+            BootstrapServerMessage::AskIdentification => "AskIdentification".to_string(),
         }
     }
 }
@@ -109,6 +134,9 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    StateFingerprint = 6u32,
+    ServerBusy = 7u32,
+    AskIdentification = 8u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -126,6 +154,7 @@ pub struct BootstrapServerMessageSerializer {
     opt_last_start_period_serializer: OptionSerializer<u64, U64VarIntSerializer>,
     opt_last_slot_before_downtime_serializer:
         OptionSerializer<Option<Slot>, OptionSerializer<Slot, SlotSerializer>>,
+    opt_hash_serializer: OptionSerializer<Hash, HashSerializer>,
 }
 
 impl Default for BootstrapServerMessageSerializer {
@@ -152,6 +181,7 @@ impl BootstrapServerMessageSerializer {
             opt_last_slot_before_downtime_serializer: OptionSerializer::new(OptionSerializer::new(
                 SlotSerializer::new(),
             )),
+            opt_hash_serializer: OptionSerializer::new(HashSerializer::new()),
         }
     }
 }
@@ -307,6 +337,21 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 )?;
                 buffer.extend(error.as_bytes())
             }
+            BootstrapServerMessage::StateFingerprint { slot, fingerprint } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateFingerprint), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.opt_hash_serializer.serialize(fingerprint, buffer)?;
+            }
+            BootstrapServerMessage::ServerBusy { retry_after } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::ServerBusy), buffer)?;
+                self.time_serializer.serialize(retry_after, buffer)?;
+            }
+            BootstrapServerMessage::AskIdentification => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::AskIdentification), buffer)?;
+            }
         }
         Ok(())
     }
@@ -331,6 +376,7 @@ pub struct BootstrapServerMessageDeserializer {
     opt_last_start_period_deserializer: OptionDeserializer<u64, U64VarIntDeserializer>,
     opt_last_slot_before_downtime_deserializer:
         OptionDeserializer<Option<Slot>, OptionDeserializer<Slot, SlotDeserializer>>,
+    opt_hash_deserializer: OptionDeserializer<Hash, HashDeserializer>,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -397,6 +443,7 @@ impl BootstrapServerMessageDeserializer {
                     (Included(0), Excluded(args.thread_count)),
                 )),
             ),
+            opt_hash_deserializer: OptionDeserializer::new(HashDeserializer::new()),
         }
     }
 }
@@ -628,6 +675,31 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     error: String::from_utf8_lossy(error).into_owned(),
                 })
                 .parse(input),
+                MessageServerTypeId::StateFingerprint => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed fingerprint deserialization", |input| {
+                        self.opt_hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(slot, fingerprint)| BootstrapServerMessage::StateFingerprint {
+                        slot,
+                        fingerprint,
+                    },
+                )
+                .parse(input),
+                MessageServerTypeId::ServerBusy => {
+                    context("Failed retry_after deserialization", |input| {
+                        self.time_deserializer.deserialize(input)
+                    })
+                    .map(|retry_after| BootstrapServerMessage::ServerBusy { retry_after })
+                    .parse(input)
+                }
+                MessageServerTypeId::AskIdentification => {
+                    Ok((input, BootstrapServerMessage::AskIdentification))
+                }
             }
         })
         .parse(buffer)
@@ -660,6 +732,29 @@ pub enum BootstrapClientMessage {
     },
     /// Bootstrap succeed
     BootstrapSuccess,
+    /// Ask for a part of the final state restricted to a range of keys, without versioning nor consensus data
+    AskStateRange {
+        /// Slot we are attached to for changes, None if nothing was received for this range yet
+        last_slot: Option<Slot>,
+        /// Last received state key of the range, or the excluded start of the range
+        last_state_step: StreamingStep<Vec<u8>>,
+        /// Last key of the range (included), None if the range goes up to the end of the state
+        end_key: Option<Vec<u8>>,
+    },
+    /// Ask for the fingerprint of the final state at the end of a given slot
+    AskStateFingerprint {
+        /// Slot at the end of which the fingerprint is asked
+        slot: Slot,
+    },
+    /// Prove the identity of the client node to the server, to be sent right after the handshake
+    Identify {
+        /// Public key of the client node
+        public_key: PublicKey,
+        /// Time at which the identification was signed
+        timestamp: MassaTime,
+        /// Signature of the hash of the last handshake message concatenated with the timestamp
+        signature: Signature,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -669,6 +764,9 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskStateRange = 4u32,
+    AskStateFingerprint = 5u32,
+    Identify = 6u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -681,6 +779,9 @@ pub struct BootstrapClientMessageSerializer {
         PreHashSetSerializer<BlockId, BlockIdSerializer>,
     >,
     bool_serializer: BoolSerializer,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+    opt_key_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    time_serializer: MassaTimeSerializer,
 }
 
 impl BootstrapClientMessageSerializer {
@@ -694,6 +795,9 @@ impl BootstrapClientMessageSerializer {
                 BlockIdSerializer::new(),
             )),
             bool_serializer: BoolSerializer::new(),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+            opt_key_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            time_serializer: MassaTimeSerializer::new(),
         }
     }
 }
@@ -764,6 +868,34 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapSuccess), buffer)?;
             }
+            BootstrapClientMessage::AskStateRange {
+                last_slot,
+                last_state_step,
+                end_key,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateRange), buffer)?;
+                self.opt_slot_serializer.serialize(last_slot, buffer)?;
+                self.state_step_serializer
+                    .serialize(last_state_step, buffer)?;
+                self.opt_key_serializer.serialize(end_key, buffer)?;
+            }
+            BootstrapClientMessage::AskStateFingerprint { slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateFingerprint), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
+            BootstrapClientMessage::Identify {
+                public_key,
+                timestamp,
+                signature,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::Identify), buffer)?;
+                buffer.extend(public_key.to_bytes());
+                self.time_serializer.serialize(timestamp, buffer)?;
+                buffer.extend(signature.to_bytes());
+            }
         }
         Ok(())
     }
@@ -780,6 +912,11 @@ pub struct BootstrapClientMessageDeserializer {
         PreHashSetDeserializer<BlockId, BlockIdDeserializer>,
     >,
     bool_deserializer: BoolDeserializer,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+    opt_key_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    public_key_deserializer: PublicKeyDeserializer,
+    time_deserializer: MassaTimeDeserializer,
+    signature_deserializer: SignatureDeserializer,
 }
 
 impl BootstrapClientMessageDeserializer {
@@ -808,6 +945,20 @@ impl BootstrapClientMessageDeserializer {
                 ),
             ),
             bool_deserializer: BoolDeserializer::new(),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
+            opt_key_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_key_length.into()),
+            )),
+            public_key_deserializer: PublicKeyDeserializer::new(),
+            time_deserializer: MassaTimeDeserializer::new((
+                Included(MassaTime::from_millis(0)),
+                Included(MassaTime::from_millis(u64::MAX)),
+            )),
+            signature_deserializer: SignatureDeserializer::new(),
         }
     }
 }
@@ -917,6 +1068,51 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::BootstrapSuccess => {
                     Ok((input, BootstrapClientMessage::BootstrapSuccess))
                 }
+                MessageClientTypeId::AskStateRange => tuple((
+                    context("Failed last_slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_state_step deserialization", |input| {
+                        self.state_step_deserializer.deserialize(input)
+                    }),
+                    context("Failed end_key deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(last_slot, last_state_step, end_key)| {
+                    BootstrapClientMessage::AskStateRange {
+                        last_slot,
+                        last_state_step,
+                        end_key,
+                    }
+                })
+                .parse(input),
+                MessageClientTypeId::AskStateFingerprint => {
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|slot| BootstrapClientMessage::AskStateFingerprint { slot })
+                    .parse(input)
+                }
+                MessageClientTypeId::Identify => tuple((
+                    context("Failed public_key deserialization", |input| {
+                        self.public_key_deserializer.deserialize(input)
+                    }),
+                    context("Failed timestamp deserialization", |input| {
+                        self.time_deserializer.deserialize(input)
+                    }),
+                    context("Failed signature deserialization", |input| {
+                        self.signature_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(public_key, timestamp, signature)| BootstrapClientMessage::Identify {
+                        public_key,
+                        timestamp,
+                        signature,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
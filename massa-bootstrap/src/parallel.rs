@@ -0,0 +1,683 @@
+//! Parallel bootstrap of the final state from several servers.
+//!
+//! The keys of the final state are split in disjoint ranges, each one streamed from a different server
+//! with the usual `StreamingStep` cursor. A complete range is then kept up to date with the changes of its keys
+//! until all the ranges are complete, so that their slots stay in the change history of the servers.
+//!
+//! A server failing its session, for example by answering a fingerprint request unexpectedly, does not abort the
+//! bootstrap: its ranges are taken over by the other sessions, and a server breaking the protocol is blacklisted.
+//!
+//! One of the sessions then catches the whole state up with the changes that happened since the oldest range,
+//! and downloads the versioning data and the consensus graph.
+//! Finally, the fingerprint of the assembled state is compared with the one every server had at the same slot:
+//! the servers on the minority side are blacklisted, and a state most servers disagree with is dropped.
+
+use massa_db_exports::{StreamBatch, LEDGER_PREFIX};
+use massa_final_state::FinalStateController;
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, Condvar as StdCondvar, Mutex as StdMutex},
+    thread,
+    time::Duration,
+};
+use tracing::{info, warn};
+
+use crate::{
+    bindings::BootstrapClientBinder,
+    client::{
+        bootstrap_handshake, connect_to_server, send_client_message,
+        stream_final_state_and_consensus, BSConnector,
+    },
+    error::BootstrapError,
+    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    BootstrapConfig, GlobalBootstrapState,
+};
+
+/// Delay after which a complete range is refreshed with the changes of its keys.
+/// It must stay well below the duration of the change history of the servers, and below their read timeout.
+const RANGE_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A range of final state keys, streamed from a single server
+struct StateRange {
+    /// Excluded start of the range, None if the range starts at the beginning of the state
+    start_key: Option<Vec<u8>>,
+    /// Included end of the range, None if the range goes up to the end of the state
+    end_key: Option<Vec<u8>>,
+    /// Last key of the range received so far
+    step: StreamingStep<Vec<u8>>,
+    /// Slot at which the received keys of the range are up to date
+    slot: Option<Slot>,
+}
+
+impl StateRange {
+    fn contains(&self, key: &[u8]) -> bool {
+        self.start_key
+            .as_deref()
+            .map_or(true, |start_key| key > start_key)
+            && self
+                .end_key
+                .as_deref()
+                .map_or(true, |end_key| key <= end_key)
+    }
+
+    /// Streams the range again from its start
+    fn restart(&mut self) {
+        self.step = match &self.start_key {
+            Some(start_key) => StreamingStep::Ongoing(start_key.clone()),
+            None => StreamingStep::Started,
+        };
+        self.slot = None;
+    }
+}
+
+/// Splits the keys of the final state in `count` ranges.
+///
+/// Any boundaries give a correct state, they are only chosen to balance the ranges:
+/// the ledger holds most of the final state, and its keys are spread evenly by the hash of their address.
+/// Ledger keys are the ledger prefix, the key version (0), the address type (0 for users, 1 for smart contracts),
+/// the address version (0) and the address hash,
+/// so the boundaries split the user addresses then the smart contract addresses on the first byte of their hash.
+fn split_state_keys(count: usize) -> Vec<StateRange> {
+    let mut ranges = Vec::with_capacity(count);
+    let mut start_key: Option<Vec<u8>> = None;
+    for index in 1..=count {
+        let end_key = if index < count {
+            let position = index * 512 / count;
+            let mut boundary = LEDGER_PREFIX.as_bytes().to_vec();
+            boundary.extend([0, (position / 256) as u8, 0, (position % 256) as u8]);
+            Some(boundary)
+        } else {
+            None
+        };
+        ranges.push(StateRange {
+            step: match &start_key {
+                Some(start_key) => StreamingStep::Ongoing(start_key.clone()),
+                None => StreamingStep::Started,
+            },
+            slot: None,
+            start_key,
+            end_key: end_key.clone(),
+        });
+        start_key = end_key;
+    }
+    ranges
+}
+
+/// Progress of the parallel bootstrap, shared by its sessions
+struct Progress {
+    /// Slot at which each range was last complete
+    range_slots: Vec<Option<Slot>>,
+    /// Most recent slot of the data written for each range
+    data_slots: Vec<Option<Slot>>,
+    /// Ranges of the failed sessions, waiting to be taken over by another session
+    orphans: Vec<(usize, StateRange)>,
+    /// Number of sessions still running
+    live_sessions: usize,
+    /// Session catching the state up, once all the ranges are complete
+    catcher: Option<usize>,
+    /// Set once the state is catching up: the ranges are not written anymore
+    catching_up: bool,
+    /// Slot at which the fingerprint of the assembled state is checked
+    check_slot: Option<Slot>,
+    /// Set when the bootstrap cannot go on: the catching up session or all the sessions failed
+    failed: bool,
+}
+
+struct Coordination {
+    progress: Mutex<Progress>,
+    condvar: Condvar,
+}
+
+impl Coordination {
+    fn new(range_count: usize) -> Self {
+        Coordination {
+            progress: Mutex::new(Progress {
+                range_slots: vec![None; range_count],
+                data_slots: vec![None; range_count],
+                orphans: Vec::new(),
+                live_sessions: range_count,
+                catcher: None,
+                catching_up: false,
+                check_slot: None,
+                failed: false,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Progress)) {
+        f(&mut self.progress.lock());
+        self.condvar.notify_all();
+    }
+
+    /// Waits at most `timeout` for `ready` to return a value
+    fn wait<T>(
+        &self,
+        timeout: Duration,
+        ready: impl Fn(&Progress) -> Option<T>,
+    ) -> Result<Option<T>, BootstrapError> {
+        let mut progress = self.progress.lock();
+        if !progress.failed && ready(&progress).is_none() {
+            self.condvar.wait_for(&mut progress, timeout);
+        }
+        if progress.failed {
+            return Err(BootstrapError::GeneralError(
+                "another session of the parallel bootstrap failed".to_string(),
+            ));
+        }
+        Ok(ready(&progress))
+    }
+
+    /// Hands the ranges of the failed session `index` over to the other sessions, unless all the ranges are already complete.
+    /// The bootstrap fails if the session was catching the state up, or if it was the last one.
+    fn release(&self, index: usize, ranges: Vec<(usize, StateRange)>) {
+        self.update(|progress| {
+            progress.live_sessions = progress.live_sessions.saturating_sub(1);
+            if progress.catcher == Some(index) || progress.live_sessions == 0 {
+                progress.failed = true;
+                return;
+            }
+            if progress.range_slots.iter().all(Option::is_some) {
+                return;
+            }
+            // the keys already written for the ranges are overwritten, and the fingerprint check catches the leftovers
+            for (range_index, mut range) in ranges {
+                range.restart();
+                progress.range_slots[range_index] = None;
+                progress.data_slots[range_index] = None;
+                progress.orphans.push((range_index, range));
+            }
+        });
+    }
+
+    /// Takes over one of the ranges of the failed sessions, if any
+    fn take_orphan(&self) -> Option<(usize, StateRange)> {
+        self.progress.lock().orphans.pop()
+    }
+
+    /// Returns true if the session `index` is the one catching the state up
+    fn claim_catch_up(&self, index: usize) -> bool {
+        let mut progress = self.progress.lock();
+        if progress.catcher.is_some() {
+            return false;
+        }
+        progress.catcher = Some(index);
+        true
+    }
+
+    /// Stops the writes of the ranges.
+    /// Returns the oldest slot at which a range was complete and the most recent slot of the data written for a range.
+    fn start_catching_up(
+        &self,
+        final_state: &Arc<RwLock<dyn FinalStateController>>,
+    ) -> Result<(Slot, Slot), BootstrapError> {
+        // the ranges are written while holding the final state lock
+        let _final_state_write = final_state.write();
+        let mut progress = self.progress.lock();
+        progress.catching_up = true;
+        let oldest = progress.range_slots.iter().flatten().min().copied();
+        let most_recent = progress.data_slots.iter().flatten().max().copied();
+        oldest.zip(most_recent).ok_or_else(|| {
+            BootstrapError::GeneralError("catching up before all the ranges are complete".into())
+        })
+    }
+}
+
+/// Outcome of a successful session of the parallel bootstrap
+struct SessionOutcome {
+    /// Fingerprint of the state of the server at the check slot, None if it is not in its history anymore
+    fingerprint: Option<Hash>,
+    /// Consensus graph and peers, given by the session that caught the state up
+    global_bootstrap_state: Option<GlobalBootstrapState>,
+}
+
+/// Downloads the final state from several servers at once.
+/// The servers found to serve inconsistent data or to break the protocol are added to `blacklist`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn parallel_bootstrap(
+    cfg: &BootstrapConfig,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    connector: &mut impl BSConnector,
+    version: Version,
+    blacklist: &mut HashSet<NodeId>,
+    interupted: &Arc<(StdMutex<bool>, StdCondvar)>,
+    massa_metrics: &MassaMetrics,
+) -> Result<GlobalBootstrapState, BootstrapError> {
+    // the ranges are assembled on an empty state
+    final_state.write().reset();
+
+    let mut sessions = Vec::with_capacity(cfg.parallel_bootstrap_servers);
+    for (addr, node_id) in bootstrap_list {
+        if sessions.len() == cfg.parallel_bootstrap_servers {
+            break;
+        }
+        match connect_to_server(
+            connector,
+            cfg,
+            addr,
+            &node_id.get_public_key(),
+            Some(cfg.rate_limit),
+        ) {
+            Ok(client) => {
+                massa_metrics.inc_bootstrap_counter();
+                sessions.push((*node_id, client));
+            }
+            Err(e) => warn!("Error while connecting to bootstrap server {}: {}", addr, e),
+        }
+    }
+    if sessions.len() < 2 {
+        return Err(BootstrapError::GeneralError(
+            "not enough bootstrap servers reachable".to_string(),
+        ));
+    }
+    info!("Bootstrapping from {} servers in parallel", sessions.len());
+
+    let ranges = split_state_keys(sessions.len());
+    let coordination = Coordination::new(ranges.len());
+    let outcomes: Vec<(NodeId, Result<SessionOutcome, BootstrapError>)> = thread::scope(|scope| {
+        let handles: Vec<_> = sessions
+            .into_iter()
+            .zip(ranges)
+            .enumerate()
+            .map(|(index, ((node_id, mut client), range))| {
+                let final_state = final_state.clone();
+                let coordination = &coordination;
+                let handle = scope.spawn(move || {
+                    let mut ranges = vec![(index, range)];
+                    let res = run_session(
+                        cfg,
+                        &mut client,
+                        index,
+                        &mut ranges,
+                        final_state,
+                        version,
+                        coordination,
+                        interupted,
+                    );
+                    if let Err(e) = &res {
+                        coordination.release(index, ranges);
+                        // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
+                        let _ = client.send_timeout(
+                            &BootstrapClientMessage::BootstrapError {
+                                error: e.to_string(),
+                            },
+                            Some(cfg.write_error_timeout.into()),
+                        );
+                    }
+                    res
+                });
+                (node_id, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|(node_id, handle)| {
+                let res = handle.join().unwrap_or_else(|_| {
+                    Err(BootstrapError::GeneralError(
+                        "parallel bootstrap session panicked".to_string(),
+                    ))
+                });
+                (node_id, res)
+            })
+            .collect()
+    });
+
+    let failed = coordination.progress.lock().failed;
+    let mut global_bootstrap_state = None;
+    let mut fingerprints = Vec::with_capacity(outcomes.len());
+    let mut error = None;
+    for (node_id, outcome) in outcomes {
+        match outcome {
+            Ok(outcome) => {
+                if outcome.global_bootstrap_state.is_some() {
+                    global_bootstrap_state = outcome.global_bootstrap_state;
+                }
+                fingerprints.push((node_id, outcome.fingerprint));
+            }
+            Err(e) => {
+                warn!("Parallel bootstrap from server {} failed: {}", node_id, e);
+                if let BootstrapError::UnexpectedServerMessage(_) = e {
+                    warn!(
+                        "Bootstrap server {} broke the bootstrap protocol, it is blacklisted",
+                        node_id
+                    );
+                    blacklist.insert(node_id);
+                }
+                // the ranges of a failed session are taken over by the other ones
+                if failed && !matches!(error, Some(BootstrapError::Interrupted(_))) {
+                    error = Some(e);
+                }
+            }
+        }
+    }
+    if let Some(e) = error {
+        return Err(e);
+    }
+    let global_bootstrap_state = global_bootstrap_state.ok_or_else(|| {
+        BootstrapError::GeneralError("no session caught the state up".to_string())
+    })?;
+
+    check_fingerprints(&final_state, fingerprints, blacklist)?;
+    info!("Successful parallel bootstrap");
+    Ok(global_bootstrap_state)
+}
+
+/// Runs the bootstrap session with one server: streams its ranges, along with the ones taken over from failed sessions,
+/// and keeps them up to date until all the ranges are complete. The first session to see it catches the whole state up,
+/// and every session finally asks the fingerprint of the state at the check slot.
+/// `ranges` holds the ranges of the session, to be handed over if it fails.
+#[allow(clippy::too_many_arguments)]
+fn run_session(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    index: usize,
+    ranges: &mut Vec<(usize, StateRange)>,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    version: Version,
+    coordination: &Coordination,
+    interupted: &(StdMutex<bool>, StdCondvar),
+) -> Result<SessionOutcome, BootstrapError> {
+    bootstrap_handshake(cfg, client, version)?;
+
+    let mut probed = false;
+    loop {
+        if let Some(orphan) = coordination.take_orphan() {
+            info!(
+                "Taking over the state range {} of a failed session",
+                orphan.0
+            );
+            ranges.push(orphan);
+        }
+        for (range_index, range) in ranges.iter_mut() {
+            stream_state_range(cfg, client, *range_index, range, &final_state, coordination)?;
+            // a server unable to give the fingerprint of its state is replaced before its range is counted
+            if let (false, Some(slot)) = (probed, range.slot) {
+                ask_state_fingerprint(cfg, client, slot)?;
+                probed = true;
+            }
+            coordination.update(|progress| progress.range_slots[*range_index] = range.slot);
+        }
+        check_interrupted(interupted)?;
+        let ranges_complete = coordination.wait(RANGE_REFRESH_INTERVAL, |progress| {
+            (progress.orphans.is_empty() && progress.range_slots.iter().all(Option::is_some))
+                .then_some(())
+        })?;
+        if ranges_complete.is_some() {
+            break;
+        }
+    }
+
+    let mut global_bootstrap_state = None;
+    let check_slot = if coordination.claim_catch_up(index) {
+        let (oldest_range_slot, most_recent_data_slot) =
+            coordination.start_catching_up(&final_state)?;
+        let mut state = GlobalBootstrapState::new(final_state.clone());
+        // ask every change since the oldest range, the versioning data and the consensus graph
+        let mut next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+            last_slot: Some(oldest_range_slot),
+            last_state_step: StreamingStep::Finished(None),
+            last_versioning_step: StreamingStep::Started,
+            last_consensus_step: StreamingStep::Started,
+            send_last_start_period: true,
+        };
+        stream_final_state_and_consensus(cfg, client, &mut next_bootstrap_message, &mut state)?;
+        let slot = final_state.read().get_slot();
+        if slot < most_recent_data_slot {
+            return Err(BootstrapError::GeneralError(format!(
+                "the state caught up at slot {} but a range was received at slot {}",
+                slot, most_recent_data_slot
+            )));
+        }
+        coordination.update(|progress| progress.check_slot = Some(slot));
+
+        let peers = match send_client_message(
+            &BootstrapClientMessage::AskBootstrapPeers,
+            client,
+            cfg.write_timeout.into(),
+            cfg.read_timeout.into(),
+            "ask bootstrap peers timed out",
+        )? {
+            BootstrapServerMessage::BootstrapPeers { peers } => peers,
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        };
+        state.peers = Some(peers);
+        global_bootstrap_state = Some(state);
+        slot
+    } else {
+        // keep the session alive while the state is catching up
+        loop {
+            if let Some(slot) =
+                coordination.wait(RANGE_REFRESH_INTERVAL, |progress| progress.check_slot)?
+            {
+                break slot;
+            }
+            check_interrupted(interupted)?;
+            if let Some(slot) = ranges.first().and_then(|(_, range)| range.slot) {
+                ask_state_fingerprint(cfg, client, slot)?;
+            }
+        }
+    };
+
+    let fingerprint = ask_state_fingerprint(cfg, client, check_slot)?;
+    client.send_timeout(
+        &BootstrapClientMessage::BootstrapSuccess,
+        Some(cfg.write_timeout.into()),
+    )?;
+    Ok(SessionOutcome {
+        fingerprint,
+        global_bootstrap_state,
+    })
+}
+
+/// Streams the keys of the range `index` that were not received yet, along with the changes on the received ones,
+/// until the server tells that the whole range is up to date.
+/// Nothing is written anymore once the state is catching up.
+fn stream_state_range(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    index: usize,
+    range: &mut StateRange,
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    coordination: &Coordination,
+) -> Result<(), BootstrapError> {
+    client.send_timeout(
+        &BootstrapClientMessage::AskStateRange {
+            last_slot: range.slot,
+            last_state_step: range.step.clone(),
+            end_key: range.end_key.clone(),
+        },
+        Some(cfg.write_timeout.into()),
+    )?;
+
+    loop {
+        match client.next_timeout(Some(cfg.read_timeout.to_duration()))? {
+            BootstrapServerMessage::BootstrapPart {
+                slot,
+                mut state_part,
+                ..
+            } => {
+                // a server cannot touch the keys of the other ranges
+                state_part.new_elements.retain(|key, _| range.contains(key));
+                state_part
+                    .updates_on_previous_elements
+                    .retain(|key, _| range.contains(key));
+                let new_last_key = state_part
+                    .new_elements
+                    .last_key_value()
+                    .map(|(key, _)| key.clone());
+
+                let write_final_state = final_state.write();
+                let mut progress = coordination.progress.lock();
+                if progress.catching_up {
+                    continue;
+                }
+                let mut db = write_final_state.get_database().write();
+                // the ranges are not all at the same slot: keep the change id of the database until the state catches up
+                state_part.change_id = db
+                    .get_change_id()
+                    .map_err(|e| BootstrapError::GeneralError(e.to_string()))?;
+                let versioning_part = StreamBatch {
+                    new_elements: BTreeMap::new(),
+                    updates_on_previous_elements: BTreeMap::new(),
+                    change_id: state_part.change_id,
+                };
+                db.write_batch_bootstrap_client(state_part, versioning_part)
+                    .map_err(|e| {
+                        BootstrapError::GeneralError(format!(
+                            "Cannot write received stream batch to disk: {}",
+                            e
+                        ))
+                    })?;
+                progress.data_slots[index] = Some(slot);
+
+                if let Some(new_last_key) = new_last_key {
+                    range.step = StreamingStep::Ongoing(new_last_key);
+                }
+                range.slot = Some(slot);
+            }
+            BootstrapServerMessage::BootstrapFinished => {
+                // refresh the whole range from now on
+                range.step = match &range.end_key {
+                    Some(end_key) => StreamingStep::Ongoing(end_key.clone()),
+                    None => StreamingStep::Finished(None),
+                };
+                return Ok(());
+            }
+            BootstrapServerMessage::SlotTooOld => {
+                return Err(BootstrapError::GeneralError(String::from("Slot too old")))
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+}
+
+/// Asks the fingerprint of the final state of the server at the end of `slot`
+fn ask_state_fingerprint(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    slot: Slot,
+) -> Result<Option<Hash>, BootstrapError> {
+    match send_client_message(
+        &BootstrapClientMessage::AskStateFingerprint { slot },
+        client,
+        cfg.write_timeout.into(),
+        cfg.read_timeout.into(),
+        "ask state fingerprint timed out",
+    )? {
+        BootstrapServerMessage::StateFingerprint {
+            slot: fingerprint_slot,
+            fingerprint,
+        } if fingerprint_slot == slot => Ok(fingerprint),
+        BootstrapServerMessage::BootstrapError { error } => {
+            Err(BootstrapError::ReceivedError(error))
+        }
+        other => Err(BootstrapError::UnexpectedServerMessage(other)),
+    }
+}
+
+/// Compares the fingerprint of the assembled state with the ones the servers had at the same slot.
+/// The servers on the minority side are blacklisted, and the state is rejected if it is not on the majority side.
+fn check_fingerprints(
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    fingerprints: Vec<(NodeId, Option<Hash>)>,
+    blacklist: &mut HashSet<NodeId>,
+) -> Result<(), BootstrapError> {
+    let (slot, fingerprint) = {
+        let final_state_read = final_state.read();
+        (
+            final_state_read.get_slot(),
+            final_state_read.get_fingerprint(),
+        )
+    };
+
+    let mut agreeing = Vec::new();
+    let mut disagreeing = Vec::new();
+    for (node_id, server_fingerprint) in fingerprints {
+        match server_fingerprint {
+            Some(server_fingerprint) if server_fingerprint == fingerprint => agreeing.push(node_id),
+            Some(_) => disagreeing.push(node_id),
+            None => warn!(
+                "Bootstrap server {} could not give its final state fingerprint at slot {}",
+                node_id, slot
+            ),
+        }
+    }
+    if agreeing.is_empty() && disagreeing.is_empty() {
+        warn!(
+            "The final state fingerprint at slot {} could not be checked against any bootstrap server",
+            slot
+        );
+        return Ok(());
+    }
+
+    let (accepted, inconsistent) = match agreeing.len().cmp(&disagreeing.len()) {
+        std::cmp::Ordering::Greater => (true, disagreeing),
+        std::cmp::Ordering::Less => (false, agreeing),
+        std::cmp::Ordering::Equal => {
+            return Err(BootstrapError::GeneralError(format!(
+                "bootstrap servers disagree on the final state fingerprint at slot {}",
+                slot
+            )))
+        }
+    };
+    for node_id in inconsistent {
+        warn!(
+            "Bootstrap server {} served a final state inconsistent with the other servers, it is blacklisted",
+            node_id
+        );
+        blacklist.insert(node_id);
+    }
+    if accepted {
+        Ok(())
+    } else {
+        Err(BootstrapError::GeneralError(format!(
+            "the final state assembled at slot {} disagrees with most bootstrap servers",
+            slot
+        )))
+    }
+}
+
+fn check_interrupted(interupted: &(StdMutex<bool>, StdCondvar)) -> Result<(), BootstrapError> {
+    if *interupted.0.lock().expect("double-lock on interupt-mutex") {
+        return Err(BootstrapError::Interrupted(
+            "Sig INT received during parallel bootstrap".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_state_keys() {
+        let ranges = split_state_keys(3);
+        assert_eq!(ranges.len(), 3);
+        assert!(ranges[0].start_key.is_none());
+        assert_eq!(ranges[0].step, StreamingStep::Started);
+        assert!(ranges[2].end_key.is_none());
+        for window in ranges.windows(2) {
+            assert_eq!(window[0].end_key, window[1].start_key);
+            let boundary = window[0].end_key.clone().unwrap();
+            assert_eq!(window[1].step, StreamingStep::Ongoing(boundary.clone()));
+            // a key belongs to exactly one range
+            assert!(window[0].contains(&boundary));
+            assert!(!window[1].contains(&boundary));
+        }
+        assert!(ranges[0].contains(b"async_pool/"));
+        assert!(ranges[2].contains(b"versioning/"));
+    }
+}
@@ -0,0 +1,352 @@
+//! Admission of clients into the bootstrap sessions of the server
+//!
+//! Clients may identify themselves with their node keypair right after the handshake.
+//! Those whose node id is in the bootstrap whitelist are priority clients: they skip the queue,
+//! are not subject to the per-IP interval, and can use the `priority_reserved_slots` that are
+//! held for them. As every session is capped at `rate_limit`, reserving slots reserves them
+//! a share of the upload bandwidth.
+//!
+//! The other clients are anonymous. When no slot is left for them they get a place in a FIFO
+//! queue and are told how long to wait before coming back. A place is kept for some time after
+//! that wait so that the queue stays fair between clients that retry at different paces.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use humantime::format_duration;
+use massa_hash::Hash;
+use massa_models::node::NodeId;
+use massa_signature::{PublicKey, Signature};
+use massa_time::MassaTime;
+use parking_lot::Mutex;
+use tracing::warn;
+
+use crate::{
+    error::BootstrapError,
+    tools::{identification_hash, to_canonical},
+    white_black_list::SharedWhiteBlackList,
+    BootstrapConfig,
+};
+
+/// How long a queued client keeps its place after the wait it was given
+const QUEUE_TICKET_GRACE: Duration = Duration::from_secs(60);
+/// Shortest wait given to a refused client
+const MIN_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Service class of a bootstrap client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClientClass {
+    /// Identified with a whitelisted node id
+    Priority,
+    /// Not identified, or identified with a node id absent from the whitelist
+    Anonymous,
+}
+
+/// Why a client was not admitted
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Refusal {
+    /// No slot available, the client should come back after the given wait
+    Busy(Duration),
+    /// The client bootstrapped too recently: time elapsed since its last bootstrap and time left to wait
+    TooGreedy(Duration, Duration),
+}
+
+impl Refusal {
+    /// Time the client should wait before retrying
+    pub(crate) fn retry_after(&self) -> Duration {
+        match self {
+            Refusal::Busy(wait) | Refusal::TooGreedy(_, wait) => *wait,
+        }
+    }
+}
+
+/// A bootstrap session slot, released when dropped
+pub(crate) struct SessionSlot {
+    inner: Arc<Mutex<SchedulerInner>>,
+    id: u64,
+}
+
+impl Drop for SessionSlot {
+    fn drop(&mut self) {
+        self.inner.lock().release(self.id, Instant::now());
+    }
+}
+
+/// Shared admission state of the bootstrap server
+#[derive(Clone)]
+pub(crate) struct BootstrapScheduler {
+    inner: Arc<Mutex<SchedulerInner>>,
+    white_black_list: SharedWhiteBlackList<'static>,
+    max_clock_delta: MassaTime,
+}
+
+impl BootstrapScheduler {
+    pub(crate) fn new(
+        config: &BootstrapConfig,
+        white_black_list: SharedWhiteBlackList<'static>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SchedulerInner {
+                max_sessions: config.max_simultaneous_bootstraps as usize,
+                reserved_sessions: (config.priority_reserved_slots as usize)
+                    .min(config.max_simultaneous_bootstraps as usize),
+                max_queue_length: config.max_bootstrap_queue_length,
+                per_ip_min_interval: config.per_ip_min_interval.to_duration(),
+                ip_list_max_size: config.ip_list_max_size,
+                ip_hist_map: HashMap::with_capacity(config.ip_list_max_size),
+                sessions: HashMap::new(),
+                next_session_id: 0,
+                queue: VecDeque::new(),
+                avg_session_duration: config.bootstrap_timeout.to_duration(),
+                seen_identifications: HashMap::new(),
+            })),
+            white_black_list,
+            max_clock_delta: config.max_clock_delta,
+        }
+    }
+
+    /// Checks the identification sent by a client and returns its class.
+    /// `prev_message` is the hash of the handshake, which the identification is bound to.
+    pub(crate) fn identify(
+        &self,
+        prev_message: &Hash,
+        public_key: &PublicKey,
+        timestamp: MassaTime,
+        signature: &Signature,
+    ) -> Result<ClientClass, BootstrapError> {
+        let now = MassaTime::now();
+        if timestamp.abs_diff(now) > self.max_clock_delta {
+            return Err(BootstrapError::IdentificationError(format!(
+                "identification timestamp {} too far from server time {}",
+                timestamp, now
+            )));
+        }
+        let hash = identification_hash(prev_message, timestamp);
+        public_key.verify_signature(&hash, signature)?;
+        // an identification can only be used once while its timestamp is valid
+        if !self.inner.lock().record_identification(
+            hash,
+            Instant::now(),
+            self.max_clock_delta.to_duration().saturating_mul(2),
+        ) {
+            return Err(BootstrapError::IdentificationError(
+                "identification replayed".to_string(),
+            ));
+        }
+        let node_id = NodeId::new(*public_key);
+        self.white_black_list.is_node_allowed(&node_id)?;
+        if self.white_black_list.is_node_prioritized(&node_id) {
+            Ok(ClientClass::Priority)
+        } else {
+            Ok(ClientClass::Anonymous)
+        }
+    }
+
+    /// Gives a session slot to the client, or tells it how long to wait
+    pub(crate) fn admit(&self, ip: IpAddr, class: ClientClass) -> Result<SessionSlot, Refusal> {
+        // slots are only held for priority clients when there are whitelisted node ids to serve
+        let reserve = self.white_black_list.has_prioritized_nodes();
+        let id = self
+            .inner
+            .lock()
+            .admit(to_canonical(ip), class, reserve, Instant::now())?;
+        Ok(SessionSlot {
+            inner: self.inner.clone(),
+            id,
+        })
+    }
+}
+
+struct SessionInfo {
+    start: Instant,
+    class: ClientClass,
+}
+
+struct QueueTicket {
+    ip: IpAddr,
+    expires: Instant,
+}
+
+pub(crate) struct SchedulerInner {
+    max_sessions: usize,
+    reserved_sessions: usize,
+    max_queue_length: usize,
+    per_ip_min_interval: Duration,
+    ip_list_max_size: usize,
+    /// last admission of each anonymous client IP
+    ip_hist_map: HashMap<IpAddr, Instant>,
+    sessions: HashMap<u64, SessionInfo>,
+    next_session_id: u64,
+    queue: VecDeque<QueueTicket>,
+    /// moving average of the session durations, used to estimate the waits
+    avg_session_duration: Duration,
+    /// identification hashes recently received, to refuse replays
+    seen_identifications: HashMap<Hash, Instant>,
+}
+
+impl SchedulerInner {
+    #[cfg(test)]
+    pub(crate) fn new(
+        max_sessions: usize,
+        reserved_sessions: usize,
+        max_queue_length: usize,
+        per_ip_min_interval: Duration,
+        avg_session_duration: Duration,
+    ) -> Self {
+        Self {
+            max_sessions,
+            reserved_sessions: reserved_sessions.min(max_sessions),
+            max_queue_length,
+            per_ip_min_interval,
+            ip_list_max_size: 1000,
+            ip_hist_map: HashMap::new(),
+            sessions: HashMap::new(),
+            next_session_id: 0,
+            queue: VecDeque::new(),
+            avg_session_duration,
+            seen_identifications: HashMap::new(),
+        }
+    }
+
+    /// Number of slots an anonymous client can still take
+    fn free_anonymous_slots(&self, reserve: bool) -> usize {
+        let priority = self
+            .sessions
+            .values()
+            .filter(|s| s.class == ClientClass::Priority)
+            .count();
+        let anonymous = self.sessions.len() - priority;
+        let held = if reserve {
+            priority.max(self.reserved_sessions)
+        } else {
+            priority
+        };
+        self.max_sessions.saturating_sub(held + anonymous)
+    }
+
+    pub(crate) fn admit(
+        &mut self,
+        ip: IpAddr,
+        class: ClientClass,
+        reserve: bool,
+        now: Instant,
+    ) -> Result<u64, Refusal> {
+        if class == ClientClass::Priority {
+            if self.sessions.len() < self.max_sessions {
+                return Ok(self.open_session(class, now));
+            }
+            return Err(Refusal::Busy(self.estimate_wait(0, now)));
+        }
+
+        self.check_ip_history(ip, now)?;
+
+        self.queue.retain(|ticket| ticket.expires > now);
+        let position = match self.queue.iter().position(|ticket| ticket.ip == ip) {
+            Some(position) => position,
+            None if self.queue.len() < self.max_queue_length => {
+                self.queue.push_back(QueueTicket { ip, expires: now });
+                self.queue.len() - 1
+            }
+            // the queue is full: come back when it has moved forward
+            None => {
+                let free = self.free_anonymous_slots(reserve);
+                let wait = self.estimate_wait(self.queue.len().saturating_sub(free), now);
+                return Err(Refusal::Busy(wait));
+            }
+        };
+
+        let free = self.free_anonymous_slots(reserve);
+        if position < free {
+            self.queue.remove(position);
+            self.ip_hist_map.insert(ip, now);
+            return Ok(self.open_session(class, now));
+        }
+
+        let wait = self.estimate_wait(position - free, now);
+        self.queue[position].expires = now + wait + QUEUE_TICKET_GRACE;
+        Err(Refusal::Busy(wait))
+    }
+
+    /// Refuses anonymous clients that bootstrapped less than `per_ip_min_interval` ago
+    fn check_ip_history(&mut self, ip: IpAddr, now: Instant) -> Result<(), Refusal> {
+        // clear IP history if necessary
+        if self.ip_hist_map.len() > self.ip_list_max_size {
+            let per_ip_min_interval = self.per_ip_min_interval;
+            self.ip_hist_map
+                .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
+            if self.ip_hist_map.len() > self.ip_list_max_size {
+                // too many IPs are spamming us: clear cache
+                warn!(
+                    "high bootstrap load: at least {} different IPs attempted bootstrap in the last {}",
+                    self.ip_hist_map.len(),
+                    format_duration(self.per_ip_min_interval)
+                );
+                self.ip_hist_map.clear();
+            }
+        }
+        if let Some(last) = self.ip_hist_map.get(&ip) {
+            let elapsed = now.duration_since(*last);
+            if elapsed <= self.per_ip_min_interval {
+                return Err(Refusal::TooGreedy(
+                    elapsed,
+                    self.per_ip_min_interval
+                        .saturating_sub(elapsed)
+                        .max(MIN_RETRY_AFTER),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn open_session(&mut self, class: ClientClass, now: Instant) -> u64 {
+        let id = self.next_session_id;
+        self.next_session_id += 1;
+        self.sessions.insert(id, SessionInfo { start: now, class });
+        id
+    }
+
+    pub(crate) fn release(&mut self, id: u64, now: Instant) {
+        if let Some(session) = self.sessions.remove(&id) {
+            let duration = now.saturating_duration_since(session.start);
+            self.avg_session_duration = (self.avg_session_duration * 7 + duration) / 8;
+        }
+    }
+
+    /// Estimated time before `sessions_to_wait + 1` sessions have ended
+    fn estimate_wait(&self, sessions_to_wait: usize, now: Instant) -> Duration {
+        let mut ends: Vec<Duration> = self
+            .sessions
+            .values()
+            .map(|s| (s.start + self.avg_session_duration).saturating_duration_since(now))
+            .collect();
+        let wait = if ends.is_empty() {
+            self.avg_session_duration
+                .saturating_mul((sessions_to_wait + 1).try_into().unwrap_or(u32::MAX))
+        } else {
+            ends.sort();
+            // once every running session has ended, the next ones last the average duration
+            let rounds: u32 = (sessions_to_wait / ends.len())
+                .try_into()
+                .unwrap_or(u32::MAX);
+            ends[sessions_to_wait % ends.len()]
+                .saturating_add(self.avg_session_duration.saturating_mul(rounds))
+        };
+        wait.max(MIN_RETRY_AFTER)
+    }
+
+    /// Returns false if the identification was already received
+    fn record_identification(&mut self, hash: Hash, now: Instant, validity: Duration) -> bool {
+        self.seen_identifications
+            .retain(|_, seen| now.saturating_duration_since(*seen) <= validity);
+        self.seen_identifications.insert(hash, now).is_none()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn queue_length(&self) -> usize {
+        self.queue.len()
+    }
+}
@@ -19,16 +19,21 @@
 //! # Worker loop
 //!
 //! 1. Checks if the stopper has been invoked.
-//! 2. Checks if the client is permited under the white/black list rules
-//! 3. Checks if there are not too many active sessions already
-//! 4. Checks if the client has attempted too recently
-//! 5. All checks have passed: spawn a thread on which to run the bootstrap session
+//! 2. Checks if there are not too many pending connections already
+//! 3. Checks if the client is permited under the white/black list rules
+//! 4. All checks have passed: spawn a thread on which to run the bootstrap session
 //!    This thread creates a new tokio runtime, and runs it with `block_on`
+//!
+//! # Session admission
+//!
+//! After the handshake, the client may identify itself with its node keypair.
+//! The [`BootstrapScheduler`] then gives it a session slot according to its class,
+//! or answers `ServerBusy` with the time the client should wait before retrying.
 
 use crossbeam::channel::tick;
 use humantime::format_duration;
 use massa_consensus_exports::{bootstrapable_graph::BootstrapableGraph, ConsensusController};
-use massa_db_exports::CHANGE_ID_DESER_ERROR;
+use massa_db_exports::{MassaDBError, StreamBatch, CHANGE_ID_DESER_ERROR};
 use massa_final_state::FinalStateController;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
@@ -43,8 +48,7 @@ use massa_time::MassaTime;
 
 use parking_lot::RwLock;
 use std::{
-    collections::HashMap,
-    net::{IpAddr, SocketAddr},
+    net::SocketAddr,
     sync::Arc,
     thread,
     time::{Duration, Instant},
@@ -59,7 +63,8 @@ use crate::{
     bindings::BootstrapServerBinder,
     error::BootstrapError,
     listener::{BootstrapListenerStopHandle, PollEvent},
-    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    messages::{BootstrapClientMessage, BootstrapServerMessage, IDENTIFICATION_MIN_MINOR_VERSION},
+    scheduler::{BootstrapScheduler, ClientClass, Refusal},
     white_black_list::SharedWhiteBlackList,
     BootstrapConfig,
 };
@@ -172,6 +177,7 @@ pub fn start_bootstrap_server(
         .expect("in `start_bootstrap_server`, OS failed to spawn list-updater thread");
 
     let w_b_list = white_black_list.clone();
+    let scheduler = BootstrapScheduler::new(&config, white_black_list.clone());
     let main_handle = thread::Builder::new()
         .name("bs-main-loop".to_string())
         .spawn(move || {
@@ -183,7 +189,7 @@ pub fn start_bootstrap_server(
                 white_black_list: w_b_list,
                 keypair,
                 version,
-                ip_hist_map: HashMap::with_capacity(config.ip_list_max_size),
+                scheduler,
                 bootstrap_config: config,
                 massa_metrics,
             }
@@ -210,7 +216,7 @@ struct BootstrapServer<'a> {
     keypair: KeyPair,
     bootstrap_config: BootstrapConfig,
     version: Version,
-    ip_hist_map: HashMap<IpAddr, Instant>,
+    scheduler: BootstrapScheduler,
     massa_metrics: MassaMetrics,
 }
 
@@ -236,9 +242,11 @@ impl BootstrapServer<'_> {
     }
 
     fn event_loop(mut self, max_bootstraps: usize) -> Result<(), BootstrapError> {
-        // Use the strong-count of this variable to track the session count
+        // Use the strong-count of this variable to track the connection count
         let bootstrap_sessions_counter: Arc<()> = Arc::new(());
-        let per_ip_min_interval = self.bootstrap_config.per_ip_min_interval.to_duration();
+        // sessions are admitted by the scheduler once the client is identified, so leave room
+        // for the connections of priority clients to reach it while every slot is taken
+        let max_connections = max_bootstraps.saturating_mul(2);
         // TODO: Work out how to integration-test this
         let limit = self.bootstrap_config.rate_limit;
         loop {
@@ -256,7 +264,6 @@ impl BootstrapServer<'_> {
             };
 
             for (dplx, remote_addr) in connections {
-                // claim a slot in the max_bootstrap_sessions
                 let server_binding = BootstrapServerBinder::new(
                     dplx,
                     self.keypair.clone(),
@@ -270,7 +277,7 @@ impl BootstrapServer<'_> {
                 // TODO: If we don't find a way to handle the counting automagically, make
                 //       a dedicated wrapper-type with doc-comments, manual drop impl that
                 //       integrates logging, etc...
-                if Arc::strong_count(&bootstrap_sessions_counter) - 1 < max_bootstraps {
+                if Arc::strong_count(&bootstrap_sessions_counter) - 1 < max_connections {
                     let bootstrap_count_token = bootstrap_sessions_counter.clone();
                     // check whether incoming peer IP is allowed.
                     if let Err(error_msg) = self.white_black_list.is_ip_allowed(&remote_addr) {
@@ -282,47 +289,20 @@ impl BootstrapServer<'_> {
                         self.massa_metrics.inc_bootstrap_peers_failed();
                         continue;
                     };
-                    massa_trace!("bootstrap.lib.run.select.accept", {
-                        "remote_addr": remote_addr
-                    });
-                    let now = Instant::now();
-
-                    // clear IP history if necessary
-                    if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                        self.ip_hist_map
-                            .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
-                        if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                            // too many IPs are spamming us: clear cache
-                            warn!("high bootstrap load: at least {} different IPs attempted bootstrap in the last {}", self.ip_hist_map.len(),format_duration(self.bootstrap_config.per_ip_min_interval.to_duration()).to_string());
-                            self.ip_hist_map.clear();
-                        }
-                    }
-
-                    // check IP's bootstrap attempt history
-                    if let Err(msg) = BootstrapServer::greedy_client_check(
-                        &mut self.ip_hist_map,
-                        remote_addr,
-                        now,
-                        per_ip_min_interval,
-                    ) {
-                        // Client has been too greedy: send out the bad-news :(
-                        let msg = format!(
-                            "Your last bootstrap on this server was {} ago and you have to wait {} before retrying.",
-                            format_duration(msg),
-                            format_duration(per_ip_min_interval.saturating_sub(msg))
+                    // do not serve a state that is being resynchronized
+                    if self.consensus_controller.is_syncing() {
+                        server_binding.close_and_send_error(
+                            "Bootstrap failed because the bootstrap server is resynchronizing."
+                                .to_string(),
+                            remote_addr,
+                            move || debug!("did not bootstrap {}: resynchronizing", remote_addr),
                         );
-                        let tracer = move || {
-                            massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
-                                "remote_addr": remote_addr
-                            })
-                        };
-                        server_binding.close_and_send_error(msg, remote_addr, tracer);
                         self.massa_metrics.inc_bootstrap_peers_failed();
                         continue;
-                    };
-
-                    // Clients Option<last-attempt> is good, and has been updated
-                    massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
+                    }
+                    massa_trace!("bootstrap.lib.run.select.accept", {
+                        "remote_addr": remote_addr
+                    });
 
                     // launch bootstrap
                     let version = self.version;
@@ -330,6 +310,7 @@ impl BootstrapServer<'_> {
                     let consensus_command_sender = self.consensus_controller.clone();
                     let protocol_controller = self.protocol_controller.clone();
                     let config = self.bootstrap_config.clone();
+                    let scheduler = self.scheduler.clone();
 
                     let massa_metrics = self.massa_metrics.clone();
 
@@ -340,6 +321,7 @@ impl BootstrapServer<'_> {
                                 server_binding,
                                 bootstrap_count_token,
                                 config,
+                                scheduler,
                                 remote_addr,
                                 data_execution,
                                 version,
@@ -363,33 +345,6 @@ impl BootstrapServer<'_> {
             }
         }
     }
-
-    /// Checks latest attempt. If too recent, provides the bad news (as an error).
-    /// Updates the latest attempt to "now" if it's all good.
-    ///
-    /// # Error
-    /// The elapsed time which is insufficient
-    fn greedy_client_check(
-        ip_hist_map: &mut HashMap<IpAddr, Instant>,
-        remote_addr: SocketAddr,
-        now: Instant,
-        per_ip_min_interval: Duration,
-    ) -> Result<(), Duration> {
-        let mut res = Ok(());
-        ip_hist_map
-            .entry(remote_addr.ip())
-            .and_modify(|occ| {
-                // Well, let's only update the latest
-                if now.duration_since(*occ) <= per_ip_min_interval {
-                    res = Err(occ.elapsed());
-                } else {
-                    // in list, expired
-                    *occ = now;
-                }
-            })
-            .or_insert(now);
-        res
-    }
 }
 
 /// To be called from a `thread::spawn` invocation
@@ -398,13 +353,14 @@ impl BootstrapServer<'_> {
 /// a multi-thread-aware tokio runtime (the bs-main-loop runtime, to be exact). When this
 /// function blocks in the `block_on`, it should thread-block, and switch to another session
 ///
-/// The arc_counter variable is used as a proxy to keep track the number of active bootstrap
-/// sessions.
+/// The arc_counter variable is used as a proxy to keep track the number of open bootstrap
+/// connections.
 #[allow(clippy::too_many_arguments)]
 fn run_bootstrap_session(
     mut server: BootstrapServerBinder,
     arc_counter: Arc<()>,
     config: BootstrapConfig,
+    scheduler: BootstrapScheduler,
     remote_addr: SocketAddr,
     data_execution: Arc<RwLock<dyn FinalStateController>>,
     version: Version,
@@ -418,6 +374,8 @@ fn run_bootstrap_session(
     let res = manage_bootstrap(
         &config,
         &mut server,
+        &scheduler,
+        remote_addr,
         data_execution,
         version,
         consensus_command_sender,
@@ -449,6 +407,14 @@ fn run_bootstrap_session(
             );
             massa_metrics.inc_bootstrap_peers_failed();
         }
+        Err(BootstrapError::ServerBusy(retry_after)) => {
+            // the client has already been told when to come back
+            debug!(
+                "did not bootstrap {}: no slot available, retry in {}",
+                remote_addr, retry_after
+            );
+            massa_metrics.inc_bootstrap_peers_failed();
+        }
         Err(err) => {
             debug!("bootstrap serving error for peer {}: {}", remote_addr, err);
             // We allow unused result because we don't care if an error is thrown when
@@ -483,8 +449,6 @@ pub fn stream_bootstrap_information(
         let last_start_period;
         let last_slot_before_downtime;
 
-        let slot_too_old = false;
-
         // Scope of the final state read
         {
             let final_state_read = final_state.read();
@@ -500,13 +464,24 @@ pub fn stream_bootstrap_information(
                 None
             };
 
-            state_part = final_state_read
+            let state_part_result = final_state_read
                 .get_database()
                 .read()
-                .get_batch_to_stream(&last_state_step, last_slot)
-                .map_err(|e| {
-                    BootstrapError::GeneralError(format!("Error get_batch_to_stream: {}", e))
-                })?;
+                .get_batch_to_stream(&last_state_step, last_slot);
+            state_part = match state_part_result {
+                Ok(state_part) => state_part,
+                // the state of the client is not covered by our change history: it needs to restart from scratch
+                Err(MassaDBError::CacheMissError(_)) if last_state_step.finished() => {
+                    drop(final_state_read);
+                    return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+                }
+                Err(e) => {
+                    return Err(BootstrapError::GeneralError(format!(
+                        "Error get_batch_to_stream: {}",
+                        e
+                    )))
+                }
+            };
 
             let new_state_step = match (&last_state_step, state_part.is_empty()) {
                 // We already finished streaming the state
@@ -609,10 +584,6 @@ pub fn stream_bootstrap_information(
             send_last_start_period = false;
         }
 
-        if slot_too_old {
-            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
-        }
-
         // Setup final state global cursor
         let final_state_global_step =
             if last_state_step.finished() && last_versioning_step.finished() {
@@ -677,6 +648,99 @@ pub fn stream_bootstrap_information(
     Ok(())
 }
 
+/// Streams the final state keys that come after `last_state_step` up to `end_key` (included),
+/// along with the changes that happened on the keys of the range that were already streamed.
+/// Neither versioning nor consensus data are sent.
+///
+/// The last part sent before `BootstrapFinished` has no new elements:
+/// its slot is the one at which the whole range is up to date on the client side.
+pub fn stream_state_range(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    mut last_slot: Option<Slot>,
+    mut last_state_step: StreamingStep<Vec<u8>>,
+    end_key: Option<Vec<u8>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+) -> Result<(), BootstrapError> {
+    loop {
+        let current_slot;
+        let mut state_part;
+
+        // Scope of the final state read
+        {
+            let final_state_read = final_state.read();
+            let db = final_state_read.get_database().read();
+
+            current_slot = db.get_change_id().expect(CHANGE_ID_DESER_ERROR);
+            if let Some(slot) = last_slot {
+                if slot > current_slot {
+                    return Err(BootstrapError::GeneralError(
+                        "Bootstrap cursor set to future slot".to_string(),
+                    ));
+                }
+            }
+
+            // if nothing was streamed yet for this range, there is no change to send
+            state_part = match db
+                .get_batch_to_stream(&last_state_step, Some(last_slot.unwrap_or(current_slot)))
+            {
+                Ok(state_part) => state_part,
+                Err(MassaDBError::CacheMissError(_)) => {
+                    drop(db);
+                    drop(final_state_read);
+                    return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+                }
+                Err(e) => {
+                    return Err(BootstrapError::GeneralError(format!(
+                        "Error get_batch_to_stream: {}",
+                        e
+                    )))
+                }
+            };
+        }
+
+        // Only keep the new elements of the range
+        if let Some(end_key) = &end_key {
+            state_part.new_elements.retain(|key, _| key <= end_key);
+        }
+        let range_finished = state_part.new_elements.is_empty();
+        if let Some((new_last_key, _)) = state_part.new_elements.last_key_value() {
+            last_state_step = StreamingStep::Ongoing(new_last_key.clone());
+        }
+        last_slot = Some(current_slot);
+
+        let Some(write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interrupted(
+                "insufficient time left to provide next state range part".to_string(),
+            ));
+        };
+        server.send_msg(
+            write_timeout,
+            BootstrapServerMessage::BootstrapPart {
+                slot: current_slot,
+                state_part,
+                versioning_part: StreamBatch {
+                    new_elements: Default::default(),
+                    updates_on_previous_elements: Default::default(),
+                    change_id: current_slot,
+                },
+                consensus_part: BootstrapableGraph {
+                    final_blocks: Default::default(),
+                },
+                consensus_outdated_ids: Default::default(),
+                last_start_period: None,
+                last_slot_before_downtime: None,
+            },
+        )?;
+
+        if range_finished {
+            server.send_msg(write_timeout, BootstrapServerMessage::BootstrapFinished)?;
+            return Ok(());
+        }
+    }
+}
+
 // derives the duration allowed for a step in the bootstrap process.
 // Returns None if the deadline for the entire bs-process has been reached
 fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Option<Duration> {
@@ -692,6 +756,8 @@ fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Opti
 pub(crate) fn manage_bootstrap(
     bootstrap_config: &BootstrapConfig,
     server: &mut BootstrapServerBinder,
+    scheduler: &BootstrapScheduler,
+    remote_addr: SocketAddr,
     final_state: Arc<RwLock<dyn FinalStateController>>,
     version: Version,
     consensus_controller: Box<dyn ConsensusController>,
@@ -709,23 +775,74 @@ pub(crate) fn manage_bootstrap(
         ));
     };
 
-    server.handshake_timeout(version, Some(hs_timeout))?;
+    let client_version = server.handshake_timeout(version, Some(hs_timeout))?;
 
-    // Check for error from client
+    // Only clients understanding it are asked to identify, the others are anonymous
+    if client_version.minor() >= IDENTIFICATION_MIN_MINOR_VERSION {
+        let Some(write_timeout) =
+            step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration())
+        else {
+            return Err(BootstrapError::Interrupted(
+                "insufficient time left to ask for identification".to_string(),
+            ));
+        };
+        server.send_msg(write_timeout, BootstrapServerMessage::AskIdentification)?;
+    }
+    let prev_message_hash = server.prev_message_hash().ok_or_else(|| {
+        BootstrapError::GeneralError("no handshake hash to identify the client".to_string())
+    })?;
+
+    // Check for error or identification from client
     if Instant::now() + read_error_timeout >= deadline {
         return Err(BootstrapError::Interrupted(
             "insufficient time to check for error from client".to_string(),
         ));
     };
-    match server.next_timeout(Some(read_error_timeout)) {
-        Err(BootstrapError::TimedOut(_)) => {}
+    let class = match server.next_timeout(Some(read_error_timeout)) {
+        Err(BootstrapError::TimedOut(_)) => ClientClass::Anonymous,
         Err(e) => return Err(e),
+        Ok(BootstrapClientMessage::Identify {
+            public_key,
+            timestamp,
+            signature,
+        }) => scheduler.identify(&prev_message_hash, &public_key, timestamp, &signature)?,
         Ok(BootstrapClientMessage::BootstrapError { error }) => {
             return Err(BootstrapError::GeneralError(error));
         }
         Ok(msg) => return Err(BootstrapError::UnexpectedClientMessage(Box::new(msg))),
     };
 
+    // Get a session slot, held until the end of the bootstrap
+    let _slot = match scheduler.admit(remote_addr.ip(), class) {
+        Ok(slot) => slot,
+        Err(refusal) => {
+            let retry_after = MassaTime::from_millis(
+                refusal
+                    .retry_after()
+                    .as_millis()
+                    .try_into()
+                    .unwrap_or(u64::MAX),
+            );
+            if let Refusal::TooGreedy(elapsed, wait) = refusal {
+                massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
+                    "remote_addr": remote_addr
+                });
+                debug!(
+                    "{} last bootstrapped {} ago and has to wait {} before retrying",
+                    remote_addr,
+                    format_duration(elapsed),
+                    format_duration(wait)
+                );
+            }
+            server.send_msg(
+                bootstrap_config.write_error_timeout.to_duration(),
+                BootstrapServerMessage::ServerBusy { retry_after },
+            )?;
+            return Err(BootstrapError::ServerBusy(retry_after));
+        }
+    };
+    debug!("admitted {} as a {:?} client", remote_addr, class);
+
     // Sync clocks
     let send_time_timeout =
         step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration());
@@ -791,10 +908,44 @@ pub(crate) fn manage_bootstrap(
                         bootstrap_config.write_timeout.to_duration(),
                     )?;
                 }
+                BootstrapClientMessage::AskStateRange {
+                    last_slot,
+                    last_state_step,
+                    end_key,
+                } => {
+                    stream_state_range(
+                        server,
+                        final_state.clone(),
+                        last_slot,
+                        last_state_step,
+                        end_key,
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                    )?;
+                }
+                BootstrapClientMessage::AskStateFingerprint { slot } => {
+                    let Some(write_timeout) = step_timeout_duration(
+                        &deadline,
+                        &bootstrap_config.write_timeout.to_duration(),
+                    ) else {
+                        return Err(BootstrapError::Interrupted(
+                            "insufficient time left to respond to the request for a state fingerprint"
+                                .to_string(),
+                        ));
+                    };
+                    let fingerprint = final_state.read().get_fingerprint_at(slot);
+                    server.send_msg(
+                        write_timeout,
+                        BootstrapServerMessage::StateFingerprint { slot, fingerprint },
+                    )?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
                 }
+                BootstrapClientMessage::Identify { .. } => {
+                    break Err(BootstrapError::UnexpectedClientMessage(Box::new(msg)));
+                }
             },
         };
     }
@@ -63,6 +63,16 @@ pub struct BootstrapConfig {
     pub ip_list_max_size: usize,
     /// Read-Write limitation for a connection in bytes per seconds
     pub rate_limit: u64,
+    /// Number of the `max_simultaneous_bootstraps` slots reserved to clients whose node id is in the bootstrap whitelist.
+    /// As every session is capped at `rate_limit`, this also reserves them a share of the upload bandwidth.
+    pub priority_reserved_slots: u32,
+    /// Max number of anonymous clients waiting in the bootstrap queue for a free slot
+    pub max_bootstrap_queue_length: usize,
+    /// Path to the node keypair, used to identify ourselves to the bootstrap servers
+    pub keypair_file: PathBuf,
+    /// Number of servers the final state is downloaded from at once, in disjoint key ranges.
+    /// 1 downloads everything from a single server at a time.
+    pub parallel_bootstrap_servers: usize,
     /// thread count
     pub thread_count: u8,
     /// period per cycle
@@ -1,7 +1,9 @@
 use crate::messages::{BootstrapClientMessage, BootstrapServerMessage};
+use crate::scheduler::BootstrapScheduler;
 use crate::server::manage_bootstrap;
 use crate::settings::{BootstrapClientConfig, BootstrapSrvBindCfg};
 
+use crate::white_black_list::SharedWhiteBlackList;
 use crate::{
     bindings::{BootstrapClientBinder, BootstrapServerBinder},
     tests::tools::get_bootstrap_config,
@@ -118,6 +120,18 @@ fn assert_client_got_msg(
     assert!(eq, "Received BootstrapServerMessage isn't the same");
 }
 
+// Scheduler with the white/black lists of the given configuration
+fn test_scheduler(bootstrap_config: &BootstrapConfig) -> BootstrapScheduler {
+    BootstrapScheduler::new(
+        bootstrap_config,
+        SharedWhiteBlackList::new(
+            bootstrap_config.bootstrap_whitelist_path.clone(),
+            bootstrap_config.bootstrap_blacklist_path.clone(),
+        )
+        .unwrap(),
+    )
+}
+
 // Initialize a pair of bootstrap server and client with the given configuration, and handshake done
 fn init_server_client_pair() -> (BootstrapServerBinder, BootstrapClientBinder) {
     let (bootstrap_config, server_keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
@@ -462,6 +476,8 @@ fn test_staying_connected_without_message_trigger_read_timeout() {
                 manage_bootstrap(
                     &bootstrap_config,
                     &mut server,
+                    &test_scheduler(&bootstrap_config),
+                    addr,
                     Arc::new(RwLock::new(get_random_final_state_bootstrap(
                         PoSFinalState::new(
                             final_state_config.pos_config.clone(),
@@ -560,6 +576,8 @@ fn test_staying_connected_pass_handshake_but_deadline_after() {
                 manage_bootstrap(
                     &bootstrap_config,
                     &mut server,
+                    &test_scheduler(&bootstrap_config),
+                    addr,
                     Arc::new(RwLock::new(get_random_final_state_bootstrap(
                         PoSFinalState::new(
                             final_state_config.pos_config.clone(),
@@ -658,6 +676,8 @@ fn test_staying_connected_pass_handshake_but_deadline_during_data_exchange() {
                 manage_bootstrap(
                     &bootstrap_config,
                     &mut server,
+                    &test_scheduler(&bootstrap_config),
+                    addr,
                     Arc::new(RwLock::new(get_random_final_state_bootstrap(
                         PoSFinalState::new(
                             final_state_config.pos_config.clone(),
@@ -684,7 +704,8 @@ fn test_staying_connected_pass_handshake_but_deadline_during_data_exchange() {
         .spawn({
             move || {
                 client.handshake(version()).unwrap();
-                // Pass the bootstrap time sent by server
+                // Pass the identification request and the bootstrap time sent by server
+                client.next_timeout(Some(read_timeout)).unwrap();
                 client.next_timeout(Some(read_timeout)).unwrap();
                 for _ in 0..10 {
                     let _ = client.send_timeout(
@@ -707,6 +728,140 @@ fn test_staying_connected_pass_handshake_but_deadline_during_data_exchange() {
     client_thread.join().unwrap();
 }
 
+#[test]
+fn test_identification_asked_to_recent_clients_only() {
+    let server_version = || Version::from_str("TEST.1.10").unwrap();
+    for (client_version, asked) in [("TEST.1.4", false), ("TEST.1.5", true)] {
+        let (bootstrap_config, server_keypair): (BootstrapConfig, KeyPair) =
+            BOOTSTRAP_CONFIG_KEYPAIR.clone();
+        let read_timeout: Duration = bootstrap_config.read_timeout.into();
+        let server = std::net::TcpListener::bind("localhost:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let client = std::net::TcpStream::connect(addr).unwrap();
+        let server = server.accept().unwrap();
+        let selector_controller = MockSelectorControllerWrapper::new();
+
+        let mut server = BootstrapServerBinder::new(
+            server.0,
+            server_keypair,
+            BootstrapSrvBindCfg {
+                rate_limit: u64::MAX,
+                thread_count: THREAD_COUNT,
+                max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+                randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+                consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+                write_error_timeout: MassaTime::from_millis(1000),
+            },
+            None,
+        );
+        let mut client = BootstrapClientBinder::test_default(
+            client,
+            bootstrap_config.bootstrap_list[0].1.get_public_key(),
+        );
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100_000_000,
+            max_versioning_elements_size: 100_000_000,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        }))
+            as Box<(dyn MassaDBController + 'static)>));
+        let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+        let final_state_config = FinalStateConfig::default();
+        let server_thread = std::thread::Builder::new()
+            .name("test_binders::server_thread".to_string())
+            .spawn({
+                move || {
+                    manage_bootstrap(
+                        &bootstrap_config,
+                        &mut server,
+                        &test_scheduler(&bootstrap_config),
+                        addr,
+                        Arc::new(RwLock::new(get_random_final_state_bootstrap(
+                            PoSFinalState::new(
+                                final_state_config.pos_config.clone(),
+                                "",
+                                &rolls_path,
+                                Box::new(selector_controller.clone()),
+                                db.clone(),
+                            )
+                            .unwrap(),
+                            final_state_config,
+                            db,
+                        ))),
+                        server_version(),
+                        Box::new(MockConsensusController::new()),
+                        Box::new(MockProtocolController::new()),
+                        Instant::now().checked_add(read_timeout * 3).unwrap(),
+                    )
+                }
+            })
+            .unwrap();
+
+        client
+            .handshake(Version::from_str(client_version).unwrap())
+            .unwrap();
+        let mut msg = client.next_timeout(Some(read_timeout)).unwrap();
+        if asked {
+            assert!(
+                matches!(msg, BootstrapServerMessage::AskIdentification),
+                "a {} client should be asked to identify",
+                client_version
+            );
+            msg = client.next_timeout(Some(read_timeout)).unwrap();
+        }
+        assert!(
+            matches!(msg, BootstrapServerMessage::BootstrapTime { .. }),
+            "a {} client should receive the bootstrap time, got {}",
+            client_version,
+            msg.to_string()
+        );
+        client
+            .send_timeout(
+                &BootstrapClientMessage::BootstrapSuccess,
+                Some(read_timeout),
+            )
+            .unwrap();
+
+        server_thread.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn test_identification_after_ask() {
+    let (bootstrap_config, _) = &*BOOTSTRAP_CONFIG_KEYPAIR;
+    let timeout = Duration::from_secs(5);
+    let (mut server, mut client) = init_server_client_pair();
+
+    server
+        .send_timeout(BootstrapServerMessage::AskIdentification, Some(timeout))
+        .unwrap();
+    assert_client_got_msg(
+        timeout,
+        &mut client,
+        BootstrapServerMessage::AskIdentification,
+    );
+
+    // the identification is chained to the request of the server
+    let keypair = KeyPair::generate(0).unwrap();
+    client.identify(&keypair, Some(timeout)).unwrap();
+    let prev_message_hash = server.prev_message_hash().unwrap();
+    let BootstrapClientMessage::Identify {
+        public_key,
+        timestamp,
+        signature,
+    } = server.next_timeout(Some(timeout)).unwrap()
+    else {
+        panic!("expected an identification");
+    };
+    assert_eq!(public_key, keypair.get_public_key());
+    test_scheduler(bootstrap_config)
+        .identify(&prev_message_hash, &public_key, timestamp, &signature)
+        .unwrap();
+}
+
 // serial test for time-taken sensitive tests: reduces parallelism noise
 #[test]
 #[serial]
@@ -49,6 +49,10 @@ impl Default for BootstrapConfig {
             ip_list_max_size: 10,
             per_ip_min_interval: MassaTime::from_millis(10000),
             rate_limit: u64::MAX,
+            priority_reserved_slots: 1,
+            max_bootstrap_queue_length: 10,
+            keypair_file: PathBuf::from("node_privkey.key"),
+            parallel_bootstrap_servers: 1,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
             thread_count: THREAD_COUNT,
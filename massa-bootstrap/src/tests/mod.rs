@@ -3,7 +3,9 @@
 mod binders;
 mod config;
 mod messages;
+mod parallel;
 mod scenarios;
+mod scheduler;
 pub(crate) mod tools;
 mod universe_client;
 mod universe_server;
@@ -0,0 +1,316 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Condvar as StdCondvar, Mutex as StdMutex},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use massa_consensus_exports::bootstrapable_graph::BootstrapableGraph;
+use massa_db_exports::{
+    MassaDBConfig, MassaDBController, ShareableMassaDBController, StreamBatch, LEDGER_PREFIX,
+    STATE_CF,
+};
+use massa_db_worker::MassaDB;
+use massa_final_state::{FinalStateController, MockFinalStateController};
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::{
+    config::{
+        BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CONSENSUS_BOOTSTRAP_PART_SIZE,
+        MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE,
+        MAX_DATASTORE_KEY_LENGTH, THREAD_COUNT,
+    },
+    node::NodeId,
+    prehash::PreHashSet,
+    slot::Slot,
+    streaming_step::StreamingStep,
+    version::Version,
+};
+use massa_protocol_exports::BootstrapPeers;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use num::rational::Ratio;
+use parking_lot::{Mutex, RwLock};
+use serial_test::serial;
+use tempfile::TempDir;
+
+use crate::{
+    bindings::BootstrapServerBinder,
+    client::MockBSConnector,
+    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    parallel::parallel_bootstrap,
+    settings::BootstrapSrvBindCfg,
+    BootstrapConfig,
+};
+
+/// Slot at which the state of the test servers is
+const STATE_SLOT: Slot = Slot {
+    period: 10,
+    thread: 0,
+};
+
+/// Ledger keys spread over the three ranges of a parallel bootstrap
+fn state_keys() -> BTreeMap<Vec<u8>, Vec<u8>> {
+    [[0, 0, 0, 10], [0, 0, 0, 200], [0, 1, 0, 200]]
+        .into_iter()
+        .map(|suffix| {
+            let mut key = LEDGER_PREFIX.as_bytes().to_vec();
+            key.extend(suffix);
+            (key, suffix.to_vec())
+        })
+        .collect()
+}
+
+fn state_part(
+    new_elements: BTreeMap<Vec<u8>, Vec<u8>>,
+    last_start_period: bool,
+) -> BootstrapServerMessage {
+    BootstrapServerMessage::BootstrapPart {
+        slot: STATE_SLOT,
+        state_part: StreamBatch {
+            new_elements,
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: STATE_SLOT,
+        },
+        versioning_part: StreamBatch {
+            new_elements: BTreeMap::new(),
+            updates_on_previous_elements: BTreeMap::new(),
+            change_id: STATE_SLOT,
+        },
+        consensus_part: BootstrapableGraph {
+            final_blocks: vec![],
+        },
+        consensus_outdated_ids: PreHashSet::default(),
+        last_start_period: last_start_period.then_some(0),
+        last_slot_before_downtime: last_start_period.then_some(None),
+    }
+}
+
+/// Starts a bootstrap server serving the state keys for a single client session.
+/// A faulty server answers the fingerprint requests with the fingerprint of another slot.
+/// Returns the address of the server and the end keys of the ranges it was asked for.
+#[allow(clippy::type_complexity)]
+fn start_fake_server(
+    keypair: KeyPair,
+    version: Version,
+    fingerprint: Hash,
+    faulty: bool,
+) -> (SocketAddr, Arc<Mutex<Vec<Option<Vec<u8>>>>>, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let asked_ranges = Arc::new(Mutex::new(Vec::new()));
+    let asked_ranges_clone = asked_ranges.clone();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut server = BootstrapServerBinder::new(
+            stream,
+            keypair,
+            BootstrapSrvBindCfg {
+                rate_limit: u64::MAX,
+                thread_count: THREAD_COUNT,
+                max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+                randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+                consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+                write_error_timeout: MassaTime::from_millis(1000),
+            },
+            Some(u64::MAX),
+        );
+        server.handshake_timeout(version, None).unwrap();
+        server
+            .send_timeout(
+                BootstrapServerMessage::BootstrapTime {
+                    server_time: MassaTime::now(),
+                    version,
+                },
+                None,
+            )
+            .unwrap();
+        loop {
+            // the client closes the connection once it is done with a failed session
+            let Ok(message) = server.next_timeout(None) else {
+                return;
+            };
+            match message {
+                BootstrapClientMessage::Identify { .. } => {}
+                BootstrapClientMessage::AskStateRange {
+                    last_state_step,
+                    end_key,
+                    ..
+                } => {
+                    asked_ranges_clone.lock().push(end_key.clone());
+                    let new_elements = state_keys()
+                        .into_iter()
+                        .filter(|(key, _)| match &last_state_step {
+                            StreamingStep::Started => true,
+                            StreamingStep::Ongoing(last_key) => key > last_key,
+                            StreamingStep::Finished(_) => false,
+                        })
+                        .filter(|(key, _)| end_key.as_ref().map_or(true, |end_key| key <= end_key))
+                        .collect();
+                    server
+                        .send_timeout(state_part(new_elements, false), None)
+                        .unwrap();
+                    server
+                        .send_timeout(BootstrapServerMessage::BootstrapFinished, None)
+                        .unwrap();
+                }
+                BootstrapClientMessage::AskStateFingerprint { slot } => {
+                    let slot = if faulty {
+                        slot.get_next_slot(THREAD_COUNT).unwrap()
+                    } else {
+                        slot
+                    };
+                    server
+                        .send_timeout(
+                            BootstrapServerMessage::StateFingerprint {
+                                slot,
+                                fingerprint: Some(fingerprint),
+                            },
+                            None,
+                        )
+                        .unwrap();
+                }
+                BootstrapClientMessage::AskBootstrapPart { .. } => {
+                    server
+                        .send_timeout(state_part(BTreeMap::new(), true), None)
+                        .unwrap();
+                    server
+                        .send_timeout(BootstrapServerMessage::BootstrapFinished, None)
+                        .unwrap();
+                }
+                BootstrapClientMessage::AskBootstrapPeers => {
+                    server
+                        .send_timeout(
+                            BootstrapServerMessage::BootstrapPeers {
+                                peers: BootstrapPeers(vec![]),
+                            },
+                            None,
+                        )
+                        .unwrap();
+                }
+                BootstrapClientMessage::BootstrapSuccess
+                | BootstrapClientMessage::BootstrapError { .. } => return,
+            }
+        }
+    });
+    (addr, asked_ranges, handle)
+}
+
+fn client_final_state(
+    database: ShareableMassaDBController,
+    fingerprint: Hash,
+) -> Arc<RwLock<MockFinalStateController>> {
+    let mut final_state = MockFinalStateController::new();
+    final_state.expect_reset().return_const(());
+    final_state
+        .expect_get_database()
+        .return_const(database.clone());
+    final_state.expect_get_slot().return_const(STATE_SLOT);
+    final_state
+        .expect_get_fingerprint()
+        .return_const(fingerprint);
+    final_state.expect_set_last_start_period().return_const(());
+    final_state
+        .expect_set_last_slot_before_downtime()
+        .return_const(());
+    let mip_store = MipStore::try_from_db(
+        database,
+        MipStatsConfig {
+            block_count_considered: 100,
+            warn_announced_version_ratio: Ratio::new(1, 2),
+        },
+    )
+    .unwrap();
+    final_state
+        .expect_get_mip_store_mut()
+        .returning(move || mip_store.clone());
+    Arc::new(RwLock::new(final_state))
+}
+
+#[test]
+#[serial]
+fn test_parallel_bootstrap_replaces_faulty_server() {
+    let version: Version = "BOOT.1.0".parse().unwrap();
+    let fingerprint = Hash::compute_from(b"state");
+
+    let mut bootstrap_list = Vec::new();
+    let mut asked_ranges = Vec::new();
+    let mut server_handles = Vec::new();
+    for faulty in [false, true, false] {
+        let keypair = KeyPair::generate(0).unwrap();
+        let node_id = NodeId::new(keypair.get_public_key());
+        let (addr, ranges, handle) = start_fake_server(keypair, version, fingerprint, faulty);
+        bootstrap_list.push((addr, node_id));
+        asked_ranges.push(ranges);
+        server_handles.push(handle);
+    }
+    let faulty_node_id = bootstrap_list[1].1;
+
+    let temp_dir = TempDir::new().expect("cannot create temp directory");
+    let database: ShareableMassaDBController =
+        Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE as usize,
+            max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE as usize,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        }))
+            as Box<(dyn MassaDBController + 'static)>));
+    database.read().set_initial_change_id(Slot::new(0, 0));
+    let final_state: Arc<RwLock<dyn FinalStateController>> =
+        client_final_state(database.clone(), fingerprint);
+
+    let mut connector = MockBSConnector::new();
+    connector
+        .expect_connect_timeout()
+        .times(3)
+        .returning(|addr, _| Ok(TcpStream::connect(addr).unwrap()));
+    let config = BootstrapConfig {
+        parallel_bootstrap_servers: 3,
+        ..Default::default()
+    };
+    let interupted = Arc::new((StdMutex::new(false), StdCondvar::new()));
+    let massa_metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:31248".parse().unwrap(),
+        THREAD_COUNT,
+        Duration::from_secs(5),
+    )
+    .0;
+
+    let mut blacklist = HashSet::new();
+    let res = parallel_bootstrap(
+        &config,
+        &bootstrap_list,
+        final_state,
+        &mut connector,
+        version,
+        &mut blacklist,
+        &interupted,
+        &massa_metrics,
+    );
+    for handle in server_handles {
+        handle.join().unwrap();
+    }
+
+    assert!(res.is_ok(), "parallel bootstrap failed: {:?}", res.err());
+    // the faulty server is blacklisted, the honest ones are not
+    assert_eq!(blacklist, HashSet::from([faulty_node_id]));
+    // the range of the faulty server was taken over by an honest server
+    let faulty_range = asked_ranges[1].lock()[0].clone();
+    assert!(
+        [&asked_ranges[0], &asked_ranges[2]]
+            .iter()
+            .any(|ranges| ranges.lock().contains(&faulty_range)),
+        "the range of the faulty server was not reassigned"
+    );
+    // the whole state was received
+    for (key, value) in state_keys() {
+        assert_eq!(database.read().get_cf(STATE_CF, key).unwrap(), Some(value));
+    }
+}
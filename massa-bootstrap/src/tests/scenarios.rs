@@ -7,9 +7,10 @@ use crate::BootstrapError;
 use massa_models::amount::Amount;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
-use massa_models::{address::Address, node::NodeId};
+use massa_models::{address::Address, node::NodeId, slot::Slot};
 use massa_signature::KeyPair;
 use massa_test_framework::TestUniverse;
+use massa_time::MassaTime;
 use serial_test::serial;
 use std::path::PathBuf;
 
@@ -78,3 +79,65 @@ fn test_bootstrap_accept_err() {
         .build();
     drop(server_universe);
 }
+
+#[test]
+#[serial]
+fn test_resync_from_change_history() {
+    let port = 8072;
+    let server_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&server_keypair.get_public_key());
+    let server_universe = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port)
+        .set_keypair(&server_keypair)
+        .set_address_balance(&address, Amount::from_mantissa_scale(100, 0).unwrap())
+        // changes that happened after the slot of the client
+        .set_slot(Slot::new(1, 0))
+        .set_bytecode(&address, Bytecode(vec![0x00, 0x01, 0x02, 0x03]))
+        .build();
+    let mut client_universe = BootstrapClientTestUniverse::new(
+        BootstrapClientForeignControllers::new_with_mocks(),
+        BootstrapConfig::default(),
+    );
+    let bootstrap_state = client_universe
+        .launch_resync(port, NodeId::new(server_keypair.get_public_key()), 1)
+        .unwrap();
+    assert!(bootstrap_state.graph.is_some());
+    client_universe.compare_database(server_universe.database.clone());
+}
+
+#[test]
+#[serial]
+fn test_resync_slot_too_old() {
+    let port = 8073;
+    let server_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&server_keypair.get_public_key());
+    // the client connects again right after being told that its slot is too old
+    let bootstrap_server_config = BootstrapConfig {
+        per_ip_min_interval: MassaTime::from_millis(0),
+        ..Default::default()
+    };
+    // the change history of the server starts after the slot of the client
+    let server_universe = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port)
+        .set_config(bootstrap_server_config)
+        .set_keypair(&server_keypair)
+        .set_accept_count(2)
+        .set_slot(Slot::new(1, 0))
+        .set_address_balance(&address, Amount::from_mantissa_scale(100, 0).unwrap())
+        .build();
+    let controllers = BootstrapClientForeignControllers::new_with_mocks();
+    // the local final state is reset before falling back to a full bootstrap
+    controllers
+        .final_state_controller
+        .write()
+        .expect_reset()
+        .times(1)
+        .return_const(());
+    let mut client_universe =
+        BootstrapClientTestUniverse::new(controllers, BootstrapConfig::default());
+    let bootstrap_state = client_universe
+        .launch_resync(port, NodeId::new(server_keypair.get_public_key()), 2)
+        .unwrap();
+    assert!(bootstrap_state.graph.is_some());
+    client_universe.compare_database(server_universe.database.clone());
+}
@@ -0,0 +1,196 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use massa_hash::Hash;
+use massa_models::access_list::{AccessEntry, AccessTarget};
+use massa_models::node::NodeId;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use tempfile::TempDir;
+
+use crate::error::BootstrapError;
+use crate::scheduler::{BootstrapScheduler, ClientClass, Refusal, SchedulerInner};
+use crate::tools::identification_hash;
+use crate::white_black_list::SharedWhiteBlackList;
+use crate::BootstrapConfig;
+
+const SESSION: Duration = Duration::from_secs(100);
+
+fn ip(last: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+}
+
+#[test]
+fn test_reserved_slots_are_kept_for_priority_clients() {
+    let now = Instant::now();
+    let mut inner = SchedulerInner::new(2, 1, 10, Duration::ZERO, SESSION);
+
+    inner
+        .admit(ip(1), ClientClass::Anonymous, true, now)
+        .unwrap();
+    // the other slot is reserved
+    assert!(matches!(
+        inner.admit(ip(2), ClientClass::Anonymous, true, now),
+        Err(Refusal::Busy(_))
+    ));
+    inner
+        .admit(ip(3), ClientClass::Priority, true, now)
+        .unwrap();
+    assert!(matches!(
+        inner.admit(ip(4), ClientClass::Priority, true, now),
+        Err(Refusal::Busy(_))
+    ));
+}
+
+#[test]
+fn test_reservation_unused_without_prioritized_nodes() {
+    let now = Instant::now();
+    let mut inner = SchedulerInner::new(2, 1, 10, Duration::ZERO, SESSION);
+
+    inner
+        .admit(ip(1), ClientClass::Anonymous, false, now)
+        .unwrap();
+    inner
+        .admit(ip(2), ClientClass::Anonymous, false, now)
+        .unwrap();
+}
+
+#[test]
+fn test_anonymous_clients_are_served_in_order() {
+    let now = Instant::now();
+    let mut inner = SchedulerInner::new(1, 0, 10, Duration::ZERO, SESSION);
+
+    let first = inner
+        .admit(ip(1), ClientClass::Anonymous, true, now)
+        .unwrap();
+    let Err(Refusal::Busy(wait_2)) = inner.admit(ip(2), ClientClass::Anonymous, true, now) else {
+        panic!("expected the second client to be queued");
+    };
+    let Err(Refusal::Busy(wait_3)) = inner.admit(ip(3), ClientClass::Anonymous, true, now) else {
+        panic!("expected the third client to be queued");
+    };
+    assert_eq!(wait_2, SESSION);
+    assert_eq!(wait_3, SESSION * 2);
+    assert_eq!(inner.queue_length(), 2);
+
+    // once the slot is free, the third client cannot overtake the second one
+    let later = now + SESSION;
+    inner.release(first, later);
+    assert!(matches!(
+        inner.admit(ip(3), ClientClass::Anonymous, true, later),
+        Err(Refusal::Busy(_))
+    ));
+    inner
+        .admit(ip(2), ClientClass::Anonymous, true, later)
+        .unwrap();
+    assert_eq!(inner.queue_length(), 1);
+}
+
+#[test]
+fn test_full_queue_and_expired_tickets() {
+    let now = Instant::now();
+    let mut inner = SchedulerInner::new(1, 0, 1, Duration::ZERO, SESSION);
+
+    let first = inner
+        .admit(ip(1), ClientClass::Anonymous, true, now)
+        .unwrap();
+    assert!(inner
+        .admit(ip(2), ClientClass::Anonymous, true, now)
+        .is_err());
+    // the queue is full, the client is not queued
+    assert!(inner
+        .admit(ip(3), ClientClass::Anonymous, true, now)
+        .is_err());
+    assert_eq!(inner.queue_length(), 1);
+
+    // the queued client never came back: its place is given to the next one
+    let much_later = now + SESSION * 10;
+    inner.release(first, much_later);
+    inner
+        .admit(ip(3), ClientClass::Anonymous, true, much_later)
+        .unwrap();
+    assert_eq!(inner.queue_length(), 0);
+}
+
+#[test]
+fn test_per_ip_interval_only_applies_to_anonymous_clients() {
+    let now = Instant::now();
+    let interval = Duration::from_secs(30);
+    let mut inner = SchedulerInner::new(4, 0, 10, interval, SESSION);
+
+    let slot = inner
+        .admit(ip(1), ClientClass::Anonymous, true, now)
+        .unwrap();
+    inner.release(slot, now);
+    let later = now + Duration::from_secs(10);
+    assert_eq!(
+        inner.admit(ip(1), ClientClass::Anonymous, true, later),
+        Err(Refusal::TooGreedy(
+            Duration::from_secs(10),
+            Duration::from_secs(20)
+        ))
+    );
+    inner
+        .admit(ip(1), ClientClass::Priority, true, later)
+        .unwrap();
+}
+
+fn scheduler_with_whitelisted(node_id: NodeId) -> (BootstrapScheduler, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let white_path = dir.path().join("whitelist.json");
+    let black_path = dir.path().join("blacklist.json");
+    let lists = SharedWhiteBlackList::new(white_path, black_path).unwrap();
+    lists
+        .add_to_whitelist(vec![AccessEntry {
+            target: AccessTarget::Node(node_id),
+            expires_at: None,
+        }])
+        .unwrap();
+    (
+        BootstrapScheduler::new(&BootstrapConfig::default(), lists),
+        dir,
+    )
+}
+
+#[test]
+fn test_identification() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let (scheduler, _dir) = scheduler_with_whitelisted(NodeId::new(keypair.get_public_key()));
+    let handshake = Hash::compute_from(b"handshake");
+    let timestamp = MassaTime::now();
+    let signature = keypair
+        .sign(&identification_hash(&handshake, timestamp))
+        .unwrap();
+
+    let class = scheduler
+        .identify(&handshake, &keypair.get_public_key(), timestamp, &signature)
+        .unwrap();
+    assert_eq!(class, ClientClass::Priority);
+
+    // the same identification cannot be replayed
+    assert!(matches!(
+        scheduler.identify(&handshake, &keypair.get_public_key(), timestamp, &signature),
+        Err(BootstrapError::IdentificationError(_))
+    ));
+
+    // an identification bound to another session is refused
+    let other_handshake = Hash::compute_from(b"other handshake");
+    assert!(scheduler
+        .identify(
+            &other_handshake,
+            &keypair.get_public_key(),
+            timestamp,
+            &signature
+        )
+        .is_err());
+
+    // nodes absent from the whitelist are anonymous
+    let other = KeyPair::generate(0).unwrap();
+    let signature = other
+        .sign(&identification_hash(&handshake, timestamp))
+        .unwrap();
+    let class = scheduler
+        .identify(&handshake, &other.get_public_key(), timestamp, &signature)
+        .unwrap();
+    assert_eq!(class, ClientClass::Anonymous);
+}
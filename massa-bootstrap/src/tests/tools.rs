@@ -354,6 +354,10 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         ip_list_max_size: 10,
         per_ip_min_interval: MassaTime::from_millis(10000),
         rate_limit: u64::MAX,
+        priority_reserved_slots: 1,
+        max_bootstrap_queue_length: 10,
+        keypair_file: PathBuf::from("node_privkey.key"),
+        parallel_bootstrap_servers: 1,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -610,7 +614,7 @@ impl BootstrapServerMessageFaultyPart {
 
 impl BootstrapServerMessage {
     pub fn generate<R: Rng>(rng: &mut R) -> Self {
-        let variant = rng.gen_range(0..6);
+        let variant = rng.gen_range(0..9);
         match variant {
             0 => {
                 let t: u64 = rng.gen();
@@ -680,6 +684,18 @@ impl BootstrapServerMessage {
             5 => BootstrapServerMessage::BootstrapError {
                 error: gen_random_string(MAX_BOOTSTRAP_ERROR_LENGTH as usize, rng),
             },
+            6 => BootstrapServerMessage::StateFingerprint {
+                slot: gen_random_slot(rng),
+                fingerprint: if rng.gen_bool(0.5) {
+                    Some(gen_random_hash(rng))
+                } else {
+                    None
+                },
+            },
+            7 => BootstrapServerMessage::ServerBusy {
+                retry_after: MassaTime::from_millis(rng.gen()),
+            },
+            8 => BootstrapServerMessage::AskIdentification,
             _ => unreachable!(),
         }
     }
@@ -1049,6 +1065,24 @@ impl BootstrapServerMessage {
                 BootstrapServerMessage::BootstrapError { error: e1 },
                 BootstrapServerMessage::BootstrapError { error: e2 },
             ) => e1 == e2,
+            (
+                BootstrapServerMessage::StateFingerprint {
+                    slot: s1,
+                    fingerprint: f1,
+                },
+                BootstrapServerMessage::StateFingerprint {
+                    slot: s2,
+                    fingerprint: f2,
+                },
+            ) => (s1 == s2) && (f1 == f2),
+            (
+                BootstrapServerMessage::ServerBusy { retry_after: r1 },
+                BootstrapServerMessage::ServerBusy { retry_after: r2 },
+            ) => r1 == r2,
+            (
+                BootstrapServerMessage::AskIdentification,
+                BootstrapServerMessage::AskIdentification,
+            ) => true,
             _ => false,
         }
     }
@@ -1113,6 +1147,34 @@ impl BootstrapClientMessage {
                 BootstrapClientMessage::BootstrapSuccess,
                 BootstrapClientMessage::BootstrapSuccess,
             ) => true,
+            (
+                BootstrapClientMessage::AskStateRange {
+                    last_slot: ls1,
+                    last_state_step: lstate1,
+                    end_key: ek1,
+                },
+                BootstrapClientMessage::AskStateRange {
+                    last_slot: ls2,
+                    last_state_step: lstate2,
+                    end_key: ek2,
+                },
+            ) => (ls1 == ls2) && (lstate1 == lstate2) && (ek1 == ek2),
+            (
+                BootstrapClientMessage::AskStateFingerprint { slot: s1 },
+                BootstrapClientMessage::AskStateFingerprint { slot: s2 },
+            ) => s1 == s2,
+            (
+                BootstrapClientMessage::Identify {
+                    public_key: p1,
+                    timestamp: t1,
+                    signature: sig1,
+                },
+                BootstrapClientMessage::Identify {
+                    public_key: p2,
+                    timestamp: t2,
+                    signature: sig2,
+                },
+            ) => (p1 == p2) && (t1 == t2) && (sig1 == sig2),
             _ => false,
         }
     }
@@ -1120,7 +1182,7 @@ impl BootstrapClientMessage {
     // Generates a message filled with random data of random size based on the limit given in
     // constants. Used for parametric testing
     pub fn generate<R: Rng>(rng: &mut R) -> Self {
-        let variant = rng.gen_range(0..4);
+        let variant = rng.gen_range(0..7);
         match variant {
             0 => BootstrapClientMessage::AskBootstrapPeers,
             1 => {
@@ -1171,6 +1233,36 @@ impl BootstrapClientMessage {
                 error: gen_random_string(MAX_BOOTSTRAP_ERROR_LENGTH as usize, rng),
             },
             3 => BootstrapClientMessage::BootstrapSuccess,
+            4 => {
+                let last_slot = if rng.gen_bool(0.5) {
+                    Some(gen_random_slot(rng))
+                } else {
+                    None
+                };
+                let data = gen_random_vector(10, rng);
+                let last_state_step = gen_random_streaming_step(rng, data);
+                let end_key = if rng.gen_bool(0.5) {
+                    Some(gen_random_vector(10, rng))
+                } else {
+                    None
+                };
+                BootstrapClientMessage::AskStateRange {
+                    last_slot,
+                    last_state_step,
+                    end_key,
+                }
+            }
+            5 => BootstrapClientMessage::AskStateFingerprint {
+                slot: gen_random_slot(rng),
+            },
+            6 => {
+                let keypair = KeyPair::generate(0).unwrap();
+                BootstrapClientMessage::Identify {
+                    public_key: keypair.get_public_key(),
+                    timestamp: MassaTime::from_millis(rng.gen()),
+                    signature: keypair.sign(&gen_random_hash(rng)).unwrap(),
+                }
+            }
             _ => unreachable!(),
         }
     }
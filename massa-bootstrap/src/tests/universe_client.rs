@@ -1,11 +1,13 @@
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::Arc,
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
 };
 
 use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
 use massa_db_worker::MassaDB;
 use massa_final_state::MockFinalStateController;
+use massa_metrics::MassaMetrics;
 use massa_models::{
     config::{
         MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE, THREAD_COUNT,
@@ -21,7 +23,7 @@ use tempfile::TempDir;
 
 use crate::{
     client::{bootstrap_from_server, connect_to_server, MockBSConnector},
-    BootstrapClientMessage, BootstrapConfig, BootstrapError, GlobalBootstrapState,
+    resync_state, BootstrapClientMessage, BootstrapConfig, BootstrapError, GlobalBootstrapState,
 };
 
 pub struct BootstrapClientForeignControllers {
@@ -141,6 +143,41 @@ impl BootstrapClientTestUniverse {
         )
     }
 
+    /// Resynchronizes the local final state from the server listening on `remote_port`,
+    /// expecting `connection_count` connections to it
+    pub fn launch_resync(
+        &mut self,
+        remote_port: u16,
+        remote_node_id: NodeId,
+        connection_count: usize,
+    ) -> Result<GlobalBootstrapState, BootstrapError> {
+        let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), remote_port);
+        self.controllers
+            .bs_connector
+            .expect_connect_timeout()
+            .times(connection_count)
+            .returning(move |_, _| Ok(std::net::TcpStream::connect(remote_addr).unwrap()));
+        let config = BootstrapConfig {
+            bootstrap_list: vec![(remote_addr, remote_node_id)],
+            ..self.config.clone()
+        };
+        resync_state(
+            &config,
+            self.controllers.final_state_controller.clone(),
+            std::mem::take(&mut self.controllers.bs_connector),
+            "BOOT.1.0".parse().unwrap(),
+            None,
+            Arc::new((Mutex::new(false), Condvar::new())),
+            MassaMetrics::new(
+                false,
+                "0.0.0.0:31249".parse().unwrap(),
+                THREAD_COUNT,
+                Duration::from_secs(5),
+            )
+            .0,
+        )
+    }
+
     //TODO: Add consensus blocks and peers
     pub fn compare_database(&self, other_database: ShareableMassaDBController) {
         assert_eq!(
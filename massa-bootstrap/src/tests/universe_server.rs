@@ -23,6 +23,7 @@ use massa_models::{
     },
     datastore::Datastore,
     prehash::PreHashSet,
+    slot::Slot,
     streaming_step::StreamingStep,
 };
 use massa_protocol_exports::{BootstrapPeers, MockProtocolControllerWrapper};
@@ -122,6 +123,8 @@ pub struct BootstrapServerTestUniverseBuilder {
     final_ledger: FinalLedger,
     socket_addr: SocketAddr,
     accept_error: bool,
+    accept_count: usize,
+    slot: Option<Slot>,
 }
 
 impl Default for BootstrapServerTestUniverseBuilder {
@@ -155,6 +158,7 @@ impl Default for BootstrapServerTestUniverseBuilder {
         controllers
             .consensus_controller
             .set_expectations(|consensus_controller| {
+                consensus_controller.expect_is_syncing().return_const(false);
                 consensus_controller.expect_get_bootstrap_part().returning(
                     move |last_consensus_step, _slot| match last_consensus_step {
                         StreamingStep::Started => Ok((
@@ -187,6 +191,8 @@ impl Default for BootstrapServerTestUniverseBuilder {
             final_ledger,
             socket_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8069),
             accept_error: false,
+            accept_count: 1,
+            slot: None,
         }
     }
 }
@@ -206,7 +212,7 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
         self
     }
 
@@ -220,7 +226,7 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
         self
     }
 
@@ -236,7 +242,7 @@ impl BootstrapServerTestUniverseBuilder {
         self.controllers
             .database
             .write()
-            .write_batch(batch, versioning_batch, None);
+            .write_batch(batch, versioning_batch, self.slot);
         self
     }
 
@@ -255,6 +261,18 @@ impl BootstrapServerTestUniverseBuilder {
         self
     }
 
+    /// Changes set after this call are written at `slot`, and recorded in the change history
+    pub fn set_slot(mut self, slot: Slot) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Number of client connections accepted before the server stops
+    pub fn set_accept_count(mut self, accept_count: usize) -> Self {
+        self.accept_count = accept_count;
+        self
+    }
+
     pub fn set_config(mut self, config: BootstrapConfig) -> Self {
         self.config = config;
         self
@@ -290,7 +308,7 @@ impl BootstrapServerTestUniverseBuilder {
             self.controllers
                 .listener
                 .expect_poll()
-                .times(1)
+                .times(self.accept_count)
                 // Mock the `accept` method here by receiving from the listen-loop thread
                 .returning(move || Ok(PollEvent::NewConnections(vec![conn_rx.recv().unwrap()])))
                 .in_sequence(&mut sequence);
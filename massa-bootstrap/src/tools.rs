@@ -1,4 +1,9 @@
+use massa_hash::Hash;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use std::net::IpAddr;
+use std::path::Path;
+use tracing::debug;
 
 // to_canonical implementation (https://doc.rust-lang.org/src/core/net/ip_addr.rs.html#1733)
 pub(crate) fn to_canonical(ip: IpAddr) -> IpAddr {
@@ -34,3 +39,26 @@ pub(crate) fn mio_stream_to_std(mio_socket: mio::net::TcpStream) -> std::net::Tc
         unsafe { std::net::TcpStream::from_raw_fd(io.into_raw_fd()) }
     }
 }
+
+/// Hash signed by a client to identify itself: it binds the identification
+/// to the current session through the hash of the last handshake message.
+pub(crate) fn identification_hash(prev_message: &Hash, timestamp: MassaTime) -> Hash {
+    let mut hash_data = prev_message.to_bytes().to_vec();
+    hash_data.extend(timestamp.as_millis().to_be_bytes());
+    Hash::compute_from(&hash_data)
+}
+
+/// Loads the node keypair used to identify ourselves to bootstrap servers.
+/// Bootstrapping anonymously is always possible, so a missing or invalid file is not an error.
+pub(crate) fn load_node_keypair(keypair_file: &Path) -> Option<KeyPair> {
+    let keypair = std::fs::read(keypair_file)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<KeyPair>(&data).ok());
+    if keypair.is_none() {
+        debug!(
+            "no node keypair found at {}, bootstrapping anonymously",
+            keypair_file.display()
+        );
+    }
+    keypair
+}
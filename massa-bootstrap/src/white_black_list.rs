@@ -1,19 +1,27 @@
 use std::{
     borrow::Cow,
-    collections::HashSet,
-    net::{IpAddr, SocketAddr},
+    net::SocketAddr,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use crate::error::BootstrapError;
 use massa_logging::massa_trace;
+use massa_models::{
+    access_list::{AccessEntry, AccessList, AccessTarget},
+    node::NodeId,
+};
+use massa_time::MassaTime;
 use parking_lot::RwLock;
 use tracing::{info, warn};
 
 use crate::tools::to_canonical;
 
-/// A wrapper around the white/black lists that allows efficient sharing between threads
+/// A wrapper around the white/black lists that allows efficient sharing between threads.
+///
+/// The lists only filter the clients bootstrapping from us: IP ranges apply to every client,
+/// clients that identify themselves with a whitelisted node id are served with priority,
+/// and those identified with a blacklisted one are refused.
 // TODO: don't clone the path-bufs...
 #[derive(Clone, Debug)]
 pub struct SharedWhiteBlackList<'a> {
@@ -36,57 +44,59 @@ impl SharedWhiteBlackList<'_> {
     }
 
     /// get the white list
-    pub fn get_white_list(&self) -> Option<HashSet<IpAddr>> {
+    pub fn get_white_list(&self) -> Option<AccessList> {
         self.inner.read().white_list.clone()
     }
 
     /// get the black list
-    pub fn get_black_list(&self) -> Option<HashSet<IpAddr>> {
+    pub fn get_black_list(&self) -> Option<AccessList> {
         self.inner.read().black_list.clone()
     }
 
-    /// Add IP address to the black list
-    pub fn add_ips_to_blacklist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Add entries to the black list
+    pub fn add_to_blacklist(&self, entries: Vec<AccessEntry>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(black_list) = &mut write_lock.black_list {
-            black_list.extend(ips);
-        } else {
-            write_lock.black_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.black_path, write_lock.black_list.as_ref().unwrap())?;
+        let black_list = write_lock
+            .black_list
+            .get_or_insert_with(AccessList::default);
+        entries
+            .into_iter()
+            .for_each(|entry| black_list.insert(entry));
+        self.write_to_file(&self.black_path, black_list)?;
         Ok(())
     }
 
-    /// Remove IPs address from the black list
-    pub fn remove_ips_from_blacklist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Remove IP ranges or nodes from the black list
+    pub fn remove_from_blacklist(&self, targets: Vec<AccessTarget>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
         if let Some(black_list) = &mut write_lock.black_list {
-            for ip in ips {
-                black_list.remove(&ip);
+            for target in targets {
+                black_list.remove(&target);
             }
             self.write_to_file(&self.black_path, black_list)?;
         }
         Ok(())
     }
 
-    /// Add IP address to the white list
-    pub fn add_ips_to_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Add entries to the white list
+    pub fn add_to_whitelist(&self, entries: Vec<AccessEntry>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
-        if let Some(white_list) = &mut write_lock.white_list {
-            white_list.extend(ips);
-        } else {
-            write_lock.white_list = Some(HashSet::from_iter(ips));
-        };
-        self.write_to_file(&self.white_path, write_lock.white_list.as_ref().unwrap())?;
+        let white_list = write_lock
+            .white_list
+            .get_or_insert_with(AccessList::default);
+        entries
+            .into_iter()
+            .for_each(|entry| white_list.insert(entry));
+        self.write_to_file(&self.white_path, white_list)?;
         Ok(())
     }
 
-    /// Remove IPs address from the white list
-    pub fn remove_ips_from_whitelist(&self, ips: Vec<IpAddr>) -> Result<(), BootstrapError> {
+    /// Remove IP ranges or nodes from the white list
+    pub fn remove_from_whitelist(&self, targets: Vec<AccessTarget>) -> Result<(), BootstrapError> {
         let mut write_lock = self.inner.write();
         if let Some(white_list) = &mut write_lock.white_list {
-            for ip in ips {
-                white_list.remove(&ip);
+            for target in targets {
+                white_list.remove(&target);
             }
             self.write_to_file(&self.white_path, white_list)?;
         }
@@ -94,11 +104,7 @@ impl SharedWhiteBlackList<'_> {
     }
 
     /// write list to file
-    fn write_to_file(
-        &self,
-        file_path: &Path,
-        data: &HashSet<IpAddr>,
-    ) -> Result<(), BootstrapError> {
+    fn write_to_file(&self, file_path: &Path, data: &AccessList) -> Result<(), BootstrapError> {
         let list = serde_json::to_string(data).map_err(|e| {
             warn!(error = ?e, "failed to serialize list");
             BootstrapError::SerializationError(e.to_string())
@@ -139,23 +145,58 @@ impl SharedWhiteBlackList<'_> {
 
     pub(crate) fn is_ip_allowed(&self, remote_addr: &SocketAddr) -> Result<(), BootstrapError> {
         let ip = to_canonical(remote_addr.ip());
+        let now = MassaTime::now();
         // whether the peer IP address is blacklisted
         let read = self.inner.read();
         if let Some(ip_list) = &read.black_list {
-            if ip_list.contains(&ip) {
+            if ip_list.contains_ip(&ip, now) {
                 massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted", {"remote_addr": remote_addr});
                 return Err(BootstrapError::BlackListed(ip.to_string()));
             }
             // whether the peer IP address is not present in the whitelist
         }
-        if let Some(ip_list) = &read.white_list {
-            if !ip_list.contains(&ip) {
+        // a whitelist holding only node ids does not restrict the clients
+        if let Some(ip_list) = read
+            .white_list
+            .as_ref()
+            .filter(|list| list.has_ip_rules() || !list.has_node_rules())
+        {
+            if !ip_list.contains_ip(&ip, now) {
                 massa_trace!("bootstrap.lib.run.select.accept.refuse_not_whitelisted", {"remote_addr": remote_addr});
                 return Err(BootstrapError::WhiteListed(ip.to_string()));
             }
         }
         Ok(())
     }
+
+    /// Refuses an identified client whose node id is blacklisted
+    pub(crate) fn is_node_allowed(&self, node_id: &NodeId) -> Result<(), BootstrapError> {
+        if let Some(black_list) = &self.inner.read().black_list {
+            if black_list.contains_node(node_id, MassaTime::now()) {
+                massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted_node", {"node_id": node_id.to_string()});
+                return Err(BootstrapError::BlackListed(node_id.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether an identified client is part of the whitelisted nodes served with priority
+    pub(crate) fn is_node_prioritized(&self, node_id: &NodeId) -> bool {
+        self.inner
+            .read()
+            .white_list
+            .as_ref()
+            .is_some_and(|list| list.contains_node(node_id, MassaTime::now()))
+    }
+
+    /// Whether the whitelist has node ids to serve with priority
+    pub(crate) fn has_prioritized_nodes(&self) -> bool {
+        self.inner
+            .read()
+            .white_list
+            .as_ref()
+            .is_some_and(|list| list.has_node_rules())
+    }
 }
 
 impl WhiteBlackListInner {
@@ -163,7 +204,7 @@ impl WhiteBlackListInner {
     fn update_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<AccessList>, Option<AccessList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, false)?,
             Self::load_list(blacklist_path, false)?,
@@ -174,17 +215,14 @@ impl WhiteBlackListInner {
     fn init_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<AccessList>, Option<AccessList>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, true)?,
             Self::load_list(blacklist_path, true)?,
         ))
     }
 
-    fn load_list(
-        list_path: &Path,
-        is_init: bool,
-    ) -> Result<Option<HashSet<IpAddr>>, BootstrapError> {
+    fn load_list(list_path: &Path, is_init: bool) -> Result<Option<AccessList>, BootstrapError> {
         match std::fs::read_to_string(list_path) {
             Err(e) => {
                 if is_init {
@@ -197,17 +235,14 @@ impl WhiteBlackListInner {
                 Ok(None)
             }
             Ok(list) => {
+                // IP ranges are canonicalized when parsed
                 let res = Some(
-                    serde_json::from_str::<HashSet<IpAddr>>(list.as_str())
-                        .map_err(|e| {
-                            BootstrapError::InitListError(format!(
-                                "Failed to parse bootstrap whitelist : {}",
-                                e
-                            ))
-                        })?
-                        .into_iter()
-                        .map(to_canonical)
-                        .collect(),
+                    serde_json::from_str::<AccessList>(list.as_str()).map_err(|e| {
+                        BootstrapError::InitListError(format!(
+                            "Failed to parse bootstrap whitelist : {}",
+                            e
+                        ))
+                    })?,
                 );
                 Ok(res)
             }
@@ -217,6 +252,6 @@ impl WhiteBlackListInner {
 
 #[derive(Default, Debug)]
 pub(crate) struct WhiteBlackListInner {
-    white_list: Option<HashSet<IpAddr>>,
-    black_list: Option<HashSet<IpAddr>>,
+    white_list: Option<AccessList>,
+    black_list: Option<AccessList>,
 }
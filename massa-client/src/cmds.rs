@@ -1,6 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::display::Output;
+use crate::script::{Script, ScriptRunner};
+use crate::settings::SETTINGS;
+use crate::watch;
 use crate::{client_warning, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
@@ -8,30 +11,45 @@ use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
     datastore::DatastoreEntryInput,
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    graph::BlockGraphDump,
+    node::NodeStatus,
     operation::OperationInput,
+    rolls::DrawInputsInfo,
+    slot::FinalStateHashesInfo,
+};
+use massa_hash::Hash;
+use massa_models::config::{
+    CHANNEL_SIZE, ENDORSEMENT_COUNT, GENESIS_KEY, MAX_BATCH_TRANSACTION_TRANSFERS,
+    MAX_GAS_PER_BLOCK, PERIODS_PER_CYCLE, SELECTOR_DRAW_CACHE_SIZE, THREAD_COUNT,
 };
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
 use massa_models::{
+    access_list::{AccessEntry, AccessTarget},
     address::Address,
     amount::Amount,
     block_id::BlockId,
     endorsement::EndorsementId,
     execution::EventFilter,
+    multisig::MultisigPolicy,
     operation::{Operation, OperationId, OperationType},
+    operation_envelope::OperationEnvelope,
+    output_event::SCOutputEvent,
     slot::Slot,
 };
-use massa_sdk::Client;
-use massa_signature::KeyPair;
+use massa_pos_exports::{perform_draws, DrawInputs, SelectorConfig};
+use massa_sdk::deploy::{deployer_datastore, ContractToDeploy};
+use massa_sdk::{Client, RpcClient};
+use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 use std::fmt::{Debug, Display};
-use std::net::IpAddr;
-use std::path::PathBuf;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
 use strum_macros::{Display, EnumIter, EnumString};
@@ -58,6 +76,17 @@ pub enum Command {
     )]
     exit,
 
+    #[strum(
+        ascii_case_insensitive,
+        serialize = "run",
+        props(
+            args = "ScriptPath [Name1=Value1 Name2=Value2 ...]",
+            exit_code = "true"
+        ),
+        message = "run a script of client commands with variables, loops and assertions, stopping at the first error (see massa-client/src/script.rs for the syntax)"
+    )]
+    run_script,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "IpAddr1 IpAddr2 ...", pwd_not_needed = "true"),
@@ -72,6 +101,27 @@ pub enum Command {
     )]
     node_unban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[Directives]", pwd_not_needed = "true"),
+        message = "set the log filter of the node (e.g. massa=info,massa_protocol_worker=debug), or show it without argument"
+    )]
+    node_set_log_filter,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "read the configuration files of the node again and apply the changes that do not require a restart (minimal fees, peer categories, API rate limits, logging)"
+    )]
+    node_reload_config,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show network diagnostics of each peer: connection, traffic, announcements, block asks answered and timed out, state, last error and version"
+    )]
+    get_peers_details,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "IpAddr1 IpAddr2 ...", pwd_not_needed = "true"),
@@ -123,25 +173,89 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add, remove or allow-all) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap whitelist IP address(es). No args returns the bootstrap whitelist"
+        props(
+            args = "(add, remove or allow-all) [IpAddr/Cidr or NodeId][@expiry_ms]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap whitelist IP ranges and node ids. No args returns the bootstrap whitelist"
     )]
     node_bootstrap_whitelist,
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap blacklist IP address(es). No args returns the bootstrap blacklist"
+        props(
+            args = "(add or remove) [IpAddr/Cidr or NodeId][@expiry_ms]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap blacklist IP ranges and node ids. No args returns the bootstrap blacklist"
     )]
     node_bootstrap_blacklist,
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage peers whitelist IP address(es). No args returns the peers whitelist"
+        props(
+            args = "(add or remove) [IpAddr/Cidr or NodeId][@expiry_ms]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage peers whitelist IP ranges and node ids. No args returns the peers whitelist"
     )]
     node_peers_whitelist,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "(add or remove) [IpAddr/Cidr or NodeId][@expiry_ms]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage peers blacklist IP ranges and node ids. No args returns the peers blacklist"
+    )]
+    node_peers_blacklist,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "(json or dot) OutputPath [StartPeriod EndPeriod]",
+            pwd_not_needed = "true"
+        ),
+        message = "export the block graph of the node to a file, as JSON or as a Graphviz DOT graph. EndPeriod is excluded. The node exports at most as many periods as its max_arguments API setting"
+    )]
+    node_export_block_graph,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "IpAddr1:PublicPort1 IpAddr2:PublicPort2 ...",
+            pwd_not_needed = "true"
+        ),
+        message = "compare the execution trail hash and the final state fingerprint of the node with other nodes, and show the first final slot where they diverged"
+    )]
+    node_compare_state_hashes,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "JsonPath DotPath", pwd_not_needed = "true", offline = "true"),
+        message = "render a block graph exported as JSON by node_export_block_graph into a Graphviz DOT graph"
+    )]
+    render_block_graph,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Cycle [JsonPath]", pwd_not_needed = "true"),
+        message = "get the inputs of the selector draws of a cycle (look back rolls and seed), and save them as JSON to JsonPath if given"
+    )]
+    get_draw_inputs,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "JsonPath [Address1 Address2 ...]",
+            pwd_not_needed = "true",
+            offline = "true"
+        ),
+        message = "reproduce the selector draws of a cycle from the inputs saved by get_draw_inputs, check their roll distribution hash and show the slots drawn for the given addresses"
+    )]
+    verify_draws,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -194,6 +308,39 @@ pub enum Command {
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "Address1 Address2 ... [--final] [--once]",
+            pwd_not_needed = "true",
+            exit_code = "true"
+        ),
+        message = "print the balance changes of addresses live, until Ctrl-C or the first change with --once. --final only follows final slots"
+    )]
+    watch_address,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "OperationId [--until included|executed|final]",
+            pwd_not_needed = "true",
+            exit_code = "true"
+        ),
+        message = "print the inclusion, execution status and finality of an operation live, until it reaches the given stage (final by default). Exits with 2 if it failed and 3 if it expired"
+    )]
+    watch_operation,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "[--emitter Address] [--operation OperationId] [--final] [--until Text]",
+            pwd_not_needed = "true",
+            exit_code = "true"
+        ),
+        message = "print smart contract events live, until Ctrl-C or an event containing the --until text"
+    )]
+    watch_events,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "show-all-keys"),
@@ -263,6 +410,68 @@ pub enum Command {
     )]
     send_transaction,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress Fee ReceiverAddress1 Amount1 ReceiverAddress2 Amount2 ..."),
+        message = "send coins from a wallet address to several recipients in a single operation"
+    )]
+    send_batch_transaction,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Threshold PublicKey1 PublicKey2 ...", pwd_not_needed = "true"),
+        message = "get the address of the multisig account of the given cosigners"
+    )]
+    multisig_address,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "Threshold PublicKeys ReceiverAddress Amount Fee DraftPath",
+            pwd_not_needed = "true"
+        ),
+        message = "write to a file the draft of a transaction emitted by a multisig address (PublicKeys are comma-separated)"
+    )]
+    multisig_create_transaction,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SignerAddress DraftPath", offline = "true"),
+        message = "add the signature of a wallet address to a multisig draft (does not need the node)"
+    )]
+    multisig_sign,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SubmitterAddress DraftPath"),
+        message = "send a multisig draft having enough signatures, on behalf of the multisig address"
+    )]
+    multisig_send,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "SenderAddress Fee ExpirePeriod OperationType Parameters...",
+            pwd_not_needed = "true"
+        ),
+        message = "build an unsigned operation to be signed offline. ExpirePeriod is a period or \"auto\" to ask the node (the chain id of the client is used otherwise). OperationType is one of: transaction ReceiverAddress Amount, buy_rolls RollCount, sell_rolls RollCount, call_smart_contract TargetAddress FunctionName Parameter MaxGas Coins"
+    )]
+    op_build,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "UnsignedOperation", offline = "true"),
+        message = "sign an operation built by op_build with the wallet, without reaching the node. A multisig operation is signed by a cosigner found in the wallet"
+    )]
+    op_sign,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SignedOperation", pwd_not_needed = "true"),
+        message = "send an operation signed by op_sign"
+    )]
+    op_broadcast,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress PathToBytecode MaxGas MaxCoins Fee"),
@@ -270,6 +479,13 @@ pub enum Command {
     )]
     execute_smart_contract,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress PathToBytecode Fee [Coins] [ConstructorArgs]"),
+        message = "deploy a smart contract through the deployer contract set in the client config: estimate the gas and storage costs, send the operation, wait for it to be final and show the address of the created contract. Coins are sent to the constructor. ConstructorArgs are the constructor arguments serialized with the Args encoding of massa-as-sdk and massa-web3, given in hex (optionally prefixed with 0x), or read as raw bytes from a file if prefixed with @"
+    )]
+    deploy_contract,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress TargetAddress FunctionName Parameter MaxGas Coins Fee"),
@@ -393,6 +609,66 @@ impl Display for ExtendedWallet {
     }
 }
 
+/// Comparison of the final state hashes of several nodes
+#[derive(Debug, Serialize)]
+pub struct StateHashesComparison {
+    /// compared nodes, the first one being the node the client is connected to
+    pub nodes: Vec<String>,
+    /// last slot that is final on all the nodes
+    pub last_common_final_slot: Slot,
+    /// last slot after which all the nodes had the same final state, None if it is older than their history
+    pub last_agreeing_slot: Option<Slot>,
+    /// first slot after which the final states differ, None if the nodes agree
+    pub first_divergent_slot: Option<Slot>,
+    /// hashes of each node right after `first_divergent_slot`
+    pub divergent_hashes: Vec<Option<FinalStateHashesInfo>>,
+}
+
+/// Draws of a cycle reproduced from their inputs
+#[derive(Debug, Serialize)]
+pub struct DrawVerification {
+    /// drawn cycle
+    pub cycle: u64,
+    /// roll distribution hash, recomputed from the rolls and checked against the one of the inputs
+    pub roll_distribution_hash: Hash,
+    /// RNG seed at look back
+    pub lookback_seed: Hash,
+    /// total number of rolls at look back
+    pub total_rolls: u64,
+    /// hash of the selections of all the slots of the cycle, in slot order
+    pub draws_digest: Hash,
+    /// slots drawn for the requested addresses
+    pub selections: BTreeMap<Address, AddressDraws>,
+}
+
+/// Smart contract deployed by `deploy_contract`
+#[derive(Debug, Serialize)]
+pub struct Deployment {
+    /// operation executing the deployer
+    pub operation_id: OperationId,
+    /// addresses of the created contracts
+    pub addresses: Vec<Address>,
+    /// gas used by the deployer in the read-only execution
+    pub estimated_gas_cost: u64,
+    /// coins spent to store the contract in the read-only execution
+    pub estimated_storage_cost: Amount,
+    /// max gas of the operation
+    pub max_gas: u64,
+    /// max coins of the operation, covering the storage cost and the coins sent to the constructor
+    pub max_coins: Amount,
+    /// final events of the operation
+    pub events: Vec<SCOutputEvent>,
+}
+
+/// Slots drawn for an address in a cycle
+#[derive(Debug, Default, Serialize)]
+pub struct AddressDraws {
+    /// slots where the address is the block producer
+    pub block_slots: Vec<Slot>,
+    /// slots and endorsement indexes where the address is an endorser
+    pub endorsement_slots: Vec<(Slot, usize)>,
+}
+
 impl Command {
     /// Display the help of the command
     /// with fancy colors and so on
@@ -420,6 +696,133 @@ impl Command {
             && self.get_str("pwd_not_needed").unwrap() == "true")
     }
 
+    /// Returns true if the exit status of the client must tell how the command ended
+    pub(crate) fn sets_exit_code(&self) -> bool {
+        self.get_str("exit_code") == Some("true")
+    }
+
+    /// Returns true if the command must not reach the node
+    pub(crate) fn is_offline(&self) -> bool {
+        self.get_str("offline") == Some("true")
+    }
+
+    /// run a command that must not reach the node, with only an access to the wallet
+    pub(crate) async fn run_offline(
+        &self,
+        wallet_opt: &mut Option<Wallet>,
+        parameters: &[String],
+        json: bool,
+    ) -> Result<Box<dyn Output>> {
+        match self {
+            Command::multisig_sign => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let path = parameters[1].parse::<PathBuf>()?;
+                let keypair = wallet
+                    .find_associated_keypair(&addr)
+                    .ok_or_else(|| anyhow!("address {} not found in the wallet", addr))?;
+                let mut draft = MultisigDraft::load(&path).await?;
+                // do not trust the ID written in the file
+                let operation_id = draft.operation.compute_multisig_id(draft.chain_id)?;
+                let OperationType::Multisig {
+                    policy, signatures, ..
+                } = &mut draft.operation.op
+                else {
+                    bail!("the draft is not a multisig operation");
+                };
+                let index = policy
+                    .get_index(&keypair.get_public_key())
+                    .ok_or_else(|| anyhow!("{} is not a cosigner of {}", addr, policy.address()))?;
+                let signature = keypair.sign(operation_id.get_hash())?;
+                signatures.retain(|(signer_index, _)| *signer_index != index);
+                signatures.push((index, signature));
+                let res = format!(
+                    "{}/{} signatures collected for operation {}",
+                    signatures.len(),
+                    policy.threshold,
+                    operation_id
+                );
+                draft.save(&path).await?;
+                Ok(Box::new(res))
+            }
+
+            Command::op_sign => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let envelope = parameters[0].parse::<OperationEnvelope>()?;
+                let OperationEnvelope::Unsigned {
+                    signer_address,
+                    operation,
+                    ..
+                } = &envelope
+                else {
+                    bail!("the operation is already signed");
+                };
+                let keypair = match &operation.op {
+                    // a multisig operation is submitted by any of its cosigners
+                    OperationType::Multisig { policy, .. } => policy
+                        .public_keys
+                        .iter()
+                        .find_map(|public_key| {
+                            wallet.find_associated_keypair(&Address::from_public_key(public_key))
+                        })
+                        .ok_or_else(|| {
+                            anyhow!("no cosigner of {} found in the wallet", signer_address)
+                        })?,
+                    _ => wallet
+                        .find_associated_keypair(signer_address)
+                        .ok_or_else(|| {
+                            anyhow!("address {} not found in the wallet", signer_address)
+                        })?,
+                };
+                let envelope = envelope.sign(keypair)?;
+                if let OperationEnvelope::Signed { operation, .. } = &envelope {
+                    if !json {
+                        println!("Operation ID: {}", operation.id);
+                        println!("Signed operation:");
+                    }
+                }
+                Ok(Box::new(envelope.to_bs58_check()?))
+            }
+
+            Command::verify_draws => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let info: DrawInputsInfo = serde_json::from_slice(
+                    &get_file_as_byte_vec(Path::new(&parameters[0])).await?,
+                )?;
+                let addresses = parse_vec::<Address>(&parameters[1..])?;
+                Ok(Box::new(verify_draws(info, &addresses)?))
+            }
+
+            Command::render_block_graph => {
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let dump: BlockGraphDump = serde_json::from_slice(
+                    &get_file_as_byte_vec(Path::new(&parameters[0])).await?,
+                )?;
+                let path = Path::new(&parameters[1]);
+                save_block_graph(&dump, BlockGraphFormat::Dot, path).await?;
+                Ok(Box::new(format!(
+                    "{} blocks rendered to {}",
+                    dump.blocks.len(),
+                    path.display()
+                )))
+            }
+
+            _ => bail!("command {} needs to reach the node", self),
+        }
+    }
+
     /// run a given command
     ///
     /// # parameters
@@ -435,6 +838,10 @@ impl Command {
         parameters: &[String],
         json: bool,
     ) -> Result<Box<dyn Output>> {
+        if self.is_offline() {
+            return self.run_offline(wallet_opt, parameters, json).await;
+        }
+
         if let Ok(node_status) = client.public.get_status().await {
             if node_status.chain_id != client.chain_id {
                 client_warning!("the chain id of the node is different from the one of the client");
@@ -486,6 +893,48 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_set_log_filter => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                if let Some(filter) = parameters.first() {
+                    match client.private.node_set_log_filter(filter.clone()).await {
+                        Ok(previous) => {
+                            if !json {
+                                println!("Log filter set to {} (was {})", filter, previous);
+                            }
+                            Ok(Box::new(()))
+                        }
+                        Err(e) => rpc_error!(e),
+                    }
+                } else {
+                    match client.private.node_get_log_filter().await {
+                        Ok(filter) => Ok(Box::new(filter)),
+                        Err(e) => rpc_error!(e),
+                    }
+                }
+            }
+
+            Command::node_reload_config => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.node_reload_config().await {
+                    Ok(report) => Ok(Box::new(report)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_peers_details => {
+                if !parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                match client.private.get_peers_details().await {
+                    Ok(peers) => Ok(Box::new(peers)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_ban_by_ip => {
                 let ips = parse_vec::<IpAddr>(parameters)?;
                 match client.private.node_ban_by_ip(ips).await {
@@ -974,77 +1423,433 @@ impl Command {
                 )
                 .await
             }
-            Command::when_moon => {
-                let res = "At night 🌔.";
-                if !json {
-                    println!("{}", res);
-                }
-                Ok(Box::new(()))
-            }
-            Command::execute_smart_contract => {
+            Command::send_batch_transaction => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                if parameters.len() != 5 {
+                if parameters.len() < 4 || parameters.len() % 2 != 0 {
                     bail!("wrong number of parameters");
                 }
                 let addr = parameters[0].parse::<Address>()?;
-                let path = parameters[1].parse::<PathBuf>()?;
-                let max_gas = parameters[2].parse::<u64>()?;
-                let max_coins = parameters[3].parse::<Amount>()?;
-                let fee = parameters[4].parse::<Amount>()?;
+                let fee = parameters[1].parse::<Amount>()?;
+                let transfers = parameters[2..]
+                    .chunks(2)
+                    .map(|transfer| {
+                        Ok((
+                            transfer[0].parse::<Address>()?,
+                            transfer[1].parse::<Amount>()?,
+                        ))
+                    })
+                    .collect::<Result<Vec<(Address, Amount)>>>()?;
+                if transfers.len() > MAX_BATCH_TRANSACTION_TRANSFERS as usize {
+                    bail!(
+                        "a batch transaction can not contain more than {} transfers",
+                        MAX_BATCH_TRANSACTION_TRANSFERS
+                    );
+                }
+
                 if !json {
+                    let total = transfers
+                        .iter()
+                        .fold(fee, |total, (_, amount)| total.saturating_add(*amount));
                     if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
                         match addresses_info.first() {
                             Some(info) => {
-                                if info.candidate_balance < fee.saturating_add(max_coins) {
+                                if info.candidate_balance < total {
                                     client_warning!("this operation may be rejected due to insufficient balance");
                                 }
                             }
                             None => {
-                                client_warning!(format!("address {} not found", addr));
+                                client_warning!(format!("address {} not found", addr))
                             }
                         }
                     }
-                };
-                let data = get_file_as_byte_vec(&path).await?;
-                if !json {
-                    let max_block_size = match client.public.get_status().await {
-                        Ok(node_status) => node_status.config.max_block_size,
-                        Err(e) => bail!("RpcError: {}", e),
-                    };
-                    if data.len() > max_block_size as usize {
-                        client_warning!("bytecode size exceeded the maximum size of a block, operation will be rejected");
-                    }
                 }
-                let datastore = BTreeMap::new();
 
                 send_operation(
                     client,
                     wallet,
-                    OperationType::ExecuteSC {
-                        data,
-                        max_gas,
-                        max_coins,
-                        datastore,
-                    },
+                    OperationType::BatchTransaction { transfers },
                     fee,
                     addr,
                     json,
                 )
                 .await
             }
-            Command::call_smart_contract => {
-                let wallet = wallet_opt.as_mut().unwrap();
+            Command::multisig_address => {
+                if parameters.len() < 2 {
+                    bail!("wrong number of parameters");
+                }
+                let threshold = parameters[0].parse::<u32>()?;
+                let public_keys = parameters[1..]
+                    .iter()
+                    .map(|key| PublicKey::from_str(key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let policy = MultisigPolicy::new(public_keys, threshold)?;
+                Ok(Box::new(policy.address().to_string()))
+            }
 
-                if parameters.len() != 7 {
+            Command::multisig_create_transaction => {
+                if parameters.len() != 6 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
-                let target_addr = parameters[1].parse::<Address>()?;
-                let target_func = parameters[2].clone();
-                let param = parameters[3].clone().into_bytes();
-                let max_gas = parameters[4].parse::<u64>()?;
-                let coins = parameters[5].parse::<Amount>()?;
+                let threshold = parameters[0].parse::<u32>()?;
+                let public_keys = parameters[1]
+                    .split(',')
+                    .map(|key| PublicKey::from_str(key.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let recipient_address = parameters[2].parse::<Address>()?;
+                let amount = parameters[3].parse::<Amount>()?;
+                let fee = parameters[4].parse::<Amount>()?;
+                let path = parameters[5].parse::<PathBuf>()?;
+                let policy = MultisigPolicy::new(public_keys, threshold)?;
+
+                let status = match client.public.get_status().await {
+                    Ok(node_status) => node_status,
+                    Err(e) => rpc_error!(e),
+                };
+                if fee.checked_sub(status.minimal_fees).is_none() {
+                    bail!(format!(
+                        "fee is too low provided: {} , minimal_fees required: {}",
+                        fee, status.minimal_fees
+                    ));
+                }
+                // cosigners must sign before the operation expires
+                let operation = Operation {
+                    fee,
+                    expire_period: get_expire_period(&status, policy.address())?,
+                    op: OperationType::Multisig {
+                        policy,
+                        signatures: Vec::new(),
+                        op: Box::new(OperationType::Transaction {
+                            recipient_address,
+                            amount,
+                        }),
+                    },
+                };
+                let draft = MultisigDraft {
+                    operation_id: operation.compute_multisig_id(status.chain_id)?,
+                    chain_id: status.chain_id,
+                    operation,
+                };
+                draft.save(&path).await?;
+                if !json {
+                    println!("Draft written to {}, operation ID to sign:", path.display());
+                }
+                Ok(Box::new(draft.operation_id.to_string()))
+            }
+
+            Command::multisig_send => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let path = parameters[1].parse::<PathBuf>()?;
+                let draft = MultisigDraft::load(&path).await?;
+                if let OperationType::Multisig {
+                    policy, signatures, ..
+                } = &draft.operation.op
+                {
+                    policy.check_signature_indexes(signatures)?;
+                } else {
+                    bail!("the draft is not a multisig operation");
+                }
+
+                // the submitter only signs the envelope: the fee is paid by the multisig address
+                let op = wallet.create_operation(draft.operation, addr)?;
+                match client
+                    .public
+                    .send_operations(vec![OperationInput {
+                        creator_public_key: op.content_creator_pub_key,
+                        serialized_content: op.serialized_data,
+                        signature: op.signature,
+                    }])
+                    .await
+                {
+                    Ok(operation_ids) => {
+                        if !json {
+                            println!("Sent operation IDs:");
+                        }
+                        Ok(Box::new(operation_ids))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::op_build => {
+                if parameters.len() < 4 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let fee = parameters[1].parse::<Amount>()?;
+                let op = parse_operation_type(&parameters[3], &parameters[4..])?;
+
+                let (expire_period, chain_id) = if parameters[2] == "auto" {
+                    let status = match client.public.get_status().await {
+                        Ok(node_status) => node_status,
+                        Err(e) => rpc_error!(e),
+                    };
+                    if fee.checked_sub(status.minimal_fees).is_none() {
+                        client_warning!(format!(
+                            "fee is too low provided: {} , minimal_fees required: {}",
+                            fee, status.minimal_fees
+                        ));
+                    }
+                    (get_expire_period(&status, addr)?, status.chain_id)
+                } else {
+                    (parameters[2].parse::<u64>()?, client.chain_id)
+                };
+
+                let envelope = OperationEnvelope::Unsigned {
+                    chain_id,
+                    signer_address: addr,
+                    operation: Operation {
+                        fee,
+                        expire_period,
+                        op,
+                    },
+                };
+                Ok(Box::new(envelope.to_bs58_check()?))
+            }
+
+            Command::op_broadcast => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let OperationEnvelope::Signed {
+                    chain_id,
+                    operation: op,
+                } = parameters[0].parse::<OperationEnvelope>()?
+                else {
+                    bail!("the operation is not signed, use op_sign first");
+                };
+                op.verify_signature()?;
+                match client.public.get_status().await {
+                    Ok(status) if status.chain_id != chain_id => bail!(
+                        "the operation was signed for chain {} but the node runs chain {}",
+                        chain_id,
+                        status.chain_id
+                    ),
+                    Ok(_) => {}
+                    Err(e) => rpc_error!(e),
+                }
+
+                match client
+                    .public
+                    .send_operations(vec![OperationInput {
+                        creator_public_key: op.content_creator_pub_key,
+                        serialized_content: op.serialized_data,
+                        signature: op.signature,
+                    }])
+                    .await
+                {
+                    Ok(operation_ids) => {
+                        if !json {
+                            println!("Sent operation IDs:");
+                        }
+                        Ok(Box::new(operation_ids))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::when_moon => {
+                let res = "At night 🌔.";
+                if !json {
+                    println!("{}", res);
+                }
+                Ok(Box::new(()))
+            }
+            Command::execute_smart_contract => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 5 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let path = parameters[1].parse::<PathBuf>()?;
+                let max_gas = parameters[2].parse::<u64>()?;
+                let max_coins = parameters[3].parse::<Amount>()?;
+                let fee = parameters[4].parse::<Amount>()?;
+                if !json {
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match addresses_info.first() {
+                            Some(info) => {
+                                if info.candidate_balance < fee.saturating_add(max_coins) {
+                                    client_warning!("this operation may be rejected due to insufficient balance");
+                                }
+                            }
+                            None => {
+                                client_warning!(format!("address {} not found", addr));
+                            }
+                        }
+                    }
+                };
+                let data = get_file_as_byte_vec(&path).await?;
+                if !json {
+                    let max_block_size = match client.public.get_status().await {
+                        Ok(node_status) => node_status.config.max_block_size,
+                        Err(e) => bail!("RpcError: {}", e),
+                    };
+                    if data.len() > max_block_size as usize {
+                        client_warning!("bytecode size exceeded the maximum size of a block, operation will be rejected");
+                    }
+                }
+                let datastore = BTreeMap::new();
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::ExecuteSC {
+                        data,
+                        max_gas,
+                        max_coins,
+                        datastore,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+            Command::deploy_contract => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() < 3 || parameters.len() > 5 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let bytecode = get_file_as_byte_vec(&parameters[1].parse::<PathBuf>()?).await?;
+                let fee = parameters[2].parse::<Amount>()?;
+                let coins = parameters
+                    .get(3)
+                    .map(|coins| coins.parse::<Amount>())
+                    .transpose()?
+                    .unwrap_or_default();
+                // the arguments are already serialized with the Args encoding, they are passed as is to the constructor
+                let constructor_args = match parameters.get(4) {
+                    Some(args) => match args.strip_prefix('@') {
+                        Some(path) => get_file_as_byte_vec(Path::new(path)).await?,
+                        None => {
+                            hex::decode(args.strip_prefix("0x").unwrap_or(args)).map_err(|e| {
+                                anyhow!("ConstructorArgs must be serialized Args in hex: {}", e)
+                            })?
+                        }
+                    },
+                    None => Vec::new(),
+                };
+                let settings = &SETTINGS.deploy;
+                let Some(deployer_bytecode_path) = &settings.deployer_bytecode_path else {
+                    bail!("no deployer contract set: set deploy.deployer_bytecode_path in the client config to a deployer bytecode, such as the deployer.wasm of massa-sc-toolkit");
+                };
+                let deployer = get_file_as_byte_vec(deployer_bytecode_path)
+                    .await
+                    .map_err(|e| {
+                        anyhow!(
+                            "could not read the deployer bytecode {}: {}",
+                            deployer_bytecode_path.display(),
+                            e
+                        )
+                    })?;
+                let contracts = vec![ContractToDeploy {
+                    bytecode,
+                    constructor_args,
+                    coins,
+                }];
+
+                let estimate = match client
+                    .public
+                    .estimate_deployment(addr, deployer.clone(), &contracts, MAX_GAS_PER_BLOCK)
+                    .await
+                {
+                    Ok(estimate) => estimate,
+                    Err(e) => bail!("could not estimate the deployment: {}", e),
+                };
+                let with_margin = |value: u64| {
+                    value.saturating_mul(100 + settings.estimation_margin_percent) / 100
+                };
+                let max_gas = with_margin(estimate.gas_cost).min(MAX_GAS_PER_BLOCK);
+                let max_coins = Amount::from_raw(with_margin(estimate.storage_cost.to_raw()))
+                    .saturating_add(estimate.constructor_coins);
+                if !json {
+                    println!(
+                        "Estimated gas cost: {}, storage cost: {}, sending with max gas {} and max coins {}",
+                        estimate.gas_cost, estimate.storage_cost, max_gas, max_coins
+                    );
+                }
+
+                let operation_id = submit_operation(
+                    client,
+                    wallet,
+                    OperationType::ExecuteSC {
+                        data: deployer,
+                        max_gas,
+                        max_coins,
+                        datastore: deployer_datastore(&contracts),
+                    },
+                    fee,
+                    addr,
+                )
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("the deployment operation was not accepted by the node"))?;
+                if !json {
+                    println!(
+                        "Sent operation {}, waiting for it to be final...",
+                        operation_id
+                    );
+                }
+                let info = match client
+                    .public
+                    .wait_for_final_operation(
+                        operation_id,
+                        settings.finality_poll_interval.to_duration(),
+                        settings.finality_timeout.to_duration(),
+                    )
+                    .await
+                {
+                    Ok(info) => info,
+                    Err(e) => rpc_error!(e),
+                };
+                let (events, addresses) =
+                    match client.public.get_deployed_addresses(operation_id).await {
+                        Ok(res) => res,
+                        Err(e) => rpc_error!(e),
+                    };
+                if info.op_exec_status == Some(false) || addresses.is_empty() {
+                    bail!(
+                        "the deployment operation {} failed, its events are:\n{}",
+                        operation_id,
+                        events
+                            .iter()
+                            .map(|event| event.data.as_str())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    );
+                }
+                Ok(Box::new(Deployment {
+                    operation_id,
+                    addresses,
+                    estimated_gas_cost: estimate.gas_cost,
+                    estimated_storage_cost: estimate.storage_cost,
+                    max_gas,
+                    max_coins,
+                    events,
+                }))
+            }
+            Command::call_smart_contract => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 7 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let target_addr = parameters[1].parse::<Address>()?;
+                let target_func = parameters[2].clone();
+                let param = parameters[3].clone().into_bytes();
+                let max_gas = parameters[4].parse::<u64>()?;
+                let coins = parameters[5].parse::<Amount>()?;
                 let fee = parameters[6].parse::<Amount>()?;
                 if !json {
                     match coins.checked_add(fee) {
@@ -1172,7 +1977,7 @@ impl Command {
             Command::node_bootstrap_blacklist => {
                 if parameters.is_empty() {
                     match client.private.node_bootstrap_blacklist().await {
-                        Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
+                        Ok(bootstraplist) => Ok(Box::new(bootstraplist)),
                         Err(e) => rpc_error!(e),
                     }
                 } else {
@@ -1184,12 +1989,15 @@ impl Command {
                     };
                     let args = &parameters[1..];
                     if args.is_empty() {
-                        bail!("[IpAddr] parameter shouldn't be empty");
+                        bail!("[IpAddr/Cidr or NodeId] parameter shouldn't be empty");
                     }
-                    let ips = parse_vec::<IpAddr>(args)?;
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
-                            match client.private.node_add_to_bootstrap_blacklist(ips).await {
+                            match client
+                                .private
+                                .node_add_to_bootstrap_blacklist(parse_vec::<AccessEntry>(args)?)
+                                .await
+                            {
                                 Ok(()) => {
                                     if !json {
                                         println!(
@@ -1204,7 +2012,9 @@ impl Command {
                         ListOperation::Remove => {
                             match client
                                 .private
-                                .node_remove_from_bootstrap_blacklist(ips)
+                                .node_remove_from_bootstrap_blacklist(parse_vec::<AccessTarget>(
+                                    args,
+                                )?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1226,7 +2036,7 @@ impl Command {
             Command::node_bootstrap_whitelist => {
                 if parameters.is_empty() {
                     match client.private.node_bootstrap_whitelist().await {
-                        Ok(bootstraplist_ips) => Ok(Box::new(bootstraplist_ips)),
+                        Ok(bootstraplist) => Ok(Box::new(bootstraplist)),
                         Err(e) => {
                             client_warning!("if bootstrap whitelist configuration file does't exists, bootstrap is allowed for everyone !!!");
                             rpc_error!(e)
@@ -1243,11 +2053,11 @@ impl Command {
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr/Cidr or NodeId] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_add_to_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_add_to_bootstrap_whitelist(parse_vec::<AccessEntry>(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1263,11 +2073,13 @@ impl Command {
                         }
                         ListOperation::Remove => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr/Cidr or NodeId] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_remove_from_bootstrap_whitelist(parse_vec::<AccessTarget>(
+                                    args,
+                                )?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1299,7 +2111,7 @@ impl Command {
             Command::node_peers_whitelist => {
                 if parameters.is_empty() {
                     match client.private.node_peers_whitelist().await {
-                        Ok(peerlist_ips) => Ok(Box::new(peerlist_ips)),
+                        Ok(peerlist) => Ok(Box::new(peerlist)),
                         Err(e) => rpc_error!(e),
                     }
                 } else {
@@ -1311,12 +2123,15 @@ impl Command {
                     };
                     let args = &parameters[1..];
                     if args.is_empty() {
-                        bail!("[IpAddr] parameter shouldn't be empty");
+                        bail!("[IpAddr/Cidr or NodeId] parameter shouldn't be empty");
                     }
-                    let ips = parse_vec::<IpAddr>(args)?;
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
-                            match client.private.node_add_to_peers_whitelist(ips).await {
+                            match client
+                                .private
+                                .node_add_to_peers_whitelist(parse_vec::<AccessEntry>(args)?)
+                                .await
+                            {
                                 Ok(()) => {
                                     if !json {
                                         println!("Request of peers whitelisting successfully sent!")
@@ -1327,7 +2142,11 @@ impl Command {
                             }
                         }
                         ListOperation::Remove => {
-                            match client.private.node_remove_from_peers_whitelist(ips).await {
+                            match client
+                                .private
+                                .node_remove_from_peers_whitelist(parse_vec::<AccessTarget>(args)?)
+                                .await
+                            {
                                 Ok(()) => {
                                     if !json {
                                         println!("Request of remove from peers whitelist successfully sent!")
@@ -1344,9 +2163,154 @@ impl Command {
                     res
                 }
             }
+            Command::node_peers_blacklist => {
+                if parameters.is_empty() {
+                    match client.private.node_peers_blacklist().await {
+                        Ok(peerlist) => Ok(Box::new(peerlist)),
+                        Err(e) => rpc_error!(e),
+                    }
+                } else {
+                    let cli_op = match parameters[0].parse::<ListOperation>() {
+                        Ok(op) => op,
+                        Err(_) => bail!(
+                            "failed to parse operation, supported operations are: [add, remove]"
+                        ),
+                    };
+                    let args = &parameters[1..];
+                    if args.is_empty() {
+                        bail!("[IpAddr/Cidr or NodeId] parameter shouldn't be empty");
+                    }
+                    let res: Result<Box<dyn Output>> = match cli_op {
+                        ListOperation::Add => {
+                            match client
+                                .private
+                                .node_add_to_peers_blacklist(parse_vec::<AccessEntry>(args)?)
+                                .await
+                            {
+                                Ok(()) => {
+                                    if !json {
+                                        println!("Request of peers blacklisting successfully sent!")
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::Remove => {
+                            match client
+                                .private
+                                .node_remove_from_peers_blacklist(parse_vec::<AccessTarget>(args)?)
+                                .await
+                            {
+                                Ok(()) => {
+                                    if !json {
+                                        println!("Request of remove from peers blacklist successfully sent!")
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::AllowAll => {
+                            bail!("\"allow-all\" command is not implemented")
+                        }
+                    };
+                    res
+                }
+            }
+            Command::node_export_block_graph => {
+                if parameters.len() != 2 && parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let format = parameters[0].parse::<BlockGraphFormat>()?;
+                let path = Path::new(&parameters[1]);
+                let (start, end) = if parameters.len() == 4 {
+                    (
+                        Some(Slot::new(parameters[2].parse::<u64>()?, 0)),
+                        Some(Slot::new(parameters[3].parse::<u64>()?, 0)),
+                    )
+                } else {
+                    (None, None)
+                };
+                match client.private.node_export_block_graph(start, end).await {
+                    Ok(dump) => {
+                        save_block_graph(&dump, format, path).await?;
+                        Ok(Box::new(format!(
+                            "{} blocks exported to {}",
+                            dump.blocks.len(),
+                            path.display()
+                        )))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+            Command::node_compare_state_hashes => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let addrs = parse_vec::<SocketAddr>(parameters)?;
+                let mut others = Vec::with_capacity(addrs.len());
+                for addr in addrs.iter() {
+                    others.push(
+                        RpcClient::from_url(&format!("http://{}", addr), &client.http_config).await,
+                    );
+                }
+                let nodes: Vec<(String, &RpcClient)> =
+                    std::iter::once(("connected node".to_string(), &client.public))
+                        .chain(addrs.iter().map(|addr| addr.to_string()).zip(others.iter()))
+                        .collect();
+                Ok(Box::new(compare_state_hashes(&nodes).await?))
+            }
+            Command::get_draw_inputs => {
+                if parameters.is_empty() || parameters.len() > 2 {
+                    bail!("wrong number of parameters");
+                }
+                let cycle = parameters[0].parse::<u64>()?;
+                let info = match client.public.get_draw_inputs(vec![cycle]).await {
+                    Ok(mut inputs) => inputs.pop().flatten().ok_or_else(|| {
+                        anyhow!("cycle {} is not in the cycle history of the node", cycle)
+                    })?,
+                    Err(e) => rpc_error!(e),
+                };
+                if let Some(path) = parameters.get(1) {
+                    tokio::fs::write(path, serde_json::to_vec_pretty(&info)?).await?;
+                    if !json {
+                        println!("Draw inputs of cycle {} saved to {}", cycle, path);
+                    }
+                }
+                Ok(Box::new(info))
+            }
+            Command::run_script => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let path = Path::new(&parameters[0]);
+                let script = Script::load(path).await?;
+                let mut runner = ScriptRunner::new(client, wallet_opt, &parameters[1..], json)?;
+                runner.run(&script).await?;
+                Ok(Box::new(format!(
+                    "script {} completed, {} commands run",
+                    path.display(),
+                    runner.commands_run
+                )))
+            }
+            Command::watch_address => Ok(Box::new(
+                watch::watch_address(client, parameters, json).await?,
+            )),
+            Command::watch_operation => Ok(Box::new(
+                watch::watch_operation(client, parameters, json).await?,
+            )),
+            Command::watch_events => Ok(Box::new(
+                watch::watch_events(client, parameters, json).await?,
+            )),
             Command::exit => {
                 std::process::exit(0);
             }
+            // returned early, they must not reach the node
+            Command::op_sign
+            | Command::multisig_sign
+            | Command::verify_draws
+            | Command::render_block_graph => unreachable!("offline command {}", self),
         }
     }
 }
@@ -1360,6 +2324,21 @@ async fn send_operation(
     addr: Address,
     json: bool,
 ) -> Result<Box<dyn Output>> {
+    let operation_ids = submit_operation(client, wallet, op, fee, addr).await?;
+    if !json {
+        println!("Sent operation IDs:");
+    }
+    Ok(Box::new(operation_ids))
+}
+
+/// Creates, signs and sends an operation, returning the IDs accepted by the node
+async fn submit_operation(
+    client: &Client,
+    wallet: &Wallet,
+    op: OperationType,
+    fee: Amount,
+    addr: Address,
+) -> Result<Vec<OperationId>> {
     let status = match client.public.get_status().await {
         Ok(node_status) => node_status,
         Err(e) => rpc_error!(e),
@@ -1373,21 +2352,10 @@ async fn send_operation(
         ));
     }
 
-    let slot = get_current_latest_block_slot(
-        status.config.thread_count,
-        status.config.t0,
-        status.config.genesis_timestamp,
-    )?
-    .unwrap_or_else(|| Slot::new(0, 0));
-    let mut expire_period = slot.period + status.config.operation_validity_periods;
-    if slot.thread >= addr.get_thread(status.config.thread_count) {
-        expire_period += 1;
-    };
-
     let op = wallet.create_operation(
         Operation {
             fee,
-            expire_period,
+            expire_period: get_expire_period(&status, addr)?,
             op,
         },
         addr,
@@ -1402,16 +2370,176 @@ async fn send_operation(
         }])
         .await
     {
-        Ok(operation_ids) => {
-            if !json {
-                println!("Sent operation IDs:");
+        Ok(operation_ids) => Ok(operation_ids),
+        Err(e) => rpc_error!(e),
+    }
+}
+
+/// Parse the type and parameters of an operation built by `op_build`
+fn parse_operation_type(kind: &str, parameters: &[String]) -> Result<OperationType> {
+    let op = match (kind, parameters) {
+        ("transaction", [recipient_address, amount]) => OperationType::Transaction {
+            recipient_address: recipient_address.parse::<Address>()?,
+            amount: amount.parse::<Amount>()?,
+        },
+        ("buy_rolls", [roll_count]) => OperationType::RollBuy {
+            roll_count: roll_count.parse::<u64>()?,
+        },
+        ("sell_rolls", [roll_count]) => OperationType::RollSell {
+            roll_count: roll_count.parse::<u64>()?,
+        },
+        ("call_smart_contract", [target_addr, target_func, param, max_gas, coins]) => {
+            OperationType::CallSC {
+                target_addr: target_addr.parse::<Address>()?,
+                target_func: target_func.clone(),
+                param: param.clone().into_bytes(),
+                max_gas: max_gas.parse::<u64>()?,
+                coins: coins.parse::<Amount>()?,
             }
-            Ok(Box::new(operation_ids))
         }
-        Err(e) => rpc_error!(e),
+        ("transaction" | "buy_rolls" | "sell_rolls" | "call_smart_contract", _) => {
+            bail!("wrong number of parameters for operation type {}", kind)
+        }
+        _ => bail!("unknown operation type {}", kind),
+    };
+    Ok(op)
+}
+
+/// Expire period of an operation emitted now by `addr`
+fn get_expire_period(status: &NodeStatus, addr: Address) -> Result<u64> {
+    let slot = get_current_latest_block_slot(
+        status.config.thread_count,
+        status.config.t0,
+        status.config.genesis_timestamp,
+    )?
+    .unwrap_or_else(|| Slot::new(0, 0));
+    let mut expire_period = slot.period + status.config.operation_validity_periods;
+    if slot.thread >= addr.get_thread(status.config.thread_count) {
+        expire_period += 1;
+    };
+    Ok(expire_period)
+}
+
+/// Multisig operation being signed offline by its cosigners, stored as JSON
+#[derive(Serialize, Deserialize)]
+struct MultisigDraft {
+    /// ID signed by the cosigners
+    operation_id: OperationId,
+    /// chain on which the operation is valid, part of the ID
+    chain_id: u64,
+    /// operation content, signatures included
+    operation: Operation,
+}
+
+impl MultisigDraft {
+    async fn load(path: &std::path::Path) -> Result<Self> {
+        Ok(serde_json::from_slice(&get_file_as_byte_vec(path).await?)?)
+    }
+
+    async fn save(&self, path: &std::path::Path) -> Result<()> {
+        Ok(tokio::fs::write(path, serde_json::to_vec_pretty(self)?).await?)
+    }
+}
+
+/// File format of a block graph export
+enum BlockGraphFormat {
+    Json,
+    Dot,
+}
+
+impl FromStr for BlockGraphFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(BlockGraphFormat::Json),
+            "dot" => Ok(BlockGraphFormat::Dot),
+            _ => bail!("unknown block graph format {}, expected json or dot", s),
+        }
     }
 }
 
+/// Number of slots whose hashes are requested at once, below the default `max_arguments` of the API
+const STATE_HASHES_BATCH_SIZE: usize = 100;
+
+/// Walks back from the last slot final on all the nodes until their final state hashes agree.
+/// The execution trail hash summarizes all the previous slots, so the nodes agree on all the slots before.
+async fn compare_state_hashes(nodes: &[(String, &RpcClient)]) -> Result<StateHashesComparison> {
+    let mut last_common_final_slot: Option<Slot> = None;
+    let mut thread_count = 0;
+    for (name, rpc) in nodes {
+        let status = match rpc.get_status().await {
+            Ok(status) => status,
+            Err(e) => bail!("could not get the status of {}: {}", name, e),
+        };
+        thread_count = status.config.thread_count;
+        let final_cursor = status.execution_stats.final_cursor;
+        last_common_final_slot = Some(match last_common_final_slot {
+            Some(slot) => std::cmp::min(slot, final_cursor),
+            None => final_cursor,
+        });
+    }
+    let last_common_final_slot =
+        last_common_final_slot.ok_or_else(|| anyhow!("no node to compare"))?;
+
+    let mut comparison = StateHashesComparison {
+        nodes: nodes.iter().map(|(name, _)| name.clone()).collect(),
+        last_common_final_slot,
+        last_agreeing_slot: None,
+        first_divergent_slot: None,
+        divergent_hashes: Vec::new(),
+    };
+    let mut next_slot = Some(last_common_final_slot);
+    while next_slot.is_some() {
+        let mut slots = Vec::with_capacity(STATE_HASHES_BATCH_SIZE);
+        while let Some(slot) = next_slot {
+            if slots.len() == STATE_HASHES_BATCH_SIZE {
+                break;
+            }
+            slots.push(slot);
+            next_slot = slot.get_prev_slot(thread_count).ok();
+        }
+
+        let mut answers = Vec::with_capacity(nodes.len());
+        for (name, rpc) in nodes {
+            match rpc.get_final_state_hashes(slots.clone()).await {
+                Ok(hashes) => answers.push(hashes),
+                Err(e) => bail!("could not get the state hashes of {}: {}", name, e),
+            }
+        }
+        for (index, slot) in slots.iter().enumerate() {
+            let hashes: Vec<Option<FinalStateHashesInfo>> = answers
+                .iter()
+                .map(|node_hashes| node_hashes.get(index).cloned().flatten())
+                .collect();
+            if hashes.iter().any(Option::is_none) {
+                // out of the history of at least one node
+                return Ok(comparison);
+            }
+            if hashes.windows(2).all(|pair| pair[0] == pair[1]) {
+                comparison.last_agreeing_slot = Some(*slot);
+                return Ok(comparison);
+            }
+            comparison.first_divergent_slot = Some(*slot);
+            comparison.divergent_hashes = hashes;
+        }
+    }
+    Ok(comparison)
+}
+
+/// Write a block graph export to a file in the given format
+async fn save_block_graph(
+    dump: &BlockGraphDump,
+    format: BlockGraphFormat,
+    path: &Path,
+) -> Result<()> {
+    let content = match format {
+        BlockGraphFormat::Json => serde_json::to_vec_pretty(dump)?,
+        BlockGraphFormat::Dot => dump.to_dot().into_bytes(),
+    };
+    Ok(tokio::fs::write(path, content).await?)
+}
+
 /// TODO: ugly utilities functions
 /// takes a slice of string and makes it into a `Vec<T>`
 pub fn parse_vec<T: std::str::FromStr>(args: &[String]) -> anyhow::Result<Vec<T>, anyhow::Error>
@@ -1426,6 +2554,64 @@ where
         .collect()
 }
 
+/// Reproduces the draws of a cycle from their inputs, after checking the roll distribution hash.
+/// The network parameters are the ones the client is built with.
+fn verify_draws(info: DrawInputsInfo, addresses: &[Address]) -> Result<DrawVerification> {
+    let inputs = DrawInputs::from(info.clone());
+    let roll_distribution_hash = inputs.roll_distribution_hash();
+    if roll_distribution_hash != info.roll_distribution_hash {
+        bail!(
+            "the roll distribution hash of the rolls is {} but the inputs announce {}",
+            roll_distribution_hash,
+            info.roll_distribution_hash
+        );
+    }
+    let cfg = SelectorConfig {
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+        channel_size: CHANNEL_SIZE,
+    };
+    let total_rolls = inputs.lookback_rolls.values().sum();
+    let cycle_draws = perform_draws(
+        &cfg,
+        inputs.cycle,
+        inputs.lookback_rolls,
+        inputs.lookback_seed,
+    )
+    .map_err(|err| anyhow!("could not reproduce the draws: {}", err))?;
+
+    let mut selections: BTreeMap<Address, AddressDraws> = addresses
+        .iter()
+        .map(|address| (*address, AddressDraws::default()))
+        .collect();
+    let mut digest_bytes = Vec::new();
+    let draws: BTreeMap<_, _> = cycle_draws.draws.into_iter().collect();
+    for (slot, selection) in draws {
+        digest_bytes.extend(selection.producer.to_prefixed_bytes());
+        if let Some(draws) = selections.get_mut(&selection.producer) {
+            draws.block_slots.push(slot);
+        }
+        for (index, endorser) in selection.endorsements.iter().enumerate() {
+            digest_bytes.extend(endorser.to_prefixed_bytes());
+            if let Some(draws) = selections.get_mut(endorser) {
+                draws.endorsement_slots.push((slot, index));
+            }
+        }
+    }
+
+    Ok(DrawVerification {
+        cycle: inputs.cycle,
+        roll_distribution_hash,
+        lookback_seed: inputs.lookback_seed,
+        total_rolls,
+        draws_digest: Hash::compute_from(&digest_bytes),
+        selections,
+    })
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
@@ -1,13 +1,20 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::cmds::ExtendedWallet;
+use crate::cmds::{Deployment, DrawVerification, ExtendedWallet, StateHashesComparison};
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::AddressInfo,
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::{NodeStatus, PeerDetailsInfo},
     operation::OperationInfo,
+    reload::ConfigReloadReport,
+    rolls::DrawInputsInfo,
 };
+use massa_models::access_list::AccessEntry;
 use massa_models::composite::PubkeySig;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
@@ -15,7 +22,6 @@ use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
-use std::net::IpAddr;
 use std::str;
 
 #[macro_export]
@@ -120,6 +126,16 @@ impl dyn Output {
         self.erased_serialize(&mut format)?;
         Ok(())
     }
+
+    pub(crate) fn to_json_value(&self) -> anyhow::Result<serde_json::Value> {
+        let mut bytes = Vec::new();
+        {
+            let json = &mut serde_json::Serializer::new(&mut bytes);
+            let mut format: Box<dyn Serializer> = Box::new(<dyn Serializer>::erase(json));
+            self.erased_serialize(&mut format)?;
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
 }
 
 impl Output for Wallet {
@@ -243,6 +259,9 @@ impl Output for NodeStatus {
             );
         }
         println!("Next slot: {}", Style::Protocol.style(self.next_slot));
+        if self.is_syncing {
+            println!("{}", Style::Pending.style("Resynchronizing"));
+        }
         println!();
 
         self.consensus_stats.pretty_print();
@@ -278,6 +297,137 @@ impl Output for NodeStatus {
     }
 }
 
+impl Output for StateHashesComparison {
+    fn pretty_print(&self) {
+        println!(
+            "Last slot final on all the nodes: {}",
+            Style::Protocol.style(self.last_common_final_slot)
+        );
+        match (self.last_agreeing_slot, self.first_divergent_slot) {
+            (Some(_), None) => println!("{}", Style::Good.style("All the nodes agree")),
+            (Some(agreeing), Some(divergent)) => println!(
+                "{} at slot {}, the nodes agreed up to slot {}",
+                Style::Bad.style("Diverged"),
+                Style::Protocol.style(divergent),
+                Style::Protocol.style(agreeing)
+            ),
+            (None, Some(divergent)) => println!(
+                "{} at or before slot {}, older slots are not in the state hash history of all the nodes",
+                Style::Bad.style("Diverged"),
+                Style::Protocol.style(divergent)
+            ),
+            (None, None) => client_warning!(
+                "the last common final slot is not in the state hash history of all the nodes"
+            ),
+        }
+        for (node, hashes) in self.nodes.iter().zip(self.divergent_hashes.iter()) {
+            if let Some(hashes) = hashes {
+                println!(
+                    "{}: execution trail hash {}, fingerprint {}",
+                    node, hashes.execution_trail_hash, hashes.fingerprint
+                );
+            }
+        }
+    }
+}
+
+impl Output for Deployment {
+    fn pretty_print(&self) {
+        println!("Operation: {}", Style::Id.style(self.operation_id));
+        println!(
+            "Estimated gas cost: {}, storage cost: {}",
+            self.estimated_gas_cost,
+            Style::Coins.style(self.estimated_storage_cost)
+        );
+        println!(
+            "Max gas: {}, max coins: {}",
+            self.max_gas,
+            Style::Coins.style(self.max_coins)
+        );
+        for event in self.events.iter() {
+            println!("Event: {}", event.data);
+        }
+        for address in self.addresses.iter() {
+            println!(
+                "{} {}",
+                Style::Good.style("Contract deployed at"),
+                Style::Wallet.style(address)
+            );
+        }
+    }
+}
+
+impl Output for ConfigReloadReport {
+    fn pretty_print(&self) {
+        if self.applied.is_empty() && self.requires_restart.is_empty() && self.failed.is_empty() {
+            println!("No configuration change");
+        }
+        for key in &self.applied {
+            println!("{} {}", Style::Good.style("applied"), key);
+        }
+        for key in &self.requires_restart {
+            println!("{} {}", Style::Bad.style("requires a restart"), key);
+        }
+        for key in &self.failed {
+            println!("{} {}", Style::Bad.style("failed"), key);
+        }
+    }
+}
+
+impl Output for Vec<PeerDetailsInfo> {
+    fn pretty_print(&self) {
+        if self.is_empty() {
+            println!("No peer");
+        }
+        for peer in self {
+            println!("{}", peer);
+        }
+    }
+}
+
+impl Output for DrawInputsInfo {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for DrawVerification {
+    fn pretty_print(&self) {
+        println!(
+            "Draws of cycle {} reproduced from {} rolls",
+            Style::Protocol.style(self.cycle),
+            self.total_rolls
+        );
+        println!(
+            "Roll distribution hash: {}",
+            Style::Good.style(self.roll_distribution_hash)
+        );
+        println!("Lookback seed: {}", self.lookback_seed);
+        println!("Draws digest: {}", Style::Id.style(self.draws_digest));
+        for (address, draws) in self.selections.iter() {
+            println!("{}:", Style::Wallet.style(address));
+            println!(
+                "\tBlock slots: {}",
+                draws
+                    .block_slots
+                    .iter()
+                    .map(|slot| slot.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            println!(
+                "\tEndorsement slots: {}",
+                draws
+                    .endorsement_slots
+                    .iter()
+                    .map(|(slot, index)| format!("{} (index {})", slot, index))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+}
+
 impl Output for ExecutionStats {
     fn pretty_print(&self) {
         println!("Execution stats:");
@@ -486,10 +636,10 @@ impl Output for Vec<EndorsementInfo> {
     }
 }
 
-impl Output for Vec<IpAddr> {
+impl Output for Vec<AccessEntry> {
     fn pretty_print(&self) {
-        for ips in self {
-            println!("{}", ips);
+        for entry in self {
+            println!("{}", entry);
         }
     }
 }
@@ -20,7 +20,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 mod cmds;
 mod display;
 mod repl;
+mod script;
 mod settings;
+mod watch;
 
 #[cfg(test)]
 pub mod tests;
@@ -157,19 +159,21 @@ async fn run(args: Args) -> Result<()> {
         std::process::exit(1);
     }));
 
-    // Note: grpc handler requires a mut handler
-    let mut client = Client::new(
-        address,
-        public_port,
-        private_port,
-        grpc_port,
-        grpc_priv_port,
-        chain_id,
-        &http_config,
-    )
-    .await?;
+    let connect = || {
+        Client::new(
+            address,
+            public_port,
+            private_port,
+            grpc_port,
+            grpc_priv_port,
+            chain_id,
+            &http_config,
+        )
+    };
     if std::io::stdout().is_terminal() && args.command == Command::help && !args.json {
         // Interactive mode
+        // Note: grpc handler requires a mut handler
+        let mut client = connect().await?;
         repl::run(&mut client, &args.wallet, args.password).await?;
     } else {
         // Non-Interactive mode
@@ -189,11 +193,18 @@ async fn run(args: Args) -> Result<()> {
             false => None,
         };
 
-        match args
-            .command
-            .run(&mut client, &mut wallet_opt, &args.parameters, args.json)
-            .await
-        {
+        // offline commands do not even try to connect to the node
+        let res = if args.command.is_offline() {
+            args.command
+                .run_offline(&mut wallet_opt, &args.parameters, args.json)
+                .await
+        } else {
+            let mut client = connect().await?;
+            args.command
+                .run(&mut client, &mut wallet_opt, &args.parameters, args.json)
+                .await
+        };
+        match res {
             Ok(output) => {
                 if args.json {
                     output
@@ -213,6 +224,10 @@ async fn run(args: Args) -> Result<()> {
                 } else {
                     println!("{}", style(format!("Error: {}", e)).red());
                 }
+                // scripts and watches are used in automation, which needs to know how they stopped
+                if args.command.sets_exit_code() {
+                    std::process::exit(watch::exit_code(&e));
+                }
             }
         }
     }
@@ -0,0 +1,424 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Scripts of client commands, run with `massa-client run ScriptPath [Name=Value ...]`.
+//!
+//! A script is a text file with one statement per line:
+//! - `# ...`: comment
+//! - `Command Param1 Param2 ...`: runs a client command and prints its output
+//! - `let Name = Command Param1 Param2 ...`: runs a client command and stores its JSON output in `Name`
+//! - `set Name Value`: stores `Value` in `Name`, parsed as JSON if possible
+//! - `echo Text`: prints `Text`
+//! - `assert Value`: fails if `Value` is empty, `false`, `null` or `0`
+//! - `assert Value1 Op Value2`: fails if the comparison is false, `Op` being one of `== != < <= > >=`
+//! - `for Name in List` ... `end`: runs the enclosed statements for each item of `List`,
+//!   a JSON array (e.g. `${addresses}`) or a list of words
+//!
+//! `${Name}` is replaced by the value of a variable, and `${Name.key.0}` by a field of its JSON value.
+//! The variable `wallet_addresses` holds the addresses of the wallet.
+//! Parameters containing spaces are quoted as in the interactive prompt.
+//! The script stops at the first failing command or assertion.
+
+use crate::cmds::Command;
+use crate::display::Output;
+use crate::repl::group_parameters;
+use anyhow::{anyhow, bail, Context, Result};
+use massa_sdk::Client;
+use massa_wallet::Wallet;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Parsed script
+#[derive(Debug)]
+pub(crate) struct Script {
+    statements: Vec<Statement>,
+}
+
+#[derive(Debug)]
+struct Statement {
+    /// line of the statement in the script, starting at 1
+    line: usize,
+    kind: StatementKind,
+}
+
+#[derive(Debug)]
+enum StatementKind {
+    Run {
+        capture: Option<String>,
+        command: Command,
+        parameters: Vec<String>,
+    },
+    Set {
+        name: String,
+        value: Vec<String>,
+    },
+    Echo(Vec<String>),
+    Assert(Vec<String>),
+    For {
+        name: String,
+        list: String,
+        body: Vec<Statement>,
+    },
+}
+
+impl FromStr for Script {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // stack of the blocks being parsed, with the header of their `for` loop
+        let mut blocks: Vec<(Vec<Statement>, Option<(usize, String, String)>)> =
+            vec![(Vec::new(), None)];
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let tokens = group_parameters(line.split_whitespace().map(String::from).collect());
+            if tokens.is_empty() {
+                bail!("line {}: unclosed quote", line_number);
+            }
+            let kind = match tokens[0].as_str() {
+                "end" => {
+                    if tokens.len() != 1 {
+                        bail!("line {}: unexpected tokens after end", line_number);
+                    }
+                    let Some((body, Some((line, name, list)))) = blocks.pop() else {
+                        bail!("line {}: end without for", line_number);
+                    };
+                    blocks
+                        .last_mut()
+                        .expect("the top level block is never closed")
+                        .0
+                        .push(Statement {
+                            line,
+                            kind: StatementKind::For { name, list, body },
+                        });
+                    continue;
+                }
+                "for" => {
+                    if tokens.len() != 4 || tokens[2] != "in" {
+                        bail!("line {}: expected for Name in List", line_number);
+                    }
+                    check_variable_name(&tokens[1], line_number)?;
+                    blocks.push((
+                        Vec::new(),
+                        Some((line_number, tokens[1].clone(), tokens[3].clone())),
+                    ));
+                    continue;
+                }
+                "let" => {
+                    if tokens.len() < 4 || tokens[2] != "=" {
+                        bail!("line {}: expected let Name = Command ...", line_number);
+                    }
+                    check_variable_name(&tokens[1], line_number)?;
+                    StatementKind::Run {
+                        capture: Some(tokens[1].clone()),
+                        command: parse_command(&tokens[3], line_number)?,
+                        parameters: tokens[4..].to_vec(),
+                    }
+                }
+                "set" => {
+                    if tokens.len() < 3 {
+                        bail!("line {}: expected set Name Value", line_number);
+                    }
+                    check_variable_name(&tokens[1], line_number)?;
+                    StatementKind::Set {
+                        name: tokens[1].clone(),
+                        value: tokens[2..].to_vec(),
+                    }
+                }
+                "echo" => StatementKind::Echo(tokens[1..].to_vec()),
+                "assert" => {
+                    if tokens.len() != 2 && tokens.len() != 4 {
+                        bail!(
+                            "line {}: expected assert Value or assert Value1 Op Value2",
+                            line_number
+                        );
+                    }
+                    StatementKind::Assert(tokens[1..].to_vec())
+                }
+                name => StatementKind::Run {
+                    capture: None,
+                    command: parse_command(name, line_number)?,
+                    parameters: tokens[1..].to_vec(),
+                },
+            };
+            blocks
+                .last_mut()
+                .expect("the top level block is never closed")
+                .0
+                .push(Statement {
+                    line: line_number,
+                    kind,
+                });
+        }
+        match blocks.pop() {
+            Some((statements, None)) if blocks.is_empty() => Ok(Script { statements }),
+            Some((_, Some((line, _, _)))) => bail!("line {}: for without end", line),
+            _ => bail!("unbalanced for and end"),
+        }
+    }
+}
+
+fn parse_command(name: &str, line: usize) -> Result<Command> {
+    let command: Command = name
+        .parse()
+        .map_err(|_| anyhow!("line {}: unknown command {}", line, name))?;
+    match command {
+        Command::run_script | Command::exit => {
+            bail!("line {}: {} cannot be used in a script", line, command)
+        }
+        command => Ok(command),
+    }
+}
+
+fn check_variable_name(name: &str, line: usize) -> Result<()> {
+    if !is_variable_name(name) {
+        bail!("line {}: invalid variable name {}", line, name);
+    }
+    Ok(())
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Script {
+    /// Reads and parses a script file
+    pub(crate) async fn load(path: &Path) -> Result<Script> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("could not read the script {}", path.display()))?;
+        content.parse()
+    }
+}
+
+/// Runs the statements of a script, keeping its variables
+pub(crate) struct ScriptRunner<'a> {
+    client: &'a mut Client,
+    wallet_opt: &'a mut Option<Wallet>,
+    json: bool,
+    variables: Variables,
+    /// number of commands run so far
+    pub(crate) commands_run: usize,
+}
+
+impl<'a> ScriptRunner<'a> {
+    /// Creates a runner whose variables are initialized with `Name=Value` definitions
+    pub(crate) fn new(
+        client: &'a mut Client,
+        wallet_opt: &'a mut Option<Wallet>,
+        definitions: &[String],
+        json: bool,
+    ) -> Result<Self> {
+        let mut variables = HashMap::new();
+        if let Some(wallet) = wallet_opt.as_ref() {
+            let mut addresses: Vec<String> = wallet
+                .get_wallet_address_list()
+                .iter()
+                .map(|address| address.to_string())
+                .collect();
+            addresses.sort();
+            variables.insert(
+                "wallet_addresses".to_string(),
+                Value::Array(addresses.into_iter().map(Value::String).collect()),
+            );
+        }
+        for definition in definitions {
+            let (name, value) = definition
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected Name=Value, got {}", definition))?;
+            if !is_variable_name(name) {
+                bail!("invalid variable name {}", name);
+            }
+            variables.insert(name.to_string(), parse_value(value));
+        }
+        Ok(ScriptRunner {
+            client,
+            wallet_opt,
+            json,
+            variables: Variables(variables),
+            commands_run: 0,
+        })
+    }
+
+    /// Runs the script, stopping at the first error
+    pub(crate) async fn run(&mut self, script: &Script) -> Result<()> {
+        self.run_block(&script.statements).await
+    }
+
+    async fn run_block(&mut self, statements: &[Statement]) -> Result<()> {
+        for statement in statements {
+            self.run_statement(statement)
+                .await
+                .map_err(|err| anyhow!("line {}: {}", statement.line, err))?;
+        }
+        Ok(())
+    }
+
+    async fn run_statement(&mut self, statement: &Statement) -> Result<()> {
+        match &statement.kind {
+            StatementKind::Run {
+                capture,
+                command,
+                parameters,
+            } => {
+                let parameters = parameters
+                    .iter()
+                    .map(|parameter| self.variables.interpolate(parameter))
+                    .collect::<Result<Vec<_>>>()?;
+                // commands print less when their output is captured as JSON
+                let json = self.json || capture.is_some();
+                let output = Box::pin(command.run(self.client, self.wallet_opt, &parameters, json))
+                    .await
+                    .map_err(|err| anyhow!("{} failed: {}", command, err))?;
+                self.commands_run += 1;
+                match capture {
+                    Some(name) => {
+                        self.variables
+                            .0
+                            .insert(name.clone(), output.to_json_value()?);
+                    }
+                    None if self.json => {
+                        output.stdout_json()?;
+                        println!();
+                    }
+                    None => output.pretty_print(),
+                }
+            }
+            StatementKind::Set { name, value } => {
+                let value = self.variables.interpolate_all(value)?;
+                self.variables.0.insert(name.clone(), parse_value(&value));
+            }
+            StatementKind::Echo(text) => println!("{}", self.variables.interpolate_all(text)?),
+            StatementKind::Assert(tokens) => {
+                let tokens = tokens
+                    .iter()
+                    .map(|token| self.variables.interpolate(token))
+                    .collect::<Result<Vec<_>>>()?;
+                let holds = match tokens.as_slice() {
+                    [value] => !matches!(value.as_str(), "" | "false" | "null" | "0"),
+                    [left, op, right] => compare(left, op, right)?,
+                    _ => bail!("expected assert Value or assert Value1 Op Value2"),
+                };
+                if !holds {
+                    bail!("assertion failed: {}", tokens.join(" "));
+                }
+            }
+            StatementKind::For { name, list, body } => {
+                for item in self.variables.list_items(list)? {
+                    self.variables.0.insert(name.clone(), item);
+                    Box::pin(self.run_block(body)).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Variables of a script, holding JSON values
+#[derive(Debug, Default)]
+pub(crate) struct Variables(pub(crate) HashMap<String, Value>);
+
+impl Variables {
+    /// Items of the list of a `for` loop
+    pub(crate) fn list_items(&self, list: &str) -> Result<Vec<Value>> {
+        if let Some(path) = list
+            .strip_prefix("${")
+            .and_then(|list| list.strip_suffix('}'))
+        {
+            if !path.contains("${") {
+                return match self.resolve(path)? {
+                    Value::Array(items) => Ok(items),
+                    Value::String(words) => Ok(split_words(&words)),
+                    Value::Null => Ok(Vec::new()),
+                    value => bail!("cannot iterate over {}", value),
+                };
+            }
+        }
+        Ok(split_words(&self.interpolate(list)?))
+    }
+
+    pub(crate) fn interpolate_all(&self, tokens: &[String]) -> Result<String> {
+        Ok(tokens
+            .iter()
+            .map(|token| self.interpolate(token))
+            .collect::<Result<Vec<_>>>()?
+            .join(" "))
+    }
+
+    /// Replaces the `${Name.path}` references of a token by their values
+    pub(crate) fn interpolate(&self, token: &str) -> Result<String> {
+        let mut res = String::with_capacity(token.len());
+        let mut rest = token;
+        while let Some(start) = rest.find("${") {
+            res.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| anyhow!("unclosed ${{ in {}", token))?;
+            res.push_str(&value_to_string(
+                &self.resolve(&rest[start + 2..start + end])?,
+            ));
+            rest = &rest[start + end + 1..];
+        }
+        res.push_str(rest);
+        Ok(res)
+    }
+
+    /// Value of `Name.key.0`
+    pub(crate) fn resolve(&self, path: &str) -> Result<Value> {
+        let mut parts = path.split('.');
+        let name = parts.next().unwrap_or_default();
+        let mut value = self
+            .0
+            .get(name)
+            .ok_or_else(|| anyhow!("unknown variable {}", name))?;
+        for part in parts {
+            value = match value {
+                Value::Array(items) => part.parse::<usize>().ok().and_then(|i| items.get(i)),
+                Value::Object(fields) => fields.get(part),
+                _ => None,
+            }
+            .ok_or_else(|| anyhow!("{} not found in {}", part, path))?;
+        }
+        Ok(value.clone())
+    }
+}
+
+pub(crate) fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn split_words(words: &str) -> Vec<Value> {
+    words
+        .split_whitespace()
+        .map(|word| Value::String(word.to_string()))
+        .collect()
+}
+
+/// Compares two values as numbers if both are numbers, as strings otherwise
+pub(crate) fn compare(left: &str, op: &str, right: &str) -> Result<bool> {
+    let ordering = match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left
+            .partial_cmp(&right)
+            .ok_or_else(|| anyhow!("cannot compare {} and {}", left, right))?,
+        _ => left.cmp(right),
+    };
+    Ok(match op {
+        "==" => ordering.is_eq(),
+        "!=" => ordering.is_ne(),
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        ">=" => ordering.is_ge(),
+        _ => bail!("unknown operator {}", op),
+    })
+}
@@ -18,6 +18,7 @@ pub struct Settings {
     pub history_file_path: PathBuf,
     pub timeout: MassaTime,
     pub client: ClientSettings,
+    pub deploy: DeploySettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -52,6 +53,20 @@ pub struct HttpSettings {
     pub enabled: bool,
 }
 
+/// Smart contract deployment settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeploySettings {
+    /// bytecode of the deployer contract, creating the contracts listed in the operation datastore.
+    /// Not shipped with the client, `deploy_contract` requires it to be set
+    pub deployer_bytecode_path: Option<PathBuf>,
+    /// margin added to the estimated gas and storage costs, in percent
+    pub estimation_margin_percent: u64,
+    /// interval between two checks of the finality of the deployment
+    pub finality_poll_interval: MassaTime,
+    /// maximum time waiting for the deployment to be final
+    pub finality_timeout: MassaTime,
+}
+
 #[cfg(test)]
 #[test]
 fn test_load_client_config() {
@@ -1,3 +1,4 @@
 // TODO: re-enable this test, currently trying to fix it in #2275
 // mod scenarios;
+mod script;
 mod tools;
@@ -0,0 +1,70 @@
+use crate::script::{compare, parse_value, Script, Variables};
+use serde_json::json;
+
+#[test]
+fn test_script_parsing() {
+    let script = r#"
+        # check the balances of the wallet addresses
+        let status = get_status
+        assert ${status.config.thread_count} == 32
+        for address in ${wallet_addresses}
+            let info = get_addresses ${address}
+            echo "balance of ${address}:" ${info.0.final_balance}
+        end
+    "#;
+    assert!(script.parse::<Script>().is_ok());
+
+    let err = "for a in x y\necho ${a}".parse::<Script>().unwrap_err();
+    assert_eq!(err.to_string(), "line 1: for without end");
+    let err = "echo a\nend".parse::<Script>().unwrap_err();
+    assert_eq!(err.to_string(), "line 2: end without for");
+    let err = "get_stauts".parse::<Script>().unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unknown command get_stauts");
+    assert!("run other.massa".parse::<Script>().is_err());
+    assert!("let a-b = get_status".parse::<Script>().is_err());
+}
+
+#[test]
+fn test_script_variables() {
+    let mut variables = Variables::default();
+    variables.0.insert(
+        "info".to_string(),
+        json!([{"address": "AU12", "rolls": 3}, {"address": "AU34", "rolls": 0}]),
+    );
+    variables
+        .0
+        .insert("words".to_string(), parse_value("first second"));
+
+    assert_eq!(
+        variables
+            .interpolate("${info.0.address}:${info.0.rolls}")
+            .unwrap(),
+        "AU12:3"
+    );
+    assert_eq!(
+        variables.interpolate("${info.1}").unwrap(),
+        r#"{"address":"AU34","rolls":0}"#
+    );
+    assert!(variables.interpolate("${info.2}").is_err());
+    assert!(variables.interpolate("${unknown}").is_err());
+    assert!(variables.interpolate("${info").is_err());
+
+    assert_eq!(variables.list_items("${info}").unwrap().len(), 2);
+    assert_eq!(
+        variables.list_items("${words}").unwrap(),
+        vec![json!("first"), json!("second")]
+    );
+    assert_eq!(
+        variables.list_items("a,${words}").unwrap(),
+        vec![json!("a,first"), json!("second")]
+    );
+}
+
+#[test]
+fn test_script_comparisons() {
+    assert!(compare("10", ">", "9").unwrap());
+    assert!(compare("1.50", "==", "1.5").unwrap());
+    assert!(compare("abc", "!=", "abd").unwrap());
+    assert!(!compare("b", "<=", "a").unwrap());
+    assert!(compare("1", "=", "1").is_err());
+}
@@ -0,0 +1,618 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Watch commands, following addresses, operations and events live through the slot execution
+//! outputs streamed by the gRPC API, until Ctrl-C or until the requested condition is met.
+//!
+//! The exit status tells scripts how the watch ended:
+//! 0 when the condition is met (or on Ctrl-C without condition), 1 on error,
+//! 2 when the watched operation failed, 3 when it expired,
+//! 130 when interrupted before the condition was met.
+
+use anyhow::{anyhow, bail, Result};
+use futures_util::{Stream, StreamExt};
+use massa_models::{address::Address, amount::Amount, operation::OperationId, slot::Slot};
+use massa_sdk::grpc::{GrpcClient, GrpcClientError, ReceivedSlotExecutionOutput};
+use massa_sdk::Client;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Exit status when the watched operation failed
+pub(crate) const EXIT_OPERATION_FAILED: i32 = 2;
+/// Exit status when the watched operation expired
+pub(crate) const EXIT_OPERATION_EXPIRED: i32 = 3;
+/// Exit status when interrupted before the condition was met
+pub(crate) const EXIT_INTERRUPTED: i32 = 130;
+
+/// Error ending a watch command with a specific exit status
+#[derive(Debug)]
+pub(crate) struct WatchFailure {
+    pub(crate) exit_code: i32,
+    reason: String,
+}
+
+impl Display for WatchFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl std::error::Error for WatchFailure {}
+
+fn failure(exit_code: i32, reason: impl Into<String>) -> anyhow::Error {
+    WatchFailure {
+        exit_code,
+        reason: reason.into(),
+    }
+    .into()
+}
+
+/// Exit status of a command that ended with `error`
+pub(crate) fn exit_code(error: &anyhow::Error) -> i32 {
+    error
+        .downcast_ref::<WatchFailure>()
+        .map_or(1, |failure| failure.exit_code)
+}
+
+/// Change printed by a watch command, as a line of text or of JSON
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum WatchEvent {
+    Balance {
+        slot: Slot,
+        is_final: bool,
+        address: Address,
+        /// `None` if the address was deleted
+        balance: Option<Amount>,
+    },
+    Operation {
+        slot: Option<Slot>,
+        is_final: bool,
+        operation_id: OperationId,
+        status: String,
+    },
+    Event {
+        slot: Slot,
+        is_final: bool,
+        emitter: Option<Address>,
+        operation_id: Option<OperationId>,
+        is_failure: bool,
+        data: String,
+    },
+}
+
+impl Display for WatchEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stage = |is_final: bool| if is_final { "final" } else { "candidate" };
+        match self {
+            WatchEvent::Balance {
+                slot,
+                is_final,
+                address,
+                balance,
+            } => match balance {
+                Some(balance) => write!(
+                    f,
+                    "[{} {}] balance of {}: {}",
+                    stage(*is_final),
+                    slot,
+                    address,
+                    balance
+                ),
+                None => write!(f, "[{} {}] {} deleted", stage(*is_final), slot, address),
+            },
+            WatchEvent::Operation {
+                slot,
+                is_final,
+                operation_id,
+                status,
+            } => match slot {
+                Some(slot) => write!(
+                    f,
+                    "[{} {}] operation {}: {}",
+                    stage(*is_final),
+                    slot,
+                    operation_id,
+                    status
+                ),
+                None => write!(f, "operation {}: {}", operation_id, status),
+            },
+            WatchEvent::Event {
+                slot,
+                is_final,
+                emitter,
+                operation_id,
+                is_failure,
+                data,
+            } => {
+                write!(f, "[{} {}] event", stage(*is_final), slot)?;
+                if let Some(emitter) = emitter {
+                    write!(f, " from {}", emitter)?;
+                }
+                if let Some(operation_id) = operation_id {
+                    write!(f, " of operation {}", operation_id)?;
+                }
+                if *is_failure {
+                    write!(f, " (failed execution)")?;
+                }
+                write!(f, ": {}", data)
+            }
+        }
+    }
+}
+
+fn emit(event: &WatchEvent, json: bool) {
+    if json {
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{}", line),
+            Err(e) => println!("{{\"error\": \"{}\"}}", e),
+        }
+    } else {
+        println!("{}", event);
+    }
+}
+
+/// Flags of a watch command, `--name value` or `--name`
+struct Flags<'a> {
+    positional: Vec<&'a String>,
+    named: Vec<(&'a str, Option<&'a String>)>,
+}
+
+impl<'a> Flags<'a> {
+    fn parse(
+        parameters: &'a [String],
+        with_value: &[&str],
+        without_value: &[&str],
+    ) -> Result<Self> {
+        let mut flags = Flags {
+            positional: Vec::new(),
+            named: Vec::new(),
+        };
+        let mut iter = parameters.iter();
+        while let Some(parameter) = iter.next() {
+            match parameter.strip_prefix("--") {
+                Some(name) if with_value.contains(&name) => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| anyhow!("missing value of --{}", name))?;
+                    flags.named.push((name, Some(value)));
+                }
+                Some(name) if without_value.contains(&name) => flags.named.push((name, None)),
+                Some(name) => bail!("unknown option --{}", name),
+                None => flags.positional.push(parameter),
+            }
+        }
+        Ok(flags)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.named.iter().any(|(flag, _)| *flag == name)
+    }
+
+    fn value<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: Display,
+    {
+        self.named
+            .iter()
+            .rev()
+            .find(|(flag, _)| *flag == name)
+            .and_then(|(_, value)| *value)
+            .map(|value| {
+                value
+                    .parse::<T>()
+                    .map_err(|e| anyhow!("invalid value of --{}: {}", name, e))
+            })
+            .transpose()
+    }
+}
+
+fn grpc_client(client: &Client) -> Result<GrpcClient> {
+    client
+        .grpc
+        .clone()
+        .ok_or_else(|| anyhow!("the public gRPC API of the node is not reachable"))
+}
+
+/// Next slot execution output, or `None` on Ctrl-C
+async fn next_output<S>(stream: &mut S) -> Result<Option<ReceivedSlotExecutionOutput>>
+where
+    S: Stream<Item = Result<ReceivedSlotExecutionOutput, GrpcClientError>> + Unpin,
+{
+    tokio::select! {
+        item = stream.next() => match item {
+            Some(Ok(output)) => Ok(Some(output)),
+            Some(Err(e)) => bail!("slot execution outputs stream error: {}", e),
+            None => bail!("slot execution outputs stream closed"),
+        },
+        _ = tokio::signal::ctrl_c() => Ok(None),
+    }
+}
+
+/// `watch_address Address1 Address2 ... [--final] [--once]`
+pub(crate) async fn watch_address(
+    client: &Client,
+    parameters: &[String],
+    json: bool,
+) -> Result<String> {
+    let flags = Flags::parse(parameters, &[], &["final", "once"])?;
+    if flags.positional.is_empty() {
+        bail!("wrong number of parameters");
+    }
+    let addresses = flags
+        .positional
+        .iter()
+        .map(|address| address.parse::<Address>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let once = flags.has("once");
+
+    let mut stream =
+        Box::pin(grpc_client(client)?.new_slot_execution_outputs(None, flags.has("final")));
+    while let Some(output) = next_output(&mut stream).await? {
+        let mut changed = false;
+        for (address, balance) in output.balance_changes() {
+            if addresses.contains(&address) {
+                changed = true;
+                emit(
+                    &WatchEvent::Balance {
+                        slot: output.slot,
+                        is_final: output.is_final,
+                        address,
+                        balance,
+                    },
+                    json,
+                );
+            }
+        }
+        if changed && once {
+            return Ok("balance changed".to_string());
+        }
+    }
+    if once {
+        return Err(failure(
+            EXIT_INTERRUPTED,
+            "interrupted before any balance change",
+        ));
+    }
+    Ok("interrupted".to_string())
+}
+
+/// Stage of an operation a `watch_operation` waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum OperationStage {
+    Included,
+    Executed,
+    Final,
+}
+
+impl std::str::FromStr for OperationStage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "included" => Ok(OperationStage::Included),
+            "executed" => Ok(OperationStage::Executed),
+            "final" => Ok(OperationStage::Final),
+            _ => bail!("expected included, executed or final, got {}", s),
+        }
+    }
+}
+
+impl OperationStage {
+    /// Whether the watch waiting for this stage ends once the operation is executed
+    fn reached_by_execution(self, is_final: bool) -> bool {
+        is_final || self <= OperationStage::Executed
+    }
+}
+
+/// Result of a watch that saw the operation executed
+fn execution_outcome(operation_id: OperationId, success: bool) -> Result<String> {
+    if success {
+        Ok(format!("operation {} executed successfully", operation_id))
+    } else {
+        Err(failure(
+            EXIT_OPERATION_FAILED,
+            format!("operation {} failed", operation_id),
+        ))
+    }
+}
+
+/// Whether an operation of `thread` not executed yet can no longer be executed
+/// after the execution of `slot`
+fn is_expired_at(slot: Slot, is_final: bool, thread: u8, expire_period: u64) -> bool {
+    is_final && slot.thread == thread && slot.period >= expire_period
+}
+
+/// `watch_operation OperationId [--until included|executed|final]`
+pub(crate) async fn watch_operation(
+    client: &Client,
+    parameters: &[String],
+    json: bool,
+) -> Result<String> {
+    let flags = Flags::parse(parameters, &["until"], &[])?;
+    if flags.positional.len() != 1 {
+        bail!("wrong number of parameters");
+    }
+    let operation_id = flags.positional[0].parse::<OperationId>()?;
+    let until = flags
+        .value::<OperationStage>("until")?
+        .unwrap_or(OperationStage::Final);
+    let status = |slot: Option<Slot>, is_final: bool, status: String| WatchEvent::Operation {
+        slot,
+        is_final,
+        operation_id,
+        status,
+    };
+
+    // subscribe before the first query so that no slot is missed
+    let mut stream = Box::pin(grpc_client(client)?.new_slot_execution_outputs(None, false));
+
+    let info = match client.public.get_operations(vec![operation_id]).await {
+        Ok(mut infos) => infos
+            .pop()
+            .ok_or_else(|| anyhow!("operation {} is unknown to the node", operation_id))?,
+        Err(e) => bail!("check if your node is running: {}", e),
+    };
+    let thread = info.thread;
+    let expire_period = info.operation.content.expire_period;
+    let mut included = !info.in_blocks.is_empty();
+    if info.in_pool {
+        emit(&status(None, false, "in pool".to_string()), json);
+    }
+    if included {
+        emit(
+            &status(
+                None,
+                false,
+                format!("included in blocks {:?}", info.in_blocks),
+            ),
+            json,
+        );
+        if until == OperationStage::Included {
+            return Ok(format!("operation {} included", operation_id));
+        }
+    }
+    if info.is_operation_final == Some(true) {
+        if let Some(success) = info.op_exec_status {
+            emit(&status(None, true, "final".to_string()), json);
+            return execution_outcome(operation_id, success);
+        }
+    }
+
+    while let Some(output) = next_output(&mut stream).await? {
+        if !included && !output.is_final {
+            if let Ok(Some(info)) = client
+                .public
+                .get_operations(vec![operation_id])
+                .await
+                .map(|mut infos| infos.pop())
+            {
+                if !info.in_blocks.is_empty() {
+                    included = true;
+                    emit(
+                        &status(
+                            Some(output.slot),
+                            false,
+                            format!("included in blocks {:?}", info.in_blocks),
+                        ),
+                        json,
+                    );
+                    if until == OperationStage::Included {
+                        return Ok(format!("operation {} included", operation_id));
+                    }
+                }
+            }
+        }
+        if let Some((_, success)) = output
+            .executed_operations()
+            .into_iter()
+            .find(|(id, _)| *id == operation_id)
+        {
+            emit(
+                &status(
+                    Some(output.slot),
+                    output.is_final,
+                    if success { "executed" } else { "failed" }.to_string(),
+                ),
+                json,
+            );
+            if until.reached_by_execution(output.is_final) {
+                return execution_outcome(operation_id, success);
+            }
+        } else if is_expired_at(output.slot, output.is_final, thread, expire_period) {
+            return Err(failure(
+                EXIT_OPERATION_EXPIRED,
+                format!(
+                    "operation {} expired at period {} without being executed",
+                    operation_id, expire_period
+                ),
+            ));
+        }
+    }
+    Err(failure(
+        EXIT_INTERRUPTED,
+        format!(
+            "interrupted before operation {} was {:?}",
+            operation_id, until
+        ),
+    ))
+}
+
+/// Filters and stop condition of a `watch_events`
+struct EventCondition {
+    emitter: Option<Address>,
+    operation_id: Option<OperationId>,
+    until: Option<String>,
+}
+
+impl EventCondition {
+    /// Whether an event emitted by `emitter` during `operation_id` is printed
+    fn accepts(&self, emitter: Option<Address>, operation_id: Option<OperationId>) -> bool {
+        (self.emitter.is_none() || emitter == self.emitter)
+            && (self.operation_id.is_none() || operation_id == self.operation_id)
+    }
+
+    /// Whether the watch ends once an event carrying `data` is printed
+    fn is_awaited(&self, data: &str) -> bool {
+        self.until
+            .as_ref()
+            .is_some_and(|text| data.contains(text.as_str()))
+    }
+}
+
+/// `watch_events [--emitter Address] [--operation OperationId] [--final] [--until Text]`
+pub(crate) async fn watch_events(
+    client: &Client,
+    parameters: &[String],
+    json: bool,
+) -> Result<String> {
+    let flags = Flags::parse(parameters, &["emitter", "operation", "until"], &["final"])?;
+    if !flags.positional.is_empty() {
+        bail!("wrong number of parameters");
+    }
+    let condition = EventCondition {
+        emitter: flags.value::<Address>("emitter")?,
+        operation_id: flags.value::<OperationId>("operation")?,
+        until: flags.value::<String>("until")?,
+    };
+
+    let mut stream =
+        Box::pin(grpc_client(client)?.new_slot_execution_outputs(None, flags.has("final")));
+    while let Some(output) = next_output(&mut stream).await? {
+        for event in output.events() {
+            let event_emitter = event.call_stack.last().copied();
+            if !condition.accepts(event_emitter, event.origin_operation_id) {
+                continue;
+            }
+            let matched = condition.is_awaited(&event.data);
+            emit(
+                &WatchEvent::Event {
+                    slot: output.slot,
+                    is_final: output.is_final,
+                    emitter: event_emitter,
+                    operation_id: event.origin_operation_id,
+                    is_failure: event.is_failure,
+                    data: event.data,
+                },
+                json,
+            );
+            if matched {
+                return Ok("event received".to_string());
+            }
+        }
+    }
+    if condition.until.is_some() {
+        return Err(failure(
+            EXIT_INTERRUPTED,
+            "interrupted before the event was received",
+        ));
+    }
+    Ok("interrupted".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::secure_share::Id;
+    use massa_signature::KeyPair;
+
+    fn address() -> Address {
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    fn operation_id(seed: &[u8]) -> OperationId {
+        OperationId::new(Hash::compute_from(seed))
+    }
+
+    fn parameters(parameters: &[&str]) -> Vec<String> {
+        parameters.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(&failure(EXIT_OPERATION_FAILED, "failed")), 2);
+        assert_eq!(exit_code(&failure(EXIT_OPERATION_EXPIRED, "expired")), 3);
+        assert_eq!(exit_code(&failure(EXIT_INTERRUPTED, "interrupted")), 130);
+        assert_eq!(exit_code(&anyhow!("check if your node is running")), 1);
+    }
+
+    #[test]
+    fn test_execution_outcome() {
+        let id = operation_id(b"op");
+        assert!(execution_outcome(id, true).is_ok());
+        let error = execution_outcome(id, false).unwrap_err();
+        assert_eq!(exit_code(&error), EXIT_OPERATION_FAILED);
+        assert_eq!(error.to_string(), format!("operation {} failed", id));
+    }
+
+    #[test]
+    fn test_operation_stage_parsing() {
+        let params = parameters(&["op", "--until", "executed"]);
+        let flags = Flags::parse(&params, &["until"], &[]).unwrap();
+        assert_eq!(flags.positional, vec!["op"]);
+        assert_eq!(
+            flags.value::<OperationStage>("until").unwrap(),
+            Some(OperationStage::Executed)
+        );
+
+        let params = parameters(&["op", "--until", "pending"]);
+        let flags = Flags::parse(&params, &["until"], &[]).unwrap();
+        assert!(flags.value::<OperationStage>("until").is_err());
+
+        assert!(Flags::parse(&parameters(&["op", "--until"]), &["until"], &[]).is_err());
+        assert!(Flags::parse(&parameters(&["op", "--final"]), &["until"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_operation_stage_reached_by_execution() {
+        for (stage, is_final, reached) in [
+            (OperationStage::Included, false, true),
+            (OperationStage::Executed, false, true),
+            (OperationStage::Final, false, false),
+            (OperationStage::Final, true, true),
+        ] {
+            assert_eq!(stage.reached_by_execution(is_final), reached, "{:?}", stage);
+        }
+    }
+
+    #[test]
+    fn test_is_expired_at() {
+        // the operation of thread 1 expires after period 10
+        for (slot, is_final, expired) in [
+            (Slot::new(10, 1), true, true),
+            (Slot::new(11, 1), true, true),
+            (Slot::new(9, 1), true, false),
+            (Slot::new(10, 1), false, false),
+            (Slot::new(10, 0), true, false),
+        ] {
+            assert_eq!(is_expired_at(slot, is_final, 1, 10), expired, "{}", slot);
+        }
+    }
+
+    #[test]
+    fn test_event_condition() {
+        let (emitter, other_emitter) = (address(), address());
+        let (id, other_id) = (operation_id(b"op"), operation_id(b"other"));
+
+        let any = EventCondition {
+            emitter: None,
+            operation_id: None,
+            until: None,
+        };
+        assert!(any.accepts(None, None));
+        assert!(any.accepts(Some(emitter), Some(id)));
+        assert!(!any.is_awaited("transfer done"));
+
+        let condition = EventCondition {
+            emitter: Some(emitter),
+            operation_id: Some(id),
+            until: Some("done".to_string()),
+        };
+        assert!(condition.accepts(Some(emitter), Some(id)));
+        assert!(!condition.accepts(Some(other_emitter), Some(id)));
+        assert!(!condition.accepts(None, Some(id)));
+        assert!(!condition.accepts(Some(emitter), Some(other_id)));
+        assert!(!condition.accepts(Some(emitter), None));
+        assert!(condition.is_awaited("transfer done"));
+        assert!(!condition.is_awaited("transfer pending"));
+    }
+}
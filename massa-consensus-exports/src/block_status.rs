@@ -42,6 +42,32 @@ pub enum DiscardReason {
     Final,
 }
 
+/// Change of the status of a block in the graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockStatusChange {
+    /// The block was added to the graph
+    Active,
+    /// The block entered the blockclique
+    EnteredBlockclique,
+    /// The block left the blockclique without becoming final
+    LeftBlockclique,
+    /// The block became final
+    Final,
+    /// The block was discarded. Final blocks discarded from memory are not notified.
+    Discarded(DiscardReason),
+}
+
+/// Block status change broadcast to subscribers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockStatusEvent {
+    /// id of the block
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// new status of the block
+    pub change: BlockStatusChange,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BlockStatusId {
     Incoming = 0,
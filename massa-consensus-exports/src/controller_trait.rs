@@ -121,6 +121,20 @@ pub trait ConsensusController: Send + Sync {
     /// * `header`: the header of the block to mark as invalid
     fn mark_invalid_block(&self, block_id: BlockId, header: SecureShare<BlockHeader, BlockId>);
 
+    /// Replace the whole graph by the final blocks of a resynchronized node.
+    /// Must be called once the final state has been resynchronized up to these blocks,
+    /// with execution suspended: it is resumed by consensus before the new graph is notified to it.
+    ///
+    /// # Arguments
+    /// * `graph`: final blocks received during the resynchronization
+    fn resync(&self, graph: BootstrapableGraph);
+
+    /// Check if the node is resynchronizing
+    ///
+    /// # Returns
+    /// true from the moment a desynchronization is detected until the graph is resynchronized
+    fn is_syncing(&self) -> bool;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ConsensusController>`.
     fn clone_box(&self) -> Box<dyn ConsensusController>;
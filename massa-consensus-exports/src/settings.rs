@@ -50,6 +50,8 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// block status changes channel capacity
+    pub broadcast_block_status_channel_capacity: usize,
     /// last start period
     pub last_start_period: u64,
     /// chain id
@@ -35,6 +35,7 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_block_status_channel_capacity: 128,
             last_start_period: 0,
             chain_id: *CHAINID,
         }
@@ -1,12 +1,15 @@
+use massa_consensus_exports::bootstrapable_graph::BootstrapableGraph;
 use massa_models::{
     block_header::BlockHeader, block_id::BlockId, secure_share::SecureShare, slot::Slot,
 };
 use massa_storage::Storage;
+use tracing::Span;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 pub enum ConsensusCommand {
-    RegisterBlock(BlockId, Slot, Storage, bool),
+    RegisterBlock(BlockId, Slot, Storage, bool, Span),
     RegisterBlockHeader(BlockId, SecureShare<BlockHeader, BlockId>),
     MarkInvalidBlock(BlockId, SecureShare<BlockHeader, BlockId>),
+    Resync(BootstrapableGraph),
 }
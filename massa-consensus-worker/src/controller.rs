@@ -281,6 +281,7 @@ impl ConsensusController for ConsensusControllerImpl {
                 slot,
                 block_storage,
                 created,
+                tracing::Span::current(),
             ))
         {
             warn!("error trying to register a block: {}", err);
@@ -315,6 +316,17 @@ impl ConsensusController for ConsensusControllerImpl {
         }
     }
 
+    fn resync(&self, graph: BootstrapableGraph) {
+        // blocking send: the resynchronized graph must not be dropped
+        if let Err(err) = self.command_sender.send(ConsensusCommand::Resync(graph)) {
+            warn!("error trying to resync the graph: {}", err);
+        }
+    }
+
+    fn is_syncing(&self) -> bool {
+        self.shared_state.read().syncing
+    }
+
     fn clone_box(&self) -> Box<dyn ConsensusController> {
         Box::new(self.clone())
     }
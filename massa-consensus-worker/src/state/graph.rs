@@ -1,7 +1,7 @@
 use std::collections::VecDeque;
 
 use massa_consensus_exports::{
-    block_status::{BlockStatus, DiscardReason},
+    block_status::{BlockStatus, BlockStatusChange, DiscardReason},
     error::ConsensusError,
 };
 use massa_logging::massa_trace;
@@ -198,6 +198,7 @@ impl ConsensusState {
             panic!("inconsistency inside block statuses removing stale blocks adding {} - block {} is missing", add_block_id, block_id);
         }
     });
+        self.notify_if_discarded(block_id);
     }
 
     pub fn list_final_blocks(&self) -> Result<PreHashSet<BlockId>, ConsensusError> {
@@ -321,6 +322,7 @@ impl ConsensusState {
                         is_blockclique: true,
                     }];
                 }
+                let final_slot = final_block.slot;
                 // update latest final blocks
                 if final_block.slot.period
                     > self.latest_final_blocks_periods[final_block.slot.thread as usize].1
@@ -330,6 +332,7 @@ impl ConsensusState {
                 }
                 // update new final blocks list
                 self.new_final_blocks.insert(block_id);
+                self.notify_block_status(block_id, final_slot, BlockStatusChange::Final);
             } else {
                 return Err(ConsensusError::ContainerInconsistency(format!("inconsistency inside block statuses updating final blocks adding {} - block {} is missing", add_block_id, block_id)));
             }
@@ -0,0 +1,281 @@
+use std::collections::{HashMap, VecDeque};
+
+use massa_consensus_exports::{
+    block_status::{BlockStatus, StorageOrBlock},
+    bootstrapable_graph::BootstrapableGraph,
+    error::ConsensusError,
+    ConsensusConfig,
+};
+use massa_execution_exports::ExecutionBlockMetadata;
+use massa_hash::Hash;
+use massa_models::{
+    active_block::ActiveBlock,
+    address::Address,
+    block::{Block, BlockSerializer, SecureShareBlock},
+    block_header::{BlockHeader, BlockHeaderSerializer},
+    block_id::BlockId,
+    clique::Clique,
+    prehash::{PreHashMap, PreHashSet},
+    secure_share::SecureShareContent,
+    slot::Slot,
+    timeslots::get_block_slot_timestamp,
+};
+use massa_time::MassaTime;
+use tracing::info;
+
+use super::{blocks_state::BlocksState, ConsensusState};
+
+/// Creates genesis block in given thread.
+///
+/// # Arguments
+/// * `cfg`: consensus configuration
+/// * `thread_number`: thread in which we want a genesis block
+///
+/// # Returns
+/// A genesis block
+pub fn create_genesis_block(
+    cfg: &ConsensusConfig,
+    thread_number: u8,
+) -> Result<SecureShareBlock, ConsensusError> {
+    let keypair = &cfg.genesis_key;
+    let header = BlockHeader::new_verifiable(
+        BlockHeader {
+            current_version: 0,
+            announced_version: None,
+            slot: Slot::new(cfg.last_start_period, thread_number),
+            parents: Vec::new(),
+            operation_merkle_root: Hash::compute_from(&Vec::new()),
+            endorsements: Vec::new(),
+            denunciations: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        keypair,
+        cfg.chain_id,
+    )?;
+
+    Ok(Block::new_verifiable(
+        Block {
+            header,
+            operations: Default::default(),
+        },
+        BlockSerializer::new(),
+        keypair,
+        cfg.chain_id,
+    )?)
+}
+
+impl ConsensusState {
+    /// Initializes the graph with the genesis blocks, or with the final blocks received from bootstrap,
+    /// and notifies execution of the current blockclique and of all final blocks.
+    ///
+    /// # Arguments
+    /// * `init_graph`: Optional graph of final blocks. If None, the graph only contains the genesis blocks.
+    pub fn init_graph(
+        &mut self,
+        init_graph: Option<BootstrapableGraph>,
+    ) -> Result<(), ConsensusError> {
+        let config = self.config.clone();
+
+        // load genesis blocks
+        let mut block_statuses = PreHashMap::default();
+        let mut genesis_block_ids = Vec::with_capacity(config.thread_count as usize);
+        for thread in 0u8..config.thread_count {
+            let block = create_genesis_block(&config, thread).map_err(|err| {
+                ConsensusError::GenesisCreationError(format!("genesis error {}", err))
+            })?;
+            let mut storage = self.storage.clone_without_refs();
+            storage.store_block(block.clone());
+            genesis_block_ids.push(block.id);
+            block_statuses.insert(
+                block.id,
+                BlockStatus::Active {
+                    a_block: Box::new(ActiveBlock {
+                        creator_address: block.content_creator_address,
+                        parents: Vec::new(),
+                        children: vec![PreHashMap::default(); config.thread_count as usize],
+                        descendants: Default::default(),
+                        is_final: true,
+                        block_id: block.id,
+                        slot: block.content.header.content.slot,
+                        fitness: block.get_fitness(),
+                        same_thread_parent_creator: None,
+                    }),
+                    storage_or_block: StorageOrBlock::Storage(storage),
+                },
+            );
+        }
+
+        // add genesis blocks to stats
+        let genesis_addr = Address::from_public_key(&config.genesis_key.get_public_key());
+        let mut final_block_stats = VecDeque::new();
+        for thread in 0..config.thread_count {
+            final_block_stats.push_back((
+                get_block_slot_timestamp(
+                    config.thread_count,
+                    config.t0,
+                    config.genesis_timestamp,
+                    Slot::new(config.last_start_period, thread),
+                )?,
+                genesis_addr,
+                false,
+            ))
+        }
+
+        // If the node starts after the genesis timestamp then it has to initialize its graph
+        // with already produced blocks received from the bootstrap.
+        if let Some(BootstrapableGraph { final_blocks }) = init_graph {
+            // load final blocks
+            let final_blocks: Vec<(ActiveBlock, StorageOrBlock)> = final_blocks
+                .into_iter()
+                .map(|export_b| export_b.to_active_block(config.thread_count))
+                .collect::<Result<_, ConsensusError>>()?;
+
+            // compute latest_final_blocks_periods
+            let mut latest_final_blocks_periods: Vec<(BlockId, u64)> =
+                genesis_block_ids.iter().map(|id| (*id, 0u64)).collect();
+            for (b, _) in &final_blocks {
+                if let Some(v) = latest_final_blocks_periods.get_mut(b.slot.thread as usize) {
+                    if b.slot.period > v.1 {
+                        *v = (b.block_id, b.slot.period);
+                    }
+                }
+            }
+            // Initialize the shared state between the worker and the interface used by the other modules.
+            self.genesis_hashes = genesis_block_ids;
+            self.best_parents = latest_final_blocks_periods.clone();
+            self.latest_final_blocks_periods = latest_final_blocks_periods;
+            for (b, storage_or_block) in final_blocks {
+                self.blocks_state
+                    .transition_map(&(b.block_id.clone()), |_, _| {
+                        Some(BlockStatus::Active {
+                            a_block: Box::new(b),
+                            storage_or_block,
+                        })
+                    });
+            }
+            self.final_block_stats = final_block_stats;
+
+            self.claim_parent_refs()?;
+        } else {
+            // Initialize the shared state between the worker and the interface used by the other modules.
+            self.latest_final_blocks_periods = genesis_block_ids.iter().map(|h| (*h, 0)).collect();
+            self.best_parents = genesis_block_ids.iter().map(|v| (*v, 0)).collect();
+            self.genesis_hashes = genesis_block_ids;
+            for (b, s) in block_statuses {
+                self.blocks_state.transition_map(&b, |_, _| Some(s));
+            }
+            self.final_block_stats = final_block_stats;
+        }
+
+        // Notify execution module of current blockclique and all final blocks.
+        // we need to do this because the bootstrap snapshots of the executor vs the consensus may not have been taken in sync
+        // because the two modules run concurrently and out of sync.
+        let mut block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata> = Default::default();
+        let notify_finals: HashMap<Slot, BlockId> = self
+            .get_all_final_blocks()
+            .into_iter()
+            .map(|(b_id, (b_slot, b_metadata))| {
+                block_metadata.insert(b_id, b_metadata);
+                (b_slot, b_id)
+            })
+            .collect();
+        let notify_blockclique: HashMap<Slot, BlockId> = self
+            .get_blockclique()
+            .iter()
+            .map(|b_id| {
+                let (a_block, storage_or_block) = self
+                    .get_full_active_block(b_id)
+                    .expect("active block missing from block_db");
+                let storage = match storage_or_block {
+                    StorageOrBlock::Storage(storage) => Some(storage.clone()),
+                    _ => None,
+                };
+                block_metadata.insert(
+                    *b_id,
+                    ExecutionBlockMetadata {
+                        same_thread_parent_creator: a_block.same_thread_parent_creator,
+                        storage,
+                    },
+                );
+                (a_block.slot, *b_id)
+            })
+            .collect();
+        self.prev_blockclique = notify_blockclique.iter().map(|(k, v)| (*v, *k)).collect();
+        self.channels
+            .execution_controller
+            .update_blockclique_status(notify_finals, Some(notify_blockclique), block_metadata);
+
+        Ok(())
+    }
+
+    /// Replaces the whole graph by the final blocks of a graph received from a resynchronization bootstrap.
+    /// Active, incoming and discarded blocks are dropped, and desynchronization detection starts over.
+    /// Execution is resumed on top of its resynchronized final state before being notified of the new graph.
+    ///
+    /// # Arguments
+    /// * `graph`: final blocks matching the resynchronized final state
+    pub fn resync(&mut self, graph: BootstrapableGraph) -> Result<(), ConsensusError> {
+        // the blocks we were waiting for are not needed anymore
+        let remove_blocks: PreHashSet<BlockId> = self.wishlist.drain().map(|(id, _)| id).collect();
+        if !remove_blocks.is_empty() {
+            self.channels
+                .protocol_controller
+                .send_wishlist_delta(Default::default(), remove_blocks)?;
+        }
+
+        // reset the graph to its state before initialization
+        self.blocks_state = BlocksState::new();
+        self.max_cliques = vec![Clique {
+            block_ids: PreHashSet::<BlockId>::default(),
+            fitness: 0,
+            is_blockclique: true,
+        }];
+        self.gi_head = Default::default();
+        self.to_propagate = Default::default();
+        self.attack_attempts = Default::default();
+        self.new_final_blocks = Default::default();
+        self.new_stale_blocks = Default::default();
+        self.active_index_without_ops = Default::default();
+        self.save_final_periods = Default::default();
+        self.stale_block_stats = Default::default();
+        self.protocol_blocks = Default::default();
+        self.prev_blockclique = Default::default();
+        self.nonfinal_active_blocks_per_slot = Default::default();
+        self.launch_time = MassaTime::now();
+
+        // Execution was suspended while its final state was resynchronized.
+        // It is resumed from this thread so that it does not receive updates from the previous graph.
+        self.channels.execution_controller.resume_from_final_state();
+        let init_result = self.init_graph(Some(graph));
+        // whatever the outcome, desynchronization detection starts over from `launch_time`,
+        // so that a failed resynchronization is eventually detected again
+        self.syncing = false;
+        init_result?;
+        info!(
+            "consensus resynchronized, latest final blocks: {:?}",
+            self.latest_final_blocks_periods
+        );
+        Ok(())
+    }
+
+    /// Internal function used at initialization of the graph to link blocks with their parents
+    fn claim_parent_refs(&mut self) -> Result<(), ConsensusError> {
+        // list active block parents
+        let active_blocks_map: PreHashMap<BlockId, (Slot, Vec<BlockId>)> = self
+            .blocks_state
+            .iter()
+            .filter_map(|(h, s)| {
+                if let BlockStatus::Active { a_block: a, .. } = s {
+                    return Some((*h, (a.slot, a.parents.iter().map(|(ph, _)| *ph).collect())));
+                }
+                None
+            })
+            .collect();
+
+        for (b_id, (b_slot, b_parents)) in active_blocks_map.into_iter() {
+            // update aprenthood relationships
+            self.insert_parents_descendants(b_id, b_slot, b_parents);
+        }
+        Ok(())
+    }
+}
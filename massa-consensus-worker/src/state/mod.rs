@@ -5,7 +5,10 @@ use std::{
 
 use massa_consensus_exports::{
     block_graph_export::BlockGraphExport,
-    block_status::{BlockStatus, ExportCompiledBlock, HeaderOrBlock, StorageOrBlock},
+    block_status::{
+        BlockStatus, BlockStatusChange, BlockStatusEvent, ExportCompiledBlock, HeaderOrBlock,
+        StorageOrBlock,
+    },
     error::ConsensusError,
     ConsensusChannels, ConsensusConfig,
 };
@@ -30,6 +33,7 @@ use self::blocks_state::BlocksState;
 pub mod blocks_state;
 mod clique_computation;
 mod graph;
+mod init;
 mod process;
 mod process_commands;
 mod prune;
@@ -73,6 +77,8 @@ pub struct ConsensusState {
     pub new_stale_blocks: PreHashMap<BlockId, (Address, Slot)>,
     /// time at which the node was launched (used for de-synchronization detection)
     pub launch_time: MassaTime,
+    /// true from the moment a desynchronization is detected until the graph is resynchronized
+    pub syncing: bool,
     /// Final block stats `(time, creator, is_from_protocol)`
     pub final_block_stats: VecDeque<(MassaTime, Address, bool)>,
     /// Blocks that come from protocol used for stats and ids are removed when inserted in `final_block_stats`
@@ -545,4 +551,32 @@ impl ConsensusState {
         }
         result
     }
+
+    /// Broadcast a change of the status of a block to subscribers, if broadcasts are enabled
+    pub fn notify_block_status(&self, block_id: BlockId, slot: Slot, change: BlockStatusChange) {
+        if !self.config.broadcast_enabled {
+            return;
+        }
+        // an error only means that nobody is subscribed
+        let _ = self
+            .channels
+            .broadcasts
+            .block_status_sender
+            .send(BlockStatusEvent {
+                block_id,
+                slot,
+                change,
+            });
+    }
+
+    /// Broadcast the discard of a block to subscribers, if the block is discarded
+    pub fn notify_if_discarded(&self, block_id: &BlockId) {
+        if let Some(BlockStatus::Discarded { slot, reason, .. }) = self.blocks_state.get(block_id) {
+            self.notify_block_status(
+                *block_id,
+                *slot,
+                BlockStatusChange::Discarded(reason.clone()),
+            );
+        }
+    }
 }
@@ -4,7 +4,7 @@ use std::{
 };
 
 use massa_consensus_exports::{
-    block_status::{BlockStatus, DiscardReason, HeaderOrBlock, StorageOrBlock},
+    block_status::{BlockStatus, BlockStatusChange, DiscardReason, HeaderOrBlock, StorageOrBlock},
     error::ConsensusError,
 };
 use massa_execution_exports::ExecutionBlockMetadata;
@@ -141,6 +141,7 @@ impl ConsensusState {
                             )
                         }
                     });
+                self.notify_if_discarded(&block_id);
                 return Ok(BTreeSet::new());
             }
 
@@ -294,6 +295,7 @@ impl ConsensusState {
                             )
                         }
                     });
+                self.notify_if_discarded(&block_id);
                 match block_infos {
                     Some(valid_block_infos) => {
                         if let Err(err) = self.add_block_to_graph(
@@ -372,13 +374,14 @@ impl ConsensusState {
 
         // if the block was added, update linked dependencies and mark satisfied ones for recheck
         if let Some(BlockStatus::Active {
+            a_block,
             storage_or_block: StorageOrBlock::Storage(storage),
-            ..
         }) = self.blocks_state.get(&block_id)
         {
             massa_trace!("consensus.block_graph.process.is_active", {
                 "block_id": block_id
             });
+            self.notify_block_status(block_id, a_block.slot, BlockStatusChange::Active);
             self.to_propagate.insert(block_id, storage.clone());
             for itm_block_id in self
                 .blocks_state
@@ -624,6 +627,7 @@ impl ConsensusState {
 
         // Get new blockclique block list with slots.
         let mut blockclique_changed = false;
+        let mut entered_blockclique = Vec::new();
         let new_blockclique: PreHashMap<BlockId, Slot> = self
             .get_blockclique()
             .iter()
@@ -645,6 +649,7 @@ impl ConsensusState {
                         _ => panic!("blockclique block not found in active blocks and/or its operations are missing"),
                     };
                     new_blocks_metadata.insert(*b_id, ExecutionBlockMetadata { same_thread_parent_creator: a_block.same_thread_parent_creator, storage: Some(storage.clone()) });
+                    entered_blockclique.push((*b_id, a_block.slot));
                     (*b_id, a_block.slot)
                 }
             })
@@ -655,6 +660,19 @@ impl ConsensusState {
             // In that case, we mark the blockclique as having changed.
             blockclique_changed = true;
         }
+
+        // Notify subscribers of blockclique changes.
+        // Final blocks are removed from the cliques: they do not leave the blockclique.
+        let finalized_ids: PreHashSet<BlockId> = finalized_blocks.values().copied().collect();
+        for (b_id, slot) in self.prev_blockclique.iter() {
+            if !finalized_ids.contains(b_id) {
+                self.notify_block_status(*b_id, *slot, BlockStatusChange::LeftBlockclique);
+            }
+        }
+        for (b_id, slot) in entered_blockclique {
+            self.notify_block_status(b_id, slot, BlockStatusChange::EnteredBlockclique);
+        }
+
         // Overwrite previous blockclique.
         // Should still be done even if unchanged because elements were removed from it above.
         self.prev_blockclique = new_blockclique.clone();
@@ -171,5 +171,6 @@ impl ConsensusState {
                 sequence_number,
             })
         });
+        self.notify_if_discarded(block_id);
     }
 }
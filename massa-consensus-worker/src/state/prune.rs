@@ -319,6 +319,7 @@ impl ConsensusState {
                     panic!("block {} should be in WaitingForDependencies state", block_id);
                 }
             });
+            self.notify_if_discarded(&block_id);
         }
 
         Ok(())
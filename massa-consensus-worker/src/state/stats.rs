@@ -63,6 +63,7 @@ impl ConsensusState {
                 self.launch_time,
             )
             .saturating_add(self.stats_desync_detection_timespan)
+            && !self.syncing
             && !self
                 .final_block_stats
                 .iter()
@@ -72,6 +73,7 @@ impl ConsensusState {
                 })
         {
             warn!("desynchronization detected because the recent final block history is empty or contains only blocks produced by this node");
+            self.syncing = true;
             let _ = self
                 .channels
                 .controller_event_tx
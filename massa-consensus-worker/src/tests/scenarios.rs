@@ -1,18 +1,20 @@
 use std::{
     collections::{HashSet, VecDeque},
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use super::{
-    tools::{consensus_test, register_block},
+    tools::{consensus_test, consensus_test_with_block_statuses, register_block},
     universe::{ConsensusForeignControllers, ConsensusTestUniverse},
 };
-use crate::tests::tools::create_block;
+use crate::tests::tools::{create_block, create_block_with_merkle_root};
+use massa_consensus_exports::block_status::{BlockStatusChange, DiscardReason};
 use massa_consensus_exports::ConsensusConfig;
 use massa_execution_exports::MockExecutionController;
 use massa_models::{
     address::Address, block::BlockGraphStatus, block_id::BlockId, config::ENDORSEMENT_COUNT,
-    slot::Slot,
+    slot::Slot, streaming_step::StreamingStep,
 };
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::{MockSelectorController, Selection};
@@ -180,6 +182,110 @@ fn test_unsorted_block() {
     register_block(&universe.module_controller, t1s2.clone(), storage.clone());
 }
 
+/// After a resynchronization, the graph is replaced by the received final blocks,
+/// and execution is resumed before being notified of them.
+#[test]
+fn test_resync() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = ConsensusConfig {
+        t0: MassaTime::from_millis(500),
+        thread_count: 2,
+        genesis_timestamp: MassaTime::now(),
+        force_keep_final_periods: 50,
+        force_keep_final_periods_without_ops: 128,
+        max_future_processing_blocks: 10,
+        genesis_key: staking_key.clone(),
+        ..ConsensusConfig::default()
+    };
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+
+    let mut foreign_controllers = ConsensusForeignControllers::new_with_mocks();
+    let storage = foreign_controllers.storage.clone();
+
+    // calls received by execution, in order
+    let execution_calls = Arc::new(Mutex::new(Vec::new()));
+    let calls = execution_calls.clone();
+    foreign_controllers
+        .execution_controller
+        .expect_update_blockclique_status()
+        .returning(move |_, _, _| calls.lock().unwrap().push("update"));
+    let calls = execution_calls.clone();
+    foreign_controllers
+        .execution_controller
+        .expect_resume_from_final_state()
+        .times(1)
+        .returning(move || calls.lock().unwrap().push("resume"));
+    foreign_controllers
+        .pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    foreign_controllers
+        .pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    foreign_controllers
+        .selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    let universe = ConsensusTestUniverse::new(foreign_controllers, cfg);
+
+    // the graph received from the resynchronization only holds the genesis blocks
+    let (graph, _, _) = universe
+        .module_controller
+        .get_bootstrap_part(
+            StreamingStep::Started,
+            StreamingStep::Finished(Some(Slot::new(0, 1))),
+        )
+        .unwrap();
+    assert_eq!(graph.final_blocks.len(), 2);
+    let genesis_hashes = universe
+        .module_controller
+        .get_block_graph_status(None, None)
+        .expect("could not get block graph status")
+        .genesis_blocks;
+
+    let t0s1 = create_block(Slot::new(1, 0), genesis_hashes.clone(), &staking_key);
+    register_block(&universe.module_controller, t0s1.clone(), storage);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !universe
+        .module_controller
+        .get_block_graph_status(None, None)
+        .unwrap()
+        .active_blocks
+        .contains_key(&t0s1.id)
+    {
+        assert!(Instant::now() < deadline, "block was not processed");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    universe.module_controller.resync(graph);
+    // execution is notified of the new graph right after being resumed
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        {
+            let calls = execution_calls.lock().unwrap();
+            if let Some(index) = calls.iter().position(|call| *call == "resume") {
+                if let Some(next_call) = calls.get(index + 1) {
+                    assert_eq!(*next_call, "update");
+                    break;
+                }
+            }
+        }
+        assert!(Instant::now() < deadline, "execution was not resumed");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    let status = universe
+        .module_controller
+        .get_block_graph_status(None, None)
+        .unwrap();
+    assert!(!status.active_blocks.contains_key(&t0s1.id));
+    for genesis_id in genesis_hashes {
+        assert!(status.active_blocks.contains_key(&genesis_id));
+    }
+    assert!(!universe.module_controller.is_syncing());
+}
+
 #[test]
 fn test_parallel_incompatibility() {
     let thread_count = 2;
@@ -306,6 +412,137 @@ fn test_parallel_incompatibility() {
     );
 }
 
+/// A block goes through the blockclique to finality while a competing block of the same slot becomes stale
+#[test]
+fn test_block_status_changes() {
+    let thread_count = 2;
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = ConsensusConfig {
+        t0: MassaTime::from_millis(100),
+        thread_count,
+        genesis_timestamp: MassaTime::now(),
+        force_keep_final_periods_without_ops: 128,
+        force_keep_final_periods: 10,
+        delta_f0: 2,
+        ..ConsensusConfig::default()
+    };
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+    consensus_test_with_block_statuses(
+        cfg.clone(),
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        move |consensus_controller, mut block_statuses| {
+            let genesis = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status")
+                .genesis_blocks;
+
+            let block = create_block(Slot::new(1, 0), vec![genesis[0], genesis[1]], &staking_key);
+            register_block(&consensus_controller, block.clone(), storage.clone());
+            std::thread::sleep(Duration::from_millis(200));
+
+            // same slot, incompatible with `block`
+            let competitor = create_block_with_merkle_root(
+                massa_hash::Hash::compute_from("competitor".as_bytes()),
+                Slot::new(1, 0),
+                vec![genesis[0], genesis[1]],
+                &staking_key,
+            );
+            register_block(&consensus_controller, competitor.clone(), storage.clone());
+            std::thread::sleep(Duration::from_millis(200));
+
+            // enough descendants for `block` to become final and `competitor` to become stale
+            let mut parent = block.id;
+            for period in 2..(cfg.delta_f0 + 5) {
+                let child =
+                    create_block(Slot::new(period, 0), vec![parent, genesis[1]], &staking_key);
+                register_block(&consensus_controller, child.clone(), storage.clone());
+                std::thread::sleep(Duration::from_millis(100));
+                parent = child.id;
+            }
+            std::thread::sleep(Duration::from_millis(500));
+
+            let mut block_changes = Vec::new();
+            let mut competitor_changes = Vec::new();
+            while let Ok(event) = block_statuses.try_recv() {
+                if event.block_id == block.id {
+                    assert_eq!(event.slot, Slot::new(1, 0));
+                    block_changes.push(event.change);
+                } else if event.block_id == competitor.id {
+                    competitor_changes.push(event.change);
+                }
+            }
+
+            // the competitor only leads while it has the same fitness, depending on the hashes
+            let competitor_led =
+                competitor_changes.get(1) == Some(&BlockStatusChange::EnteredBlockclique);
+            if competitor_led {
+                assert_eq!(
+                    block_changes,
+                    vec![
+                        BlockStatusChange::Active,
+                        BlockStatusChange::EnteredBlockclique,
+                        BlockStatusChange::LeftBlockclique,
+                        BlockStatusChange::EnteredBlockclique,
+                        BlockStatusChange::Final,
+                    ]
+                );
+                assert_eq!(
+                    competitor_changes,
+                    vec![
+                        BlockStatusChange::Active,
+                        BlockStatusChange::EnteredBlockclique,
+                        BlockStatusChange::LeftBlockclique,
+                        BlockStatusChange::Discarded(DiscardReason::Stale),
+                    ]
+                );
+            } else {
+                assert_eq!(
+                    block_changes,
+                    vec![
+                        BlockStatusChange::Active,
+                        BlockStatusChange::EnteredBlockclique,
+                        BlockStatusChange::Final,
+                    ]
+                );
+                assert_eq!(
+                    competitor_changes,
+                    vec![
+                        BlockStatusChange::Active,
+                        BlockStatusChange::Discarded(DiscardReason::Stale),
+                    ]
+                );
+            }
+        },
+    );
+}
+
 #[test]
 fn test_parent_in_the_future() {
     let staking_key: KeyPair = KeyPair::generate(0).unwrap();
@@ -33,7 +33,9 @@ impl ConsensusWorker {
                 write_shared_state.register_block_header(block_id, header, self.previous_slot)?;
                 write_shared_state.block_db_changed()
             }
-            ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created) => {
+            ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created, span) => {
+                let _span =
+                    massa_logging::otel::stage_span(&span, "consensus.register_block").entered();
                 write_shared_state.register_block(
                     block_id,
                     slot,
@@ -47,6 +49,7 @@ impl ConsensusWorker {
                 write_shared_state.mark_invalid_block(&block_id, header);
                 Ok(())
             }
+            ConsensusCommand::Resync(graph) => write_shared_state.resync(graph),
         }
     }
 
@@ -65,7 +65,7 @@ pub fn start_consensus_worker(
         config.t0.checked_mul(config.periods_per_cycle * 2).unwrap();
     let broadcasts = channels.broadcasts.clone();
     let shared_state = Arc::new(RwLock::new(ConsensusState {
-        storage: storage.clone(),
+        storage,
         config: config.clone(),
         channels,
         max_cliques: vec![Clique {
@@ -89,6 +89,7 @@ pub fn start_consensus_worker(
         protocol_blocks: Default::default(),
         wishlist: Default::default(),
         launch_time: MassaTime::now(),
+        syncing: false,
         stats_desync_detection_timespan,
         stats_history_timespan: std::cmp::max(
             stats_desync_detection_timespan,
@@ -101,7 +102,7 @@ pub fn start_consensus_worker(
 
     let shared_state_cloned = shared_state.clone();
     let mut consensus_worker =
-        ConsensusWorker::new(config.clone(), rx, shared_state_cloned, init_graph, storage).unwrap();
+        ConsensusWorker::new(config.clone(), rx, shared_state_cloned, init_graph).unwrap();
 
     let consensus_thread = thread::Builder::new()
         .name("consensus worker".into())
@@ -2,12 +2,10 @@ use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
 use parking_lot::RwLock;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::{fmt::Debug, sync::Arc};
 
-#[cfg(feature = "test-exports")]
-use std::collections::BTreeMap;
-
 pub type ShareableMassaDBController = Arc<RwLock<Box<dyn MassaDBController>>>;
 
 /// Controller trait for the MassaDB
@@ -60,6 +58,14 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Get the current extended state hash of the database
     fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES>;
 
+    /// Get the extended state hash of the database right after the changes of the given slot,
+    /// or None if that slot is not in the change history anymore
+    fn get_xof_db_hash_at(&self, slot: Slot) -> Option<HashXof<HASH_XOF_SIZE_BYTES>>;
+
+    /// Get the state changes written at the given slot (None values being deletions),
+    /// or None if that slot is not in the change history anymore
+    fn get_changes_at(&self, slot: Slot) -> Option<BTreeMap<Key, Option<Value>>>;
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError>;
 
@@ -48,6 +48,9 @@ pub struct RawMassaDB<
     pub change_history: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
     /// same as change_history but for versioning
     pub change_history_versioning: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    /// The state hash of the database after each of the changes kept in change_history,
+    /// useful for bootstrap clients to check the state they assembled against several servers.
+    pub state_hash_history: BTreeMap<ChangeID, HashXof<HASH_XOF_SIZE_BYTES>>,
     /// A serializer for the ChangeID type
     pub change_id_serializer: ChangeIDSerializer,
     /// A deserializer for the ChangeID type
@@ -170,11 +173,16 @@ where
                 StreamingStep::Ongoing(max_key) => {
                     let mut iter = self
                         .db
-                        .iterator_cf(handle, IteratorMode::From(max_key, Direction::Forward));
-                    iter.next();
-                    iter
+                        .iterator_cf(handle, IteratorMode::From(max_key, Direction::Forward))
+                        .peekable();
+                    // The cursor is not necessarily in the DB (it may have been deleted since, or be a range boundary):
+                    // only skip it if it is there, otherwise the first key after it would never be streamed
+                    iter.next_if(
+                        |item| matches!(item, Ok((key, _)) if key.as_ref() == max_key.as_slice()),
+                    );
+                    Box::new(iter) as Box<dyn Iterator<Item = _> + '_>
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => Box::new(self.db.iterator_cf(handle, IteratorMode::Start)),
             };
 
             let u64_ser = U64VarIntSerializer::new();
@@ -299,11 +307,16 @@ where
                 StreamingStep::Ongoing(max_key) => {
                     let mut iter = self
                         .db
-                        .iterator_cf(handle, IteratorMode::From(max_key, Direction::Forward));
-                    iter.next();
-                    iter
+                        .iterator_cf(handle, IteratorMode::From(max_key, Direction::Forward))
+                        .peekable();
+                    // The cursor is not necessarily in the DB (it may have been deleted since, or be a range boundary):
+                    // only skip it if it is there, otherwise the first key after it would never be streamed
+                    iter.next_if(
+                        |item| matches!(item, Ok((key, _)) if key.as_ref() == max_key.as_slice()),
+                    );
+                    Box::new(iter) as Box<dyn Iterator<Item = _> + '_>
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => Box::new(self.db.iterator_cf(handle, IteratorMode::Start)),
             };
             let u64_ser = U64VarIntSerializer::new();
             for (serialized_key, serialized_value) in db_iterator.flatten() {
@@ -446,12 +459,22 @@ where
 
         if reset_history {
             self.change_history.clear();
+            self.state_hash_history.clear();
         }
 
+        self.state_hash_history.insert(
+            self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+            current_xor_hash,
+        );
+
         while self.change_history.len() > self.config.max_history_length {
             self.change_history.pop_first();
         }
 
+        while self.state_hash_history.len() > self.config.max_history_length {
+            self.state_hash_history.pop_first();
+        }
+
         while self.change_history_versioning.len() > self.config.max_history_length {
             self.change_history_versioning.pop_first();
         }
@@ -561,6 +584,17 @@ where
             .unwrap_or(HashXof(*STATE_HASH_INITIAL_BYTES))
     }
 
+    /// Get the XOF state hash the database had right after the changes associated to `change_id`,
+    /// if they are still in our history
+    pub fn get_xof_db_hash_at(&self, change_id: &ChangeID) -> Option<HashXof<HASH_XOF_SIZE_BYTES>> {
+        self.state_hash_history.get(change_id).copied()
+    }
+
+    /// Get the state changes associated to `change_id`, if they are still in our history
+    pub fn get_changes_at(&self, change_id: &ChangeID) -> Option<BTreeMap<Key, Option<Value>>> {
+        self.change_history.get(change_id).cloned()
+    }
+
     /// Get the current XOF state hash of the database
     fn get_xof_db_hash_opt(&self) -> Option<HashXof<HASH_XOF_SIZE_BYTES>> {
         let db = &self.db;
@@ -613,6 +647,7 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             config,
             change_history: BTreeMap::new(),
             change_history_versioning: BTreeMap::new(),
+            state_hash_history: BTreeMap::new(),
             change_id_serializer: SlotSerializer::new(),
             change_id_deserializer,
             current_batch,
@@ -722,6 +757,7 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
     fn reset(&mut self, slot: Slot) {
         self.set_initial_change_id(slot);
         self.change_history.clear();
+        self.state_hash_history.clear();
     }
 
     fn get_cf(&self, handle_cf: &str, key: Key) -> Result<Option<Value>, MassaDBError> {
@@ -795,6 +831,16 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_xof_db_hash()
     }
 
+    /// Get the extended state hash of the database right after the changes of the given slot, if still in history
+    fn get_xof_db_hash_at(&self, slot: Slot) -> Option<HashXof<HASH_XOF_SIZE_BYTES>> {
+        self.get_xof_db_hash_at(&slot)
+    }
+
+    /// Get the state changes written at the given slot, if still in history
+    fn get_changes_at(&self, slot: Slot) -> Option<BTreeMap<Key, Option<Value>>> {
+        self.get_changes_at(&slot)
+    }
+
     /// Get the current change_id attached to the database.
     fn get_change_id(&self) -> Result<Slot, ModelsError> {
         self.get_change_id()
@@ -1573,6 +1619,76 @@ mod test {
         assert_eq!(stream_batch.change_id, slot_2);
     }
 
+    #[test]
+    fn test_db_stream_from_missing_cursor() {
+        // Init db + add data
+        // Stream from a cursor that is not in the db: the first key after it must be streamed
+        // Check the state hash history
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        };
+        let mut db_opts = MassaDB::default_db_opts();
+        // Additional checks (only for testing)
+        db_opts.set_paranoid_checks(true);
+
+        let _db = MassaDB::new_with_options(db_config, db_opts.clone()).unwrap();
+        let db = Arc::new(RwLock::new(
+            Box::new(_db) as Box<(dyn MassaDBController + 'static)>
+        ));
+
+        let batch_key_1 = vec![1, 2, 3];
+        let batch_value_1 = vec![4, 5, 6];
+        let batch_key_2 = vec![11, 22, 33];
+        let batch_value_2 = vec![44, 55, 66];
+        let batch = DBBatch::from([
+            (batch_key_1.clone(), Some(batch_value_1.clone())),
+            (batch_key_2.clone(), Some(batch_value_2.clone())),
+        ]);
+        let slot_1 = Slot::new(1, 0);
+        db.write().write_batch(batch, DBBatch::new(), Some(slot_1));
+        let hash_1 = db.read().get_xof_db_hash();
+
+        // Cursor between the 2 keys
+        let last_state_step: StreamingStep<Vec<u8>> = StreamingStep::Ongoing(vec![5]);
+        let stream_batch = db
+            .read()
+            .get_batch_to_stream(&last_state_step, Some(slot_1))
+            .unwrap();
+        assert_eq!(
+            stream_batch.new_elements,
+            BTreeMap::from([(batch_key_2.clone(), batch_value_2)])
+        );
+
+        // Cursor on an existing key
+        let last_state_step: StreamingStep<Vec<u8>> = StreamingStep::Ongoing(batch_key_1.clone());
+        let stream_batch = db
+            .read()
+            .get_batch_to_stream(&last_state_step, Some(slot_1))
+            .unwrap();
+        assert!(!stream_batch.new_elements.contains_key(&batch_key_1));
+        assert!(stream_batch.new_elements.contains_key(&batch_key_2));
+
+        // State hash history
+        let slot_2 = Slot::new(2, 0);
+        db.write().write_batch(
+            DBBatch::from([(batch_key_1, None)]),
+            DBBatch::new(),
+            Some(slot_2),
+        );
+        let hash_2 = db.read().get_xof_db_hash();
+        assert_ne!(hash_1, hash_2);
+        assert_eq!(db.read().get_xof_db_hash_at(slot_1), Some(hash_1));
+        assert_eq!(db.read().get_xof_db_hash_at(slot_2), Some(hash_2));
+        assert_eq!(db.read().get_xof_db_hash_at(Slot::new(3, 0)), None);
+    }
+
     #[test]
     fn test_db_stream_3() {
         // Init db + add data
@@ -3,11 +3,13 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, FinalLedgerStats,
+    ReadOnlyExecutionRequest,
 };
 
 use crate::ExecutionError;
 use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use massa_final_state::FinalStateHashes;
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
@@ -18,6 +20,7 @@ use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
+use massa_pos_exports::DrawInputs;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
@@ -40,6 +43,15 @@ pub trait ExecutionController: Send + Sync {
         block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
     );
 
+    /// Suspends slot execution while the final state is being resynchronized.
+    /// Returns once any ongoing slot execution has finished.
+    /// Blockclique updates received while suspended are dropped.
+    fn suspend(&self);
+
+    /// Resets the active execution state on top of the current final state and resumes slot execution.
+    /// Called by consensus after `suspend`, once the final state has been resynchronized.
+    fn resume_from_final_state(&self);
+
     /// Atomically query the execution state with multiple requests
     fn query_state(&self, req: ExecutionQueryRequest) -> ExecutionQueryResponse;
 
@@ -85,6 +97,23 @@ pub trait ExecutionController: Send + Sync {
     /// By default it returns an empty map.
     fn get_cycle_active_rolls(&self, cycle: u64) -> BTreeMap<Address, u64>;
 
+    /// Get aggregate data about the coins and the ledger at the last final slot
+    fn get_final_ledger_stats(&self) -> FinalLedgerStats;
+
+    /// Get at most `count` addresses with the highest final balances, sorted by decreasing balance
+    fn get_final_top_balances(&self, count: usize) -> Vec<(Address, Amount)>;
+
+    /// Get the number of bytes (keys and values) used by the final datastore of each address
+    fn get_final_datastore_sizes(&self, addresses: &[Address]) -> Vec<u64>;
+
+    /// Get the execution trail hash and the final state fingerprint right after each of the given final slots,
+    /// or None for the slots that are not final yet or are not in the state hash history anymore
+    fn get_final_state_hashes(&self, slots: &[Slot]) -> Vec<Option<FinalStateHashes>>;
+
+    /// Get the inputs of the selector draws of each of the given cycles,
+    /// or None for the cycles whose look back cycles are not in the final cycle history
+    fn get_draw_inputs(&self, cycles: &[u64]) -> Vec<Option<DrawInputs>>;
+
     /// Execute read-only SC function call without causing modifications to the consensus state
     ///
     /// # arguments
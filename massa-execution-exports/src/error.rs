@@ -62,6 +62,12 @@ pub enum ExecutionError {
         error: VMError,
     },
 
+    /// Read-only execution aborted: the execution state changed during the last {0} attempts, please retry
+    ReadOnlyStateChanged(usize),
+
+    /// Read-only execution timeout: {0}
+    ReadOnlyTimeout(String),
+
     /// Cache error: {0}
     CacheError(#[from] CacheError),
 
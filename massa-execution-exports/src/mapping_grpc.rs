@@ -51,12 +51,16 @@ pub fn to_querystate_filter(
                 Ok(ExecutionQueryRequestItem::AddressDatastoreKeysCandidate {
                     addr: Address::from_str(&value.address)?,
                     prefix: value.prefix,
+                    offset: value.offset,
+                    limit: value.limit,
                 })
             }
             exec::RequestItem::AddressDatastoreKeysFinal(value) => {
                 Ok(ExecutionQueryRequestItem::AddressDatastoreKeysFinal {
                     addr: Address::from_str(&value.address)?,
                     prefix: value.prefix,
+                    offset: value.offset,
+                    limit: value.limit,
                 })
             }
             exec::RequestItem::AddressDatastoreValueCandidate(value) => {
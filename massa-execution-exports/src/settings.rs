@@ -24,6 +24,13 @@ pub struct StorageCostsConstants {
 pub struct ExecutionConfig {
     /// read-only execution request queue length
     pub readonly_queue_length: usize,
+    /// number of threads executing read-only requests.
+    /// Each of them has the 200 MB stack of the execution thread.
+    pub readonly_thread_count: usize,
+    /// maximum time a read-only request can wait in the queue before being rejected
+    pub readonly_queue_timeout: MassaTime,
+    /// maximum time a read-only request can take to execute, retries included
+    pub readonly_execution_timeout: MassaTime,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
     /// maximum available gas for asynchronous messages execution
@@ -29,6 +29,9 @@ impl Default for ExecutionConfig {
 
         Self {
             readonly_queue_length: 100,
+            readonly_thread_count: 2,
+            readonly_queue_timeout: MassaTime::from_millis(10000),
+            readonly_execution_timeout: MassaTime::from_millis(10000),
             max_final_events: 1000,
             max_async_gas: MAX_ASYNC_GAS,
             async_msg_cst_gas_cost: ASYNC_MSG_CST_GAS_COST,
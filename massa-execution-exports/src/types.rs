@@ -72,6 +72,10 @@ pub enum ExecutionQueryRequestItem {
         addr: Address,
         /// Filter only entries whose key starts with a prefix
         prefix: Vec<u8>,
+        /// Number of matching keys to skip, in key order
+        offset: Option<u64>,
+        /// Maximum number of keys to return
+        limit: Option<u64>,
     },
     /// gets the datastore keys (final) of an address, returns ExecutionQueryResponseItem::KeyList(keys) or an error if the address is not found
     AddressDatastoreKeysFinal {
@@ -79,6 +83,10 @@ pub enum ExecutionQueryRequestItem {
         addr: Address,
         /// Filter only entries whose key starts with a prefix
         prefix: Vec<u8>,
+        /// Number of matching keys to skip, in key order
+        offset: Option<u64>,
+        /// Maximum number of keys to return
+        limit: Option<u64>,
     },
     /// gets a datastore value (candidate) for an address, returns ExecutionQueryResponseItem::DatastoreValue(keys) or an error if the address or key is not found
     AddressDatastoreValueCandidate {
@@ -340,3 +348,38 @@ pub struct ExecutionStackElement {
     /// Datastore (key value store) for `ExecuteSC` Operation
     pub operation_datastore: Option<Datastore>,
 }
+
+/// Aggregate data about the coins and the ledger at the last final slot
+#[derive(Debug, Clone, Default)]
+pub struct FinalLedgerStats {
+    /// last final slot
+    pub slot: Slot,
+    /// sum of the balances of all the ledger entries
+    pub ledger_balance: Amount,
+    /// number of rolls owned by all the addresses
+    pub roll_count: u64,
+    /// value of all the rolls at the roll price
+    pub rolls_value: Amount,
+    /// coins that will be credited later, for example after a roll sell
+    pub deferred_credits: Amount,
+    /// number of user addresses having a ledger entry
+    pub user_address_count: u64,
+    /// number of smart contract addresses having a ledger entry
+    pub sc_address_count: u64,
+    /// bytes used by all the datastores (keys and values)
+    pub datastore_bytes: u64,
+}
+
+impl FinalLedgerStats {
+    /// All the existing coins: ledger balances, rolls at the roll price and deferred credits
+    pub fn total_supply(&self) -> Amount {
+        self.ledger_balance
+            .saturating_add(self.rolls_value)
+            .saturating_add(self.deferred_credits)
+    }
+
+    /// Coins that can be spent right away, that is the ledger balances
+    pub fn circulating_supply(&self) -> Amount {
+        self.ledger_balance
+    }
+}
@@ -11,7 +11,7 @@ use massa_models::{
 use massa_pos_exports::DeferredCredits;
 use std::collections::VecDeque;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 /// History of the outputs of recently executed slots.
 /// Slots should be consecutive, oldest at the beginning and latest at the back.
 pub(crate) struct ActiveHistory(pub VecDeque<ExecutionOutput>);
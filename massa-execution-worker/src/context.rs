@@ -981,7 +981,7 @@ impl ExecutionContext {
 
         // Do not allow user addresses to store bytecode.
         // See: https://github.com/massalabs/massa/discussions/2952
-        if let Address::User(_) = address {
+        if address.is_user() {
             return Err(ExecutionError::RuntimeError(format!(
                 "can't set the bytecode of address {} because this is not a smart contract address",
                 address
@@ -4,14 +4,14 @@
 //! See `massa-execution-exports/controller_traits.rs` for functional details.
 
 use crate::execution::ExecutionState;
-use crate::request_queue::{RequestQueue, RequestWithResponseSender};
-use massa_channel::MassaChannel;
+use crate::readonly_pool::ReadOnlyQueue;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionController, ExecutionError,
+    ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
+    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
+    FinalLedgerStats, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
+use massa_final_state::FinalStateHashes;
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
@@ -19,6 +19,7 @@ use massa_models::prehash::PreHashMap;
 use massa_models::stats::ExecutionStats;
 use massa_models::{address::Address, amount::Amount, operation::OperationId};
 use massa_models::{block_id::BlockId, slot::Slot};
+use massa_pos_exports::DrawInputs;
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
@@ -42,16 +43,20 @@ pub(crate) struct ExecutionInputData {
     pub new_blockclique: Option<HashMap<Slot, BlockId>>,
     /// storage instances for previously unprocessed blocks
     pub block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
-    /// queue for read-only execution requests and response MPSCs to send back their outputs
-    pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// true while slot execution is suspended, kept across `take`
+    pub suspended: bool,
+    /// set reset to true to rebuild the active state from the final state
+    pub reset: bool,
 }
 
 impl Display for ExecutionInputData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "stop={:?}, finalized={:?}, blockclique={:?}, readonly={:?}, storage={:?}",
+            "stop={:?}, suspended={:?}, reset={:?}, finalized={:?}, blockclique={:?}, storage={:?}",
             self.stop,
+            self.suspended,
+            self.reset,
             self.finalized_blocks
                 .iter()
                 .map(|(slot, id)| (*slot, *id))
@@ -60,7 +65,6 @@ impl Display for ExecutionInputData {
                 .iter()
                 .map(|(slot, id)| (*slot, *id))
                 .collect::<BTreeMap<Slot, BlockId>>()),
-            self.readonly_requests,
             self.block_metadata.keys().collect::<Vec<&BlockId>>(),
         )
     }
@@ -68,29 +72,27 @@ impl Display for ExecutionInputData {
 
 impl ExecutionInputData {
     /// Creates a new empty `ExecutionInputData`
-    pub fn new(config: ExecutionConfig) -> Self {
+    pub fn new() -> Self {
         ExecutionInputData {
             stop: Default::default(),
             finalized_blocks: Default::default(),
             new_blockclique: Default::default(),
             block_metadata: Default::default(),
-            readonly_requests: RequestQueue::new(config.max_final_events),
+            suspended: Default::default(),
+            reset: Default::default(),
         }
     }
 
     /// Takes the current input data into a clone that is returned,
     /// and resets self.
     pub fn take(&mut self) -> Self {
-        let max_final_events = self.readonly_requests.capacity();
         ExecutionInputData {
             stop: std::mem::take(&mut self.stop),
             finalized_blocks: std::mem::take(&mut self.finalized_blocks),
             new_blockclique: std::mem::take(&mut self.new_blockclique),
             block_metadata: std::mem::take(&mut self.block_metadata),
-            readonly_requests: std::mem::replace(
-                &mut self.readonly_requests,
-                RequestQueue::new(max_final_events),
-            ),
+            suspended: self.suspended,
+            reset: std::mem::take(&mut self.reset),
         }
     }
 }
@@ -103,6 +105,8 @@ pub struct ExecutionControllerImpl {
     pub(crate) input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
     /// current execution state (see execution.rs for details)
     pub(crate) execution_state: Arc<RwLock<ExecutionState>>,
+    /// queue of the read-only threads
+    pub(crate) readonly_queue: ReadOnlyQueue,
 }
 
 impl ExecutionController for ExecutionControllerImpl {
@@ -136,6 +140,26 @@ impl ExecutionController for ExecutionControllerImpl {
         self.input_data.0.notify_one();
     }
 
+    /// Suspends slot execution until `resume_from_final_state` is called.
+    fn suspend(&self) {
+        {
+            let mut input_data = self.input_data.1.lock();
+            input_data.suspended = true;
+            self.input_data.0.notify_one();
+        }
+        // The VM loop checks the suspended flag while holding the execution state lock:
+        // once we get the lock, no slot execution is ongoing and none will start.
+        drop(self.execution_state.write());
+    }
+
+    /// Rebuilds the active state from the final state and resumes slot execution.
+    fn resume_from_final_state(&self) {
+        let mut input_data = self.input_data.1.lock();
+        input_data.suspended = false;
+        input_data.reset = true;
+        self.input_data.0.notify_one();
+    }
+
     /// Atomically query the execution state with multiple requests
     fn query_state(&self, req: ExecutionQueryRequest) -> ExecutionQueryResponse {
         let execution_lock = self.execution_state.read();
@@ -195,19 +219,39 @@ impl ExecutionController for ExecutionControllerImpl {
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
                     }
                 }
-                ExecutionQueryRequestItem::AddressDatastoreKeysCandidate { addr, prefix } => {
+                ExecutionQueryRequestItem::AddressDatastoreKeysCandidate {
+                    addr,
+                    prefix,
+                    offset,
+                    limit,
+                } => {
                     let (_final_v, speculative_v) =
                         execution_lock.get_final_and_candidate_datastore_keys(&addr, &prefix);
                     match speculative_v {
-                        Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(keys)),
+                        Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(
+                            keys.into_iter()
+                                .skip(offset.unwrap_or_default() as usize)
+                                .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                                .collect(),
+                        )),
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
                     }
                 }
-                ExecutionQueryRequestItem::AddressDatastoreKeysFinal { addr, prefix } => {
+                ExecutionQueryRequestItem::AddressDatastoreKeysFinal {
+                    addr,
+                    prefix,
+                    offset,
+                    limit,
+                } => {
                     let (final_v, _speculative_v) =
                         execution_lock.get_final_and_candidate_datastore_keys(&addr, &prefix);
                     match final_v {
-                        Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(keys)),
+                        Some(keys) => Ok(ExecutionQueryResponseItem::KeyList(
+                            keys.into_iter()
+                                .skip(offset.unwrap_or_default() as usize)
+                                .take(limit.map_or(usize::MAX, |limit| limit as usize))
+                                .collect(),
+                        )),
                         None => Err(ExecutionQueryError::NotFound(format!("Account {}", addr))),
                     }
                 }
@@ -386,44 +430,40 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_cycle_active_rolls(cycle)
     }
 
+    /// Get aggregate data about the coins and the ledger at the last final slot
+    fn get_final_ledger_stats(&self) -> FinalLedgerStats {
+        self.execution_state.read().get_final_ledger_stats()
+    }
+
+    /// Get at most `count` addresses with the highest final balances, sorted by decreasing balance
+    fn get_final_top_balances(&self, count: usize) -> Vec<(Address, Amount)> {
+        self.execution_state.read().get_final_top_balances(count)
+    }
+
+    /// Get the number of bytes (keys and values) used by the final datastore of each address
+    fn get_final_datastore_sizes(&self, addresses: &[Address]) -> Vec<u64> {
+        self.execution_state
+            .read()
+            .get_final_datastore_sizes(addresses)
+    }
+
+    /// Get the execution trail hash and the final state fingerprint right after each of the given final slots
+    fn get_final_state_hashes(&self, slots: &[Slot]) -> Vec<Option<FinalStateHashes>> {
+        self.execution_state.read().get_final_state_hashes(slots)
+    }
+
+    /// Get the inputs of the selector draws of each of the given cycles
+    fn get_draw_inputs(&self, cycles: &[u64]) -> Vec<Option<DrawInputs>> {
+        self.execution_state.read().get_draw_inputs(cycles)
+    }
+
     /// Executes a read-only request
     /// Read-only requests do not modify consensus state
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
-        let resp_rx = {
-            let mut input_data = self.input_data.1.lock();
-
-            // if the read-only queue is already full, return an error
-            if input_data.readonly_requests.is_full() {
-                return Err(ExecutionError::ChannelError(
-                    "too many queued readonly requests".into(),
-                ));
-            }
-
-            // prepare the channel to send back the result of the read-only execution
-            let (resp_tx, resp_rx) = MassaChannel::new("read_only_request".to_string(), None);
-
-            // append the request to the queue of input read-only requests
-            input_data
-                .readonly_requests
-                .push(RequestWithResponseSender::new(req, resp_tx));
-
-            // wake up the execution main loop
-            self.input_data.0.notify_one();
-
-            resp_rx
-        };
-
-        // Wait for the result of the execution
-        match resp_rx.recv() {
-            Ok(result) => result,
-            Err(err) => Err(ExecutionError::ChannelError(format!(
-                "readonly execution response channel readout failed: {}",
-                err
-            ))),
-        }
+        self.readonly_queue.execute(req)
     }
 
     /// Check if a denunciation has been executed given a `DenunciationIndex`
@@ -530,6 +570,10 @@ pub struct ExecutionManagerImpl {
     pub(crate) input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
     /// handle used to join the worker thread
     pub(crate) thread_handle: Option<std::thread::JoinHandle<()>>,
+    /// queue of the read-only threads
+    pub(crate) readonly_queue: ReadOnlyQueue,
+    /// handles used to join the read-only threads
+    pub(crate) readonly_thread_handles: Vec<std::thread::JoinHandle<()>>,
 }
 
 impl ExecutionManager for ExecutionManagerImpl {
@@ -546,6 +590,13 @@ impl ExecutionManager for ExecutionManagerImpl {
         if let Some(join_handle) = self.thread_handle.take() {
             join_handle.join().expect("VM controller thread panicked");
         }
+        // stop the read-only threads, cancelling pending requests
+        self.readonly_queue.stop();
+        for join_handle in self.readonly_thread_handles.drain(..) {
+            join_handle
+                .join()
+                .expect("read-only execution thread panicked");
+        }
         info!("execution controller stopped");
     }
 }
@@ -11,6 +11,9 @@
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
 use crate::interface_impl::InterfaceImpl;
+use crate::readonly_pool::{ExecutionStateVersions, ReadOnlySnapshot};
+#[cfg(feature = "dump-block")]
+use crate::replay_record::{ReplayBlock, SlotReplayRecord, SlotReplayRecordSerializer};
 use crate::stats::ExecutionStatsCounter;
 #[cfg(feature = "dump-block")]
 use crate::storage_backend::StorageBackend;
@@ -18,10 +21,9 @@ use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
     EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
     ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    ExecutionStackElement, FinalLedgerStats, SlotExecutionOutput,
 };
-use massa_final_state::FinalStateController;
+use massa_final_state::{FinalStateController, FinalStateHashes};
 use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
@@ -42,12 +44,13 @@ use massa_models::{
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{DrawInputs, SelectorController};
 use massa_sc_runtime::{Interface, Response, VMError};
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
@@ -71,6 +74,8 @@ use massa_models::secure_share::SecureShare;
 #[cfg(feature = "dump-block")]
 use massa_proto_rs::massa::model::v1 as grpc_model;
 #[cfg(feature = "dump-block")]
+use massa_serialization::Serializer;
+#[cfg(feature = "dump-block")]
 use prost::Message;
 
 /// Used to acquire a lock on the execution context
@@ -104,6 +109,8 @@ pub(crate) struct ExecutionState {
     // its output is popped from the front of active_history and applied to the final state.
     // It has atomic R/W access.
     active_history: Arc<RwLock<ActiveHistory>>,
+    // versions of the active history and final state, used by read-only executions to detect changes
+    versions: Arc<ExecutionStateVersions>,
     // a cursor pointing to the highest executed slot
     pub active_cursor: Slot,
     // a cursor pointing to the highest executed final slot
@@ -204,6 +211,7 @@ impl ExecutionState {
             execution_interface,
             // empty execution output history: it is not recovered through bootstrap
             active_history,
+            versions: Default::default(),
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
             // no active slots executed yet: set active_cursor to the last final block
@@ -271,6 +279,16 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
+        // the block and its operations are final and executed: end their spans
+        if massa_logging::otel::is_enabled() {
+            if let Some(block_info) = &exec_out.block_info {
+                massa_logging::otel::close_object_span("block", &block_info.block_id);
+            }
+            for op_id in exec_out.state_changes.executed_ops_changes.keys() {
+                massa_logging::otel::close_object_span("operation", op_id);
+            }
+        }
+
         let exec_out_2 = exec_out.clone();
         #[cfg(feature = "slot-replayer")]
         {
@@ -418,6 +436,7 @@ impl ExecutionState {
 
         // add the execution output at the end of the output history
         self.active_history.write().0.push_back(exec_out);
+        self.versions.bump_history();
 
         // update the prometheus metrics
         self.massa_metrics
@@ -506,6 +525,24 @@ impl ExecutionState {
             return Err(ExecutionError::InvalidSlotRange);
         }
 
+        // batch transactions and multisig operations are only accepted once their MIP is active
+        if matches!(operation.content.op, OperationType::Multisig { .. })
+            && !self.is_mip_component_active(&MipComponent::Multisig, &block_slot)
+        {
+            return Err(ExecutionError::IncludeOperationError(
+                "multisig operations are not active yet".to_string(),
+            ));
+        }
+        if matches!(
+            operation.content.op.get_emitted_op(),
+            OperationType::BatchTransaction { .. }
+        ) && !self.is_mip_component_active(&MipComponent::BatchTransaction, &block_slot)
+        {
+            return Err(ExecutionError::IncludeOperationError(
+                "batch transactions are not active yet".to_string(),
+            ));
+        }
+
         // check remaining block gas
         let op_gas = operation.get_gas_usage(
             self.config.base_operation_gas_cost,
@@ -550,22 +587,26 @@ impl ExecutionState {
         #[cfg(not(feature = "execution-trace"))]
         let res = ();
         // Call the execution process specific to the operation type.
-        let mut execution_result = match &operation.content.op {
-            OperationType::ExecuteSC { .. } => {
-                self.execute_executesc_op(&operation.content.op, sender_addr)
-            }
-            OperationType::CallSC { .. } => {
-                self.execute_callsc_op(&operation.content.op, sender_addr)
-            }
+        // Multisig operations execute the operation they wrap on behalf of the multisig address.
+        let emitted_op = operation.content.op.get_emitted_op();
+        let mut execution_result = match emitted_op {
+            OperationType::ExecuteSC { .. } => self.execute_executesc_op(emitted_op, sender_addr),
+            OperationType::CallSC { .. } => self.execute_callsc_op(emitted_op, sender_addr),
             OperationType::RollBuy { .. } => self
-                .execute_roll_buy_op(&operation.content.op, sender_addr)
+                .execute_roll_buy_op(emitted_op, sender_addr)
                 .map(|_| res),
             OperationType::RollSell { .. } => self
-                .execute_roll_sell_op(&operation.content.op, sender_addr)
+                .execute_roll_sell_op(emitted_op, sender_addr)
                 .map(|_| res),
             OperationType::Transaction { .. } => self
-                .execute_transaction_op(&operation.content.op, sender_addr)
+                .execute_transaction_op(emitted_op, sender_addr)
+                .map(|_| res),
+            OperationType::BatchTransaction { .. } => self
+                .execute_batch_transaction_op(emitted_op, sender_addr)
                 .map(|_| res),
+            OperationType::Multisig { .. } => Err(ExecutionError::IncludeOperationError(
+                "nested multisig operation".to_string(),
+            )),
         };
 
         {
@@ -920,6 +961,55 @@ impl ExecutionState {
         Ok(())
     }
 
+    /// Execute an operation of type `BatchTransaction`
+    /// Will panic if called with another operation type
+    ///
+    /// Transfers are applied in order. If one of them fails, the error is returned
+    /// and the caller reverts the context to its snapshot so that none of them is applied.
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be a `BatchTransaction`
+    /// * `sender_addr`: address of the sender
+    pub fn execute_batch_transaction_op(
+        &self,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process batch transaction operations only
+        let transfers = match operation {
+            OperationType::BatchTransaction { transfers } => transfers,
+            _ => panic!("unexpected operation type"),
+        };
+
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: transfers.iter().fold(Amount::zero(), |total, (_, amount)| {
+                total.saturating_add(*amount)
+            }),
+            owned_addresses: vec![sender_addr],
+            operation_datastore: None,
+        }];
+
+        // transfer coins from sender to each destination
+        for (index, (recipient_address, amount)) in transfers.iter().enumerate() {
+            if let Err(err) =
+                context.transfer_coins(Some(sender_addr), Some(*recipient_address), *amount, true)
+            {
+                return Err(ExecutionError::TransactionError(format!(
+                    "transfer {} of the batch ({} coins from {} to {}) failed: {}",
+                    index, amount, sender_addr, recipient_address, err
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Execute an operation of type `ExecuteSC`
     /// Will panic if called with another operation type
     ///
@@ -1366,7 +1456,7 @@ impl ExecutionState {
                             slot_trace
                                 .operation_call_stacks
                                 .insert(operation.id, _op_return.0);
-                            match &operation.content.op {
+                            match operation.content.op.get_emitted_op() {
                                 OperationType::Transaction {
                                     recipient_address,
                                     amount,
@@ -1416,13 +1506,26 @@ impl ExecutionState {
                                         fee: operation.content.fee,
                                     });
                                 }
+                                OperationType::BatchTransaction { transfers: batch } => {
+                                    for (recipient_address, amount) in batch {
+                                        transfers.push(Transfer {
+                                            from: operation.content_creator_address,
+                                            to: *recipient_address,
+                                            amount: *amount,
+                                            effective_received_amount: *amount,
+                                            op_id: operation.id,
+                                            succeed: _op_return.1,
+                                            fee: operation.content.fee,
+                                        });
+                                    }
+                                }
                                 _ => {}
                             }
                         }
 
                         #[cfg(feature = "execution-info")]
                         {
-                            match &operation.content.op {
+                            match operation.content.op.get_emitted_op() {
                                 OperationType::RollBuy { roll_count } => exec_info
                                     .operations
                                     .push(OperationInfo::RollBuy(*roll_count)),
@@ -1616,6 +1719,10 @@ impl ExecutionState {
             "execute_candidate_slot: executing slot={} target={:?}",
             slot, target_id
         );
+        let _span = target_id.map(|id| {
+            massa_logging::otel::object_span("block", &id, "execution.execute_candidate_slot")
+                .entered()
+        });
 
         if slot <= &self.final_cursor {
             panic!(
@@ -1633,6 +1740,7 @@ impl ExecutionState {
             self.active_history
                 .write()
                 .truncate_from(slot, self.config.thread_count);
+            self.versions.bump_history();
             self.active_cursor = slot
                 .get_prev_slot(self.config.thread_count)
                 .expect("overflow when iterating on slots");
@@ -1676,6 +1784,9 @@ impl ExecutionState {
             "execute_final_slot: executing slot={} target={:?}",
             slot, target_id
         );
+        let _span = target_id.map(|id| {
+            massa_logging::otel::object_span("block", &id, "execution.execute_final_slot").entered()
+        });
 
         if slot <= &self.final_cursor {
             debug!(
@@ -1687,6 +1798,7 @@ impl ExecutionState {
 
         // check if the final slot execution result is already cached at the front of the speculative execution history
         let first_exec_output = self.active_history.write().0.pop_front();
+        self.versions.bump_history();
 
         if let Some(exec_out) = first_exec_output {
             if &exec_out.slot == slot
@@ -1695,6 +1807,8 @@ impl ExecutionState {
                 // speculative execution front result matches what we want to compute
                 // apply the cached output and return
                 self.apply_final_execution_output(exec_out);
+                #[cfg(feature = "dump-block")]
+                self.dump_replay_record(slot, exec_target);
                 return;
             } else {
                 // speculative cache mismatch
@@ -1713,6 +1827,9 @@ impl ExecutionState {
 
         // truncate the whole execution queue
         self.active_history.write().0.clear();
+        self.versions.bump_history();
+        // the final state will receive changes that are not in the active history
+        self.versions.bump_final_divergence();
         self.active_cursor = self.final_cursor;
 
         // execute slot
@@ -1720,183 +1837,128 @@ impl ExecutionState {
 
         // apply execution output to final state
         self.apply_final_execution_output(exec_out);
+        #[cfg(feature = "dump-block")]
+        self.dump_replay_record(slot, exec_target);
 
         debug!(
             "execute_final_slot: execution finished & result applied & versioning stats updated"
         );
     }
 
-    /// Runs a read-only execution request.
-    /// The executed bytecode appears to be able to read and write the consensus state,
-    /// but all accumulated changes are simply returned as an `ExecutionOutput` object,
-    /// and not actually applied to the consensus state.
-    ///
-    /// # Arguments
-    /// * `req`: a read-only execution request
-    ///
-    /// # Returns
-    ///  `ExecutionOutput` describing the output of the execution, or an error
-    pub(crate) fn execute_readonly_request(
+    /// Writes what is needed to replay a final slot that was just applied, along with the resulting state hashes
+    #[cfg(feature = "dump-block")]
+    fn dump_replay_record(
         &self,
-        req: ReadOnlyExecutionRequest,
-    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
-        // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
-        // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
-
-        // check if read only request max gas is above the threshold
-        if req.max_gas > self.config.max_read_only_gas {
-            return Err(ExecutionError::TooMuchGas(format!(
-                "execution gas for read-only call is {} which is above the maximum allowed {}",
-                req.max_gas, self.config.max_read_only_gas
-            )));
-        }
-
-        // set the execution slot to be the one after the latest executed active slot
-        let slot = self
-            .active_cursor
-            .get_next_slot(self.config.thread_count)
-            .expect("slot overflow in readonly execution from active slot");
-
-        // create a readonly execution context
-        let execution_context = ExecutionContext::readonly(
-            self.config.clone(),
-            slot,
-            req.call_stack,
-            self.final_state.clone(),
-            self.active_history.clone(),
-            self.module_cache.clone(),
-            self.mip_store.clone(),
-        );
-
-        // run the interpreter according to the target type
-        let exec_response = match req.target {
-            ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
-                {
-                    let mut context = context_guard!(self);
-                    *context = execution_context;
-
-                    let call_stack_addr = context.get_call_stack();
-
-                    // transfer fee
-                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.first()) {
-                        context.transfer_coins(Some(*addr), None, fee, false)?;
-                    }
-                }
-
-                // load the tmp module
-                let module = self
-                    .module_cache
-                    .read()
-                    .load_tmp_module(&bytecode, req.max_gas)?;
-
-                // run the VM
-                massa_sc_runtime::run_main(
-                    &*self.execution_interface,
-                    module,
-                    req.max_gas,
-                    self.config.gas_costs.clone(),
-                )
-                .map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::BytecodeExecution".to_string(),
-                    error,
-                })?
+        slot: &Slot,
+        exec_target: Option<&(BlockId, ExecutionBlockMetadata)>,
+    ) {
+        let block = exec_target.map(|(block_id, block_metadata)| {
+            let storage = block_metadata
+                .storage
+                .as_ref()
+                .expect("Cannot dump a block for which the storage is missing");
+            let block = storage
+                .read_blocks()
+                .get(block_id)
+                .expect("Missing block in storage.")
+                .clone();
+            let operations = {
+                let ops = storage.read_operations();
+                block
+                    .content
+                    .operations
+                    .iter()
+                    .map(|op_id| {
+                        ops.get(op_id)
+                            .expect("block operation absent from storage")
+                            .clone()
+                    })
+                    .collect()
+            };
+            ReplayBlock {
+                block,
+                operations,
+                same_thread_parent_creator: block_metadata.same_thread_parent_creator,
             }
+        });
 
-            ReadOnlyExecutionTarget::FunctionCall {
-                target_addr,
-                target_func,
-                parameter,
-            } => {
-                // get the bytecode, default to an empty vector
-                let bytecode = execution_context
-                    .get_bytecode(&target_addr)
-                    .unwrap_or_default()
-                    .0;
-
-                {
-                    let mut context = context_guard!(self);
-                    *context = execution_context;
-
-                    // Ensure that the target address is an SC address and exists
-                    context.check_target_sc_address(target_addr)?;
-
-                    let call_stack_addr = context.get_call_stack();
-
-                    // transfer fee
-                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.first()) {
-                        context.transfer_coins(Some(*addr), None, fee, false)?;
-                    }
+        let record = {
+            let final_state = self.final_state.read();
+            let state_changes = final_state
+                .get_database()
+                .read()
+                .get_changes_at(*slot)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        key,
+                        value.map(|value| massa_hash::Hash::compute_from(&value)),
+                    )
+                })
+                .collect();
+            SlotReplayRecord {
+                block,
+                final_state_fingerprint: final_state.get_fingerprint(),
+                execution_trail_hash: final_state.get_execution_trail_hash(),
+                state_changes,
+            }
+        };
 
-                    // transfer coins
-                    if let (Some(coins), Some(from), Some(to)) =
-                        (req.coins, call_stack_addr.first(), call_stack_addr.get(1))
-                    {
-                        context.transfer_coins(Some(*from), Some(*to), coins, false)?;
-                    }
-                }
+        let mut buffer = Vec::new();
+        SlotReplayRecordSerializer::new()
+            .serialize(&record, &mut buffer)
+            .expect("Unable to serialize replay record");
+        self.block_storage_backend
+            .write()
+            .write_replay_record(slot, &buffer);
+    }
 
-                // load and execute the compiled module
-                // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-                let module = self
-                    .module_cache
-                    .write()
-                    .load_module(&bytecode, req.max_gas)?;
-
-                let response = massa_sc_runtime::run_function(
-                    &*self.execution_interface,
-                    module,
-                    &target_func,
-                    &parameter,
-                    req.max_gas,
-                    self.config.gas_costs.clone(),
-                );
+    /// Drops the active history and moves both cursors to the slot of the final state.
+    /// Used once the final state has been resynchronized underneath the execution state.
+    ///
+    /// # Returns
+    /// The new final cursor
+    pub fn reset_to_final_state(&mut self) -> Slot {
+        let final_slot = self.final_state.read().get_slot();
+        self.active_history.write().0.clear();
+        self.versions.bump_history();
+        // the final state was changed without going through the active history
+        self.versions.bump_final_divergence();
+        self.active_cursor = final_slot;
+        self.final_cursor = final_slot;
+        debug!("reset_to_final_state: cursors reset to slot {}", final_slot);
+        final_slot
+    }
 
-                match response {
-                    Ok(Response { init_gas_cost, .. })
-                    | Err(VMError::ExecutionError { init_gas_cost, .. }) => {
-                        self.module_cache
-                            .write()
-                            .set_init_cost(&bytecode, init_gas_cost);
-                    }
-                    _ => (),
-                }
+    /// Takes a snapshot of the active history on top of which read-only requests are executed
+    pub(crate) fn get_readonly_snapshot(&self) -> ReadOnlySnapshot {
+        ReadOnlySnapshot {
+            history_version: self.versions.history.load(Ordering::Acquire),
+            final_divergence: self.versions.final_divergence.load(Ordering::Acquire),
+            active_cursor: self.active_cursor,
+            active_history: Arc::new(RwLock::new(self.active_history.read().clone())),
+        }
+    }
 
-                response.map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::FunctionCall".to_string(),
-                    error,
-                })?
-            }
-        };
+    /// Get the versions of the execution state
+    pub(crate) fn get_versions(&self) -> Arc<ExecutionStateVersions> {
+        self.versions.clone()
+    }
 
-        // return the execution output
-        let execution_output = context_guard!(self).settle_slot(None);
-        let exact_exec_cost = req.max_gas.saturating_sub(exec_response.remaining_gas);
-
-        // compute a gas cost, estimating the gas of the last SC call to be max_instance_cost
-        let corrected_cost = match (context_guard!(self)).gas_remaining_before_subexecution {
-            Some(gas_remaining) => req
-                .max_gas
-                .saturating_sub(gas_remaining) // yield gas used until last subexecution
-                .saturating_add(self.config.gas_costs.max_instance_cost),
-            None => self.config.gas_costs.max_instance_cost, // no subexecution, just max_instance_cost
-        };
+    /// Get a shared access to the final state
+    pub(crate) fn get_final_state(&self) -> Arc<RwLock<dyn FinalStateController>> {
+        self.final_state.clone()
+    }
 
-        // keep the max of the two so the last SC call has at least max_instance_cost of gas
-        let estimated_cost = u64::max(exact_exec_cost, corrected_cost);
-        debug!(
-            "execute_readonly_request:
-            exec_response.remaining_gas: {}
-            exact_exec_cost: {}
-            corrected_cost: {}
-            estimated_cost: {}",
-            exec_response.remaining_gas, exact_exec_cost, corrected_cost, estimated_cost
-        );
+    /// Get a shared access to the module cache
+    pub(crate) fn get_module_cache(&self) -> Arc<RwLock<ModuleCache>> {
+        self.module_cache.clone()
+    }
 
-        Ok(ReadOnlyExecutionOutput {
-            out: execution_output,
-            gas_cost: estimated_cost,
-            call_result: exec_response.ret,
-        })
+    /// Get the MIP store
+    pub(crate) fn get_mip_store(&self) -> MipStore {
+        self.mip_store.clone()
     }
 
     /// Gets a balance both at the latest final and candidate executed slots
@@ -2047,6 +2109,69 @@ impl ExecutionState {
             .get_all_active_rolls(cycle)
     }
 
+    /// Gets aggregate data about the coins and the ledger at the last final slot.
+    /// The ledger data is maintained incrementally, but rolls and deferred credits are read from the final state.
+    pub fn get_final_ledger_stats(&self) -> FinalLedgerStats {
+        let final_state = self.final_state.read();
+        let ledger_stats = final_state.get_ledger().get_stats();
+        let pos_state = final_state.get_pos_state();
+        let roll_count: u64 = match pos_state.cycle_history_cache.back() {
+            Some((cycle, _)) => pos_state.get_all_roll_counts(*cycle).values().sum(),
+            None => 0,
+        };
+        let deferred_credits = pos_state
+            .get_deferred_credits_range(.., None)
+            .credits
+            .values()
+            .flat_map(|credits| credits.values())
+            .fold(Amount::zero(), |total, amount| {
+                total.saturating_add(*amount)
+            });
+        FinalLedgerStats {
+            slot: final_state.get_slot(),
+            ledger_balance: ledger_stats.total_balance,
+            roll_count,
+            rolls_value: self.config.roll_price.saturating_mul_u64(roll_count),
+            deferred_credits,
+            user_address_count: ledger_stats.user_address_count,
+            sc_address_count: ledger_stats.sc_address_count,
+            datastore_bytes: ledger_stats.datastore_bytes,
+        }
+    }
+
+    /// Gets at most `count` addresses with the highest final balances, sorted by decreasing balance
+    pub fn get_final_top_balances(&self, count: usize) -> Vec<(Address, Amount)> {
+        self.final_state.read().get_ledger().get_top_balances(count)
+    }
+
+    /// Gets the number of bytes used by the final datastore of each address
+    pub fn get_final_datastore_sizes(&self, addresses: &[Address]) -> Vec<u64> {
+        let final_state = self.final_state.read();
+        addresses
+            .iter()
+            .map(|addr| final_state.get_ledger().get_datastore_size(addr))
+            .collect()
+    }
+
+    /// Gets the execution trail hash and the final state fingerprint right after each of the given final slots
+    pub fn get_final_state_hashes(&self, slots: &[Slot]) -> Vec<Option<FinalStateHashes>> {
+        let final_state = self.final_state.read();
+        slots
+            .iter()
+            .map(|slot| final_state.get_state_hashes_at(*slot))
+            .collect()
+    }
+
+    /// Gets the inputs of the selector draws of each of the given cycles,
+    /// or None for the cycles whose look back cycles are not in the final cycle history
+    pub fn get_draw_inputs(&self, cycles: &[u64]) -> Vec<Option<DrawInputs>> {
+        let final_state = self.final_state.read();
+        cycles
+            .iter()
+            .map(|cycle| final_state.get_pos_state().get_draw_inputs(*cycle).ok())
+            .collect()
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -2254,6 +2379,21 @@ impl ExecutionState {
             .collect()
     }
 
+    /// Check whether a MIP component introducing a new operation type is active at the given slot
+    fn is_mip_component_active(&self, component: &MipComponent, slot: &Slot) -> bool {
+        let slot_ts = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            *slot,
+        )
+        .expect("Cannot get timestamp from slot");
+
+        self.mip_store
+            .get_latest_component_version_at(component, slot_ts)
+            >= 1
+    }
+
     /// Update MipStore with block header stats
     pub fn update_versioning_stats(&mut self, block_info: &Option<ExecutedBlockInfo>, slot: &Slot) {
         let slot_ts = get_block_slot_timestamp(
@@ -13,7 +13,7 @@ use massa_execution_exports::ExecutionStackElement;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::{
-    address::{Address, SCAddress, UserAddress},
+    address::{Address, MultisigAddress, SCAddress, UserAddress},
     amount::Amount,
     slot::Slot,
     timeslots::get_block_slot_timestamp,
@@ -955,10 +955,10 @@ impl Interface for InterfaceImpl {
         Ok(recovered.serialize().to_vec())
     }
 
-    // Return true if the address is a User address, false if it is an SC address.
+    // Return true if the address is a User or Multisig address, false if it is an SC address.
     fn is_address_eoa(&self, address_: &str) -> Result<bool> {
         let address = Address::from_str(address_)?;
-        Ok(matches!(address, Address::User(..)))
+        Ok(address.is_user())
     }
 
     /// Transfer coins from the current address (top of the call stack) towards a target address.
@@ -1504,7 +1504,7 @@ impl Interface for InterfaceImpl {
     fn get_address_category_wasmv1(&self, to_check: &str) -> Result<AddressCategory> {
         let addr = Address::from_str(to_check)?;
         match addr {
-            Address::User(_) => Ok(AddressCategory::ScAddress),
+            Address::User(_) | Address::Multisig(_) => Ok(AddressCategory::ScAddress),
             Address::SC(_) => Ok(AddressCategory::UserAddress),
             #[allow(unreachable_patterns)]
             _ => Ok(AddressCategory::Unspecified),
@@ -1517,6 +1517,7 @@ impl Interface for InterfaceImpl {
             Address::User(UserAddress::UserAddressV0(_)) => Ok(0),
             // Address::User(UserAddress::UserAddressV1(_)) => Ok(1),
             Address::SC(SCAddress::SCAddressV0(_)) => Ok(0),
+            Address::Multisig(MultisigAddress::MultisigAddressV0(_)) => Ok(0),
             // Address::SC(SCAddress::SCAddressV1(_)) => Ok(1),
             #[allow(unreachable_patterns)]
             _ => bail!("Unknown address version"),
@@ -38,7 +38,7 @@
 //!
 //! ## worker.rs
 //! This module runs the main loop of the worker thread.
-//! It contains the logic to process incoming blockclique change notifications and read-only execution requests.
+//! It contains the logic to process incoming blockclique change notifications.
 //! It sequences the blocks according to their slot number into queues,
 //! and requests the execution of active and final slots to execution.rs.
 //!
@@ -67,6 +67,10 @@
 //! ## `speculative_executed_ops.rs`
 //! A speculative (non-final) list of previously executed operations to prevent reuse.
 //!
+//! ## `readonly_pool.rs`
+//! Runs read-only execution requests on a pool of dedicated threads,
+//! against snapshots of the active history, so that they do not delay slot execution.
+//!
 //! ## `request_queue.rs`
 //! This module contains the implementation of a generic finite-size execution request queue.
 //! It handles requests that come with an MPSC to send back the result of their execution once it's done.
@@ -77,6 +81,12 @@
 //! ## execution-info
 //!
 //! See documentation in execution_info.rs file.
+//!
+//! ## replay
+//!
+//! With the 'dump-block' feature, a replay record is written for each final slot next to the dumped block.
+//! The 'replay' feature provides `SlotReplayer`, which re-executes those slots on top of a final state
+//! snapshot and compares the resulting state hashes with the recorded ones.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
@@ -86,6 +96,12 @@ mod context;
 mod controller;
 mod execution;
 mod interface_impl;
+mod readonly_pool;
+/// Re-execution of the final slots of a dump
+#[cfg(feature = "replay")]
+pub mod replay;
+/// Records written by the dump-block feature to replay final slots
+pub mod replay_record;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -0,0 +1,632 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module runs read-only execution requests on a pool of dedicated threads,
+//! so that they neither wait for nor block the execution of slots.
+//!
+//! Each request is executed against a snapshot of the active history taken at a fixed active cursor,
+//! on top of the final state. Final slots applied from that active history do not change what the
+//! request observes. If the final state diverged from the snapshot during the execution
+//! (speculative execution cache mismatch, or finalization beyond the snapshot's active cursor),
+//! the request is executed again against a fresh snapshot.
+//!
+//! The VM can not be interrupted: the execution of a request is bounded by its gas. The caller
+//! gets a timeout error if its request is not answered within the queue and execution timeouts.
+
+use crate::active_history::ActiveHistory;
+use crate::context::ExecutionContext;
+use crate::execution::ExecutionState;
+use crate::interface_impl::InterfaceImpl;
+use crate::request_queue::{RequestQueue, RequestWithResponseSender};
+use crate::worker::EXECUTION_THREAD_STACK_SIZE;
+use massa_channel::receiver::MassaReceiver;
+use massa_channel::MassaChannel;
+use massa_execution_exports::{
+    ExecutionConfig, ExecutionError, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
+};
+use massa_final_state::FinalStateController;
+use massa_metrics::MassaMetrics;
+use massa_models::slot::Slot;
+use massa_module_cache::controller::ModuleCache;
+use massa_sc_runtime::{Interface, Response, VMError};
+use massa_versioning::versioning::MipStore;
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Maximal number of times a read-only request is executed when the state keeps changing under it
+pub(crate) const MAX_READONLY_ATTEMPTS: usize = 3;
+
+/// Counters allowing read-only executors to detect changes of the execution state.
+/// They are only incremented while holding the write lock of the `ExecutionState`.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutionStateVersions {
+    /// incremented every time the active history changes
+    pub history: AtomicU64,
+    /// incremented every time the final state receives changes that were not taken from the active history
+    pub final_divergence: AtomicU64,
+}
+
+impl ExecutionStateVersions {
+    /// Signal a change of the active history
+    pub fn bump_history(&self) {
+        self.history.fetch_add(1, Ordering::Release);
+    }
+
+    /// Signal that the final state diverged from the active history
+    pub fn bump_final_divergence(&self) {
+        self.final_divergence.fetch_add(1, Ordering::Release);
+    }
+}
+
+/// Immutable view of the active execution state used by read-only executions
+pub(crate) struct ReadOnlySnapshot {
+    /// value of the history version when the snapshot was taken
+    pub history_version: u64,
+    /// value of the final divergence version when the snapshot was taken
+    pub final_divergence: u64,
+    /// latest executed active slot covered by the snapshot
+    pub active_cursor: Slot,
+    /// copy of the active history, never modified
+    pub active_history: Arc<RwLock<ActiveHistory>>,
+}
+
+/// Executes read-only requests with its own execution context,
+/// sharing the final state and module cache with the execution thread
+pub(crate) struct ReadOnlyExecutor {
+    /// execution config
+    config: ExecutionConfig,
+    /// final state with atomic R/W access
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    /// cache of pre compiled sc modules
+    module_cache: Arc<RwLock<ModuleCache>>,
+    /// MipStore (Versioning)
+    mip_store: MipStore,
+    /// execution context dedicated to this executor
+    execution_context: Arc<Mutex<ExecutionContext>>,
+    /// execution interface allowing the VM runtime to access the execution context
+    execution_interface: Box<dyn Interface>,
+}
+
+impl ReadOnlyExecutor {
+    /// Creates a new `ReadOnlyExecutor`
+    pub fn new(
+        config: ExecutionConfig,
+        final_state: Arc<RwLock<dyn FinalStateController>>,
+        module_cache: Arc<RwLock<ModuleCache>>,
+        mip_store: MipStore,
+    ) -> Self {
+        let execution_context = Arc::new(Mutex::new(placeholder_context(
+            &config,
+            &final_state,
+            &module_cache,
+            &mip_store,
+        )));
+        let execution_interface = Box::new(InterfaceImpl::new(
+            config.clone(),
+            execution_context.clone(),
+        ));
+        ReadOnlyExecutor {
+            config,
+            final_state,
+            module_cache,
+            mip_store,
+            execution_context,
+            execution_interface,
+        }
+    }
+
+    /// Runs a read-only execution request.
+    /// The executed bytecode appears to be able to read and write the consensus state,
+    /// but all accumulated changes are simply returned as an `ExecutionOutput` object,
+    /// and not actually applied to the consensus state.
+    ///
+    /// # Arguments
+    /// * `req`: a read-only execution request
+    /// * `snapshot`: the active state on top of which the request is executed
+    ///
+    /// # Returns
+    ///  `ExecutionOutput` describing the output of the execution, or an error
+    pub fn execute(
+        &self,
+        req: ReadOnlyExecutionRequest,
+        snapshot: &ReadOnlySnapshot,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        let outcome = self.execute_request(req, snapshot);
+
+        // Reset the speculative state whatever the outcome, errors included:
+        // the changes of the request and the snapshot they refer to must not outlive it.
+        *self.execution_context.lock() = placeholder_context(
+            &self.config,
+            &self.final_state,
+            &self.module_cache,
+            &self.mip_store,
+        );
+
+        outcome
+    }
+
+    /// Runs a read-only execution request, leaving its speculative state in the execution context
+    fn execute_request(
+        &self,
+        req: ReadOnlyExecutionRequest,
+        snapshot: &ReadOnlySnapshot,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        // check if read only request max gas is above the threshold
+        if req.max_gas > self.config.max_read_only_gas {
+            return Err(ExecutionError::TooMuchGas(format!(
+                "execution gas for read-only call is {} which is above the maximum allowed {}",
+                req.max_gas, self.config.max_read_only_gas
+            )));
+        }
+
+        // set the execution slot to be the one after the latest executed active slot
+        let slot = snapshot
+            .active_cursor
+            .get_next_slot(self.config.thread_count)
+            .expect("slot overflow in readonly execution from active slot");
+
+        // create a readonly execution context
+        let execution_context = ExecutionContext::readonly(
+            self.config.clone(),
+            slot,
+            req.call_stack,
+            self.final_state.clone(),
+            snapshot.active_history.clone(),
+            self.module_cache.clone(),
+            self.mip_store.clone(),
+        );
+
+        // run the interpreter according to the target type
+        let exec_response = match req.target {
+            ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
+                {
+                    let mut context = self.execution_context.lock();
+                    *context = execution_context;
+
+                    let call_stack_addr = context.get_call_stack();
+
+                    // transfer fee
+                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.first()) {
+                        context.transfer_coins(Some(*addr), None, fee, false)?;
+                    }
+                }
+
+                // load the tmp module
+                let module = self
+                    .module_cache
+                    .read()
+                    .load_tmp_module(&bytecode, req.max_gas)?;
+
+                // run the VM
+                massa_sc_runtime::run_main(
+                    &*self.execution_interface,
+                    module,
+                    req.max_gas,
+                    self.config.gas_costs.clone(),
+                )
+                .map_err(|error| ExecutionError::VMError {
+                    context: "ReadOnlyExecutionTarget::BytecodeExecution".to_string(),
+                    error,
+                })?
+            }
+
+            ReadOnlyExecutionTarget::FunctionCall {
+                target_addr,
+                target_func,
+                parameter,
+            } => {
+                // get the bytecode, default to an empty vector
+                let bytecode = execution_context
+                    .get_bytecode(&target_addr)
+                    .unwrap_or_default()
+                    .0;
+
+                {
+                    let mut context = self.execution_context.lock();
+                    *context = execution_context;
+
+                    // Ensure that the target address is an SC address and exists
+                    context.check_target_sc_address(target_addr)?;
+
+                    let call_stack_addr = context.get_call_stack();
+
+                    // transfer fee
+                    if let (Some(fee), Some(addr)) = (req.fee, call_stack_addr.first()) {
+                        context.transfer_coins(Some(*addr), None, fee, false)?;
+                    }
+
+                    // transfer coins
+                    if let (Some(coins), Some(from), Some(to)) =
+                        (req.coins, call_stack_addr.first(), call_stack_addr.get(1))
+                    {
+                        context.transfer_coins(Some(*from), Some(*to), coins, false)?;
+                    }
+                }
+
+                // load and execute the compiled module
+                // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
+                let module = self
+                    .module_cache
+                    .write()
+                    .load_module(&bytecode, req.max_gas)?;
+
+                let response = massa_sc_runtime::run_function(
+                    &*self.execution_interface,
+                    module,
+                    &target_func,
+                    &parameter,
+                    req.max_gas,
+                    self.config.gas_costs.clone(),
+                );
+
+                match response {
+                    Ok(Response { init_gas_cost, .. })
+                    | Err(VMError::ExecutionError { init_gas_cost, .. }) => {
+                        self.module_cache
+                            .write()
+                            .set_init_cost(&bytecode, init_gas_cost);
+                    }
+                    _ => (),
+                }
+
+                response.map_err(|error| ExecutionError::VMError {
+                    context: "ReadOnlyExecutionTarget::FunctionCall".to_string(),
+                    error,
+                })?
+            }
+        };
+
+        // return the execution output
+        let execution_output = self.execution_context.lock().settle_slot(None);
+        let exact_exec_cost = req.max_gas.saturating_sub(exec_response.remaining_gas);
+
+        // compute a gas cost, estimating the gas of the last SC call to be max_instance_cost
+        let corrected_cost = match self
+            .execution_context
+            .lock()
+            .gas_remaining_before_subexecution
+        {
+            Some(gas_remaining) => req
+                .max_gas
+                .saturating_sub(gas_remaining) // yield gas used until last subexecution
+                .saturating_add(self.config.gas_costs.max_instance_cost),
+            None => self.config.gas_costs.max_instance_cost, // no subexecution, just max_instance_cost
+        };
+
+        // keep the max of the two so the last SC call has at least max_instance_cost of gas
+        let estimated_cost = u64::max(exact_exec_cost, corrected_cost);
+        debug!(
+            "execute_readonly_request:
+            exec_response.remaining_gas: {}
+            exact_exec_cost: {}
+            corrected_cost: {}
+            estimated_cost: {}",
+            exec_response.remaining_gas, exact_exec_cost, corrected_cost, estimated_cost
+        );
+
+        Ok(ReadOnlyExecutionOutput {
+            out: execution_output,
+            gas_cost: estimated_cost,
+            call_result: exec_response.ret,
+        })
+    }
+}
+
+/// Execution context holding no speculative state, used between read-only executions
+fn placeholder_context(
+    config: &ExecutionConfig,
+    final_state: &Arc<RwLock<dyn FinalStateController>>,
+    module_cache: &Arc<RwLock<ModuleCache>>,
+    mip_store: &MipStore,
+) -> ExecutionContext {
+    let execution_trail_hash = final_state.read().get_execution_trail_hash();
+    ExecutionContext::new(
+        config.clone(),
+        final_state.clone(),
+        Default::default(),
+        module_cache.clone(),
+        mip_store.clone(),
+        execution_trail_hash,
+    )
+}
+
+/// Requests waiting to be picked by a read-only thread
+struct ReadOnlyInput {
+    /// set stop to true to stop the read-only threads
+    stop: bool,
+    /// queue for read-only requests and response MPSCs to send back their outputs
+    requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+}
+
+/// Queue feeding the read-only threads, shared by the controller and the manager
+#[derive(Clone)]
+pub(crate) struct ReadOnlyQueue {
+    /// queued requests with a wake-up condition variable
+    input: Arc<(Condvar, Mutex<ReadOnlyInput>)>,
+    /// maximum time to wait for the outcome of a request, from the time it is queued
+    response_timeout: Duration,
+    /// prometheus metrics
+    massa_metrics: MassaMetrics,
+}
+
+impl ReadOnlyQueue {
+    /// Creates a new `ReadOnlyQueue` holding at most `max_items` requests
+    pub fn new(max_items: usize, response_timeout: Duration, massa_metrics: MassaMetrics) -> Self {
+        ReadOnlyQueue {
+            input: Arc::new((
+                Condvar::new(),
+                Mutex::new(ReadOnlyInput {
+                    stop: false,
+                    requests: RequestQueue::new(max_items),
+                }),
+            )),
+            response_timeout,
+            massa_metrics,
+        }
+    }
+
+    /// Queues a read-only request and waits for the outcome of its execution
+    pub fn execute(
+        &self,
+        req: ReadOnlyExecutionRequest,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        let resp_rx = self.push(req)?;
+        match resp_rx.recv_timeout(self.response_timeout) {
+            Ok(result) => result,
+            Err(err) if err.is_timeout() => Err(ExecutionError::ReadOnlyTimeout(format!(
+                "no outcome after {} ms",
+                self.response_timeout.as_millis()
+            ))),
+            Err(err) => Err(ExecutionError::ChannelError(format!(
+                "readonly execution response channel readout failed: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Queues a read-only request
+    ///
+    /// # Returns
+    /// The receiver on which the result of the execution will be sent
+    #[allow(clippy::type_complexity)]
+    pub fn push(
+        &self,
+        req: ReadOnlyExecutionRequest,
+    ) -> Result<MassaReceiver<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>
+    {
+        let mut input = self.input.1.lock();
+
+        if input.stop {
+            return Err(ExecutionError::ChannelError(
+                "readonly execution cancelled because the execution worker is closing".into(),
+            ));
+        }
+
+        // if the read-only queue is already full, return an error
+        if input.requests.is_full() {
+            self.massa_metrics.inc_readonly_rejected();
+            return Err(ExecutionError::ChannelError(
+                "too many queued readonly requests".into(),
+            ));
+        }
+
+        // prepare the channel to send back the result of the read-only execution
+        let (resp_tx, resp_rx) = MassaChannel::new("read_only_request".to_string(), None);
+
+        // append the request to the queue and wake up a read-only thread
+        input
+            .requests
+            .push(RequestWithResponseSender::new(req, resp_tx));
+        self.massa_metrics
+            .set_readonly_queue_length(input.requests.len());
+        self.input.0.notify_one();
+
+        Ok(resp_rx)
+    }
+
+    /// Waits for a request to execute
+    ///
+    /// # Returns
+    /// The oldest queued request, or None if the read-only threads need to stop
+    fn wait_request(
+        &self,
+    ) -> Option<RequestWithResponseSender<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>> {
+        let mut input = self.input.1.lock();
+        while !input.stop && input.requests.is_empty() {
+            self.input.0.wait(&mut input);
+        }
+        if input.stop {
+            return None;
+        }
+        let req = input.requests.pop();
+        self.massa_metrics
+            .set_readonly_queue_length(input.requests.len());
+        req
+    }
+
+    /// Stops the read-only threads and cancels the pending requests
+    pub fn stop(&self) {
+        let mut input = self.input.1.lock();
+        input.stop = true;
+        input.requests.cancel(ExecutionError::ChannelError(
+            "readonly execution cancelled because the execution worker is closing".into(),
+        ));
+        self.massa_metrics.set_readonly_queue_length(0);
+        self.input.0.notify_all();
+    }
+}
+
+/// Latest snapshot taken, shared by the read-only threads
+type SnapshotCache = Arc<Mutex<Option<Arc<ReadOnlySnapshot>>>>;
+
+/// Structure gathering all elements needed by a read-only thread
+struct ReadOnlyThread {
+    /// execution config
+    config: ExecutionConfig,
+    /// queue from which requests are taken
+    queue: ReadOnlyQueue,
+    /// execution state from which snapshots are taken
+    execution_state: Arc<RwLock<ExecutionState>>,
+    /// versions of the execution state, used to detect outdated snapshots
+    versions: Arc<ExecutionStateVersions>,
+    /// latest snapshot taken by any read-only thread
+    snapshot_cache: SnapshotCache,
+    /// executor owned by this thread
+    executor: ReadOnlyExecutor,
+    /// final state, used to check that the snapshot is still consistent with it
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    /// prometheus metrics
+    massa_metrics: MassaMetrics,
+}
+
+impl ReadOnlyThread {
+    /// Get a snapshot of the current active state, reusing the cached one if the active history did not change
+    fn get_snapshot(&self) -> Arc<ReadOnlySnapshot> {
+        let mut cache = self.snapshot_cache.lock();
+        if let Some(snapshot) = cache.as_ref() {
+            if snapshot.history_version == self.versions.history.load(Ordering::Acquire)
+                && snapshot.final_divergence
+                    == self.versions.final_divergence.load(Ordering::Acquire)
+            {
+                return snapshot.clone();
+            }
+        }
+        let snapshot = Arc::new(self.execution_state.read().get_readonly_snapshot());
+        *cache = Some(snapshot.clone());
+        snapshot
+    }
+
+    /// Checks that the final state did not diverge from `snapshot`
+    fn is_consistent(&self, snapshot: &ReadOnlySnapshot) -> bool {
+        snapshot.final_divergence == self.versions.final_divergence.load(Ordering::Acquire)
+            && self.final_state.read().get_slot() <= snapshot.active_cursor
+    }
+
+    /// Executes a request against the latest snapshot, retrying if the final state diverged from it
+    fn execute(
+        &self,
+        req: ReadOnlyExecutionRequest,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        execute_with_retries(
+            self.config.readonly_execution_timeout.to_duration(),
+            || {
+                let snapshot = self.get_snapshot();
+                let outcome = self.executor.execute(req.clone(), &snapshot);
+                (outcome, self.is_consistent(&snapshot))
+            },
+            || self.massa_metrics.inc_readonly_retries(),
+        )
+    }
+
+    /// Main loop of a read-only thread
+    fn main_loop(&self) {
+        let queue_timeout = self.config.readonly_queue_timeout.to_duration();
+        while let Some(req_resp) = self.queue.wait_request() {
+            // reject requests that waited too long: their emitter is likely not waiting anymore
+            if req_resp.received_at().elapsed() > queue_timeout {
+                self.massa_metrics.inc_readonly_rejected();
+                req_resp.cancel(ExecutionError::ReadOnlyTimeout(
+                    "request timed out in the execution queue".into(),
+                ));
+                continue;
+            }
+
+            let (req, resp_tx) = req_resp.into_request_sender_pair();
+            let start = Instant::now();
+            let outcome = self.execute(req);
+            self.massa_metrics
+                .set_readonly_execution_time(start.elapsed().as_secs_f64());
+            self.massa_metrics.inc_readonly_executions();
+
+            // Send the execution output through resp_tx.
+            // Ignore errors because they just mean that the request emitter dropped the received
+            // because it doesn't need the response anymore.
+            let _ = resp_tx.send(outcome);
+        }
+    }
+}
+
+/// Runs `execute` until the outcome it returns is flagged as consistent with the execution state.
+///
+/// Gives up after `MAX_READONLY_ATTEMPTS` executions, or before retrying once `timeout` expired.
+/// `on_retry` is called before every new attempt.
+pub(crate) fn execute_with_retries<T>(
+    timeout: Duration,
+    mut execute: impl FnMut() -> (Result<T, ExecutionError>, bool),
+    mut on_retry: impl FnMut(),
+) -> Result<T, ExecutionError> {
+    let start = Instant::now();
+    let mut attempt = 1;
+    loop {
+        let (outcome, consistent) = execute();
+        if consistent {
+            return outcome;
+        }
+        if attempt >= MAX_READONLY_ATTEMPTS {
+            warn!(
+                "read-only execution: state changed during the last {} attempts",
+                attempt
+            );
+            return Err(ExecutionError::ReadOnlyStateChanged(attempt));
+        }
+        if start.elapsed() >= timeout {
+            return Err(ExecutionError::ReadOnlyTimeout(format!(
+                "state changed during the execution and {} ms elapsed",
+                timeout.as_millis()
+            )));
+        }
+        on_retry();
+        attempt += 1;
+    }
+}
+
+/// Launches `config.readonly_thread_count` read-only threads consuming `queue`
+///
+/// # Returns
+/// The handles of the launched threads
+pub(crate) fn start_readonly_threads(
+    config: &ExecutionConfig,
+    queue: &ReadOnlyQueue,
+    execution_state: Arc<RwLock<ExecutionState>>,
+    massa_metrics: MassaMetrics,
+) -> Vec<thread::JoinHandle<()>> {
+    let (versions, final_state, module_cache, mip_store) = {
+        let state = execution_state.read();
+        (
+            state.get_versions(),
+            state.get_final_state(),
+            state.get_module_cache(),
+            state.get_mip_store(),
+        )
+    };
+    let snapshot_cache: SnapshotCache = Default::default();
+
+    (0..config.readonly_thread_count.max(1))
+        .map(|index| {
+            let read_only_thread = ReadOnlyThread {
+                config: config.clone(),
+                queue: queue.clone(),
+                execution_state: execution_state.clone(),
+                versions: versions.clone(),
+                snapshot_cache: snapshot_cache.clone(),
+                executor: ReadOnlyExecutor::new(
+                    config.clone(),
+                    final_state.clone(),
+                    module_cache.clone(),
+                    mip_store.clone(),
+                ),
+                final_state: final_state.clone(),
+                massa_metrics: massa_metrics.clone(),
+            };
+            thread::Builder::new()
+                .stack_size(EXECUTION_THREAD_STACK_SIZE)
+                .name(format!("readonly_execution_{}", index))
+                .spawn(move || read_only_thread.main_loop())
+                .expect("failed to spawn thread : readonly_execution")
+        })
+        .collect()
+}
@@ -0,0 +1,241 @@
+//! Re-execution of the final slots recorded by the dump-block feature.
+//!
+//! The replayer executes the recorded blocks on top of a final state snapshot, slot by slot,
+//! with the execution code of the current binary. After each slot, the final state fingerprint
+//! and the execution trail hash are compared with the ones recorded by the node that made the dump,
+//! which allows checking VM or gas changes against real history before an upgrade.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use massa_db_exports::{Key, Value};
+use massa_execution_exports::{
+    ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionError,
+};
+use massa_final_state::FinalStateController;
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::{block_id::BlockId, slot::Slot};
+use massa_pos_exports::SelectorController;
+use massa_serialization::{DeserializeError, Deserializer};
+use massa_storage::Storage;
+use massa_versioning::versioning::MipStore;
+use massa_wallet::Wallet;
+use parking_lot::RwLock;
+
+use crate::execution::ExecutionState;
+use crate::replay_record::{ReplayBlockDeserializer, SlotReplayRecordDeserializer};
+use crate::storage_backend::StorageBackend;
+
+/// Result of the replay of a final slot
+#[derive(Debug, Clone)]
+pub struct SlotReplayReport {
+    /// Replayed slot
+    pub slot: Slot,
+    /// Block of the slot, None if the slot was missed
+    pub block_id: Option<BlockId>,
+    /// Final state fingerprint recorded in the dump
+    pub expected_fingerprint: Hash,
+    /// Final state fingerprint after the replay
+    pub fingerprint: Hash,
+    /// Execution trail hash recorded in the dump
+    pub expected_execution_trail_hash: Hash,
+    /// Execution trail hash after the replay
+    pub execution_trail_hash: Hash,
+    /// First final state key, in key order, that the replay did not write like the recorded execution
+    pub first_divergent_key: Option<Vec<u8>>,
+}
+
+impl SlotReplayReport {
+    /// Whether the replay produced the recorded state
+    pub fn is_consistent(&self) -> bool {
+        self.expected_fingerprint == self.fingerprint
+            && self.expected_execution_trail_hash == self.execution_trail_hash
+            && self.first_divergent_key.is_none()
+    }
+}
+
+/// Replays the final slots of a dump on top of a final state
+pub struct SlotReplayer {
+    execution_state: ExecutionState,
+    selector: Box<dyn SelectorController>,
+    dump: Arc<RwLock<dyn StorageBackend>>,
+    record_deserializer: SlotReplayRecordDeserializer,
+    thread_count: u8,
+}
+
+impl SlotReplayer {
+    /// Creates a replayer starting right after the slot of `final_state`.
+    /// The final state is modified by the replay.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config: ExecutionConfig,
+        final_state: Arc<RwLock<dyn FinalStateController>>,
+        selector: Box<dyn SelectorController>,
+        mip_store: MipStore,
+        channels: ExecutionChannels,
+        wallet: Arc<RwLock<Wallet>>,
+        massa_metrics: MassaMetrics,
+        dump: Arc<RwLock<dyn StorageBackend>>,
+    ) -> Self {
+        let record_deserializer = SlotReplayRecordDeserializer::new(ReplayBlockDeserializer::new(
+            config.thread_count,
+            config.endorsement_count as u32,
+            config.max_datastore_value_size,
+            config.max_function_length,
+            config.max_parameter_length,
+            config.chain_id,
+        ));
+        let thread_count = config.thread_count;
+        let execution_state = ExecutionState::new(
+            config,
+            final_state,
+            mip_store,
+            selector.clone_box(),
+            channels,
+            wallet,
+            massa_metrics,
+            // the replay must not overwrite the dump it reads
+            #[cfg(feature = "dump-block")]
+            Arc::new(RwLock::new(NoDump)),
+        );
+        Self {
+            execution_state,
+            selector,
+            dump,
+            record_deserializer,
+            thread_count,
+        }
+    }
+
+    /// Last slot applied to the final state
+    pub fn final_slot(&self) -> Slot {
+        self.execution_state.final_cursor
+    }
+
+    /// Re-executes the slot following the final state and compares the result with the dump
+    pub fn replay_next_slot(&mut self) -> Result<SlotReplayReport, ExecutionError> {
+        let slot = self
+            .execution_state
+            .final_cursor
+            .get_next_slot(self.thread_count)?;
+        let bytes = self.dump.read().read_replay_record(&slot).ok_or_else(|| {
+            ExecutionError::RuntimeError(format!("no replay record for slot {}", slot))
+        })?;
+        let (_, record) = self
+            .record_deserializer
+            .deserialize::<DeserializeError>(&bytes)
+            .map_err(|err| {
+                ExecutionError::RuntimeError(format!(
+                    "invalid replay record for slot {}: {}",
+                    slot, err
+                ))
+            })?;
+
+        let exec_target = record.block.map(|replay_block| {
+            let block_id = replay_block.block.id;
+            let mut storage = Storage::create_root();
+            storage.store_operations(replay_block.operations);
+            storage.store_block(replay_block.block);
+            (
+                block_id,
+                ExecutionBlockMetadata {
+                    same_thread_parent_creator: replay_block.same_thread_parent_creator,
+                    storage: Some(storage),
+                },
+            )
+        });
+        self.execution_state.execute_final_slot(
+            &slot,
+            exec_target.as_ref(),
+            self.selector.clone_box(),
+        );
+
+        let final_state = self.execution_state.get_final_state();
+        let final_state = final_state.read();
+        let changes = final_state
+            .get_database()
+            .read()
+            .get_changes_at(slot)
+            .unwrap_or_default();
+        Ok(SlotReplayReport {
+            slot,
+            block_id: exec_target.map(|(block_id, _)| block_id),
+            expected_fingerprint: record.final_state_fingerprint,
+            fingerprint: final_state.get_fingerprint(),
+            expected_execution_trail_hash: record.execution_trail_hash,
+            execution_trail_hash: final_state.get_execution_trail_hash(),
+            first_divergent_key: first_divergent_key(&record.state_changes, changes),
+        })
+    }
+}
+
+/// First key, in key order, that was not written with the expected value
+fn first_divergent_key(
+    expected: &BTreeMap<Vec<u8>, Option<Hash>>,
+    changes: BTreeMap<Key, Option<Value>>,
+) -> Option<Vec<u8>> {
+    let replayed: BTreeMap<Vec<u8>, Option<Hash>> = changes
+        .into_iter()
+        .map(|(key, value)| (key, value.map(|value| Hash::compute_from(&value))))
+        .collect();
+    let keys: BTreeSet<&Vec<u8>> = expected.keys().chain(replayed.keys()).collect();
+    keys.into_iter()
+        .find(|key| expected.get(*key) != replayed.get(*key))
+        .cloned()
+}
+
+/// Storage backend given to the replayed execution state, that drops everything
+#[cfg(feature = "dump-block")]
+struct NoDump;
+
+#[cfg(feature = "dump-block")]
+impl StorageBackend for NoDump {
+    fn write(&mut self, _slot: &Slot, _value: &[u8]) {}
+
+    fn read(&self, _slot: &Slot) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn write_replay_record(&mut self, _slot: &Slot, _value: &[u8]) {}
+
+    fn read_replay_record(&self, _slot: &Slot) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_divergent_key() {
+        let mut expected = BTreeMap::new();
+        expected.insert(b"a".to_vec(), Some(Hash::compute_from(b"1")));
+        expected.insert(b"c".to_vec(), None);
+        expected.insert(b"d".to_vec(), Some(Hash::compute_from(b"4")));
+
+        let mut changes = BTreeMap::new();
+        changes.insert(b"a".to_vec(), Some(b"1".to_vec()));
+        changes.insert(b"c".to_vec(), None);
+        changes.insert(b"d".to_vec(), Some(b"4".to_vec()));
+        assert_eq!(first_divergent_key(&expected, changes.clone()), None);
+
+        // a key written by the replay only
+        let mut extra = changes.clone();
+        extra.insert(b"b".to_vec(), Some(b"2".to_vec()));
+        assert_eq!(first_divergent_key(&expected, extra), Some(b"b".to_vec()));
+
+        // a different value, and a deletion instead of a write
+        let mut different = changes.clone();
+        different.insert(b"d".to_vec(), None);
+        assert_eq!(
+            first_divergent_key(&expected, different),
+            Some(b"d".to_vec())
+        );
+
+        // a key missing from the replay
+        changes.remove(b"c".as_slice());
+        assert_eq!(first_divergent_key(&expected, changes), Some(b"c".to_vec()));
+    }
+}
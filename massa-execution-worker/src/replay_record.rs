@@ -0,0 +1,358 @@
+//! Replay records written by the dump-block feature.
+//!
+//! For each final slot, on top of the block written in the gRPC format, the dump contains a
+//! `SlotReplayRecord`: what is needed to execute the slot again (the block, its operations and
+//! the creator of the parent block in the same thread), and hashes of the final state that the
+//! execution of the slot produced.
+
+use std::collections::BTreeMap;
+use std::ops::Bound::Included;
+
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
+use massa_models::{
+    address::{Address, AddressDeserializer, AddressSerializer},
+    block::{Block, BlockDeserializer, BlockDeserializerArgs, SecureShareBlock},
+    config::{
+        MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_OPERATIONS_PER_BLOCK,
+        MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+    },
+    operation::{Operation, OperationDeserializer, SecureShareOperation},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
+    serialization::{VecU8Deserializer, VecU8Serializer},
+};
+use massa_serialization::{
+    Deserializer, OptionDeserializer, OptionSerializer, SerializeError, Serializer,
+    U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+
+/// Block of a replayed slot, with what its execution needs
+#[derive(Debug, Clone)]
+pub struct ReplayBlock {
+    /// The block
+    pub block: SecureShareBlock,
+    /// Operations of the block, in the block order
+    pub operations: Vec<SecureShareOperation>,
+    /// Creator of the parent of the block in the same thread
+    pub same_thread_parent_creator: Option<Address>,
+}
+
+/// Everything needed to re-execute a final slot and check the result
+#[derive(Debug, Clone)]
+pub struct SlotReplayRecord {
+    /// Block of the slot, None if the slot was missed
+    pub block: Option<ReplayBlock>,
+    /// Fingerprint of the final state after the execution of the slot
+    pub final_state_fingerprint: Hash,
+    /// Execution trail hash after the execution of the slot
+    pub execution_trail_hash: Hash,
+    /// Final state entries written by the slot, with the hash of their new value (None for a deletion)
+    pub state_changes: BTreeMap<Vec<u8>, Option<Hash>>,
+}
+
+/// Serializer for `ReplayBlock`
+#[derive(Clone)]
+pub struct ReplayBlockSerializer {
+    secure_share_serializer: SecureShareSerializer,
+    operation_count_serializer: U32VarIntSerializer,
+    parent_creator_serializer: OptionSerializer<Address, AddressSerializer>,
+}
+
+impl ReplayBlockSerializer {
+    /// Creates a new `ReplayBlockSerializer`
+    pub fn new() -> Self {
+        Self {
+            secure_share_serializer: SecureShareSerializer::new(),
+            operation_count_serializer: U32VarIntSerializer::new(),
+            parent_creator_serializer: OptionSerializer::new(AddressSerializer::new()),
+        }
+    }
+}
+
+impl Default for ReplayBlockSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<ReplayBlock> for ReplayBlockSerializer {
+    fn serialize(&self, value: &ReplayBlock, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.secure_share_serializer
+            .serialize(&value.block, buffer)?;
+        let operation_count: u32 = value.operations.len().try_into().map_err(|err| {
+            SerializeError::NumberTooBig(format!("too many operations in the block: {}", err))
+        })?;
+        self.operation_count_serializer
+            .serialize(&operation_count, buffer)?;
+        for operation in &value.operations {
+            self.secure_share_serializer.serialize(operation, buffer)?;
+        }
+        self.parent_creator_serializer
+            .serialize(&value.same_thread_parent_creator, buffer)
+    }
+}
+
+/// Deserializer for `ReplayBlock`
+pub struct ReplayBlockDeserializer {
+    block_deserializer: SecureShareDeserializer<Block, BlockDeserializer>,
+    operation_count_deserializer: U32VarIntDeserializer,
+    operation_deserializer: SecureShareDeserializer<Operation, OperationDeserializer>,
+    parent_creator_deserializer: OptionDeserializer<Address, AddressDeserializer>,
+}
+
+impl ReplayBlockDeserializer {
+    /// Creates a new `ReplayBlockDeserializer`
+    pub fn new(
+        thread_count: u8,
+        endorsement_count: u32,
+        max_datastore_value_length: u64,
+        max_function_name_length: u16,
+        max_parameters_size: u32,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            block_deserializer: SecureShareDeserializer::new(
+                BlockDeserializer::new(BlockDeserializerArgs {
+                    thread_count,
+                    max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+                    endorsement_count,
+                    max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+                    last_start_period: None,
+                    chain_id,
+                }),
+                chain_id,
+            ),
+            operation_count_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_OPERATIONS_PER_BLOCK),
+            ),
+            operation_deserializer: SecureShareDeserializer::new(
+                OperationDeserializer::new(
+                    max_datastore_value_length,
+                    max_function_name_length,
+                    max_parameters_size,
+                    MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+                    MAX_OPERATION_DATASTORE_KEY_LENGTH,
+                    MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+                ),
+                chain_id,
+            ),
+            parent_creator_deserializer: OptionDeserializer::new(AddressDeserializer::new()),
+        }
+    }
+}
+
+impl Deserializer<ReplayBlock> for ReplayBlockDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], ReplayBlock, E> {
+        context(
+            "Failed ReplayBlock deserialization",
+            tuple((
+                context("Failed block deserialization", |input| {
+                    self.block_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed operations deserialization",
+                    length_count(
+                        context("Failed operation count deserialization", |input| {
+                            self.operation_count_deserializer.deserialize(input)
+                        }),
+                        context("Failed operation deserialization", |input| {
+                            self.operation_deserializer.deserialize(input)
+                        }),
+                    ),
+                ),
+                context(
+                    "Failed same_thread_parent_creator deserialization",
+                    |input| self.parent_creator_deserializer.deserialize(input),
+                ),
+            )),
+        )
+        .map(
+            |(block, operations, same_thread_parent_creator)| ReplayBlock {
+                block,
+                operations,
+                same_thread_parent_creator,
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+/// Serializer for `SlotReplayRecord`
+pub struct SlotReplayRecordSerializer {
+    block_serializer: OptionSerializer<ReplayBlock, ReplayBlockSerializer>,
+    hash_serializer: HashSerializer,
+    change_count_serializer: U64VarIntSerializer,
+    key_serializer: VecU8Serializer,
+    value_hash_serializer: OptionSerializer<Hash, HashSerializer>,
+}
+
+impl SlotReplayRecordSerializer {
+    /// Creates a new `SlotReplayRecordSerializer`
+    pub fn new() -> Self {
+        Self {
+            block_serializer: OptionSerializer::new(ReplayBlockSerializer::new()),
+            hash_serializer: HashSerializer::new(),
+            change_count_serializer: U64VarIntSerializer::new(),
+            key_serializer: VecU8Serializer::new(),
+            value_hash_serializer: OptionSerializer::new(HashSerializer::new()),
+        }
+    }
+}
+
+impl Default for SlotReplayRecordSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<SlotReplayRecord> for SlotReplayRecordSerializer {
+    fn serialize(
+        &self,
+        value: &SlotReplayRecord,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.block_serializer.serialize(&value.block, buffer)?;
+        self.hash_serializer
+            .serialize(&value.final_state_fingerprint, buffer)?;
+        self.hash_serializer
+            .serialize(&value.execution_trail_hash, buffer)?;
+        self.change_count_serializer
+            .serialize(&(value.state_changes.len() as u64), buffer)?;
+        for (key, value_hash) in &value.state_changes {
+            self.key_serializer.serialize(key, buffer)?;
+            self.value_hash_serializer.serialize(value_hash, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `SlotReplayRecord`
+pub struct SlotReplayRecordDeserializer {
+    block_deserializer: OptionDeserializer<ReplayBlock, ReplayBlockDeserializer>,
+    hash_deserializer: HashDeserializer,
+    change_count_deserializer: U64VarIntDeserializer,
+    key_deserializer: VecU8Deserializer,
+    value_hash_deserializer: OptionDeserializer<Hash, HashDeserializer>,
+}
+
+impl SlotReplayRecordDeserializer {
+    /// Creates a new `SlotReplayRecordDeserializer`
+    pub fn new(block_deserializer: ReplayBlockDeserializer) -> Self {
+        Self {
+            block_deserializer: OptionDeserializer::new(block_deserializer),
+            hash_deserializer: HashDeserializer::new(),
+            change_count_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            key_deserializer: VecU8Deserializer::new(Included(0), Included(u64::MAX)),
+            value_hash_deserializer: OptionDeserializer::new(HashDeserializer::new()),
+        }
+    }
+}
+
+impl Deserializer<SlotReplayRecord> for SlotReplayRecordDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SlotReplayRecord, E> {
+        context(
+            "Failed SlotReplayRecord deserialization",
+            tuple((
+                context("Failed block deserialization", |input| {
+                    self.block_deserializer.deserialize(input)
+                }),
+                context("Failed final_state_fingerprint deserialization", |input| {
+                    self.hash_deserializer.deserialize(input)
+                }),
+                context("Failed execution_trail_hash deserialization", |input| {
+                    self.hash_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed state_changes deserialization",
+                    length_count(
+                        context("Failed change count deserialization", |input| {
+                            self.change_count_deserializer.deserialize(input)
+                        }),
+                        tuple((
+                            context("Failed key deserialization", |input| {
+                                self.key_deserializer.deserialize(input)
+                            }),
+                            context("Failed value hash deserialization", |input| {
+                                self.value_hash_deserializer.deserialize(input)
+                            }),
+                        )),
+                    ),
+                ),
+            )),
+        )
+        .map(
+            |(block, final_state_fingerprint, execution_trail_hash, state_changes)| {
+                SlotReplayRecord {
+                    block,
+                    final_state_fingerprint,
+                    execution_trail_hash,
+                    state_changes: state_changes.into_iter().collect(),
+                }
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::config::{
+        CHAINID, ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH,
+        MAX_PARAMETERS_SIZE, THREAD_COUNT,
+    };
+    use massa_serialization::DeserializeError;
+
+    #[test]
+    fn test_slot_replay_record_ser_deser() {
+        let mut state_changes = BTreeMap::new();
+        state_changes.insert(b"ledger_key".to_vec(), Some(Hash::compute_from(b"value")));
+        state_changes.insert(b"deleted_key".to_vec(), None);
+        let record = SlotReplayRecord {
+            block: None,
+            final_state_fingerprint: Hash::compute_from(b"fingerprint"),
+            execution_trail_hash: Hash::compute_from(b"trail"),
+            state_changes,
+        };
+
+        let mut buffer = Vec::new();
+        SlotReplayRecordSerializer::new()
+            .serialize(&record, &mut buffer)
+            .unwrap();
+        let deserializer = SlotReplayRecordDeserializer::new(ReplayBlockDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            *CHAINID,
+        ));
+        let (rest, deserialized) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert!(deserialized.block.is_none());
+        assert_eq!(
+            deserialized.final_state_fingerprint,
+            record.final_state_fingerprint
+        );
+        assert_eq!(
+            deserialized.execution_trail_hash,
+            record.execution_trail_hash
+        );
+        assert_eq!(deserialized.state_changes, record.state_changes);
+    }
+}
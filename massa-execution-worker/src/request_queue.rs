@@ -5,6 +5,7 @@
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionError;
 use std::collections::VecDeque;
+use std::time::Instant;
 
 /// Represents an execution request T coupled with an MPSC sender for a result of type R
 #[derive(Debug)]
@@ -13,6 +14,8 @@ pub(crate) struct RequestWithResponseSender<T, R> {
     request: T,
     /// An std::mpsc::Sender to later send the execution output R (or an error)
     response_tx: MassaSender<Result<R, ExecutionError>>,
+    /// When the request was received
+    received_at: Instant,
 }
 
 impl<T, R> RequestWithResponseSender<T, R> {
@@ -25,9 +28,15 @@ impl<T, R> RequestWithResponseSender<T, R> {
         RequestWithResponseSender {
             request,
             response_tx,
+            received_at: Instant::now(),
         }
     }
 
+    /// Returns the instant at which the request was received
+    pub fn received_at(&self) -> Instant {
+        self.received_at
+    }
+
     /// Cancel the request by consuming the object and sending an error through the response channel.
     ///
     /// # Arguments
@@ -70,37 +79,6 @@ impl<T, R> RequestQueue<T, R> {
         }
     }
 
-    /// Returns the max number of items the queue can contain
-    pub fn capacity(&self) -> usize {
-        self.max_items
-    }
-
-    /// Extends Self with the contents of another `RequestQueue`.
-    /// The contents of the incoming queue are appended last.
-    /// Excess items with respect to `self.max_items` are canceled and dropped.
-    pub fn extend(&mut self, mut other: RequestQueue<T, R>) {
-        // compute the number of available item slots
-        let free_slots = self.max_items.saturating_sub(self.queue.len());
-
-        // if there are no available slots remaining, do nothing
-        if free_slots == 0 {
-            return;
-        }
-
-        // if there are not enough available slots to fit the entire incoming queue
-        if free_slots < other.queue.len() {
-            // truncate the incoming queue to the size that fits, cancelling excess items
-            other.queue.drain(free_slots..).for_each(|req| {
-                req.cancel(ExecutionError::ChannelError(
-                    "maximal request queue capacity reached".into(),
-                ))
-            });
-        }
-
-        // append the kept part of the incoming queue
-        self.queue.extend(other.queue);
-    }
-
     /// Cancel all queued items.
     ///
     /// # Arguments
@@ -154,6 +132,11 @@ impl<T, R> RequestQueue<T, R> {
         self.queue.len() >= self.max_items
     }
 
+    /// Returns the number of queued items
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
     /// Checks whether the queue is empty
     ///
     /// # Returns
@@ -18,6 +18,20 @@ pub trait StorageBackend: Send + Sync {
     /// Reads the value from the storage backend.
     /// The slot is used as the key to the value.
     fn read(&self, slot: &Slot) -> Option<Vec<u8>>;
+
+    /// Writes the replay record of a slot (see `SlotReplayRecord`).
+    /// It is removed along with the block of the slot.
+    fn write_replay_record(&mut self, slot: &Slot, value: &[u8]);
+
+    /// Reads the replay record of a slot, if any.
+    fn read_replay_record(&self, slot: &Slot) -> Option<Vec<u8>>;
+}
+
+/// Prefix of the keys of the replay records in `RocksDBStorageBackend`
+const REPLAY_RECORD_PREFIX: &[u8] = b"replay_";
+
+fn replay_record_key(slot: &Slot) -> Vec<u8> {
+    [REPLAY_RECORD_PREFIX, &slot.to_bytes_key()].concat()
 }
 
 /// A storage backend that uses the file system as the underlying storage engine.
@@ -35,6 +49,11 @@ impl FileStorageBackend {
             max_blocks,
         }
     }
+
+    fn replay_record_path(&self, slot: &Slot) -> PathBuf {
+        self.folder
+            .join(format!("replay_slot_{}_{}.bin", slot.thread, slot.period))
+    }
 }
 
 impl StorageBackend for FileStorageBackend {
@@ -46,6 +65,8 @@ impl StorageBackend for FileStorageBackend {
                 slot_to_remove.thread, slot_to_remove.period
             ));
             std::fs::remove_file(block_file_path).expect("Unable to delete block from disk");
+            // dumps made before replay records were introduced do not have them
+            let _ = std::fs::remove_file(self.replay_record_path(&slot_to_remove));
         }
         let block_file_path = self
             .folder
@@ -73,6 +94,16 @@ impl StorageBackend for FileStorageBackend {
 
         Some(buffer)
     }
+
+    fn write_replay_record(&mut self, slot: &Slot, value: &[u8]) {
+        let record_path = self.replay_record_path(slot);
+        std::fs::write(&record_path, value)
+            .unwrap_or_else(|_| panic!("Cannot write file: {:?}", record_path));
+    }
+
+    fn read_replay_record(&self, slot: &Slot) -> Option<Vec<u8>> {
+        std::fs::read(self.replay_record_path(slot)).ok()
+    }
 }
 
 /// A storage backend that uses RocksDB as the underlying storage engine.
@@ -107,6 +138,9 @@ impl StorageBackend for RocksDBStorageBackend {
             self.db
                 .delete(slot_to_remove.to_bytes_key())
                 .expect("Unable to delete block from db");
+            self.db
+                .delete(replay_record_key(&slot_to_remove))
+                .expect("Unable to delete replay record from db");
         }
         self.db
             .put(slot.to_bytes_key(), value)
@@ -123,6 +157,22 @@ impl StorageBackend for RocksDBStorageBackend {
             }
         }
     }
+
+    fn write_replay_record(&mut self, slot: &Slot, value: &[u8]) {
+        self.db
+            .put(replay_record_key(slot), value)
+            .expect("Unable to write replay record to db");
+    }
+
+    fn read_replay_record(&self, slot: &Slot) -> Option<Vec<u8>> {
+        match self.db.get(replay_record_key(slot)) {
+            Ok(val) => val,
+            Err(e) => {
+                println!("Error: {} reading replay record of slot {}", e, slot);
+                None
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -14,6 +14,10 @@
 //!
 //! Unit tests for the ActiveHistory struct.
 //!
+//! ## tests_readonly_pool.rs
+//!
+//! Unit tests for the read-only request queue and the retry logic of the read-only threads.
+//!
 //! ## scenarios_mandatories.rs
 //!
 //! Complex unit tests using mocks for some parts of the massa node. See universe.rs for more information
@@ -35,4 +39,7 @@ mod universe;
 #[cfg(test)]
 mod tests_active_history;
 
+#[cfg(test)]
+mod tests_readonly_pool;
+
 mod interface;
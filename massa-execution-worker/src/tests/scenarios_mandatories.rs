@@ -31,13 +31,15 @@ use massa_pos_exports::{
 };
 use massa_signature::KeyPair;
 use massa_test_framework::{TestUniverse, WaitPoint};
+use massa_versioning::versioning::MipComponent;
 use mockall::predicate;
 use num::rational::Ratio;
 use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::{cmp::Reverse, collections::BTreeMap, str::FromStr, time::Duration};
 
-use super::universe::{ExecutionForeignControllers, ExecutionTestUniverse};
+use super::universe::{create_mip_store, ExecutionForeignControllers, ExecutionTestUniverse};
 
 #[cfg(feature = "execution-trace")]
 use massa_execution_exports::types_trace_info::AbiTrace;
@@ -68,10 +70,10 @@ fn final_state_boilerplate(
     custom_async_pool: Option<AsyncPool>,
     custom_pos_state: Option<PoSFinalState>,
 ) {
+    // read by the execution worker at startup, then by the read-only threads after each execution
     mock_final_state
         .write()
         .expect_get_slot()
-        .times(1)
         .returning(move || Slot::new(0, 0));
 
     mock_final_state
@@ -1295,6 +1297,442 @@ fn send_and_receive_transaction() {
     finalized_waitpoint.wait();
 }
 
+#[test]
+fn readonly_execution_alongside_slot_execution() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let recipient_address =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            assert_eq!(
+                changes
+                    .ledger_changes
+                    .get_balance_or_else(&recipient_address, || None),
+                Some(Amount::from_str("190").unwrap())
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+
+    // read-only requests are executed by their own threads while the slot is executed
+    let readonly_controller = universe.module_controller.clone();
+    let readonly_requests = std::thread::spawn(move || {
+        (0..10)
+            .map(|_| {
+                readonly_controller.execute_readonly_request(ReadOnlyExecutionRequest {
+                    max_gas: 100_000_000,
+                    call_stack: vec![ExecutionStackElement {
+                        address: Address::from_public_key(
+                            &KeyPair::from_str(TEST_SK_2).unwrap().get_public_key(),
+                        ),
+                        coins: Amount::zero(),
+                        owned_addresses: vec![],
+                        operation_datastore: None,
+                    }],
+                    target: ReadOnlyExecutionTarget::BytecodeExecution(
+                        include_bytes!("./wasm/event_test.wasm").to_vec(),
+                    ),
+                    coins: None,
+                    fee: None,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address,
+                amount: Amount::from_str("90").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+
+    for res in readonly_requests.join().unwrap() {
+        let mut res = res.expect("readonly execution failed");
+        assert_eq!(res.out.events.take().len(), 1, "wrong number of events");
+    }
+}
+
+#[test]
+fn execution_suspended_until_resumed() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let resumed = Arc::new(AtomicBool::new(false));
+    let resumed_clone = resumed.clone();
+    let recipient_address =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            assert!(
+                resumed_clone.load(Ordering::SeqCst),
+                "slot executed while execution was suspended"
+            );
+            assert_eq!(
+                changes
+                    .ledger_changes
+                    .get_balance_or_else(&recipient_address, || None),
+                Some(Amount::from_str("90").unwrap())
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address,
+                amount: Amount::from_str("90").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+
+    // the block is dropped while execution is suspended
+    universe.module_controller.suspend();
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block.clone());
+    std::thread::sleep(Duration::from_millis(500));
+
+    // consensus notifies its graph again once execution is resumed
+    resumed.store(true, Ordering::SeqCst);
+    universe.module_controller.resume_from_final_state();
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+}
+
+#[test]
+fn batch_transaction_before_activation() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let recipient_address_1 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let recipient_address_2 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            // the MIP store of the test universe is empty: the operation is not included
+            for recipient_address in [recipient_address_1, recipient_address_2] {
+                assert_eq!(
+                    changes
+                        .ledger_changes
+                        .get_balance_or_else(&recipient_address, || None),
+                    None
+                );
+            }
+            // 100 in the get_balance in the `final_state_boilerplate`, no fee was spent
+            assert_eq!(
+                changes.ledger_changes.get_balance_or_else(
+                    &Address::from_public_key(
+                        &KeyPair::from_str(TEST_SK_1).unwrap().get_public_key()
+                    ),
+                    || None
+                ),
+                Some(
+                    exec_cfg
+                        .block_reward
+                        .saturating_add(Amount::from_str("100").unwrap())
+                )
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+    // create the operation
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::BatchTransaction {
+                transfers: vec![
+                    (recipient_address_1, Amount::from_str("30").unwrap()),
+                    (recipient_address_2, Amount::from_str("40").unwrap()),
+                ],
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    // create the block containing the batch transaction operation
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+    // store the block in storage
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+}
+
+#[test]
+fn batch_transaction() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let recipient_address_1 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let recipient_address_2 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            // 100 in the get_balance in the `final_state_boilerplate` and 30 / 40 from the batch.
+            assert_eq!(
+                changes
+                    .ledger_changes
+                    .get_balance_or_else(&recipient_address_1, || None),
+                Some(Amount::from_str("130").unwrap())
+            );
+            assert_eq!(
+                changes
+                    .ledger_changes
+                    .get_balance_or_else(&recipient_address_2, || None),
+                Some(Amount::from_str("140").unwrap())
+            );
+            // 100 - 70 (batch) - 10 (fee) + 10 (fee as block producer)
+            assert_eq!(
+                changes.ledger_changes.get_balance_or_else(
+                    &Address::from_public_key(
+                        &KeyPair::from_str(TEST_SK_1).unwrap().get_public_key()
+                    ),
+                    || None
+                ),
+                Some(
+                    exec_cfg
+                        .block_reward
+                        .saturating_add(Amount::from_str("30").unwrap())
+                )
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new_with_mip_store(
+        foreign_controllers,
+        exec_cfg.clone(),
+        create_mip_store(&[MipComponent::BatchTransaction]),
+    );
+    // create the operation
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::BatchTransaction {
+                transfers: vec![
+                    (recipient_address_1, Amount::from_str("30").unwrap()),
+                    (recipient_address_2, Amount::from_str("40").unwrap()),
+                ],
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    // create the block containing the batch transaction operation
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+    // store the block in storage
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+}
+
+#[test]
+fn batch_transaction_reverted() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let recipient_address_1 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let recipient_address_2 =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |_, changes| {
+            // the second transfer can't be paid: the first one is reverted as well
+            for recipient_address in [recipient_address_1, recipient_address_2] {
+                assert_eq!(
+                    changes
+                        .ledger_changes
+                        .get_balance_or_else(&recipient_address, || None),
+                    None
+                );
+            }
+            // 100 - 10 (fee) + 10 (fee as block producer)
+            assert_eq!(
+                changes.ledger_changes.get_balance_or_else(
+                    &Address::from_public_key(
+                        &KeyPair::from_str(TEST_SK_1).unwrap().get_public_key()
+                    ),
+                    || None
+                ),
+                Some(
+                    exec_cfg
+                        .block_reward
+                        .saturating_add(Amount::from_str("100").unwrap())
+                )
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new_with_mip_store(
+        foreign_controllers,
+        exec_cfg.clone(),
+        create_mip_store(&[MipComponent::BatchTransaction]),
+    );
+    // create the operation, 30 + 80 is more than the 90 coins left after the fee
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::BatchTransaction {
+                transfers: vec![
+                    (recipient_address_1, Amount::from_str("30").unwrap()),
+                    (recipient_address_2, Amount::from_str("80").unwrap()),
+                ],
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    // create the block containing the batch transaction operation
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+    // store the block in storage
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+}
+
 #[test]
 fn roll_buy() {
     // setup
@@ -2179,10 +2617,14 @@ fn datastore_manipulations() {
                 ExecutionQueryRequestItem::AddressDatastoreKeysCandidate {
                     addr,
                     prefix: vec![],
+                    offset: None,
+                    limit: None,
                 },
                 ExecutionQueryRequestItem::AddressDatastoreKeysFinal {
                     addr,
                     prefix: vec![],
+                    offset: None,
+                    limit: None,
                 },
                 ExecutionQueryRequestItem::AddressDatastoreValueCandidate {
                     addr,
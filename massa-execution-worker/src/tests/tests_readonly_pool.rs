@@ -0,0 +1,133 @@
+use crate::readonly_pool::{execute_with_retries, ReadOnlyQueue, MAX_READONLY_ATTEMPTS};
+use massa_execution_exports::{ExecutionError, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget};
+use massa_metrics::MassaMetrics;
+use std::time::Duration;
+
+fn create_queue(max_items: usize, response_timeout: Duration) -> ReadOnlyQueue {
+    let massa_metrics = MassaMetrics::new(
+        false,
+        "0.0.0.0:9898".parse().unwrap(),
+        32,
+        Duration::from_secs(5),
+    )
+    .0;
+    ReadOnlyQueue::new(max_items, response_timeout, massa_metrics)
+}
+
+fn create_request() -> ReadOnlyExecutionRequest {
+    ReadOnlyExecutionRequest {
+        max_gas: 1_000_000,
+        call_stack: vec![],
+        target: ReadOnlyExecutionTarget::BytecodeExecution(vec![]),
+        coins: None,
+        fee: None,
+    }
+}
+
+#[test]
+fn test_readonly_queue_full() {
+    let queue = create_queue(2, Duration::from_secs(10));
+    let _rx_1 = queue.push(create_request()).unwrap();
+    let _rx_2 = queue.push(create_request()).unwrap();
+    assert!(matches!(
+        queue.push(create_request()),
+        Err(ExecutionError::ChannelError(_))
+    ));
+}
+
+#[test]
+fn test_readonly_queue_cancelled_on_stop() {
+    let queue = create_queue(10, Duration::from_secs(10));
+    let rx_1 = queue.push(create_request()).unwrap();
+    let rx_2 = queue.push(create_request()).unwrap();
+    queue.stop();
+
+    // queued requests are answered with an error
+    for rx in [rx_1, rx_2] {
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_secs(1)),
+            Ok(Err(ExecutionError::ChannelError(_)))
+        ));
+    }
+
+    // new requests are refused
+    assert!(matches!(
+        queue.push(create_request()),
+        Err(ExecutionError::ChannelError(_))
+    ));
+}
+
+#[test]
+fn test_readonly_queue_response_timeout() {
+    // no read-only thread consumes the queue
+    let queue = create_queue(10, Duration::from_millis(100));
+    assert!(matches!(
+        queue.execute(create_request()),
+        Err(ExecutionError::ReadOnlyTimeout(_))
+    ));
+}
+
+#[test]
+fn test_readonly_retry_on_divergence() {
+    let mut executions = 0;
+    let mut retries = 0;
+    let outcome = execute_with_retries(
+        Duration::from_secs(10),
+        || {
+            executions += 1;
+            // the state changed during the first execution only
+            (Ok(executions), executions > 1)
+        },
+        || retries += 1,
+    );
+    assert_eq!(outcome.unwrap(), 2);
+    assert_eq!(retries, 1);
+}
+
+#[test]
+fn test_readonly_state_keeps_changing() {
+    let mut executions = 0;
+    let outcome: Result<(), _> = execute_with_retries(
+        Duration::from_secs(10),
+        || {
+            executions += 1;
+            (Ok(()), false)
+        },
+        || {},
+    );
+    assert!(matches!(
+        outcome,
+        Err(ExecutionError::ReadOnlyStateChanged(MAX_READONLY_ATTEMPTS))
+    ));
+    assert_eq!(executions, MAX_READONLY_ATTEMPTS);
+}
+
+#[test]
+fn test_readonly_retry_timeout() {
+    let mut executions = 0;
+    let outcome: Result<(), _> = execute_with_retries(
+        Duration::ZERO,
+        || {
+            executions += 1;
+            (Ok(()), false)
+        },
+        || {},
+    );
+    assert!(matches!(outcome, Err(ExecutionError::ReadOnlyTimeout(_))));
+    assert_eq!(executions, 1);
+}
+
+#[test]
+fn test_readonly_error_not_retried() {
+    let mut executions = 0;
+    let outcome: Result<(), _> = execute_with_retries(
+        Duration::from_secs(10),
+        || {
+            executions += 1;
+            (Err(ExecutionError::RuntimeError("failed".into())), true)
+        },
+        || {},
+    );
+    assert!(matches!(outcome, Err(ExecutionError::RuntimeError(_))));
+    assert_eq!(executions, 1);
+}
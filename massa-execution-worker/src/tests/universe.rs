@@ -36,7 +36,11 @@ use massa_pos_exports::MockSelectorControllerWrapper;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_test_framework::TestUniverse;
-use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_time::MassaTime;
+use massa_versioning::{
+    test_helpers::versioning_helpers::advance_state_until,
+    versioning::{ComponentState, MipComponent, MipInfo, MipStatsConfig, MipStore},
+};
 use massa_wallet::test_exports::create_test_wallet;
 use num::rational::Ratio;
 use parking_lot::RwLock;
@@ -96,12 +100,49 @@ impl TestUniverse for ExecutionTestUniverse {
     type Config = ExecutionConfig;
 
     fn new(controllers: Self::ForeignControllers, config: Self::Config) -> Self {
+        Self::new_with_mip_store(controllers, config, create_mip_store(&[]))
+    }
+}
+
+impl Drop for ExecutionTestUniverse {
+    fn drop(&mut self) {
+        self.module_manager.stop();
+    }
+}
+
+/// Create a MIP store where the given components are active for any slot of the tests
+pub fn create_mip_store(active_components: &[MipComponent]) -> MipStore {
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    if active_components.is_empty() {
+        return MipStore::try_from(([], mip_stats_config)).unwrap();
+    }
+    let mip_info = MipInfo {
+        name: "MIP-TEST".to_string(),
+        version: 1,
+        components: active_components
+            .iter()
+            .map(|component| (component.clone(), 1))
+            .collect(),
+        start: MassaTime::from_millis(2),
+        timeout: MassaTime::from_millis(10),
+        activation_delay: MassaTime::from_millis(2),
+    };
+    let mip_state =
+        advance_state_until(ComponentState::active(MassaTime::from_millis(5)), &mip_info);
+    MipStore::try_from(([(mip_info, mip_state)], mip_stats_config)).unwrap()
+}
+
+impl ExecutionTestUniverse {
+    /// Create a test universe whose execution worker uses the given MIP store
+    pub fn new_with_mip_store(
+        controllers: ExecutionForeignControllers,
+        config: ExecutionConfig,
+        mip_store: MipStore,
+    ) -> Self {
         let storage = Storage::create_root();
-        let mip_stats_config = MipStatsConfig {
-            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-            warn_announced_version_ratio: Ratio::new_raw(30, 100),
-        };
-        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
         let (tx, rx) = broadcast::channel(16);
         #[cfg(feature = "execution-trace")]
         let (tx_traces, rx_traces) = broadcast::channel(16);
@@ -158,15 +199,7 @@ impl TestUniverse for ExecutionTestUniverse {
         universe.initialize();
         universe
     }
-}
 
-impl Drop for ExecutionTestUniverse {
-    fn drop(&mut self) {
-        self.module_manager.stop();
-    }
-}
-
-impl ExecutionTestUniverse {
     /// Create an operation for the given sender with `data` as bytecode.
     pub fn create_execute_sc_operation(
         sender_keypair: &KeyPair,
@@ -4,18 +4,19 @@
 //! The worker thread processes incoming notifications of blockclique changes,
 //! orders active and final blocks in queues sorted by increasing slot number,
 //! and requests the execution of active and final slots from execution.rs.
+//! Read-only requests are executed by the threads launched from readonly_pool.rs.
 
 #[cfg(feature = "dump-block")]
 use crate::storage_backend::StorageBackend;
 use crate::{
     controller::{ExecutionControllerImpl, ExecutionInputData, ExecutionManagerImpl},
     execution::ExecutionState,
-    request_queue::RequestQueue,
+    readonly_pool::{start_readonly_threads, ReadOnlyQueue},
     slot_sequencer::SlotSequencer,
 };
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionManager,
 };
 use massa_final_state::FinalStateController;
 use massa_metrics::MassaMetrics;
@@ -30,16 +31,21 @@ use std::sync::Arc;
 use std::thread;
 use tracing::debug;
 
+/// Stack size of the execution thread, also used by the read-only threads.
+/// We set it to 200 Mb instead of the default 2 Mb to avoid stack overflows
+/// as a temporary workaround fully fixed by https://github.com/massalabs/massa/pull/4729
+pub(crate) const EXECUTION_THREAD_STACK_SIZE: usize = 200 * 1024 * 1024;
+
 /// Structure gathering all elements needed by the execution thread
 pub(crate) struct ExecutionThread {
+    // Execution config
+    config: ExecutionConfig,
     // A copy of the input data allowing access to incoming requests
     input_data: Arc<(Condvar, Mutex<ExecutionInputData>)>,
     // Total continuous slot sequence
     slot_sequencer: SlotSequencer,
     // Execution state (see execution.rs) to which execution requests are sent
     execution_state: Arc<RwLock<ExecutionState>>,
-    /// queue for read-only requests and response MPSCs to send back their outputs
-    readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
     /// Selector controller
     selector: Box<dyn SelectorController>,
 }
@@ -59,59 +65,18 @@ impl ExecutionThread {
         selector: Box<dyn SelectorController>,
     ) -> Self {
         // get the latest executed final slot, at the output of which the final ledger is attached
-        // if we are restarting the network, use last genesis slot of the last start.
-
-        let final_cursor = std::cmp::max(
-            execution_state.read().final_cursor,
-            Slot {
-                period: config.last_start_period,
-                thread: config.thread_count.saturating_sub(1),
-            },
-        );
+        let final_cursor = execution_state.read().final_cursor;
 
         // create and return the ExecutionThread
         ExecutionThread {
+            slot_sequencer: new_slot_sequencer(&config, final_cursor),
+            config,
             input_data,
-            readonly_requests: RequestQueue::new(config.readonly_queue_length),
             execution_state,
-            slot_sequencer: SlotSequencer::new(config, final_cursor),
             selector,
         }
     }
 
-    /// Append incoming read-only requests to the relevant queue,
-    /// Cancel those that are in excess if there are too many.
-    fn update_readonly_requests(
-        &mut self,
-        new_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
-    ) {
-        // Append incoming readonly requests to our readonly request queue
-        // Excess requests are cancelled
-        self.readonly_requests.extend(new_requests);
-    }
-
-    /// Executes a read-only request from the queue, if any.
-    /// The result of the execution is sent asynchronously through the response channel provided with the request.
-    ///
-    /// # Returns
-    /// true if a request was executed, false otherwise
-    fn execute_one_readonly_request(&mut self) -> bool {
-        if let Some(req_resp) = self.readonly_requests.pop() {
-            let (req, resp_tx) = req_resp.into_request_sender_pair();
-
-            // Acquire write access to the execution state (for cache updates) and execute the read-only request
-            let outcome = self.execution_state.write().execute_readonly_request(req);
-
-            // Send the execution output through resp_tx.
-            // Ignore errors because they just mean that the request emitter dropped the received
-            // because it doesn't need the response anymore.
-            let _ = resp_tx.send(outcome);
-
-            return true;
-        }
-        false
-    }
-
     /// Waits for an event to trigger a new iteration in the execution main loop.
     ///
     /// # Returns
@@ -130,11 +95,22 @@ impl ExecutionThread {
                 return (input_data, true);
             }
 
+            // the active state needs to be rebuilt from the final state
+            if input_data.reset {
+                return (input_data, false);
+            }
+
+            // while suspended, incoming data is dropped:
+            // consensus notifies its whole blockclique again once resynchronized
+            if input_data.suspended {
+                self.input_data.0.wait(&mut input_data_lock);
+                continue;
+            }
+
             // check if there is some input data
             if input_data.new_blockclique.is_some()
                 || !input_data.finalized_blocks.is_empty()
                 || !input_data.block_metadata.is_empty()
-                || !input_data.readonly_requests.is_empty()
             {
                 return (input_data, false);
             }
@@ -144,11 +120,6 @@ impl ExecutionThread {
                 return (input_data, false);
             }
 
-            // there are read-only requests ready
-            if !self.readonly_requests.is_empty() {
-                return (input_data, false);
-            }
-
             // Compute when the next slot will be
             // This is useful to wait for the next speculative miss to append to active slots.
             let wakeup_deadline = self.slot_sequencer.get_next_slot_deadline();
@@ -172,22 +143,22 @@ impl ExecutionThread {
     /// Main loop of the execution worker
     pub fn main_loop(&mut self) {
         // This loop restarts every time an execution happens for easier tracking.
-        // It also prioritizes executions in the following order:
-        // 1 - final executions
-        // 2 - speculative executions
-        // 3 - read-only executions
+        // It also prioritizes final executions over speculative executions.
         loop {
             let (input_data, stop) = self.wait_loop_event();
             debug!("Execution loop triggered, input_data = {}", input_data);
 
-            // update the sequence of read-only requests
-            self.update_readonly_requests(input_data.readonly_requests);
-
             if stop {
                 // we need to stop
                 break;
             }
 
+            if input_data.reset {
+                // the final state was resynchronized: restart execution on top of it
+                let final_cursor = self.execution_state.write().reset_to_final_state();
+                self.slot_sequencer = new_slot_sequencer(&self.config, final_cursor);
+            }
+
             // update slot sequencer
             self.slot_sequencer.update(
                 input_data.finalized_blocks,
@@ -200,14 +171,15 @@ impl ExecutionThread {
                 |is_final: bool,
                  slot: &Slot,
                  content: Option<&(BlockId, ExecutionBlockMetadata)>| {
+                    let mut execution_state = self.execution_state.write();
+                    // checked under the execution state lock, see `ExecutionController::suspend`
+                    if self.input_data.1.lock().suspended {
+                        return;
+                    }
                     if is_final {
-                        self.execution_state.write().execute_final_slot(
-                            slot,
-                            content,
-                            self.selector.clone(),
-                        );
+                        execution_state.execute_final_slot(slot, content, self.selector.clone());
                     } else {
-                        self.execution_state.write().execute_candidate_slot(
+                        execution_state.execute_candidate_slot(
                             slot,
                             content,
                             self.selector.clone(),
@@ -219,26 +191,23 @@ impl ExecutionThread {
                 // A slot was executed: continue.
                 continue;
             }
-
-            // low priority: execute a read-only request (note that the queue is of finite length), if there is one ready.
-            self.execute_one_readonly_request();
         }
-
-        // We are quitting the loop.
-
-        // Cancel pending readonly requests
-        let cancel_err = ExecutionError::ChannelError(
-            "readonly execution cancelled because the execution worker is closing".into(),
-        );
-        self.input_data
-            .1
-            .lock()
-            .take()
-            .readonly_requests
-            .cancel(cancel_err);
     }
 }
 
+/// Creates a slot sequencer starting after the given final cursor.
+/// If we are restarting the network, the sequencer starts after the last genesis slot of the last start.
+fn new_slot_sequencer(config: &ExecutionConfig, final_cursor: Slot) -> SlotSequencer {
+    let final_cursor = std::cmp::max(
+        final_cursor,
+        Slot {
+            period: config.last_start_period,
+            thread: config.thread_count.saturating_sub(1),
+        },
+    );
+    SlotSequencer::new(config.clone(), final_cursor)
+}
+
 /// Launches an execution worker thread and returns an `ExecutionManager` to interact with it
 ///
 /// # parameters
@@ -272,30 +241,42 @@ pub fn start_execution_worker(
         selector.clone(),
         channels,
         wallet,
-        massa_metrics,
+        massa_metrics.clone(),
         #[cfg(feature = "dump-block")]
         block_storage_backend,
     )));
 
     // define the input data interface
-    let input_data = Arc::new((
-        Condvar::new(),
-        Mutex::new(ExecutionInputData::new(config.clone())),
-    ));
+    let input_data = Arc::new((Condvar::new(), Mutex::new(ExecutionInputData::new())));
+
+    // launch the read-only threads
+    let readonly_queue = ReadOnlyQueue::new(
+        config.readonly_queue_length,
+        config
+            .readonly_queue_timeout
+            .saturating_add(config.readonly_execution_timeout)
+            .to_duration(),
+        massa_metrics.clone(),
+    );
+    let readonly_thread_handles = start_readonly_threads(
+        &config,
+        &readonly_queue,
+        execution_state.clone(),
+        massa_metrics,
+    );
 
     // create a controller
     let controller = ExecutionControllerImpl {
         input_data: input_data.clone(),
         execution_state: execution_state.clone(),
+        readonly_queue: readonly_queue.clone(),
     };
 
     // launch the execution thread
     let input_data_clone = input_data.clone();
 
-    // We set the stack size to 200 Mb instead of the default 2 Mb to avoid stack overflows
-    // as a temporary workaround fully fixed by https://github.com/massalabs/massa/pull/4729
     let thread_builder = thread::Builder::new()
-        .stack_size(200 * 1024 * 1024)
+        .stack_size(EXECUTION_THREAD_STACK_SIZE)
         .name("execution".into());
     let thread_handle = thread_builder
         .spawn(move || {
@@ -306,6 +287,8 @@ pub fn start_execution_worker(
     let manager = ExecutionManagerImpl {
         input_data,
         thread_handle: Some(thread_handle),
+        readonly_queue,
+        readonly_thread_handles,
     };
 
     // return the execution manager and controller pair
@@ -45,4 +45,6 @@ pub struct FinalStateConfig {
     pub genesis_timestamp: MassaTime,
     /// Interval of periods between creation of each ledger backup
     pub ledger_backup_periods_interval: u64,
+    /// number of final slots for which the execution trail hash and the fingerprint are kept
+    pub state_hash_history_length: usize,
 }
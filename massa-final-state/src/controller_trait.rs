@@ -7,7 +7,7 @@ use massa_models::{operation::OperationId, slot::Slot};
 use massa_pos_exports::PoSFinalState;
 use massa_versioning::versioning::MipStore;
 
-use crate::{FinalStateError, StateChanges};
+use crate::{FinalStateError, FinalStateHashes, StateChanges};
 
 /// Trait for final state controller.
 #[cfg_attr(feature = "test-exports", mockall::automock)]
@@ -16,6 +16,14 @@ pub trait FinalStateController: Send + Sync {
     /// Note that only one atomic write per final slot occurs, so this can be safely queried at any time.
     fn get_fingerprint(&self) -> Hash;
 
+    /// Get the fingerprint the final state had at the end of the given slot,
+    /// or None if that slot is not in the change history anymore
+    fn get_fingerprint_at(&self, slot: Slot) -> Option<Hash>;
+
+    /// Get the execution trail hash and the fingerprint the final state had at the end of the given slot,
+    /// or None if that slot is not in the state hash history
+    fn get_state_hashes_at(&self, slot: Slot) -> Option<FinalStateHashes>;
+
     /// Get the slot at the end of which the final state is attached
     fn get_slot(&self) -> Slot;
 
@@ -30,6 +38,9 @@ pub trait FinalStateController: Send + Sync {
     /// Performs the initial draws.
     fn compute_initial_draws(&mut self) -> Result<(), FinalStateError>;
 
+    /// Performs the draws that became available after a resynchronization of the final state.
+    fn feed_missing_draws(&mut self) -> Result<(), FinalStateError>;
+
     /// Applies changes to the execution state at a given slot, and settles that slot forever.
     /// Once this is called, the state is attached at the output of the provided slot.
     ///
@@ -6,6 +6,7 @@
 //! and need to be bootstrapped by nodes joining the network.
 
 use crate::controller_trait::FinalStateController;
+use crate::state_hash_history::{FinalStateHashes, StateHashHistory};
 use crate::{config::FinalStateConfig, error::FinalStateError, state_changes::StateChanges};
 
 use anyhow::{anyhow, Result as AnyResult};
@@ -56,6 +57,8 @@ pub struct FinalState {
     pub last_slot_before_downtime: Option<Slot>,
     /// the RocksDB instance used to write every final_state struct on disk
     pub db: ShareableMassaDBController,
+    /// hashes of the final state after each of the last final slots
+    pub(crate) state_hash_history: StateHashHistory,
 }
 
 impl FinalState {
@@ -106,6 +109,8 @@ impl FinalState {
         let executed_denunciations =
             ExecutedDenunciations::new(config.executed_denunciations_config.clone(), db.clone());
 
+        let state_hash_history = StateHashHistory::new(config.state_hash_history_length);
+
         let mut final_state = FinalState {
             ledger,
             async_pool,
@@ -116,6 +121,7 @@ impl FinalState {
             mip_store,
             last_start_period: 0,
             last_slot_before_downtime: None,
+            state_hash_history,
             db,
         };
 
@@ -497,6 +503,13 @@ impl FinalState {
         // compute the final state hash
         info!("final_state hash at slot {}: {}", slot, final_state_hash);
 
+        // keep the hashes to compare them with other nodes
+        self.state_hash_history.push(FinalStateHashes {
+            slot,
+            execution_trail_hash: self.get_execution_trail_hash(),
+            fingerprint: Hash::compute_from(final_state_hash.to_bytes()),
+        });
+
         // Backup DB if needed
         #[cfg(feature = "bootstrap_server")]
         if slot.period % self.config.ledger_backup_periods_interval == 0
@@ -794,6 +807,12 @@ impl FinalStateController for FinalState {
             .map_err(|err| FinalStateError::PosError(err.to_string()))
     }
 
+    fn feed_missing_draws(&mut self) -> Result<(), FinalStateError> {
+        self.pos_state
+            .feed_missing_draws()
+            .map_err(|err| FinalStateError::PosError(err.to_string()))
+    }
+
     fn finalize(&mut self, slot: Slot, changes: StateChanges) {
         self._finalize(slot, changes).unwrap()
     }
@@ -818,6 +837,17 @@ impl FinalStateController for FinalState {
         Hash::compute_from(internal_hash.to_bytes())
     }
 
+    fn get_fingerprint_at(&self, slot: Slot) -> Option<Hash> {
+        self.db
+            .read()
+            .get_xof_db_hash_at(slot)
+            .map(|internal_hash| Hash::compute_from(internal_hash.to_bytes()))
+    }
+
+    fn get_state_hashes_at(&self, slot: Slot) -> Option<FinalStateHashes> {
+        self.state_hash_history.get(&slot)
+    }
+
     fn get_slot(&self) -> Slot {
         self.db
             .read()
@@ -841,6 +871,7 @@ impl FinalStateController for FinalState {
         self.executed_ops.recompute_sorted_ops_and_op_exec_status();
         self.executed_denunciations.recompute_sorted_denunciations();
         self.pos_state.recompute_pos_state_caches();
+        self.ledger.recompute_stats();
     }
 
     fn reset(&mut self) {
@@ -852,6 +883,7 @@ impl FinalStateController for FinalState {
         self.executed_ops.reset();
         self.executed_denunciations.reset();
         self.mip_store.reset_db(self.db.clone());
+        self.state_hash_history.clear();
         // delete the execution trail hash
         self.db
             .write()
@@ -1011,6 +1043,7 @@ mod test {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             ledger_backup_periods_interval: 10,
+            state_hash_history_length: 100,
             genesis_timestamp,
         };
 
@@ -93,6 +93,7 @@ mod error;
 mod final_state;
 mod mapping_grpc;
 mod state_changes;
+mod state_hash_history;
 
 pub use config::FinalStateConfig;
 pub use controller_trait::FinalStateController;
@@ -100,6 +101,7 @@ pub use error::FinalStateError;
 pub use final_state::FinalState;
 use num as _;
 pub use state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
+pub use state_hash_history::FinalStateHashes;
 
 #[cfg(feature = "test-exports")]
 pub use controller_trait::MockFinalStateController;
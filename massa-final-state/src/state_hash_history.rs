@@ -0,0 +1,95 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the history of the hashes of the final state,
+//! kept to compare the final state of the node with the one of other nodes
+
+use std::collections::VecDeque;
+
+use massa_hash::Hash;
+use massa_models::slot::Slot;
+
+/// Hashes of the final state right after the execution of a final slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalStateHashes {
+    /// final slot
+    pub slot: Slot,
+    /// execution trail hash, summarizing all the executed slots up to `slot`
+    pub execution_trail_hash: Hash,
+    /// fingerprint of the whole final state
+    pub fingerprint: Hash,
+}
+
+/// Hashes of the last final slots, in increasing slot order
+#[derive(Debug, Clone)]
+pub(crate) struct StateHashHistory {
+    max_length: usize,
+    hashes: VecDeque<FinalStateHashes>,
+}
+
+impl StateHashHistory {
+    /// Creates an empty history keeping at most `max_length` slots
+    pub fn new(max_length: usize) -> Self {
+        StateHashHistory {
+            max_length,
+            hashes: VecDeque::new(),
+        }
+    }
+
+    /// Records the hashes of a new final slot, forgetting the oldest slot if the history is full.
+    /// Slots must be pushed in increasing order.
+    pub fn push(&mut self, hashes: FinalStateHashes) {
+        if self.max_length == 0 {
+            return;
+        }
+        if self.hashes.len() == self.max_length {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(hashes);
+    }
+
+    /// Gets the hashes recorded for `slot`, if it is still in the history
+    pub fn get(&self, slot: &Slot) -> Option<FinalStateHashes> {
+        self.hashes
+            .binary_search_by(|hashes| hashes.slot.cmp(slot))
+            .ok()
+            .map(|index| self.hashes[index])
+    }
+
+    /// Forgets all the recorded hashes
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(period: u64) -> FinalStateHashes {
+        FinalStateHashes {
+            slot: Slot::new(period, 0),
+            execution_trail_hash: Hash::compute_from(&period.to_be_bytes()),
+            fingerprint: Hash::compute_from(&(period + 1000).to_be_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_state_hash_history_window() {
+        let mut history = StateHashHistory::new(3);
+        for period in 1..=5 {
+            history.push(hashes(period));
+        }
+        assert_eq!(history.get(&Slot::new(1, 0)), None);
+        assert_eq!(history.get(&Slot::new(2, 0)), None);
+        assert_eq!(history.get(&Slot::new(3, 0)), Some(hashes(3)));
+        assert_eq!(history.get(&Slot::new(5, 0)), Some(hashes(5)));
+        assert_eq!(history.get(&Slot::new(6, 0)), None);
+
+        history.clear();
+        assert_eq!(history.get(&Slot::new(5, 0)), None);
+
+        let mut disabled = StateHashHistory::new(0);
+        disabled.push(hashes(1));
+        assert_eq!(disabled.get(&Slot::new(1, 0)), None);
+    }
+}
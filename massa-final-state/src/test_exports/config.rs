@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use num::rational::Ratio;
 
-use crate::{FinalState, FinalStateConfig};
+use crate::{state_hash_history::StateHashHistory, FinalState, FinalStateConfig};
 use massa_async_pool::{AsyncPool, AsyncPoolConfig};
 use massa_db_exports::ShareableMassaDBController;
 use massa_executed_ops::{
@@ -48,6 +48,7 @@ impl FinalState {
                 },
             ))
             .unwrap(),
+            state_hash_history: StateHashHistory::new(config.state_hash_history_length),
             config,
             last_start_period: 0,
             last_slot_before_downtime: None,
@@ -92,6 +93,7 @@ impl Default for FinalStateConfig {
             t0: T0,
             genesis_timestamp: *GENESIS_TIMESTAMP,
             ledger_backup_periods_interval: 100,
+            state_hash_history_length: 100,
         }
     }
 }
@@ -27,7 +27,10 @@ use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use tempfile::NamedTempFile;
 
-use crate::{controller_trait::FinalStateController, FinalState, FinalStateConfig};
+use crate::{
+    controller_trait::FinalStateController, state_hash_history::StateHashHistory, FinalState,
+    FinalStateConfig,
+};
 
 #[allow(clippy::too_many_arguments)]
 /// Create a `FinalState` from pre-set values
@@ -42,6 +45,7 @@ pub fn create_final_state(
     db: ShareableMassaDBController,
 ) -> FinalState {
     FinalState {
+        state_hash_history: StateHashHistory::new(config.state_hash_history_length),
         config,
         ledger,
         async_pool,
@@ -202,6 +206,7 @@ pub fn get_sample_state(
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
         ledger_backup_periods_interval: 10,
+        state_hash_history_length: 100,
     };
 
     let mut final_state = if last_start_period > 0 {
@@ -3,8 +3,9 @@
 use massa_models::amount::Amount;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
+use parking_lot::RwLock;
 use serde::Deserialize;
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 /// gRPC configuration.
 /// the gRPC configuration
@@ -134,8 +135,9 @@ pub struct GrpcConfig {
     pub client_private_key_path: PathBuf,
     /// chain id
     pub chain_id: u64,
-    /// minimal fees
-    pub minimal_fees: Amount,
+    /// minimal fees, shared with the pool so that a reload is seen by the gRPC API
+    #[serde(skip)]
+    pub minimal_fees: Arc<RwLock<Amount>>,
 }
 
 /// gRPC API configuration.
@@ -45,6 +45,8 @@ pub enum GrpcError {
     InvalidArgument(String),
     /// Not implemented error: {0}
     Unimplemented(String),
+    /// Rate limited: {0}
+    RateLimited(String),
 }
 
 impl From<GrpcError> for tonic::Status {
@@ -64,6 +66,7 @@ impl From<GrpcError> for tonic::Status {
             GrpcError::ReflectionError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::InvalidArgument(e) => tonic::Status::invalid_argument(e),
             GrpcError::Unimplemented(e) => tonic::Status::unimplemented(e),
+            GrpcError::RateLimited(e) => tonic::Status::resource_exhausted(e),
         }
     }
 }
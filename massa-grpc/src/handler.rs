@@ -5,16 +5,16 @@ use massa_proto_rs::massa::api::v1 as grpc_api;
 use crate::private::{
     add_staking_secret_keys, add_to_bootstrap_blacklist, add_to_bootstrap_whitelist,
     add_to_peers_whitelist, allow_everyone_to_bootstrap, ban_nodes_by_ids, ban_nodes_by_ips,
-    get_bootstrap_blacklist, get_bootstrap_whitelist, get_mip_status, get_node_status,
-    get_peers_whitelist, remove_from_bootstrap_blacklist, remove_from_bootstrap_whitelist,
-    remove_from_peers_whitelist, remove_staking_addresses, shutdown_gracefully, sign_messages,
-    unban_nodes_by_ids, unban_nodes_by_ips,
+    get_bootstrap_blacklist, get_bootstrap_whitelist, get_log_filter, get_mip_status,
+    get_node_status, get_peers_whitelist, remove_from_bootstrap_blacklist,
+    remove_from_bootstrap_whitelist, remove_from_peers_whitelist, remove_staking_addresses,
+    set_log_filter, shutdown_gracefully, sign_messages, unban_nodes_by_ids, unban_nodes_by_ips,
 };
 use crate::public::{
     execute_read_only_call, get_blocks, get_datastore_entries, get_endorsements,
-    get_next_block_best_parents, get_operations, get_sc_execution_events, get_selector_draws,
-    get_stakers, get_status, get_transactions_throughput, query_state, search_blocks,
-    search_endorsements, search_operations,
+    get_final_state_hashes, get_next_block_best_parents, get_operations, get_sc_execution_events,
+    get_selector_draws, get_stakers, get_status, get_transactions_throughput, query_state,
+    search_blocks, search_endorsements, search_operations,
 };
 
 #[cfg(feature = "execution-trace")]
@@ -24,6 +24,7 @@ use crate::stream::new_slot_transfers::new_slot_transfers;
 
 use crate::server::{MassaPrivateGrpc, MassaPublicGrpc};
 use crate::stream::{
+    new_block_statuses::{new_block_statuses, NewBlockStatusesStreamType},
     new_blocks::{new_blocks, NewBlocksStreamType},
     new_endorsements::{new_endorsements, NewEndorsementsStreamType},
     new_filled_blocks::{new_filled_blocks, NewFilledBlocksStreamType},
@@ -150,6 +151,14 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         Ok(tonic::Response::new(get_status(self, request)?))
     }
 
+    /// handler for get final state hashes
+    async fn get_final_state_hashes(
+        &self,
+        request: tonic::Request<grpc_api::GetFinalStateHashesRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetFinalStateHashesResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_final_state_hashes(self, request)?))
+    }
+
     /// handler for get next block best parents
     async fn get_next_block_best_parents(
         &self,
@@ -244,6 +253,18 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         Ok(tonic::Response::new(new_blocks(self, request).await?))
     }
 
+    type NewBlockStatusesStream = NewBlockStatusesStreamType;
+
+    /// handler for subscribe block status changes
+    async fn new_block_statuses(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::NewBlockStatusesRequest>>,
+    ) -> Result<tonic::Response<Self::NewBlockStatusesStream>, tonic::Status> {
+        Ok(tonic::Response::new(
+            new_block_statuses(self, request).await?,
+        ))
+    }
+
     type NewEndorsementsStream = NewEndorsementsStreamType;
 
     /// handler for subscribe new operations stream
@@ -443,6 +464,13 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
             self, request,
         )?))
     }
+    /// Get the directives of the current log filter
+    async fn get_log_filter(
+        &self,
+        request: tonic::Request<grpc_api::GetLogFilterRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetLogFilterResponse>, tonic::Status> {
+        Ok(tonic::Response::new(get_log_filter(self, request)?))
+    }
     /// handler for get mip status (versioning)
     async fn get_mip_status(
         &self,
@@ -511,6 +539,13 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
             self, request,
         )?))
     }
+    /// Replace the log filter of the node
+    async fn set_log_filter(
+        &self,
+        request: tonic::Request<grpc_api::SetLogFilterRequest>,
+    ) -> Result<tonic::Response<grpc_api::SetLogFilterResponse>, tonic::Status> {
+        Ok(tonic::Response::new(set_log_filter(self, request)?))
+    }
     /// Sign messages with node's key
     async fn sign_messages(
         &self,
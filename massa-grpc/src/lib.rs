@@ -36,6 +36,8 @@ pub mod handler;
 pub mod private;
 /// business code for non stream methods
 pub mod public;
+/// per-client rate limiting
+pub mod rate_limit;
 /// gRPC service initialization and serve
 pub mod server;
 /// business code for stream methods
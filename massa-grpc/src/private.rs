@@ -1,25 +1,25 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use std::net::IpAddr;
 use std::str::FromStr;
 
 use crate::error::GrpcError;
 use crate::server::MassaPrivateGrpc;
 use massa_execution_exports::ExecutionQueryRequest;
 use massa_hash::Hash;
+use massa_models::access_list::{AccessEntry, AccessTarget};
 use massa_models::config::CompactConfig;
 use massa_models::node::NodeId;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
-use massa_protocol_exports::{PeerConnectionType, PeerId};
+use massa_protocol_exports::{PeerConnectionType, PeerId, PeerListType};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use tracing::warn;
 // use massa_proto_rs::massa::model::v1 "add_to_bootstrap_blacklist"as grpc_model;
 
-/// Add IP addresses to node bootstrap blacklist
+/// Add IP ranges or node ids, with an optional expiry, to node bootstrap blacklist
 pub(crate) fn add_to_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::AddToBootstrapBlacklistRequest>,
@@ -29,8 +29,8 @@ pub(crate) fn add_to_bootstrap_blacklist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|entry| match AccessEntry::from_str(&entry) {
+            Ok(entry) => Some(entry),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -39,14 +39,14 @@ pub(crate) fn add_to_bootstrap_blacklist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.add_ips_to_blacklist(ips) {
+        if let Err(e) = bs_list.add_to_blacklist(ips) {
             warn!("error when adding ips to bootstrap blacklist : {}", e)
         }
     }
 
     Ok(grpc_api::AddToBootstrapBlacklistResponse {})
 }
-/// Add IP addresses to node bootstrap whitelist
+/// Add IP ranges or node ids, with an optional expiry, to node bootstrap whitelist
 pub(crate) fn add_to_bootstrap_whitelist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::AddToBootstrapWhitelistRequest>,
@@ -56,8 +56,8 @@ pub(crate) fn add_to_bootstrap_whitelist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|entry| match AccessEntry::from_str(&entry) {
+            Ok(entry) => Some(entry),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -66,22 +66,30 @@ pub(crate) fn add_to_bootstrap_whitelist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.add_ips_to_whitelist(ips) {
+        if let Err(e) = bs_list.add_to_whitelist(ips) {
             warn!("error when adding ips to bootstrap whitelist : {}", e)
         }
     }
 
     Ok(grpc_api::AddToBootstrapWhitelistResponse {})
 }
-/// Add IP addresses to node peers whitelist. No confirmation to expect.
-/// Note: If the ip was unknown it adds it to the known peers, otherwise it updates the peer type
+/// Add IP ranges or node ids, with an optional expiry, to node peers whitelist.
+/// Once a whitelist exists, only the peers it matches are accepted.
 pub(crate) fn add_to_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::AddToPeersWhitelistRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::AddToPeersWhitelistRequest>,
 ) -> Result<grpc_api::AddToPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "add_to_peers_whitelist".to_string(),
-    ))
+    let entries = request
+        .into_inner()
+        .ips
+        .iter()
+        .map(|entry| AccessEntry::from_str(entry))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    grpc.protocol_controller
+        .add_to_peers_list(PeerListType::Whitelist, entries)?;
+
+    Ok(grpc_api::AddToPeersWhitelistResponse {})
 }
 /// Add staking secret keys to wallet
 pub(crate) fn add_staking_secret_keys(
@@ -164,8 +172,9 @@ pub(crate) fn get_bootstrap_blacklist(
             Some(ref bs_list) => bs_list
                 .get_black_list()
                 .unwrap_or_default()
+                .entries()
                 .into_iter()
-                .map(|ip| ip.to_string())
+                .map(|entry| entry.to_string())
                 .collect(),
             None => Vec::new(),
         }
@@ -182,8 +191,9 @@ pub(crate) fn get_bootstrap_whitelist(
             Some(ref bs_list) => bs_list
                 .get_white_list()
                 .unwrap_or_default()
+                .entries()
                 .into_iter()
-                .map(|ip| ip.to_string())
+                .map(|entry| entry.to_string())
                 .collect(),
             None => Vec::new(),
         }
@@ -191,6 +201,16 @@ pub(crate) fn get_bootstrap_whitelist(
 
     Ok(grpc_api::GetBootstrapWhitelistResponse { ips: list })
 }
+
+/// Get the directives of the current log filter
+pub(crate) fn get_log_filter(
+    grpc: &MassaPrivateGrpc,
+    _request: tonic::Request<grpc_api::GetLogFilterRequest>,
+) -> Result<grpc_api::GetLogFilterResponse, GrpcError> {
+    Ok(grpc_api::GetLogFilterResponse {
+        directives: grpc.log_filter.get_filter(),
+    })
+}
 // Get MIP store dump
 pub(crate) fn get_mip_status(
     grpc: &MassaPrivateGrpc,
@@ -311,14 +331,22 @@ pub(crate) fn get_node_status(
         status: Some(status),
     })
 }
-/// Get node peers whitelist IP addresses
+/// Get node peers whitelist entries, empty if there is no whitelist
 pub(crate) fn get_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
+    grpc: &MassaPrivateGrpc,
     _request: tonic::Request<grpc_api::GetPeersWhitelistRequest>,
 ) -> Result<grpc_api::GetPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented("get_peers_whitelist".to_string()))
+    let ips = grpc
+        .protocol_controller
+        .get_peers_list(PeerListType::Whitelist)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| entry.to_string())
+        .collect();
+
+    Ok(grpc_api::GetPeersWhitelistResponse { ips })
 }
-/// Remove from bootstrap blacklist given IP addresses
+/// Remove from bootstrap blacklist given IP ranges or node ids
 pub(crate) fn remove_from_bootstrap_blacklist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::RemoveFromBootstrapBlacklistRequest>,
@@ -327,8 +355,8 @@ pub(crate) fn remove_from_bootstrap_blacklist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|target| match AccessTarget::from_str(&target) {
+            Ok(target) => Some(target),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -337,14 +365,14 @@ pub(crate) fn remove_from_bootstrap_blacklist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.remove_ips_from_blacklist(ips) {
+        if let Err(e) = bs_list.remove_from_blacklist(ips) {
             warn!("error when removing ips to bootstrap blacklist : {}", e)
         }
     }
 
     Ok(grpc_api::RemoveFromBootstrapBlacklistResponse {})
 }
-/// Remove from bootstrap whitelist given IP addresses
+/// Remove from bootstrap whitelist given IP ranges or node ids
 pub(crate) fn remove_from_bootstrap_whitelist(
     grpc: &MassaPrivateGrpc,
     request: tonic::Request<grpc_api::RemoveFromBootstrapWhitelistRequest>,
@@ -353,8 +381,8 @@ pub(crate) fn remove_from_bootstrap_whitelist(
     let ips = inner_req
         .ips
         .into_iter()
-        .filter_map(|ip| match IpAddr::from_str(&ip) {
-            Ok(ip_addr) => Some(ip_addr),
+        .filter_map(|target| match AccessTarget::from_str(&target) {
+            Ok(target) => Some(target),
             Err(e) => {
                 warn!("error when parsing address : {}", e);
                 None
@@ -363,21 +391,29 @@ pub(crate) fn remove_from_bootstrap_whitelist(
         .collect();
 
     if let Some(bs_list) = &grpc.bs_white_black_list {
-        if let Err(e) = bs_list.remove_ips_from_whitelist(ips) {
+        if let Err(e) = bs_list.remove_from_whitelist(ips) {
             warn!("error when removing ips to bootstrap whitelist : {}", e)
         }
     }
 
     Ok(grpc_api::RemoveFromBootstrapWhitelistResponse {})
 }
-/// Remove from peers whitelist given IP addresses
+/// Remove from peers whitelist given IP ranges or node ids
 pub(crate) fn remove_from_peers_whitelist(
-    _grpc: &MassaPrivateGrpc,
-    _request: tonic::Request<grpc_api::RemoveFromPeersWhitelistRequest>,
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::RemoveFromPeersWhitelistRequest>,
 ) -> Result<grpc_api::RemoveFromPeersWhitelistResponse, GrpcError> {
-    Err(GrpcError::Unimplemented(
-        "remove_from_peers_whitelist".to_string(),
-    ))
+    let targets = request
+        .into_inner()
+        .ips
+        .iter()
+        .map(|target| AccessTarget::from_str(target))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    grpc.protocol_controller
+        .remove_from_peers_list(PeerListType::Whitelist, targets)?;
+
+    Ok(grpc_api::RemoveFromPeersWhitelistResponse {})
 }
 /// Remove addresses from staking
 pub(crate) fn remove_staking_addresses(
@@ -423,6 +459,22 @@ pub(crate) fn sign_messages(
         signatures,
     })
 }
+/// Replace the log filter, returning the previous directives
+pub(crate) fn set_log_filter(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::SetLogFilterRequest>,
+) -> Result<grpc_api::SetLogFilterResponse, GrpcError> {
+    let directives = request.into_inner().directives;
+    let previous_directives = grpc
+        .log_filter
+        .set_filter(&directives)
+        .map_err(GrpcError::InvalidArgument)?;
+
+    Ok(grpc_api::SetLogFilterResponse {
+        previous_directives,
+    })
+}
+
 /// Shutdown the node gracefully
 pub(crate) fn shutdown_gracefully(
     _grpc: &MassaPrivateGrpc,
@@ -1,6 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use crate::error::GrpcError;
+use crate::rate_limit::charge_read_only_gas;
 use crate::server::MassaPublicGrpc;
 use crate::{EndorsementDraw, SlotDraw, SlotRange};
 
@@ -46,6 +47,19 @@ pub(crate) fn execute_read_only_call(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
 ) -> Result<grpc_api::ExecuteReadOnlyCallResponse, GrpcError> {
+    let max_gas = request
+        .get_ref()
+        .call
+        .as_ref()
+        .map(|call| call.max_gas)
+        .unwrap_or_default();
+    charge_read_only_gas(
+        grpc.rate_limiter.as_ref(),
+        &request,
+        "execute_read_only_call",
+        max_gas,
+    )?;
+
     let call: grpc_model::ReadOnlyExecutionCall = request
         .into_inner()
         .call
@@ -143,16 +157,17 @@ pub(crate) fn execute_read_only_call(
             .transpose()?,
     };
 
+    let minimal_fees = *grpc.grpc_config.minimal_fees.read();
     if read_only_call
         .fee
         .unwrap_or_default()
-        .checked_sub(grpc.grpc_config.minimal_fees)
+        .checked_sub(minimal_fees)
         .is_none()
     {
         return Err(GrpcError::InvalidArgument(format!(
             "fee is too low provided: {} , minimal_fees required: {}",
             read_only_call.fee.unwrap_or_default(),
-            grpc.grpc_config.minimal_fees
+            minimal_fees
         )));
     }
 
@@ -690,6 +705,42 @@ pub(crate) fn get_slot_abi_call_stacks(
     Ok(resp)
 }
 
+/// Get the final state hashes at the given slots
+pub(crate) fn get_final_state_hashes(
+    grpc: &MassaPublicGrpc,
+    request: tonic::Request<grpc_api::GetFinalStateHashesRequest>,
+) -> Result<grpc_api::GetFinalStateHashesResponse, GrpcError> {
+    let slots = request.into_inner().slots;
+
+    if slots.is_empty() {
+        return Err(GrpcError::InvalidArgument("no slots specified".to_string()));
+    }
+
+    if slots.len() as u64 > grpc.grpc_config.max_arguments {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many slots received. Only a maximum of {} slots are accepted per request",
+            grpc.grpc_config.max_arguments
+        )));
+    }
+
+    let slots: Vec<Slot> = slots.into_iter().map(Slot::from).collect();
+    let entries = grpc
+        .execution_controller
+        .get_final_state_hashes(&slots)
+        .into_iter()
+        .zip(slots)
+        .map(|(hashes, slot)| grpc_model::FinalStateHashesEntry {
+            slot: Some(slot.into()),
+            hashes: hashes.map(|hashes| grpc_model::FinalStateHashes {
+                execution_trail_hash: hashes.execution_trail_hash.to_string(),
+                fingerprint: hashes.fingerprint.to_string(),
+            }),
+        })
+        .collect();
+
+    Ok(grpc_api::GetFinalStateHashesResponse { entries })
+}
+
 /// Get next block best parents
 pub(crate) fn get_next_block_best_parents(
     grpc: &MassaPublicGrpc,
@@ -959,7 +1010,7 @@ pub(crate) fn get_status(
         final_state_fingerprint: state.final_state_fingerprint.to_string(),
         config: Some(config.into()),
         chain_id: grpc.grpc_config.chain_id,
-        minimal_fees: Some(grpc.grpc_config.minimal_fees.into()),
+        minimal_fees: Some((*grpc.grpc_config.minimal_fees.read()).into()),
     };
 
     Ok(grpc_api::GetStatusResponse {
@@ -0,0 +1,169 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::{Body, Request, Response};
+use massa_api_exports::rate_limit::{RateLimitClient, RateLimitExceeded, RateLimiter};
+use tonic::body::BoxBody;
+use tonic::metadata::MetadataValue;
+use tonic::transport::server::TcpConnectInfo;
+use tower::{Layer, Service};
+
+use crate::error::GrpcError;
+
+/// label used in metrics
+const API_LABEL: &str = "grpc";
+/// label of paths that are not part of the massa API
+const UNKNOWN_METHOD: &str = "unknown";
+/// prefix of the paths of the massa gRPC services
+const MASSA_SERVICES_PREFIX: &str = "/massa.api.v1.";
+
+/// Layer charging every gRPC call (or stream opening) to the bucket of its client
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    /// Create a new rate limiting layer
+    pub fn new(rate_limiter: Arc<RateLimiter>) -> Self {
+        RateLimitLayer { rate_limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
+}
+
+/// Service enforcing the rate limits before routing gRPC requests
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let client = identify_client(&self.rate_limiter, &req);
+        let path = req.uri().path();
+        // gRPC methods are named like their JSON-RPC counterparts to share the same costs
+        let method = match path.strip_prefix(MASSA_SERVICES_PREFIX) {
+            Some(service_method) => service_method
+                .rsplit('/')
+                .next()
+                .map(to_snake_case)
+                .unwrap_or_else(|| UNKNOWN_METHOD.to_string()),
+            None => UNKNOWN_METHOD.to_string(),
+        };
+
+        let cost = self.rate_limiter.method_cost(&method, None);
+        if let Err(e) = self.rate_limiter.check(API_LABEL, &method, &client, cost) {
+            let status = throttled_status(&e);
+            return Box::pin(async move { Ok(status.to_http()) });
+        }
+
+        // handlers charging extra units (read-only executions) need to know the client
+        req.extensions_mut().insert(client);
+        Box::pin(self.inner.call(req))
+    }
+}
+
+/// Identify a client from its API key, the IP forwarded by a reverse proxy or its peer address
+fn identify_client(rate_limiter: &RateLimiter, req: &Request<Body>) -> RateLimitClient {
+    let headers = req.headers();
+    let api_key = headers
+        .get(rate_limiter.config().api_key_header.as_str())
+        .and_then(|value| value.to_str().ok());
+    let forwarded_ip = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok())
+        })
+        .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+    let peer_ip = req
+        .extensions()
+        .get::<TcpConnectInfo>()
+        .and_then(|info| info.remote_addr())
+        .map(|addr| addr.ip());
+    rate_limiter.identify(api_key, forwarded_ip, peer_ip)
+}
+
+/// Charge the gas based part of the cost of a read-only execution to the client of `request`
+pub(crate) fn charge_read_only_gas<T>(
+    rate_limiter: Option<&Arc<RateLimiter>>,
+    request: &tonic::Request<T>,
+    method: &str,
+    max_gas: u64,
+) -> Result<(), GrpcError> {
+    let Some(rate_limiter) = rate_limiter else {
+        return Ok(());
+    };
+    let client = request
+        .extensions()
+        .get::<RateLimitClient>()
+        .cloned()
+        .unwrap_or(RateLimitClient::Anonymous);
+    // the base cost of the method was already charged by the layer
+    let cost = rate_limiter
+        .method_cost(method, Some(max_gas))
+        .saturating_sub(rate_limiter.method_cost(method, None));
+    if cost == 0 {
+        return Ok(());
+    }
+    rate_limiter
+        .check(API_LABEL, method, &client, cost)
+        .map_err(|e| GrpcError::RateLimited(e.to_string()))
+}
+
+/// `ExecuteReadOnlyCall` -> `execute_read_only_call`
+fn to_snake_case(name: &str) -> String {
+    let mut res = String::with_capacity(name.len() + 8);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                res.push('_');
+            }
+            res.push(c.to_ascii_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+fn throttled_status(exceeded: &RateLimitExceeded) -> tonic::Status {
+    let mut status = tonic::Status::resource_exhausted(exceeded.to_string());
+    if let Some(retry_after_ms) = exceeded.retry_after_ms {
+        status
+            .metadata_mut()
+            .insert("retry-after-ms", MetadataValue::from(retry_after_ms));
+    }
+    status
+}
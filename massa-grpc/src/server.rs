@@ -11,11 +11,14 @@ use std::sync::{Arc, Condvar, Mutex};
 
 use crate::config::{GrpcConfig, ServiceName};
 use crate::error::GrpcError;
+use crate::rate_limit::RateLimitLayer;
 use futures_util::FutureExt;
 use hyper::service::Service;
 use hyper::{Body, Method, Request, Response};
+use massa_api_exports::rate_limit::RateLimiter;
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
 use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_logging::subscriber::LogFilterHandle;
 use massa_pool_exports::{PoolBroadcasts, PoolController};
 use massa_pos_exports::SelectorController;
 use massa_proto_rs::massa::api::v1::{
@@ -35,6 +38,7 @@ use tonic::transport::NamedService;
 use tonic::transport::{Certificate, Identity, ServerTlsConfig};
 use tonic_health::server::HealthReporter;
 use tonic_web::GrpcWebLayer;
+use tower::util::option_layer;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 
@@ -66,6 +70,8 @@ pub struct MassaPrivateGrpc {
     pub version: massa_models::version::Version,
     /// white/black list of bootstrap
     pub bs_white_black_list: Option<SharedWhiteBlackList<'static>>,
+    /// handle to change the log filter at runtime
+    pub log_filter: LogFilterHandle,
 }
 
 impl MassaPrivateGrpc {
@@ -87,7 +93,7 @@ impl MassaPrivateGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(service, config, None).await
     }
 }
 
@@ -122,11 +128,14 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// per-client rate limiter, if enabled
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let rate_limit = self.rate_limiter.clone().map(RateLimitLayer::new);
         let mut service = PublicServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -142,7 +151,7 @@ impl MassaPublicGrpc {
                 service = service.send_compressed(CompressionEncoding::Gzip);
             };
         }
-        serve(service, config).await
+        serve(service, config, rate_limit).await
     }
 }
 
@@ -171,7 +180,11 @@ async fn massa_service_status(mut reporter: HealthReporter) {
 }
 
 // Configure and start the gRPC API with the given service
-async fn serve<S>(service: S, config: &GrpcConfig) -> Result<StopHandle, GrpcError>
+async fn serve<S>(
+    service: S,
+    config: &GrpcConfig,
+    rate_limit: Option<RateLimitLayer>,
+) -> Result<StopHandle, GrpcError>
 where
     S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
         + NamedService
@@ -273,6 +286,7 @@ where
                 .accept_http1(true)
                 .layer(cors)
                 .layer(GrpcWebLayer::new())
+                .layer(option_layer(rate_limit))
                 .add_optional_service(reflection_service_opt)
                 .add_optional_service(health_service_opt)
                 .add_service(service);
@@ -284,6 +298,7 @@ where
             let router_with_http1 = server_builder
                 .accept_http1(true)
                 .layer(GrpcWebLayer::new())
+                .layer(option_layer(rate_limit))
                 .add_optional_service(reflection_service_opt)
                 .add_optional_service(health_service_opt)
                 .add_service(service);
@@ -294,6 +309,7 @@ where
         }
     } else {
         let router = server_builder
+            .layer(option_layer(rate_limit))
             .add_optional_service(reflection_service_opt)
             .add_optional_service(health_service_opt)
             .add_service(service);
@@ -1,5 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+/// subscribe block status changes
+pub mod new_block_statuses;
 /// stream new blocks
 pub mod new_blocks;
 /// stream new endorsements
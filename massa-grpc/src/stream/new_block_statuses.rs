@@ -0,0 +1,308 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::GrpcConfig;
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaPublicGrpc;
+use crate::SlotRange;
+use futures_util::StreamExt;
+use massa_consensus_exports::block_status::{BlockStatusChange, BlockStatusEvent, DiscardReason};
+use massa_models::block_id::BlockId;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1::{self as grpc_api};
+use massa_proto_rs::massa::model::v1::{self as grpc_model};
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use std::str::FromStr;
+use tokio::select;
+use tonic::{Request, Streaming};
+use tracing::{error, warn};
+
+/// Type declaration for NewBlockStatuses
+pub type NewBlockStatusesStreamType = Pin<
+    Box<
+        dyn futures_util::Stream<Item = Result<grpc_api::NewBlockStatusesResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+// Type declaration for NewBlockStatusesFilter
+#[derive(Clone, Debug)]
+struct Filter {
+    // Block ids to filter
+    block_ids: Option<HashSet<BlockId>>,
+    // Slot range to filter
+    slot_ranges: Option<HashSet<SlotRange>>,
+}
+
+/// Creates a new stream of block status changes: blockclique, finality and discards
+pub(crate) async fn new_block_statuses(
+    grpc: &MassaPublicGrpc,
+    request: Request<Streaming<grpc_api::NewBlockStatusesRequest>>,
+) -> Result<NewBlockStatusesStreamType, GrpcError> {
+    // Create a channel to handle communication with the client
+    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+    // Subscribe to the block status changes channel
+    let mut subscriber = grpc.consensus_broadcasts.block_status_sender.subscribe();
+    // Clone grpc to be able to use it in the spawned task
+    let grpc_config = grpc.grpc_config.clone();
+
+    tokio::spawn(async move {
+        if let Some(Ok(request)) = in_stream.next().await {
+            let mut filters = match get_filter(request, &grpc_config) {
+                Ok(filter) => filter,
+                Err(err) => {
+                    error!("failed to get filter: {}", err);
+                    // Send the error response back to the client
+                    if let Err(e) = tx.send(Err(err.into())).await {
+                        error!("failed to send back NewBlockStatuses error response: {}", e);
+                    }
+                    return;
+                }
+            };
+
+            loop {
+                select! {
+                    // Receive a new block status change from the subscriber
+                    event = subscriber.recv() => {
+                        match event {
+                            Ok(status_event) => {
+                                // Check if the change should be sent
+                                if !should_send(&status_event, &filters, &grpc_config) {
+                                    continue;
+                                }
+                                // Send the change through the channel
+                                if let Err(e) = tx.send(Ok(grpc_api::NewBlockStatusesResponse {
+                                    block_status: Some(to_grpc_block_status(status_event))
+                                })).await {
+                                    error!("failed to send new block status : {}", e);
+                                    break;
+                                }
+                            },
+                            Err(e) => error!("error on receive new block status : {}", e)
+                        }
+                    },
+                    res = in_stream.next() => {
+                        match res {
+                            Some(res) => {
+                                match res {
+                                    Ok(message) => {
+                                        // Update current filter
+                                        filters = match get_filter(message, &grpc_config) {
+                                            Ok(filter) => filter,
+                                            Err(err) => {
+                                                error!("failed to get filter: {}", err);
+                                                // Send the error response back to the client
+                                                if let Err(e) = tx.send(Err(err.into())).await {
+                                                    error!("failed to send back NewBlockStatuses error response: {}", e);
+                                                }
+                                                return;
+                                            }
+                                        };
+                                    },
+                                    Err(err) => {
+                                        // Check if the error matches any IO errors
+                                        if let Some(io_err) = match_for_io_error(&err) {
+                                            if io_err.kind() == ErrorKind::BrokenPipe {
+                                                warn!("client disconnected, broken pipe: {}", io_err);
+                                                break;
+                                            }
+                                        }
+                                        error!("{}", err);
+                                        // Send the error response back to the client
+                                        if let Err(e) = tx.send(Err(err)).await {
+                                            error!("failed to send back NewBlockStatuses error response: {}", e);
+                                            break;
+                                        }
+                                    }
+                                }
+                            },
+                            None => {
+                                // The client has disconnected
+                                break;
+                            },
+                        }
+                    }
+                }
+            }
+        } else {
+            error!("empty request");
+        }
+    });
+
+    // Create a new stream from the received channel
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+
+    // Return the new stream of block status changes
+    Ok(Box::pin(out_stream) as NewBlockStatusesStreamType)
+}
+
+// This function returns a filter from the request
+fn get_filter(
+    request: grpc_api::NewBlockStatusesRequest,
+    grpc_config: &GrpcConfig,
+) -> Result<Filter, GrpcError> {
+    if request.filters.len() as u32 > grpc_config.max_filters_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many filters received. Only a maximum of {} filters are accepted per request",
+            grpc_config.max_filters_per_request
+        )));
+    }
+
+    let mut block_ids_filter: Option<HashSet<BlockId>> = None;
+    let mut slot_ranges_filter: Option<HashSet<SlotRange>> = None;
+
+    // Get params filter from the request.
+    for query in request.filters.into_iter() {
+        if let Some(filter) = query.filter {
+            match filter {
+                grpc_api::new_block_statuses_filter::Filter::BlockIds(ids) => {
+                    if ids.block_ids.len() as u32 > grpc_config.max_block_ids_per_request {
+                        return Err(GrpcError::InvalidArgument(format!(
+                            "too many block ids received. Only a maximum of {} block ids are accepted per request",
+                            grpc_config.max_block_ids_per_request
+                        )));
+                    }
+
+                    let block_ids = block_ids_filter.get_or_insert_with(HashSet::new);
+                    for block_id in ids.block_ids {
+                        block_ids.insert(BlockId::from_str(&block_id).map_err(|_| {
+                            GrpcError::InvalidArgument(format!("invalid block id: {}", block_id))
+                        })?);
+                    }
+                }
+                grpc_api::new_block_statuses_filter::Filter::SlotRange(s_range) => {
+                    let slot_ranges = slot_ranges_filter.get_or_insert_with(HashSet::new);
+                    if slot_ranges.len() as u32 > grpc_config.max_slot_ranges_per_request {
+                        return Err(GrpcError::InvalidArgument(format!(
+                            "too many slot ranges received. Only a maximum of {} slot ranges are accepted per request",
+                         grpc_config.max_slot_ranges_per_request
+                        )));
+                    }
+
+                    let start_slot = s_range.start_slot.map(|s| s.into());
+                    let end_slot = s_range.end_slot.map(|s| s.into());
+
+                    let slot_range = SlotRange {
+                        start_slot,
+                        end_slot,
+                    };
+                    slot_range.check()?;
+                    slot_ranges.insert(slot_range);
+                }
+            }
+        }
+    }
+
+    Ok(Filter {
+        block_ids: block_ids_filter,
+        slot_ranges: slot_ranges_filter,
+    })
+}
+
+// This function checks if the block status change should be sent
+fn should_send(event: &BlockStatusEvent, filters: &Filter, grpc_config: &GrpcConfig) -> bool {
+    if let Some(block_ids) = &filters.block_ids {
+        if !block_ids.contains(&event.block_id) {
+            return false;
+        }
+    }
+
+    if let Some(slot_ranges) = &filters.slot_ranges {
+        let mut start_slot = Slot::new(0, 0); // inclusive
+        let mut end_slot = Slot::new(u64::MAX, grpc_config.thread_count - 1); // exclusive
+
+        for slot_range in slot_ranges {
+            start_slot = start_slot.max(slot_range.start_slot.unwrap_or_else(|| Slot::new(0, 0)));
+            end_slot = end_slot.min(
+                slot_range
+                    .end_slot
+                    .unwrap_or_else(|| Slot::new(u64::MAX, grpc_config.thread_count - 1)),
+            );
+        }
+        end_slot = end_slot.max(start_slot);
+
+        return event.slot >= start_slot // inclusive
+            && event.slot < end_slot; // exclusive
+    }
+
+    true
+}
+
+// Converts a block status change into its gRPC representation
+fn to_grpc_block_status(event: BlockStatusEvent) -> grpc_model::BlockStatusEvent {
+    let (change, discard_reason) = match event.change {
+        BlockStatusChange::Active => (grpc_model::BlockStatusChange::Active, None),
+        BlockStatusChange::EnteredBlockclique => {
+            (grpc_model::BlockStatusChange::EnteredBlockclique, None)
+        }
+        BlockStatusChange::LeftBlockclique => {
+            (grpc_model::BlockStatusChange::LeftBlockclique, None)
+        }
+        BlockStatusChange::Final => (grpc_model::BlockStatusChange::Final, None),
+        BlockStatusChange::Discarded(reason) => {
+            let discard_reason = match reason {
+                DiscardReason::Invalid(reason) => grpc_model::DiscardReason {
+                    r#type: grpc_model::DiscardReasonType::Invalid as i32,
+                    invalid_reason: reason,
+                },
+                DiscardReason::Stale => grpc_model::DiscardReason {
+                    r#type: grpc_model::DiscardReasonType::Stale as i32,
+                    invalid_reason: String::new(),
+                },
+                DiscardReason::Final => grpc_model::DiscardReason {
+                    r#type: grpc_model::DiscardReasonType::Final as i32,
+                    invalid_reason: String::new(),
+                },
+            };
+            (
+                grpc_model::BlockStatusChange::Discarded,
+                Some(discard_reason),
+            )
+        }
+    };
+    grpc_model::BlockStatusEvent {
+        block_id: event.block_id.to_string(),
+        slot: Some(event.slot.into()),
+        change: change as i32,
+        discard_reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_grpc_block_status() {
+        let block_id = BlockId::generate_from_hash(massa_hash::Hash::compute_from(b"block"));
+        let event = to_grpc_block_status(BlockStatusEvent {
+            block_id,
+            slot: Slot::new(3, 1),
+            change: BlockStatusChange::Discarded(DiscardReason::Invalid("bad".to_string())),
+        });
+        assert_eq!(event.block_id, block_id.to_string());
+        assert_eq!(event.slot, Some(Slot::new(3, 1).into()));
+        assert_eq!(
+            event.change,
+            grpc_model::BlockStatusChange::Discarded as i32
+        );
+        let discard_reason = event.discard_reason.unwrap();
+        assert_eq!(
+            discard_reason.r#type,
+            grpc_model::DiscardReasonType::Invalid as i32
+        );
+        assert_eq!(discard_reason.invalid_reason, "bad");
+
+        let event = to_grpc_block_status(BlockStatusEvent {
+            block_id,
+            slot: Slot::new(3, 1),
+            change: BlockStatusChange::Final,
+        });
+        assert_eq!(event.change, grpc_model::BlockStatusChange::Final as i32);
+        assert!(event.discard_reason.is_none());
+    }
+}
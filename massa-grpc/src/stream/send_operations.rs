@@ -98,9 +98,9 @@ pub(crate) async fn send_operations(
                                     let verified_op_res = match operation_deserializer.deserialize::<DeserializeError>(&proto_operation) {
                                         Ok(tuple) => {
                                             let (rest, res_operation): (&[u8], SecureShareOperation) = tuple;
-                                            match res_operation.content.op {
+                                            match res_operation.content.op.get_emitted_op() {
                                                 OperationType::CallSC { max_gas, .. } | OperationType::ExecuteSC { max_gas, .. } => {
-                                                    if max_gas > config.max_gas_per_block {
+                                                    if *max_gas > config.max_gas_per_block {
                                                         return Err(GrpcError::InvalidArgument("Gas limit of the operation is higher than the block gas limit. Your operation will never be included in a block.".into()));
                                                     }
                                                 },
@@ -113,7 +113,7 @@ pub(crate) async fn send_operations(
                                             }
 
 
-                                            if res_operation.content.fee.checked_sub(config.minimal_fees).is_none() {
+                                            if res_operation.content.fee.checked_sub(*config.minimal_fees.read()).is_none() {
                                                 return Err(GrpcError::InvalidArgument("Operation fee is lower than the minimal fee. Your operation will never be included in a block.".into()));
                                             }
 
@@ -5,7 +5,6 @@ use crate::config::{GrpcConfig, ServiceName};
 use crate::server::MassaPublicGrpc;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{ExecutionChannels, MockExecutionController};
-use massa_models::amount::Amount;
 use massa_models::{
     config::{
         ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
@@ -110,7 +109,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         client_private_key_path: PathBuf::default(),
         max_query_items_per_request: 50,
         chain_id: *CHAINID,
-        minimal_fees: Amount::zero(),
+        minimal_fees: Default::default(),
     };
 
     let mip_stats_config = MipStatsConfig {
@@ -125,6 +124,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
             block_sender: tokio::sync::broadcast::channel(100).0,
             block_header_sender: tokio::sync::broadcast::channel(100).0,
             filled_block_sender: tokio::sync::broadcast::channel(100).0,
+            block_status_sender: tokio::sync::broadcast::channel(100).0,
         },
         consensus_controller: consensus_ctrl,
         execution_controller: execution_ctrl,
@@ -148,5 +148,6 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        rate_limiter: None,
     }
 }
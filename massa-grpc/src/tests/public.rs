@@ -3,7 +3,10 @@
 use crate::tests::mock::grpc_public_service;
 use massa_consensus_exports::MockConsensusController;
 use massa_execution_exports::{EventStore, MockExecutionController};
+use massa_final_state::FinalStateHashes;
+use massa_hash::Hash;
 use massa_models::address::Address;
+use massa_models::amount::Amount;
 use massa_models::block::BlockGraphStatus;
 use massa_models::block_id::BlockId;
 use massa_models::config::VERSION;
@@ -16,10 +19,10 @@ use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
 use massa_proto_rs::massa::api::v1::{
     search_blocks_filter, AddressBalanceCandidate, ExecuteReadOnlyCallRequest,
     ExecutionQueryRequestItem, GetBlocksRequest, GetEndorsementsRequest,
-    GetNextBlockBestParentsRequest, GetOperationsRequest, GetScExecutionEventsRequest,
-    GetSelectorDrawsRequest, GetStatusRequest, GetTransactionsThroughputRequest, QueryStateRequest,
-    SearchBlocksFilter, SearchBlocksRequest, SearchEndorsementsRequest, SearchOperationsRequest,
-    SelectorDrawsFilter,
+    GetFinalStateHashesRequest, GetNextBlockBestParentsRequest, GetOperationsRequest,
+    GetScExecutionEventsRequest, GetSelectorDrawsRequest, GetStatusRequest,
+    GetTransactionsThroughputRequest, QueryStateRequest, SearchBlocksFilter, SearchBlocksRequest,
+    SearchEndorsementsRequest, SearchOperationsRequest, SelectorDrawsFilter,
 };
 use massa_proto_rs::massa::model::v1::read_only_execution_call::Target;
 use massa_proto_rs::massa::model::v1::{
@@ -30,6 +33,7 @@ use massa_protocol_exports::test_exports::tools::{
     create_block, create_block_with_endorsements, create_block_with_operations, create_endorsement,
     create_operation_with_expire_period,
 };
+use massa_sdk::grpc::{GrpcClient, GrpcClientConfig};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::collections::{BTreeMap, VecDeque};
@@ -546,6 +550,64 @@ async fn get_endorsements() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn get_final_state_hashes() {
+    let addr: SocketAddr = "[::]:4015".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let known_slot = Slot::new(1, 0);
+    let hashes = FinalStateHashes {
+        slot: known_slot,
+        execution_trail_hash: Hash::compute_from(b"trail"),
+        fingerprint: Hash::compute_from(b"fingerprint"),
+    };
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_get_final_state_hashes()
+        .returning(move |slots| {
+            slots
+                .iter()
+                .map(|slot| (*slot == known_slot).then_some(hashes))
+                .collect()
+        });
+
+    public_server.execution_controller = exec_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let result = public_client
+        .get_final_state_hashes(GetFinalStateHashesRequest { slots: vec![] })
+        .await;
+    assert!(result.is_err());
+
+    let result = public_client
+        .get_final_state_hashes(GetFinalStateHashesRequest {
+            slots: vec![known_slot.into(), Slot::new(2, 0).into()],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(result.entries.len(), 2);
+    let known = &result.entries[0];
+    assert_eq!(known.slot, Some(known_slot.into()));
+    let known_hashes = known.hashes.as_ref().unwrap();
+    assert_eq!(
+        known_hashes.execution_trail_hash,
+        hashes.execution_trail_hash.to_string()
+    );
+    assert_eq!(known_hashes.fingerprint, hashes.fingerprint.to_string());
+    assert!(result.entries[1].hashes.is_none());
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_next_block_best_parents() {
     let addr: SocketAddr = "[::]:4009".parse().unwrap();
@@ -942,6 +1004,53 @@ async fn query_state() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn sdk_typed_query_state() {
+    let addr: SocketAddr = "[::]:4034".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl.expect_query_state().returning(|request| {
+        massa_execution_exports::ExecutionQueryResponse {
+            responses: request
+                .requests
+                .iter()
+                .map(|_| {
+                    Ok(massa_execution_exports::ExecutionQueryResponseItem::Amount(
+                        Amount::from_str("12.5").unwrap(),
+                    ))
+                })
+                .collect(),
+            candidate_cursor: Slot::new(1, 2),
+            final_cursor: Slot::new(1, 7),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        }
+    });
+    public_server.execution_controller = exec_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut client_config = GrpcClientConfig::new(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ));
+    // force the request to be split
+    client_config.max_items_per_request = 2;
+    let client = GrpcClient::connect(client_config).await.unwrap();
+
+    let addresses: Vec<Address> = (0..5)
+        .map(|_| Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()))
+        .collect();
+    let balances = client.get_balances(&addresses, true).await.unwrap();
+    assert_eq!(balances, vec![Amount::from_str("12.5").unwrap(); 5]);
+
+    // the node answers an amount where a roll count is expected
+    assert!(client.get_roll_counts(&addresses, true).await.is_err());
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn search_blocks() {
     let addr: SocketAddr = "[::]:4013".parse().unwrap();
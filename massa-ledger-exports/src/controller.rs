@@ -1,7 +1,7 @@
 use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
 use std::collections::BTreeSet;
 
-use crate::{LedgerChanges, LedgerError};
+use crate::{LedgerChanges, LedgerError, LedgerStats};
 use massa_db_exports::DBBatch;
 
 #[cfg(feature = "test-exports")]
@@ -56,6 +56,23 @@ pub trait LedgerController: Send + Sync {
     /// Deserializes the key and value, useful after bootstrap
     fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool;
 
+    /// Gets the aggregate data about the whole ledger
+    fn get_stats(&self) -> LedgerStats;
+
+    /// Gets the addresses having the highest balances
+    ///
+    /// # Returns
+    /// At most `count` addresses with their balance, sorted by decreasing balance
+    fn get_top_balances(&self, count: usize) -> Vec<(Address, Amount)>;
+
+    /// Gets the number of bytes (keys and values) used by the datastore of an address
+    fn get_datastore_size(&self, addr: &Address) -> u64;
+
+    /// Recomputes the aggregate data from the whole ledger.
+    ///
+    /// USED AFTER BOOTSTRAP OR LOAD FROM DISK ONLY
+    fn recompute_stats(&mut self);
+
     /// Get every address and their corresponding balance.
     ///
     /// IMPORTANT: This should only be used for debug and test purposes.
@@ -9,6 +9,7 @@ mod key;
 mod ledger_changes;
 mod ledger_entry;
 mod mapping_grpc;
+mod stats;
 mod types;
 
 pub use config::LedgerConfig;
@@ -24,6 +25,7 @@ pub use ledger_changes::{
     LedgerEntryUpdateDeserializer, LedgerEntryUpdateSerializer,
 };
 pub use ledger_entry::{LedgerEntry, LedgerEntryDeserializer, LedgerEntrySerializer};
+pub use stats::LedgerStats;
 pub use types::{
     Applicable, SetOrDelete, SetOrKeep, SetOrKeepDeserializer, SetOrKeepSerializer,
     SetUpdateOrDelete, SetUpdateOrDeleteDeserializer, SetUpdateOrDeleteSerializer,
@@ -0,0 +1,18 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the aggregate data maintained by the `FinalLedger`
+
+use massa_models::amount::Amount;
+
+/// Aggregate data about the final ledger, kept up to date as `LedgerChanges` are applied
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerStats {
+    /// sum of the balances of all the ledger entries
+    pub total_balance: Amount,
+    /// number of user addresses having a ledger entry
+    pub user_address_count: u64,
+    /// number of smart contract addresses having a ledger entry
+    pub sc_address_count: u64,
+    /// bytes used by all the datastores (keys and values)
+    pub datastore_bytes: u64,
+}
@@ -3,9 +3,10 @@
 //! This file defines the final ledger associating addresses to their balances, bytecode and data.
 
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
+use crate::ledger_stats::LedgerStatsCache;
 use massa_db_exports::{DBBatch, ShareableMassaDBController};
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError, LedgerStats,
 };
 use massa_models::{
     address::Address,
@@ -26,6 +27,8 @@ pub struct FinalLedger {
     pub(crate) config: LedgerConfig,
     /// ledger tree, sorted by address
     pub(crate) sorted_ledger: LedgerDB,
+    /// aggregate data about the ledger
+    pub(crate) stats: LedgerStatsCache,
 }
 
 impl FinalLedger {
@@ -43,6 +46,7 @@ impl FinalLedger {
         FinalLedger {
             sorted_ledger,
             config,
+            stats: Default::default(),
         }
     }
 }
@@ -147,10 +151,12 @@ impl LedgerController for FinalLedger {
     /// USED FOR BOOTSTRAP ONLY
     fn reset(&mut self) {
         self.sorted_ledger.reset();
+        self.stats = Default::default();
     }
 
     /// Allows applying `LedgerChanges` to the final ledger
     fn apply_changes_to_batch(&mut self, changes: LedgerChanges, ledger_batch: &mut DBBatch) {
+        self.stats.apply_changes(&changes);
         self.sorted_ledger
             .apply_changes_to_batch(changes, ledger_batch);
     }
@@ -161,6 +167,31 @@ impl LedgerController for FinalLedger {
             .is_key_value_valid(serialized_key, serialized_value)
     }
 
+    /// Gets the aggregate data about the whole ledger
+    fn get_stats(&self) -> LedgerStats {
+        self.stats.get_stats()
+    }
+
+    /// Gets the addresses having the highest balances
+    ///
+    /// # Returns
+    /// At most `count` addresses with their balance, sorted by decreasing balance
+    fn get_top_balances(&self, count: usize) -> Vec<(Address, Amount)> {
+        self.stats.get_top_balances(count)
+    }
+
+    /// Gets the number of bytes (keys and values) used by the datastore of an address
+    fn get_datastore_size(&self, addr: &Address) -> u64 {
+        self.stats.get_datastore_size(addr)
+    }
+
+    /// Recomputes the aggregate data from the whole ledger.
+    ///
+    /// USED AFTER BOOTSTRAP OR LOAD FROM DISK ONLY
+    fn recompute_stats(&mut self) {
+        self.stats.recompute(&self.sorted_ledger);
+    }
+
     /// Get every address and their corresponding balance.
     ///
     /// IMPORTANT: This should only be used for debug and test purposes.
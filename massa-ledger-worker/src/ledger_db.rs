@@ -131,7 +131,9 @@ impl LedgerDB {
             match change {
                 // the incoming change sets a ledger entry to a new one
                 SetUpdateOrDelete::Set(new_entry) => {
-                    // inserts/overwrites the entry with the incoming one
+                    // inserts/overwrites the entry with the incoming one,
+                    // the datastore keys of the previous entry are not kept
+                    self.delete_entry(&addr, batch);
                     self.put_entry(&addr, new_entry, batch);
                 }
                 // the incoming change updates an existing ledger entry
@@ -210,6 +212,29 @@ impl LedgerDB {
         )
     }
 
+    /// Deserialize a balance read from the disk ledger
+    pub(crate) fn deserialize_balance(&self, bytes: &[u8]) -> Amount {
+        self.amount_deserializer
+            .deserialize::<DeserializeError>(bytes)
+            .expect("critical: invalid balance format")
+            .1
+    }
+
+    /// Call `f` on every sub-entry of the ledger with its key and serialized value
+    pub(crate) fn for_each_sub_entry<F: FnMut(Key, &[u8])>(&self, mut f: F) {
+        let db = self.db.read();
+        for (serialized_key, serialized_value) in db
+            .prefix_iterator_cf(STATE_CF, LEDGER_PREFIX.as_bytes())
+            .take_while(|(key, _)| key.starts_with(LEDGER_PREFIX.as_bytes()))
+        {
+            let (_rest, key) = self
+                .key_deserializer_db
+                .deserialize::<DeserializeError>(&serialized_key)
+                .expect("could not deserialize ledger key from state db");
+            f(key, &serialized_value);
+        }
+    }
+
     pub fn reset(&self) {
         self.db.write().delete_prefix(LEDGER_PREFIX, STATE_CF, None);
     }
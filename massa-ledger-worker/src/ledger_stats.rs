@@ -0,0 +1,339 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file maintains aggregate data about the final ledger without having to scan it.
+//! The whole ledger is only scanned once, after bootstrap or load from disk.
+
+use crate::ledger_db::LedgerDB;
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    KeyType, LedgerChanges, LedgerStats, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::prehash::PreHashMap;
+use std::collections::BTreeSet;
+
+/// Aggregate data about an address of the ledger
+#[derive(Debug, Default)]
+struct AddressStats {
+    /// balance, `None` if the address has no ledger entry
+    balance: Option<Amount>,
+    /// size (key and value) of each datastore entry, indexed by the hash of its key
+    datastore: PreHashMap<Hash, u64>,
+    /// total size of the datastore
+    datastore_size: u64,
+}
+
+/// Aggregate data about the final ledger, kept up to date as `LedgerChanges` are applied
+#[derive(Debug, Default)]
+pub(crate) struct LedgerStatsCache {
+    /// global aggregates
+    stats: LedgerStats,
+    /// addresses having a non-zero balance, sorted by balance
+    balances: BTreeSet<(Amount, Address)>,
+    /// per-address data, so that changes are accounted for without reading the disk ledger
+    /// when they are finalized. It costs about 50 bytes of memory per datastore entry.
+    addresses: PreHashMap<Address, AddressStats>,
+}
+
+impl LedgerStatsCache {
+    /// Rebuild the cache from the whole disk ledger
+    pub fn recompute(&mut self, ledger_db: &LedgerDB) {
+        *self = LedgerStatsCache::default();
+        ledger_db.for_each_sub_entry(|key, value| match key.key_type {
+            KeyType::BALANCE => {
+                let balance = ledger_db.deserialize_balance(value);
+                self.set_balance(&key.address, Some(balance));
+            }
+            KeyType::DATASTORE(datastore_key) => {
+                self.set_datastore_entry(&key.address, &datastore_key, Some(value.len()));
+            }
+            KeyType::VERSION | KeyType::BYTECODE => {}
+        });
+    }
+
+    /// Update the cache with changes applied to the disk ledger
+    pub fn apply_changes(&mut self, changes: &LedgerChanges) {
+        for (addr, change) in changes.0.iter() {
+            match change {
+                SetUpdateOrDelete::Set(new_entry) => {
+                    // the new entry replaces the previous one, datastore included
+                    self.delete_address(addr);
+                    self.set_balance(addr, Some(new_entry.balance));
+                    for (key, value) in new_entry.datastore.iter() {
+                        self.set_datastore_entry(addr, key, Some(value.len()));
+                    }
+                }
+                SetUpdateOrDelete::Update(entry_update) => {
+                    if let SetOrKeep::Set(new_balance) = entry_update.balance {
+                        self.set_balance(addr, Some(new_balance));
+                    }
+                    for (key, update) in entry_update.datastore.iter() {
+                        let value_len = match update {
+                            SetOrDelete::Set(value) => Some(value.len()),
+                            SetOrDelete::Delete => None,
+                        };
+                        self.set_datastore_entry(addr, key, value_len);
+                    }
+                }
+                SetUpdateOrDelete::Delete => self.delete_address(addr),
+            }
+        }
+    }
+
+    /// Get the global aggregates
+    pub fn get_stats(&self) -> LedgerStats {
+        self.stats
+    }
+
+    /// Get at most `count` addresses, sorted by decreasing balance
+    pub fn get_top_balances(&self, count: usize) -> Vec<(Address, Amount)> {
+        self.balances
+            .iter()
+            .rev()
+            .take(count)
+            .map(|(balance, addr)| (*addr, *balance))
+            .collect()
+    }
+
+    /// Get the datastore size of an address
+    pub fn get_datastore_size(&self, addr: &Address) -> u64 {
+        self.addresses
+            .get(addr)
+            .map(|stats| stats.datastore_size)
+            .unwrap_or_default()
+    }
+
+    /// Set the balance of an address, `None` removing its ledger entry
+    fn set_balance(&mut self, addr: &Address, new_balance: Option<Amount>) {
+        let old_balance = std::mem::replace(
+            &mut self.addresses.entry(*addr).or_default().balance,
+            new_balance,
+        );
+        match (old_balance, new_balance) {
+            (None, Some(_)) => self.add_address(addr),
+            (Some(_), None) => self.remove_address(addr),
+            _ => {}
+        }
+        let old_balance = old_balance.unwrap_or_default();
+        let new_balance = new_balance.unwrap_or_default();
+        if !old_balance.is_zero() {
+            self.balances.remove(&(old_balance, *addr));
+        }
+        if !new_balance.is_zero() {
+            self.balances.insert((new_balance, *addr));
+        }
+        self.stats.total_balance = self
+            .stats
+            .total_balance
+            .saturating_sub(old_balance)
+            .saturating_add(new_balance);
+    }
+
+    /// Set the value length of a datastore entry, `None` deleting it
+    fn set_datastore_entry(&mut self, addr: &Address, key: &[u8], value_len: Option<usize>) {
+        let stats = self.addresses.entry(*addr).or_default();
+        let key_hash = Hash::compute_from(key);
+        let new_size = value_len
+            .map(|len| key.len().saturating_add(len) as u64)
+            .unwrap_or_default();
+        let old_size = if new_size > 0 {
+            stats.datastore.insert(key_hash, new_size)
+        } else {
+            stats.datastore.remove(&key_hash)
+        }
+        .unwrap_or_default();
+        stats.datastore_size = stats
+            .datastore_size
+            .saturating_sub(old_size)
+            .saturating_add(new_size);
+        if stats.balance.is_none() && stats.datastore.is_empty() {
+            self.addresses.remove(addr);
+        }
+        self.stats.datastore_bytes = self
+            .stats
+            .datastore_bytes
+            .saturating_sub(old_size)
+            .saturating_add(new_size);
+    }
+
+    /// Remove an address and its datastore
+    fn delete_address(&mut self, addr: &Address) {
+        if !self.addresses.contains_key(addr) {
+            return;
+        }
+        self.set_balance(addr, None);
+        if let Some(stats) = self.addresses.remove(addr) {
+            self.stats.datastore_bytes = self
+                .stats
+                .datastore_bytes
+                .saturating_sub(stats.datastore_size);
+        }
+    }
+
+    fn add_address(&mut self, addr: &Address) {
+        match addr {
+            Address::User(_) | Address::Multisig(_) => self.stats.user_address_count += 1,
+            Address::SC(_) => self.stats.sc_address_count += 1,
+        }
+    }
+
+    fn remove_address(&mut self, addr: &Address) {
+        match addr {
+            Address::User(_) | Address::Multisig(_) => {
+                self.stats.user_address_count = self.stats.user_address_count.saturating_sub(1)
+            }
+            Address::SC(_) => {
+                self.stats.sc_address_count = self.stats.sc_address_count.saturating_sub(1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController, ShareableMassaDBController};
+    use massa_db_worker::MassaDB;
+    use massa_hash::Hash;
+    use massa_ledger_exports::{LedgerEntry, LedgerEntryUpdate};
+    use massa_models::address::{SCAddress, SCAddressV0};
+    use massa_signature::KeyPair;
+    use parking_lot::RwLock;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn apply(
+        cache: &mut LedgerStatsCache,
+        ledger_db: &LedgerDB,
+        db: &ShareableMassaDBController,
+        changes: LedgerChanges,
+    ) {
+        cache.apply_changes(&changes);
+        let mut batch = DBBatch::new();
+        ledger_db.apply_changes_to_batch(changes, &mut batch);
+        db.write().write_batch(batch, Default::default(), None);
+    }
+
+    #[test]
+    fn test_ledger_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100_000,
+            max_versioning_elements_size: 100_000,
+            max_ledger_backups: 10,
+            thread_count: 32,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let ledger_db = LedgerDB::new(db.clone(), 32, 255, 1000);
+        let mut cache = LedgerStatsCache::default();
+
+        let user_1 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let user_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let sc = Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::compute_from(
+            b"sc",
+        ))));
+
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            user_1,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("100").unwrap(),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(
+            user_2,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("50").unwrap(),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(
+            sc,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("10").unwrap(),
+                datastore: BTreeMap::from([(b"key".to_vec(), b"value".to_vec())]),
+                ..Default::default()
+            }),
+        );
+        apply(&mut cache, &ledger_db, &db, changes);
+
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            user_2,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_str("200").unwrap()),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(
+            sc,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                datastore: BTreeMap::from([
+                    (b"key".to_vec(), SetOrDelete::Set(b"v".to_vec())),
+                    (b"other".to_vec(), SetOrDelete::Set(b"data".to_vec())),
+                ]),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(user_1, SetUpdateOrDelete::Delete);
+        apply(&mut cache, &ledger_db, &db, changes);
+
+        let expected = LedgerStats {
+            total_balance: Amount::from_str("210").unwrap(),
+            user_address_count: 1,
+            sc_address_count: 1,
+            datastore_bytes: 13,
+        };
+        assert_eq!(cache.get_stats(), expected);
+        assert_eq!(
+            cache.get_top_balances(5),
+            vec![
+                (user_2, Amount::from_str("200").unwrap()),
+                (sc, Amount::from_str("10").unwrap())
+            ]
+        );
+        assert_eq!(cache.get_top_balances(1).len(), 1);
+        assert_eq!(cache.get_datastore_size(&sc), 13);
+        assert_eq!(cache.get_datastore_size(&user_1), 0);
+
+        // the incremental updates match a full scan
+        let mut recomputed = LedgerStatsCache::default();
+        recomputed.recompute(&ledger_db);
+        assert_eq!(recomputed.get_stats(), expected);
+        assert_eq!(recomputed.get_top_balances(5), cache.get_top_balances(5));
+        assert_eq!(recomputed.get_datastore_size(&sc), 13);
+
+        // setting an existing address replaces its whole entry
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            sc,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("20").unwrap(),
+                datastore: BTreeMap::from([(b"new".to_vec(), b"value".to_vec())]),
+                ..Default::default()
+            }),
+        );
+        apply(&mut cache, &ledger_db, &db, changes);
+
+        let expected = LedgerStats {
+            total_balance: Amount::from_str("220").unwrap(),
+            user_address_count: 1,
+            sc_address_count: 1,
+            datastore_bytes: 8,
+        };
+        assert_eq!(cache.get_stats(), expected);
+        assert_eq!(cache.get_datastore_size(&sc), 8);
+        let mut recomputed = LedgerStatsCache::default();
+        recomputed.recompute(&ledger_db);
+        assert_eq!(recomputed.get_stats(), expected);
+        assert_eq!(recomputed.get_top_balances(5), cache.get_top_balances(5));
+    }
+}
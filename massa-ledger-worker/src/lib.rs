@@ -19,6 +19,10 @@
 //! and can be manipulated using `LedgerChanges` (see `ledger_changes.rs`).
 //! The `FinalLedger` is bootstrapped using tooling available in bootstrap.rs
 //!
+//! ## `ledger_stats.rs`
+//! Maintains aggregate data about the `FinalLedger` (coins, address counts, datastore sizes, rich list)
+//! from the `LedgerChanges` applied to it, without scanning the whole ledger.
+//!
 //! ## `ledger_entry.rs`
 //! Represents an entry in the ledger for a given address.
 //! It contains balances, executable bytecode and an arbitrary datastore.
@@ -40,6 +44,7 @@
 
 mod ledger;
 mod ledger_db;
+mod ledger_stats;
 
 pub use ledger::FinalLedger;
 
@@ -5,7 +5,7 @@ use massa_ledger_exports::{LedgerConfig, LedgerController, LedgerEntry};
 use massa_models::address::Address;
 use std::collections::HashMap;
 
-use crate::{ledger_db::LedgerDB, FinalLedger};
+use crate::{ledger_db::LedgerDB, ledger_stats::LedgerStatsCache, FinalLedger};
 
 /// This file defines tools to test the ledger bootstrap
 
@@ -22,9 +22,12 @@ pub fn create_final_ledger(
         config.max_datastore_value_length,
     );
     ledger_db.load_initial_ledger(initial_ledger);
+    let mut stats = LedgerStatsCache::default();
+    stats.recompute(&ledger_db);
     FinalLedger {
         config,
         sorted_ledger: ledger_db,
+        stats,
     }
 }
 
@@ -33,6 +33,7 @@ impl Default for FinalLedger {
         FinalLedger {
             config: Default::default(),
             sorted_ledger: db,
+            stats: Default::default(),
         }
     }
 }
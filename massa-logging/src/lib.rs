@@ -6,6 +6,9 @@
 pub use serde_json;
 pub use tracing;
 
+pub mod otel;
+pub mod subscriber;
+
 #[macro_export]
 /// tracing with some context
 macro_rules! massa_trace {
@@ -0,0 +1,254 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Spans following one object (block, operation) across the modules of the node.
+//!
+//! The first module seeing an object opens a root span for it with `object_span`, looked up by the
+//! kind and id of the object. Within the node, the span is carried along with the commands sent to
+//! the other modules, which open their stages as children of it with `stage_span`.
+//! The root span ends when `close_object_span` is called, so its duration is the end-to-end latency
+//! (e.g. from "header received" to "block final and executed").
+//!
+//! Without the `opentelemetry` feature, all functions are no-ops.
+
+use std::fmt::Display;
+
+use tracing::Span;
+
+/// Returns true if the node is built with OpenTelemetry support
+pub const fn is_enabled() -> bool {
+    cfg!(feature = "opentelemetry")
+}
+
+/// Opens the span of the stage `stage` of the object `kind`/`id`,
+/// as a child of the root span of the object (opened if needed)
+#[allow(unused_variables)]
+pub fn object_span(kind: &'static str, id: &dyn Display, stage: &'static str) -> Span {
+    #[cfg(feature = "opentelemetry")]
+    {
+        let root = registry::root_span(kind, id);
+        tracing::info_span!(parent: &root, "stage", otel.name = stage, kind, id = %id)
+    }
+    #[cfg(not(feature = "opentelemetry"))]
+    {
+        Span::none()
+    }
+}
+
+/// Opens the span of the stage `stage` as a child of `parent`,
+/// the span carried by the command being processed
+#[allow(unused_variables)]
+pub fn stage_span(parent: &Span, stage: &'static str) -> Span {
+    #[cfg(feature = "opentelemetry")]
+    {
+        tracing::info_span!(parent: parent, "stage", otel.name = stage)
+    }
+    #[cfg(not(feature = "opentelemetry"))]
+    {
+        Span::none()
+    }
+}
+
+/// Ends the root span of the object `kind`/`id`, if any
+#[allow(unused_variables)]
+pub fn close_object_span(kind: &'static str, id: &dyn Display) {
+    #[cfg(feature = "opentelemetry")]
+    registry::close(kind, id);
+}
+
+/// Flushes the spans not yet exported, to be called before the node exits
+pub fn shutdown() {
+    #[cfg(feature = "opentelemetry")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Builds the layer exporting the spans to the OTLP collector at `endpoint` (e.g. `http://localhost:4317`).
+/// Must be called from within a tokio runtime.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn otlp_layer<S>(
+    endpoint: &str,
+) -> Result<impl tracing_subscriber::Layer<S> + Send + Sync, String>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry_otlp::WithExportConfig;
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                "service.name",
+                "massa-node",
+            )]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|err| format!("could not start the OTLP exporter: {}", err))?;
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+#[cfg(feature = "opentelemetry")]
+mod registry {
+    use std::collections::{HashMap, VecDeque};
+    use std::fmt::Display;
+    use std::sync::{Mutex, OnceLock};
+
+    use tracing::Span;
+
+    /// Maximum number of objects followed at the same time.
+    /// Objects that are never closed (e.g. blocks that never become final) are forgotten oldest first.
+    const MAX_TRACKED_OBJECTS: usize = 10_000;
+
+    type Key = (&'static str, String);
+
+    /// Root spans of the followed objects.
+    /// Closing an object does not look for it in `order`: its entry there becomes stale
+    /// (its generation no longer matches) and is skipped when evicting.
+    struct Registry {
+        spans: HashMap<Key, (u64, Span)>,
+        order: VecDeque<(u64, Key)>,
+        next_generation: u64,
+        capacity: usize,
+    }
+
+    impl Registry {
+        fn new(capacity: usize) -> Self {
+            Registry {
+                spans: HashMap::new(),
+                order: VecDeque::new(),
+                next_generation: 0,
+                capacity,
+            }
+        }
+
+        fn get_or_open(&mut self, key: Key, open: impl FnOnce(&str) -> Span) -> Span {
+            if let Some((_, span)) = self.spans.get(&key) {
+                return span.clone();
+            }
+            while self.spans.len() >= self.capacity {
+                let Some((generation, oldest)) = self.order.pop_front() else {
+                    break;
+                };
+                if self
+                    .spans
+                    .get(&oldest)
+                    .is_some_and(|(g, _)| *g == generation)
+                {
+                    self.spans.remove(&oldest);
+                }
+            }
+            // drop the stale entries once they outnumber the followed objects
+            if self.order.len() >= 2 * self.capacity {
+                let spans = &self.spans;
+                self.order.retain(|(generation, key)| {
+                    spans.get(key).is_some_and(|(g, _)| g == generation)
+                });
+            }
+            let span = open(&key.1);
+            let generation = self.next_generation;
+            self.next_generation += 1;
+            self.order.push_back((generation, key.clone()));
+            self.spans.insert(key, (generation, span.clone()));
+            span
+        }
+
+        fn close(&mut self, key: &Key) {
+            // dropping the last reference to the span ends it
+            self.spans.remove(key);
+        }
+    }
+
+    fn registry() -> &'static Mutex<Registry> {
+        static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+        REGISTRY.get_or_init(|| Mutex::new(Registry::new(MAX_TRACKED_OBJECTS)))
+    }
+
+    pub(super) fn root_span(kind: &'static str, id: &dyn Display) -> Span {
+        let Ok(mut registry) = registry().lock() else {
+            return Span::none();
+        };
+        registry.get_or_open(
+            (kind, id.to_string()),
+            |id| tracing::info_span!(parent: None, "object", otel.name = kind, id = %id),
+        )
+    }
+
+    pub(super) fn close(kind: &'static str, id: &dyn Display) {
+        if let Ok(mut registry) = registry().lock() {
+            registry.close(&(kind, id.to_string()));
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn key(id: u32) -> Key {
+            ("block", id.to_string())
+        }
+
+        #[test]
+        fn test_registry_reuses_open_spans() {
+            let mut registry = Registry::new(4);
+            let mut opened = 0;
+            for _ in 0..3 {
+                registry.get_or_open(key(1), |_| {
+                    opened += 1;
+                    Span::none()
+                });
+            }
+            assert_eq!(opened, 1);
+            registry.close(&key(1));
+            registry.get_or_open(key(1), |_| {
+                opened += 1;
+                Span::none()
+            });
+            assert_eq!(opened, 2);
+        }
+
+        #[test]
+        fn test_registry_evicts_oldest_open_objects() {
+            let mut registry = Registry::new(3);
+            for id in 0..3 {
+                registry.get_or_open(key(id), |_| Span::none());
+            }
+            // closed objects leave stale entries that must not evict open ones
+            registry.close(&key(0));
+            registry.get_or_open(key(3), |_| Span::none());
+            assert_eq!(registry.spans.len(), 3);
+            registry.get_or_open(key(4), |_| Span::none());
+            assert_eq!(registry.spans.len(), 3);
+            assert!(!registry.spans.contains_key(&key(1)));
+            for id in 2..5 {
+                assert!(registry.spans.contains_key(&key(id)));
+            }
+        }
+
+        #[test]
+        fn test_registry_drops_stale_entries() {
+            let mut registry = Registry::new(2);
+            for id in 0..10 {
+                registry.get_or_open(key(id), |_| Span::none());
+                registry.close(&key(id));
+            }
+            assert!(registry.spans.is_empty());
+            assert!(registry.order.len() <= 2 * registry.capacity);
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "opentelemetry")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_disabled() {
+        assert!(!is_enabled());
+        let span = object_span("block", &1, "stage");
+        assert!(span.is_none());
+        assert!(stage_span(&span, "next_stage").is_none());
+        close_object_span("block", &1);
+    }
+}
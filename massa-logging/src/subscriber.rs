@@ -0,0 +1,220 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Setup of the node tracing subscriber, with a filter that can be changed at runtime
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+/// Rotation period of the log files
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// a new file every minute
+    Minutely,
+    /// a new file every hour
+    Hourly,
+    /// a new file every day
+    Daily,
+    /// a single file
+    Never,
+}
+
+/// File sink of the logs
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogFileConfig {
+    /// directory where the log files are written
+    pub directory: PathBuf,
+    /// prefix of the log file names
+    pub file_name_prefix: String,
+    /// rotation period
+    pub rotation: LogRotation,
+}
+
+/// Logging configuration
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    /// 0: ERROR, 1: WARN, 2: INFO, 3: DEBUG, 4: TRACE, applied to the massa crates only
+    pub level: usize,
+    /// filter directives in the `RUST_LOG` syntax (e.g. `massa=info,massa_protocol_worker=debug`),
+    /// replacing `level` if set
+    pub filter: Option<String>,
+    /// write the logs as JSON objects instead of plain text
+    pub json: bool,
+    /// also write the logs to rotating files
+    pub file: Option<LogFileConfig>,
+    /// OTLP collector receiving the spans, requires the `opentelemetry` feature
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Filter directives equivalent to a log level applied to the massa crates only
+pub fn level_directives(level: usize) -> String {
+    let level = match level {
+        4 => "trace",
+        3 => "debug",
+        2 => "info",
+        1 => "warn",
+        _ => "error",
+    };
+    // ignore non-massa logs
+    format!("off,massa={}", level)
+}
+
+/// Handle to change the log filter of the node while it is running
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Arc<RwLock<String>>,
+}
+
+impl LogFilterHandle {
+    /// Replaces the log filter by `directives`, in the `RUST_LOG` syntax.
+    /// Returns the previous directives.
+    pub fn set_filter(&self, directives: &str) -> Result<String, String> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|err| format!("invalid log filter {}: {}", directives, err))?;
+        self.handle
+            .reload(filter)
+            .map_err(|err| format!("could not reload the log filter: {}", err))?;
+        let mut current = self.current.write().map_err(|err| err.to_string())?;
+        Ok(std::mem::replace(&mut *current, directives.to_string()))
+    }
+
+    /// Gets the directives of the current log filter
+    pub fn get_filter(&self) -> String {
+        self.current
+            .read()
+            .map(|current| current.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Installs the global tracing subscriber described by `config`
+/// and returns the handle to change its filter at runtime
+pub fn init_subscriber(config: &LoggingConfig) -> Result<LogFilterHandle, String> {
+    let directives = config
+        .filter
+        .clone()
+        .unwrap_or_else(|| level_directives(config.level));
+    let filter = EnvFilter::try_new(&directives)
+        .map_err(|err| format!("invalid log filter {}: {}", directives, err))?;
+    let (filter_layer, handle) = reload::Layer::new(filter);
+
+    let stdout_layer = if config.json {
+        tracing_subscriber::fmt::layer().json().boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+    let file_layer = config.file.as_ref().map(|file| {
+        let rotation = match file.rotation {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        };
+        let appender = RollingFileAppender::new(rotation, &file.directory, &file.file_name_prefix);
+        let layer = tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(appender);
+        if config.json {
+            layer.json().boxed()
+        } else {
+            layer.boxed()
+        }
+    });
+
+    #[cfg(feature = "opentelemetry")]
+    let otlp_layer = match &config.otlp_endpoint {
+        Some(endpoint) => Some(crate::otel::otlp_layer(endpoint)?),
+        None => None,
+    };
+    #[cfg(not(feature = "opentelemetry"))]
+    let otlp_layer: Option<tracing_subscriber::layer::Identity> = None;
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(stdout_layer)
+        .with(file_layer)
+        .with(otlp_layer)
+        .try_init()
+        .map_err(|err| format!("could not install the tracing subscriber: {}", err))?;
+
+    if !crate::otel::is_enabled() && config.otlp_endpoint.is_some() {
+        tracing::warn!("an OTLP endpoint is configured but the node is built without the opentelemetry feature");
+    }
+
+    Ok(LogFilterHandle {
+        handle,
+        current: Arc::new(RwLock::new(directives)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Handle on a filter layer that is not installed as the global subscriber,
+    /// the layer must be kept alive for the handle to reload it
+    fn filter_handle(directives: &str) -> (reload::Layer<EnvFilter, Registry>, LogFilterHandle) {
+        let (layer, handle) = reload::Layer::new(EnvFilter::try_new(directives).unwrap());
+        let log_filter = LogFilterHandle {
+            handle,
+            current: Arc::new(RwLock::new(directives.to_string())),
+        };
+        (layer, log_filter)
+    }
+
+    #[test]
+    fn test_level_directives() {
+        assert_eq!(level_directives(0), "off,massa=error");
+        assert_eq!(level_directives(1), "off,massa=warn");
+        assert_eq!(level_directives(2), "off,massa=info");
+        assert_eq!(level_directives(3), "off,massa=debug");
+        assert_eq!(level_directives(4), "off,massa=trace");
+        // out of range levels only keep the errors
+        assert_eq!(level_directives(5), "off,massa=error");
+        for level in 0..5 {
+            assert!(EnvFilter::try_new(level_directives(level)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_set_filter() {
+        let (_layer, log_filter) = filter_handle(&level_directives(2));
+        assert_eq!(
+            log_filter.set_filter("massa=debug"),
+            Ok(level_directives(2))
+        );
+        assert_eq!(log_filter.get_filter(), "massa=debug");
+        assert_eq!(
+            log_filter
+                .handle
+                .with_current(|filter| filter.to_string())
+                .unwrap(),
+            "massa=debug"
+        );
+        assert_eq!(
+            log_filter.set_filter("massa=trace"),
+            Ok("massa=debug".to_string())
+        );
+        assert_eq!(log_filter.get_filter(), "massa=trace");
+    }
+
+    #[test]
+    fn test_set_invalid_filter() {
+        let (_layer, log_filter) = filter_handle("massa=info");
+        assert!(log_filter.set_filter("massa=verbose").is_err());
+        assert_eq!(log_filter.get_filter(), "massa=info");
+        assert_eq!(
+            log_filter
+                .handle
+                .with_current(|filter| filter.to_string())
+                .unwrap(),
+            "massa=info"
+        );
+    }
+}
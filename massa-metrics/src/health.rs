@@ -0,0 +1,243 @@
+//! Health of the node, exposed on the metrics server for load balancers and orchestrators:
+//! - `/health/live`: the process is up and serving
+//! - `/health/ready`: the node is bootstrapped and in sync, it can take traffic
+//! - `/status`: JSON summary of the data used to decide readiness
+
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Thresholds used to decide whether the node is ready
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+    /// number of threads, used to count the slots between two cursors
+    pub thread_count: u8,
+    /// maximum number of slots between the final execution cursor and the current wall-clock slot
+    pub max_final_slot_lag: u64,
+    /// maximum number of slots between the candidate execution cursor and the current wall-clock slot
+    pub max_candidate_slot_lag: u64,
+    /// minimum number of connected peers
+    pub min_connected_peers: usize,
+    /// the node is not ready during this delay after a desynchronization was detected
+    pub need_sync_grace_period: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig {
+            thread_count: 32,
+            max_final_slot_lag: 320,
+            max_candidate_slot_lag: 64,
+            min_connected_peers: 1,
+            need_sync_grace_period: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthInputs {
+    bootstrapped: bool,
+    final_cursor: Option<(u64, u8)>,
+    candidate_cursor: Option<(u64, u8)>,
+    current_slot: Option<(u64, u8)>,
+    connected_peers: Option<usize>,
+    last_need_sync: Option<Instant>,
+}
+
+/// Summary of the health of the node, served as JSON on `/status` and `/health/ready`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// true if the node can take traffic
+    pub ready: bool,
+    /// reasons why the node is not ready, empty if it is
+    pub reasons: Vec<String>,
+    /// true once the node has bootstrapped
+    pub bootstrapped: bool,
+    /// final execution cursor (period, thread)
+    pub final_cursor: Option<(u64, u8)>,
+    /// candidate execution cursor (period, thread)
+    pub candidate_cursor: Option<(u64, u8)>,
+    /// current wall-clock slot (period, thread)
+    pub current_slot: Option<(u64, u8)>,
+    /// number of slots between the final cursor and the current slot
+    pub final_slot_lag: Option<u64>,
+    /// number of slots between the candidate cursor and the current slot
+    pub candidate_slot_lag: Option<u64>,
+    /// number of connected peers
+    pub connected_peers: Option<usize>,
+    /// seconds elapsed since the last detected desynchronization
+    pub seconds_since_need_sync: Option<u64>,
+}
+
+/// Shared health state of the node, updated by the node modules and read by the metrics server
+#[derive(Debug, Clone, Default)]
+pub struct NodeHealth {
+    inner: Arc<RwLock<(HealthConfig, HealthInputs)>>,
+}
+
+impl NodeHealth {
+    pub fn set_config(&self, config: HealthConfig) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.0 = config;
+        }
+    }
+
+    pub fn set_bootstrapped(&self, bootstrapped: bool) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.1.bootstrapped = bootstrapped;
+        }
+    }
+
+    /// Records the execution cursors and the current wall-clock slot, as (period, thread)
+    pub fn set_sync_state(
+        &self,
+        final_cursor: (u64, u8),
+        candidate_cursor: (u64, u8),
+        current_slot: Option<(u64, u8)>,
+    ) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.1.final_cursor = Some(final_cursor);
+            inner.1.candidate_cursor = Some(candidate_cursor);
+            inner.1.current_slot = current_slot;
+        }
+    }
+
+    pub fn set_connected_peers(&self, nb: usize) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.1.connected_peers = Some(nb);
+        }
+    }
+
+    /// Records that consensus detected a desynchronization
+    pub fn signal_need_sync(&self) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.1.last_need_sync = Some(Instant::now());
+        }
+    }
+
+    /// Evaluates the readiness of the node from the last recorded data
+    pub fn report(&self) -> HealthReport {
+        let Ok(inner) = self.inner.read() else {
+            return HealthReport {
+                ready: false,
+                reasons: vec!["health state unavailable".to_string()],
+                bootstrapped: false,
+                final_cursor: None,
+                candidate_cursor: None,
+                current_slot: None,
+                final_slot_lag: None,
+                candidate_slot_lag: None,
+                connected_peers: None,
+                seconds_since_need_sync: None,
+            };
+        };
+        let (config, inputs) = &*inner;
+        let lag = |cursor: Option<(u64, u8)>| match (cursor, inputs.current_slot) {
+            (Some(cursor), Some(current)) => Some(
+                slot_index(current, config.thread_count)
+                    .saturating_sub(slot_index(cursor, config.thread_count)),
+            ),
+            _ => None,
+        };
+        let final_slot_lag = lag(inputs.final_cursor);
+        let candidate_slot_lag = lag(inputs.candidate_cursor);
+        let since_need_sync = inputs.last_need_sync.map(|instant| instant.elapsed());
+
+        let mut reasons = Vec::new();
+        if !inputs.bootstrapped {
+            reasons.push("bootstrap not completed".to_string());
+        }
+        match final_slot_lag {
+            Some(lag) if lag > config.max_final_slot_lag => reasons.push(format!(
+                "final cursor is {} slots behind (max {})",
+                lag, config.max_final_slot_lag
+            )),
+            None => reasons.push("final cursor unknown".to_string()),
+            _ => {}
+        }
+        match candidate_slot_lag {
+            Some(lag) if lag > config.max_candidate_slot_lag => reasons.push(format!(
+                "candidate cursor is {} slots behind (max {})",
+                lag, config.max_candidate_slot_lag
+            )),
+            None => reasons.push("candidate cursor unknown".to_string()),
+            _ => {}
+        }
+        match inputs.connected_peers {
+            Some(nb) if nb < config.min_connected_peers => reasons.push(format!(
+                "{} connected peers (min {})",
+                nb, config.min_connected_peers
+            )),
+            None => reasons.push("connected peers unknown".to_string()),
+            _ => {}
+        }
+        if let Some(elapsed) = since_need_sync {
+            if elapsed < config.need_sync_grace_period {
+                reasons.push(format!(
+                    "desynchronization detected {}s ago",
+                    elapsed.as_secs()
+                ));
+            }
+        }
+
+        HealthReport {
+            ready: reasons.is_empty(),
+            reasons,
+            bootstrapped: inputs.bootstrapped,
+            final_cursor: inputs.final_cursor,
+            candidate_cursor: inputs.candidate_cursor,
+            current_slot: inputs.current_slot,
+            final_slot_lag,
+            candidate_slot_lag,
+            connected_peers: inputs.connected_peers,
+            seconds_since_need_sync: since_need_sync.map(|elapsed| elapsed.as_secs()),
+        }
+    }
+}
+
+fn slot_index((period, thread): (u64, u8), thread_count: u8) -> u64 {
+    period
+        .saturating_mul(thread_count as u64)
+        .saturating_add(thread as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness() {
+        let health = NodeHealth::default();
+        health.set_config(HealthConfig {
+            thread_count: 2,
+            max_final_slot_lag: 10,
+            max_candidate_slot_lag: 2,
+            min_connected_peers: 1,
+            need_sync_grace_period: Duration::from_secs(60),
+        });
+        let report = health.report();
+        assert!(!report.ready);
+        assert_eq!(report.reasons.len(), 4);
+
+        health.set_bootstrapped(true);
+        health.set_connected_peers(3);
+        health.set_sync_state((10, 0), (14, 1), Some((15, 0)));
+        let report = health.report();
+        assert_eq!(report.final_slot_lag, Some(10));
+        assert_eq!(report.candidate_slot_lag, Some(1));
+        assert!(report.ready, "{:?}", report.reasons);
+
+        // final cursor too far behind the wall-clock slot
+        health.set_sync_state((9, 1), (14, 1), Some((15, 0)));
+        assert!(!health.report().ready);
+
+        health.set_sync_state((10, 0), (14, 1), Some((15, 0)));
+        health.signal_need_sync();
+        let report = health.report();
+        assert!(!report.ready);
+        assert_eq!(report.seconds_since_need_sync, Some(0));
+    }
+}
@@ -13,12 +13,15 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntCounterVec, IntGauge, Opts};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
+pub mod health;
 mod server;
 
+use health::NodeHealth;
+
 lazy_static! {
     // use lazy_static for these metrics because they are used in storage which implement default
     static ref OPERATIONS_COUNTER: IntGauge = register_int_gauge!(
@@ -169,9 +172,28 @@ pub struct MassaMetrics {
     final_cursor_thread: IntGauge,
     final_cursor_period: IntGauge,
 
+    /// units consumed by API clients, by api and method
+    api_request_units: IntCounterVec,
+    /// number of API requests refused by the rate limiter, by api and method
+    api_throttled_requests: IntCounterVec,
+
+    /// number of read-only execution requests waiting to be executed
+    readonly_queue_length: IntGauge,
+    /// number of executed read-only requests
+    readonly_executions: IntCounter,
+    /// number of read-only requests refused because the queue was full or they waited too long
+    readonly_rejected: IntCounter,
+    /// number of read-only executions run again because the state changed during their execution
+    readonly_retries: IntCounter,
+    /// read-only execution time
+    readonly_execution_time: Histogram,
+
     // peer bandwidth (bytes sent, bytes received)
     peers_bandwidth: Arc<RwLock<HashMap<String, (IntCounter, IntCounter)>>>,
 
+    /// health of the node, served on the metrics server
+    health: NodeHealth,
+
     pub tick_delay: Duration,
 }
 
@@ -406,6 +428,55 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // api rate limiting
+        let api_request_units = IntCounterVec::new(
+            Opts::new(
+                "api_request_units",
+                "number of rate limiting units consumed by API clients",
+            ),
+            &["api", "method"],
+        )
+        .unwrap();
+        let api_throttled_requests = IntCounterVec::new(
+            Opts::new(
+                "api_throttled_requests",
+                "number of API requests refused by the rate limiter",
+            ),
+            &["api", "method"],
+        )
+        .unwrap();
+
+        // read-only executions
+        let readonly_queue_length = IntGauge::new(
+            "readonly_queue_length",
+            "number of read-only execution requests waiting to be executed",
+        )
+        .unwrap();
+        let readonly_executions = IntCounter::new(
+            "readonly_executions",
+            "number of executed read-only requests",
+        )
+        .unwrap();
+        let readonly_rejected = IntCounter::new(
+            "readonly_rejected",
+            "number of read-only requests refused because the queue was full or timed out",
+        )
+        .unwrap();
+        let readonly_retries = IntCounter::new(
+            "readonly_retries",
+            "number of read-only executions run again because the state changed",
+        )
+        .unwrap();
+        let readonly_execution_time = Histogram::with_opts(
+            prometheus::HistogramOpts::new("readonly_execution_time", "read-only execution time")
+                .buckets(vec![
+                    0.001, 0.005, 0.010, 0.050, 0.100, 0.250, 0.500, 1.0, 2.0, 5.0,
+                ]),
+        )
+        .unwrap();
+
+        let health = NodeHealth::default();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -458,8 +529,15 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
-
-                stopper = server::bind_metrics(addr);
+                let _ = prometheus::register(Box::new(api_request_units.clone()));
+                let _ = prometheus::register(Box::new(api_throttled_requests.clone()));
+                let _ = prometheus::register(Box::new(readonly_queue_length.clone()));
+                let _ = prometheus::register(Box::new(readonly_executions.clone()));
+                let _ = prometheus::register(Box::new(readonly_rejected.clone()));
+                let _ = prometheus::register(Box::new(readonly_retries.clone()));
+                let _ = prometheus::register(Box::new(readonly_execution_time.clone()));
+
+                stopper = server::bind_metrics(addr, health.clone());
             }
         }
 
@@ -512,7 +590,15 @@ impl MassaMetrics {
                 active_cursor_period,
                 final_cursor_thread,
                 final_cursor_period,
+                api_request_units,
+                api_throttled_requests,
+                readonly_queue_length,
+                readonly_executions,
+                readonly_rejected,
+                readonly_retries,
+                readonly_execution_time,
                 peers_bandwidth: Arc::new(RwLock::new(HashMap::new())),
+                health,
                 tick_delay,
             },
             stopper,
@@ -523,6 +609,10 @@ impl MassaMetrics {
         self.enabled
     }
 
+    pub fn health(&self) -> &NodeHealth {
+        &self.health
+    }
+
     pub fn get_metrics_for_survey_thread(&self) -> (i64, i64, u64, u64) {
         (
             self.active_in_connections.clone().get(),
@@ -702,6 +792,38 @@ impl MassaMetrics {
         self.block_slot_delay.observe(delay);
     }
 
+    pub fn inc_api_request_units(&self, api: &str, method: &str, units: u64) {
+        self.api_request_units
+            .with_label_values(&[api, method])
+            .inc_by(units);
+    }
+
+    pub fn inc_api_throttled_requests(&self, api: &str, method: &str) {
+        self.api_throttled_requests
+            .with_label_values(&[api, method])
+            .inc();
+    }
+
+    pub fn set_readonly_queue_length(&self, nb: usize) {
+        self.readonly_queue_length.set(nb as i64);
+    }
+
+    pub fn inc_readonly_executions(&self) {
+        self.readonly_executions.inc();
+    }
+
+    pub fn inc_readonly_rejected(&self) {
+        self.readonly_rejected.inc();
+    }
+
+    pub fn inc_readonly_retries(&self) {
+        self.readonly_retries.inc();
+    }
+
+    pub fn set_readonly_execution_time(&self, duration: f64) {
+        self.readonly_execution_time.observe(duration);
+    }
+
     /// Update the bandwidth metrics for all peers
     /// HashMap<peer_id, (tx, rx)>
     pub fn update_peers_tx_rx(&self, data: HashMap<String, (u64, u64)>) {
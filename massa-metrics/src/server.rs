@@ -9,10 +9,13 @@ use hyper::{
 use prometheus::{Encoder, TextEncoder};
 use tracing::{error, info};
 
-use crate::MetricsStopper;
+use crate::{
+    health::{HealthReport, NodeHealth},
+    MetricsStopper,
+};
 
 #[allow(dead_code)]
-pub(crate) fn bind_metrics(addr: SocketAddr) -> MetricsStopper {
+pub(crate) fn bind_metrics(addr: SocketAddr, health: NodeHealth) -> MetricsStopper {
     let (tx, rx) = tokio::sync::oneshot::channel::<()>();
     let handle = std::thread::spawn(move || {
         let rt = tokio::runtime::Builder::new_current_thread()
@@ -21,8 +24,11 @@ pub(crate) fn bind_metrics(addr: SocketAddr) -> MetricsStopper {
             .expect("error on build tokio runtime for metrics server");
 
         rt.block_on(async {
-            let server = hyper::Server::bind(&addr).serve(make_service_fn(|_| async {
-                Ok::<_, hyper::Error>(service_fn(serve_req))
+            let server = hyper::Server::bind(&addr).serve(make_service_fn(move |_| {
+                let health = health.clone();
+                async move {
+                    Ok::<_, hyper::Error>(service_fn(move |req| serve_req(req, health.clone())))
+                }
             }));
 
             let graceful_server = server.with_graceful_shutdown(async {
@@ -42,7 +48,21 @@ pub(crate) fn bind_metrics(addr: SocketAddr) -> MetricsStopper {
 }
 
 #[allow(dead_code)]
-async fn serve_req(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+async fn serve_req(req: Request<Body>, health: NodeHealth) -> Result<Response<Body>, hyper::Error> {
+    match req.uri().path() {
+        "/health/live" => {
+            return Ok(Response::builder()
+                .status(200)
+                .body(Body::from("OK"))
+                .unwrap())
+        }
+        "/health/ready" => {
+            let report = health.report();
+            return Ok(json_response(if report.ready { 200 } else { 503 }, &report));
+        }
+        "/status" => return Ok(json_response(200, &health.report())),
+        _ => {}
+    }
     if req.uri().path() != "/metrics" {
         // return hyper error
         Ok(Response::builder()
@@ -65,3 +85,13 @@ async fn serve_req(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
         Ok(response)
     }
 }
+
+#[allow(dead_code)]
+fn json_response(status: u16, report: &HealthReport) -> Response<Body> {
+    let body = serde_json::to_vec(report).expect("Failed to serialize health report");
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
@@ -0,0 +1,479 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::{error::ModelsError, node::NodeId};
+use massa_signature::PublicKey;
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+/// Separates a rule from its expiry timestamp in the text form of an `AccessEntry`
+const EXPIRY_SEPARATOR: char = '@';
+
+/// Range of IP addresses: a CIDR block like `10.0.0.0/8`, or a single address
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Range of the addresses sharing the first `prefix_len` bits of `addr`.
+    /// IPv4-mapped IPv6 ranges are converted to IPv4 ones.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, ModelsError> {
+        let (addr, prefix_len) = match addr {
+            IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+                Some(v4) if prefix_len >= 96 => (IpAddr::V4(v4), prefix_len - 96),
+                _ => (addr, prefix_len),
+            },
+            IpAddr::V4(_) => (addr, prefix_len),
+        };
+        if prefix_len > max_prefix_len(&addr) {
+            return Err(ModelsError::AccessRuleParseError(format!(
+                "prefix length {} is too long for {}",
+                prefix_len, addr
+            )));
+        }
+        Ok(IpRange {
+            network: mask(addr, prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// First address of the range
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    /// Number of leading bits shared by the addresses of the range
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Whether `ip` belongs to the range
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        ip.is_ipv4() == self.network.is_ipv4() && mask(ip, self.prefix_len) == self.network
+    }
+}
+
+impl From<IpAddr> for IpRange {
+    fn from(addr: IpAddr) -> Self {
+        let addr = addr.to_canonical();
+        IpRange {
+            network: addr,
+            prefix_len: max_prefix_len(&addr),
+        }
+    }
+}
+
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.prefix_len == max_prefix_len(&self.network) {
+            write!(f, "{}", self.network)
+        } else {
+            write!(f, "{}/{}", self.network, self.prefix_len)
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = ModelsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_addr = |addr: &str| {
+            IpAddr::from_str(addr).map_err(|e| {
+                ModelsError::AccessRuleParseError(format!("invalid IP address {}: {}", addr, e))
+            })
+        };
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => {
+                let prefix_len = prefix_len.parse::<u8>().map_err(|e| {
+                    ModelsError::AccessRuleParseError(format!(
+                        "invalid prefix length {}: {}",
+                        prefix_len, e
+                    ))
+                })?;
+                IpRange::new(parse_addr(addr)?, prefix_len)
+            }
+            None => Ok(IpRange::from(parse_addr(s)?)),
+        }
+    }
+}
+
+fn max_prefix_len(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Keeps the first `prefix_len` bits of `addr` and zeroes the others
+fn mask(addr: IpAddr, prefix_len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(v4) => {
+            let mask = u32::MAX
+                .checked_shl(32 - prefix_len as u32)
+                .unwrap_or_default();
+            IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+        }
+        IpAddr::V6(v6) => {
+            let mask = u128::MAX
+                .checked_shl(128 - prefix_len as u32)
+                .unwrap_or_default();
+            IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+        }
+    }
+}
+
+/// What an access rule matches
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessTarget {
+    /// Every address of an IP range
+    Ip(IpRange),
+    /// A node, whatever its address. It can be given as a node id or as a peer id.
+    Node(NodeId),
+}
+
+impl std::fmt::Display for AccessTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AccessTarget::Ip(range) => write!(f, "{}", range),
+            AccessTarget::Node(node_id) => write!(f, "{}", node_id),
+        }
+    }
+}
+
+impl FromStr for AccessTarget {
+    type Err = ModelsError;
+
+    /// ## Example
+    /// ```rust
+    /// # use std::str::FromStr;
+    /// # use massa_models::access_list::AccessTarget;
+    /// let range = AccessTarget::from_str("10.1.2.3/8").unwrap();
+    /// assert_eq!(range.to_string(), "10.0.0.0/8");
+    /// let node = AccessTarget::from_str("N12UbyLJDS7zimGWf3LTHe8hYY67RdLke1iDRZqJbQQLHQSKPW8j").unwrap();
+    /// assert!(matches!(node, AccessTarget::Node(_)));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // IP addresses never start with these prefixes
+        match s.chars().next() {
+            Some('N') => Ok(AccessTarget::Node(NodeId::from_str(s)?)),
+            // peer ids are displayed as the public key of the node
+            Some('P') => Ok(AccessTarget::Node(NodeId::new(PublicKey::from_str(s)?))),
+            _ => Ok(AccessTarget::Ip(IpRange::from_str(s)?)),
+        }
+    }
+}
+
+/// Allow/deny list entry: an IP range or a node, optionally until an expiry time.
+///
+/// Its text form is the target, followed by `@` and the expiry timestamp in milliseconds if any:
+/// `10.0.0.0/8`, `2001:db8::/32@1700000000000`, `N12UbyLJDS7zimGWf3LTHe8hYY67RdLke1iDRZqJbQQLHQSKPW8j`.
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct AccessEntry {
+    /// matched IP range or node
+    pub target: AccessTarget,
+    /// time after which the entry stops matching, None if it never expires
+    pub expires_at: Option<MassaTime>,
+}
+
+impl AccessEntry {
+    /// Whether the entry stopped matching at `now`
+    pub fn is_expired(&self, now: MassaTime) -> bool {
+        is_expired(&self.expires_at, now)
+    }
+}
+
+fn is_expired(expires_at: &Option<MassaTime>, now: MassaTime) -> bool {
+    expires_at.map_or(false, |expires_at| expires_at <= now)
+}
+
+impl From<AccessTarget> for AccessEntry {
+    fn from(target: AccessTarget) -> Self {
+        AccessEntry {
+            target,
+            expires_at: None,
+        }
+    }
+}
+
+impl std::fmt::Display for AccessEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.expires_at {
+            Some(expires_at) => write!(
+                f,
+                "{}{}{}",
+                self.target,
+                EXPIRY_SEPARATOR,
+                expires_at.as_millis()
+            ),
+            None => write!(f, "{}", self.target),
+        }
+    }
+}
+
+impl FromStr for AccessEntry {
+    type Err = ModelsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.split_once(EXPIRY_SEPARATOR) {
+            Some((target, expires_at)) => Ok(AccessEntry {
+                target: AccessTarget::from_str(target)?,
+                expires_at: Some(MassaTime::from_millis(expires_at.parse::<u64>().map_err(
+                    |e| {
+                        ModelsError::AccessRuleParseError(format!(
+                            "invalid expiry timestamp {}: {}",
+                            expires_at, e
+                        ))
+                    },
+                )?)),
+            }),
+            None => Ok(AccessTarget::from_str(s)?.into()),
+        }
+    }
+}
+
+/// Allow or deny list of IP ranges and nodes.
+///
+/// IP ranges are indexed by prefix length, so matching an address costs one lookup per distinct prefix length.
+/// It is serialized as the list of its entries, which keeps lists of plain IP addresses readable.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<AccessEntry>", into = "Vec<AccessEntry>")]
+pub struct AccessList {
+    /// expiry time of the IP ranges, by prefix length then by network
+    ip_ranges: BTreeMap<u8, HashMap<IpAddr, Option<MassaTime>>>,
+    /// expiry time of the nodes
+    nodes: HashMap<NodeId, Option<MassaTime>>,
+}
+
+impl AccessList {
+    /// Adds an entry, or updates the expiry time of its target if it is already listed
+    pub fn insert(&mut self, entry: AccessEntry) {
+        match entry.target {
+            AccessTarget::Ip(range) => {
+                self.ip_ranges
+                    .entry(range.prefix_len)
+                    .or_default()
+                    .insert(range.network, entry.expires_at);
+            }
+            AccessTarget::Node(node_id) => {
+                self.nodes.insert(node_id, entry.expires_at);
+            }
+        }
+    }
+
+    /// Removes a target, returns whether it was listed
+    pub fn remove(&mut self, target: &AccessTarget) -> bool {
+        match target {
+            AccessTarget::Ip(range) => {
+                let Some(networks) = self.ip_ranges.get_mut(&range.prefix_len) else {
+                    return false;
+                };
+                let removed = networks.remove(&range.network).is_some();
+                if networks.is_empty() {
+                    self.ip_ranges.remove(&range.prefix_len);
+                }
+                removed
+            }
+            AccessTarget::Node(node_id) => self.nodes.remove(node_id).is_some(),
+        }
+    }
+
+    /// Whether `ip` is in an IP range of the list that has not expired at `now`
+    pub fn contains_ip(&self, ip: &IpAddr, now: MassaTime) -> bool {
+        let ip = ip.to_canonical();
+        let max_len = max_prefix_len(&ip);
+        self.ip_ranges
+            .range(..=max_len)
+            .any(|(prefix_len, networks)| {
+                networks
+                    .get(&mask(ip, *prefix_len))
+                    .map_or(false, |expires_at| !is_expired(expires_at, now))
+            })
+    }
+
+    /// Whether `node_id` is in the list and has not expired at `now`
+    pub fn contains_node(&self, node_id: &NodeId, now: MassaTime) -> bool {
+        self.nodes
+            .get(node_id)
+            .map_or(false, |expires_at| !is_expired(expires_at, now))
+    }
+
+    /// Whether the list has IP ranges
+    pub fn has_ip_rules(&self) -> bool {
+        !self.ip_ranges.is_empty()
+    }
+
+    /// Whether the list has nodes
+    pub fn has_node_rules(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    /// Whether the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.ip_ranges.is_empty() && self.nodes.is_empty()
+    }
+
+    /// Removes the entries expired at `now`, returns whether some were removed
+    pub fn remove_expired(&mut self, now: MassaTime) -> bool {
+        let len = self.len();
+        self.ip_ranges.retain(|_, networks| {
+            networks.retain(|_, expires_at| !is_expired(expires_at, now));
+            !networks.is_empty()
+        });
+        self.nodes
+            .retain(|_, expires_at| !is_expired(expires_at, now));
+        self.len() != len
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.ip_ranges.values().map(HashMap::len).sum::<usize>() + self.nodes.len()
+    }
+
+    /// Sorted entries of the list
+    pub fn entries(&self) -> Vec<AccessEntry> {
+        let mut entries: Vec<AccessEntry> = self
+            .ip_ranges
+            .iter()
+            .flat_map(|(prefix_len, networks)| {
+                networks.iter().map(|(network, expires_at)| AccessEntry {
+                    target: AccessTarget::Ip(IpRange {
+                        network: *network,
+                        prefix_len: *prefix_len,
+                    }),
+                    expires_at: *expires_at,
+                })
+            })
+            .chain(self.nodes.iter().map(|(node_id, expires_at)| AccessEntry {
+                target: AccessTarget::Node(*node_id),
+                expires_at: *expires_at,
+            }))
+            .collect();
+        entries.sort_unstable();
+        entries
+    }
+}
+
+impl FromIterator<AccessEntry> for AccessList {
+    fn from_iter<I: IntoIterator<Item = AccessEntry>>(iter: I) -> Self {
+        let mut list = AccessList::default();
+        iter.into_iter().for_each(|entry| list.insert(entry));
+        list
+    }
+}
+
+impl From<Vec<AccessEntry>> for AccessList {
+    fn from(entries: Vec<AccessEntry>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl From<AccessList> for Vec<AccessEntry> {
+    fn from(list: AccessList) -> Self {
+        list.entries()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_ip_range() {
+        let range = IpRange::from_str("192.168.12.34/20").unwrap();
+        assert_eq!(range.to_string(), "192.168.0.0/20");
+        assert!(range.contains(&IpAddr::from_str("192.168.15.255").unwrap()));
+        assert!(!range.contains(&IpAddr::from_str("192.168.16.0").unwrap()));
+        // IPv4-mapped addresses match IPv4 ranges
+        assert!(range.contains(&IpAddr::from_str("::ffff:192.168.1.1").unwrap()));
+        assert_eq!(
+            IpRange::from_str("::ffff:10.0.0.0/104").unwrap(),
+            IpRange::from_str("10.0.0.0/8").unwrap()
+        );
+
+        let all = IpRange::from_str("0.0.0.0/0").unwrap();
+        assert!(all.contains(&IpAddr::from_str("8.8.8.8").unwrap()));
+        assert!(!all.contains(&IpAddr::from_str("2001:db8::1").unwrap()));
+
+        assert_eq!(IpRange::from_str("2001:db8::1").unwrap().prefix_len(), 128);
+        assert!(IpRange::from_str("10.0.0.0/33").is_err());
+        assert!(IpRange::from_str("10.0.0/8").is_err());
+    }
+
+    #[test]
+    fn test_access_entry_text_form() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let node_id = NodeId::new(keypair.get_public_key());
+        assert_eq!(
+            AccessTarget::from_str(&keypair.get_public_key().to_string()).unwrap(),
+            AccessTarget::Node(node_id)
+        );
+        for text in [
+            "10.0.0.0/8".to_string(),
+            "2001:db8::/32@1700000000000".to_string(),
+            "1.2.3.4".to_string(),
+            node_id.to_string(),
+            format!("{}@42", node_id),
+        ] {
+            assert_eq!(AccessEntry::from_str(&text).unwrap().to_string(), text);
+        }
+        assert!(AccessEntry::from_str("1.2.3.4@soon").is_err());
+        assert!(AccessEntry::from_str("Nnot_a_node").is_err());
+
+        // lists of plain IP addresses keep their format
+        let list: AccessList = serde_json::from_str(r#"["1.2.3.4", "10.0.0.0/8"]"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&list).unwrap(),
+            r#"["1.2.3.4","10.0.0.0/8"]"#
+        );
+    }
+
+    #[test]
+    fn test_access_list() {
+        let now = MassaTime::from_millis(1000);
+        let node_id = NodeId::new(KeyPair::generate(0).unwrap().get_public_key());
+        let other_node_id = NodeId::new(KeyPair::generate(0).unwrap().get_public_key());
+        let mut list: AccessList = [
+            "10.0.0.0/8",
+            "192.168.1.1",
+            "172.16.0.0/12@500",
+            "2001:db8::/32@2000",
+        ]
+        .iter()
+        .map(|text| AccessEntry::from_str(text).unwrap())
+        .collect();
+        list.insert(AccessTarget::Node(node_id).into());
+
+        assert!(list.contains_ip(&IpAddr::from_str("10.20.30.40").unwrap(), now));
+        assert!(list.contains_ip(&IpAddr::from_str("192.168.1.1").unwrap(), now));
+        assert!(!list.contains_ip(&IpAddr::from_str("192.168.1.2").unwrap(), now));
+        assert!(list.contains_ip(&IpAddr::from_str("2001:db8::1").unwrap(), now));
+        // expired entries do not match anymore
+        assert!(!list.contains_ip(&IpAddr::from_str("172.16.0.1").unwrap(), now));
+        assert!(list.contains_node(&node_id, now));
+        assert!(!list.contains_node(&other_node_id, now));
+
+        assert_eq!(list.len(), 5);
+        assert!(list.remove_expired(now));
+        assert_eq!(list.len(), 4);
+        assert!(list.remove(&AccessTarget::from_str("10.0.0.0/8").unwrap()));
+        assert!(!list.remove(&AccessTarget::from_str("10.0.0.0/8").unwrap()));
+        assert!(!list.contains_ip(&IpAddr::from_str("10.20.30.40").unwrap(), now));
+        assert!(list.remove(&AccessTarget::Node(node_id)));
+        assert!(!list.has_node_rules());
+        assert!(list.has_ip_rules());
+    }
+}
@@ -15,13 +15,15 @@ use std::ops::Bound::{Excluded, Included};
 use std::str::FromStr;
 use transition::Versioned;
 
-/// Top level address representation that can differentiate between User and SC address
+/// Top level address representation that can differentiate between User, SC and Multisig address
 #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Address {
     #[allow(missing_docs)]
     User(UserAddress),
     #[allow(missing_docs)]
     SC(SCAddress),
+    #[allow(missing_docs)]
+    Multisig(MultisigAddress),
 }
 
 #[allow(missing_docs)]
@@ -36,16 +38,24 @@ pub struct SCAddress(pub Hash);
 #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct UserAddress(pub Hash);
 
+#[allow(missing_docs)]
+/// Derived from a multisig policy.
+#[transition::versioned(versions("0"))]
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MultisigAddress(pub Hash);
+
 const ADDRESS_PREFIX: char = 'A';
 // serialized with varint
 const USER_PREFIX: u64 = 0;
 const SC_PREFIX: u64 = 1;
+const MULTISIG_PREFIX: u64 = 2;
 
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Address::User(address) => address.fmt(f),
             Address::SC(address) => address.fmt(f),
+            Address::Multisig(address) => address.fmt(f),
         }
     }
 }
@@ -66,6 +76,14 @@ impl std::fmt::Display for SCAddress {
     }
 }
 
+impl std::fmt::Display for MultisigAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MultisigAddress::MultisigAddressV0(address) => address.fmt(f),
+        }
+    }
+}
+
 #[transition::impl_version(versions("0"))]
 impl std::fmt::Display for UserAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -102,6 +120,24 @@ impl std::fmt::Display for SCAddress {
     }
 }
 
+#[transition::impl_version(versions("0"))]
+impl std::fmt::Display for MultisigAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let u64_serializer = U64VarIntSerializer::new();
+        let mut bytes: Vec<u8> = Vec::new();
+        u64_serializer
+            .serialize(&Self::VERSION, &mut bytes)
+            .map_err(|_| std::fmt::Error)?;
+        bytes.extend(self.0.to_bytes());
+        write!(
+            f,
+            "{}M{}",
+            ADDRESS_PREFIX,
+            bs58::encode(bytes).with_check().into_string()
+        )
+    }
+}
+
 // See https://github.com/massalabs/massa/pull/3479#issuecomment-1408694720
 // as to why more information is not provided
 impl std::fmt::Debug for Address {
@@ -115,6 +151,7 @@ impl ::serde::Serialize for Address {
         match self {
             Address::User(address) => address.serialize(s),
             Address::SC(address) => address.serialize(s),
+            Address::Multisig(address) => address.serialize(s),
         }
     }
 }
@@ -135,6 +172,14 @@ impl ::serde::Serialize for SCAddress {
     }
 }
 
+impl ::serde::Serialize for MultisigAddress {
+    fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            MultisigAddress::MultisigAddressV0(address) => address.serialize(s),
+        }
+    }
+}
+
 #[transition::impl_version(versions("0"))]
 impl ::serde::Serialize for UserAddress {
     fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
@@ -157,6 +202,17 @@ impl ::serde::Serialize for SCAddress {
     }
 }
 
+#[transition::impl_version(versions("0"))]
+impl ::serde::Serialize for MultisigAddress {
+    fn serialize<S: ::serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(&self.to_string())
+        } else {
+            s.serialize_bytes(&self.to_prefixed_bytes())
+        }
+    }
+}
+
 impl<'de> ::serde::Deserialize<'de> for Address {
     fn deserialize<D: ::serde::Deserializer<'de>>(d: D) -> Result<Address, D::Error> {
         if d.is_human_readable() {
@@ -166,7 +222,7 @@ impl<'de> ::serde::Deserialize<'de> for Address {
                 type Value = Address;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                    formatter.write_str("A + {U | S} + base58::encode(version + hash)")
+                    formatter.write_str("A + {U | S | M} + base58::encode(version + hash)")
                 }
 
                 fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
@@ -219,7 +275,7 @@ impl FromStr for Address {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let err = Err(ModelsError::AddressParseError(s.to_string()));
 
-        // Handle the prefix ("A{U|S}")
+        // Handle the prefix ("A{U|S|M}")
         let mut chars = s.chars();
         let Some(ADDRESS_PREFIX) = chars.next() else {
             return err;
@@ -231,6 +287,9 @@ impl FromStr for Address {
         let res = match pref {
             'U' => Address::User(UserAddress::from_str_without_prefixed_type(chars.as_str())?),
             'S' => Address::SC(SCAddress::from_str_without_prefixed_type(chars.as_str())?),
+            'M' => Address::Multisig(MultisigAddress::from_str_without_prefixed_type(
+                chars.as_str(),
+            )?),
             _ => return err,
         };
         Ok(res)
@@ -245,6 +304,7 @@ impl Address {
             Address::User(addr) => addr.get_thread(thread_count),
             // TODO: tmp behaviour, discuss how we would want this to work
             Address::SC(_addr) => 0,
+            Address::Multisig(addr) => addr.get_thread(thread_count),
         }
     }
 
@@ -259,8 +319,14 @@ impl Address {
         match self {
             Address::User(addr) => addr.to_prefixed_bytes(),
             Address::SC(addr) => addr.to_prefixed_bytes(),
+            Address::Multisig(addr) => addr.to_prefixed_bytes(),
         }
     }
+
+    /// Whether the address is controlled by keys, directly or through a multisig policy
+    pub fn is_user(&self) -> bool {
+        matches!(self, Address::User(_) | Address::Multisig(_))
+    }
 }
 
 impl UserAddress {
@@ -446,6 +512,93 @@ impl SCAddress {
     }
 }
 
+impl MultisigAddress {
+    /// Gets the associated thread. Depends on the `thread_count`
+    fn get_thread(&self, thread_count: u8) -> u8 {
+        match self {
+            MultisigAddress::MultisigAddressV0(addr) => addr.get_thread(thread_count),
+        }
+    }
+
+    fn from_str_without_prefixed_type(s: &str) -> Result<Self, ModelsError> {
+        let decoded_bs58_check = bs58::decode(s).with_check(None).into_vec().map_err(|err| {
+            ModelsError::AddressParseError(format!(
+                "in MultisigAddress from_str_without_prefixed_type: {}",
+                err
+            ))
+        })?;
+        let u64_deserializer = U64VarIntDeserializer::new(Included(0), Included(u64::MAX));
+        let (rest, version) = u64_deserializer
+            .deserialize::<DeserializeError>(&decoded_bs58_check[..])
+            .map_err(|err| {
+                ModelsError::AddressParseError(format!(
+                    "in MultisigAddress from_str_without_prefixed_type: {}",
+                    err
+                ))
+            })?;
+
+        match version {
+            <MultisigAddress!["0"]>::VERSION => Ok(MultisigAddressVariant!["0"](
+                <MultisigAddress!["0"]>::from_bytes(rest)?,
+            )),
+            unhandled_version => Err(ModelsError::AddressParseError(format!(
+                "version {} is not handled for MultisigAddress",
+                unhandled_version
+            ))),
+        }
+    }
+
+    /// Serialize the address as bytes. Includes the type and version prefixes
+    pub fn to_prefixed_bytes(self) -> Vec<u8> {
+        match self {
+            MultisigAddress::MultisigAddressV0(addr) => addr.to_prefixed_bytes(),
+        }
+    }
+}
+
+#[transition::impl_version(versions("0"))]
+impl MultisigAddress {
+    /// Fetches the version of the Multisig Address
+    pub fn get_version(&self) -> u64 {
+        Self::VERSION
+    }
+
+    /// Serialize the address as bytes. Includes the type and version prefixes
+    pub fn to_prefixed_bytes(self) -> Vec<u8> {
+        let mut buff = vec![];
+        let addr_type_ser = U64VarIntSerializer::new();
+        let addr_vers_ser = U64VarIntSerializer::new();
+        addr_type_ser
+            .serialize(&MULTISIG_PREFIX, &mut buff)
+            .expect("impl always returns Ok(())");
+        addr_vers_ser
+            .serialize(&Self::VERSION, &mut buff)
+            .expect("impl always returns Ok(())");
+        buff.extend_from_slice(&self.0.to_bytes()[..]);
+        buff
+    }
+
+    /// Gets the associated thread. Depends on the `thread_count`
+    fn get_thread(&self, thread_count: u8) -> u8 {
+        (self.0.to_bytes()[0])
+            .checked_shr(8 - thread_count.trailing_zeros())
+            .unwrap_or(0)
+    }
+
+    /// Deserialize the address without considering the version byte
+    fn from_bytes(data: &[u8]) -> Result<MultisigAddress, ModelsError> {
+        Ok(MultisigAddress(Hash::from_bytes(
+            &data.try_into().map_err(|_| {
+                ModelsError::BufferError(format!(
+                    "expected a buffer of size {}, but found a size of {}",
+                    HASH_SIZE_BYTES,
+                    &data.len()
+                ))
+            })?,
+        )))
+    }
+}
+
 /* /!\ SCAddressV1 not prehashed! */
 impl PreHashed for Address {}
 
@@ -471,6 +624,7 @@ impl Serializer<Address> for AddressSerializer {
         match value {
             Address::User(addr) => self.serialize(addr, buffer),
             Address::SC(addr) => self.serialize(addr, buffer),
+            Address::Multisig(addr) => self.serialize(addr, buffer),
         }
     }
 }
@@ -513,6 +667,33 @@ impl Serializer<SCAddress> for AddressSerializer {
     }
 }
 
+impl Serializer<MultisigAddress> for AddressSerializer {
+    fn serialize(
+        &self,
+        value: &MultisigAddress,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.type_serializer.serialize(&MULTISIG_PREFIX, buffer)?;
+        match value {
+            MultisigAddress::MultisigAddressV0(addr) => self.serialize(addr, buffer),
+        }
+    }
+}
+
+#[transition::impl_version(versions("0"), structures("MultisigAddress"))]
+impl Serializer<MultisigAddress> for AddressSerializer {
+    fn serialize(
+        &self,
+        value: &MultisigAddress,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.version_serializer
+            .serialize(&value.get_version(), buffer)?;
+        buffer.extend_from_slice(&value.0.into_bytes());
+        Ok(())
+    }
+}
+
 /// Deserializer for `Address`
 #[derive(Clone)]
 pub struct AddressDeserializer {
@@ -531,7 +712,7 @@ impl AddressDeserializer {
     /// Creates a new deserializer for `Address`
     pub const fn new() -> Self {
         Self {
-            type_deserializer: U64VarIntDeserializer::new(Included(0), Included(1)),
+            type_deserializer: U64VarIntDeserializer::new(Included(0), Included(2)),
             version_deserializer: U64VarIntDeserializer::new(Included(0), Excluded(u64::MAX)),
             hash_deserializer: HashDeserializer::new(),
         }
@@ -562,6 +743,10 @@ impl Deserializer<Address> for AddressDeserializer {
                 let (rest, addr) = self.deserialize(rest)?;
                 Ok((rest, Address::SC(addr)))
             }
+            MULTISIG_PREFIX => {
+                let (rest, addr) = self.deserialize(rest)?;
+                Ok((rest, Address::Multisig(addr)))
+            }
             _ => Err(nom::Err::Error(E::from_error_kind(buffer, ErrorKind::Eof))),
         }
     }
@@ -645,6 +830,45 @@ impl Deserializer<SCAddress> for AddressDeserializer {
     }
 }
 
+impl Deserializer<MultisigAddress> for AddressDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultisigAddress, E> {
+        // Verify that we at least have a version and something else
+        if buffer.len() < 2 {
+            return Err(nom::Err::Error(E::from_error_kind(buffer, ErrorKind::Eof)));
+        }
+        let (rest, addr_vers) =
+            self.version_deserializer
+                .deserialize(buffer)
+                .map_err(|_: nom::Err<E>| {
+                    nom::Err::Error(E::from_error_kind(buffer, ErrorKind::Eof))
+                })?;
+        match addr_vers {
+            <MultisigAddress!["0"]>::VERSION => {
+                let (rest, addr) = self.deserialize(rest)?;
+                Ok((rest, MultisigAddressVariant!["0"](addr)))
+            }
+            _ => Err(nom::Err::Error(E::from_error_kind(buffer, ErrorKind::Eof))),
+        }
+    }
+}
+
+#[transition::impl_version(versions("0"), structures("MultisigAddress"))]
+impl Deserializer<MultisigAddress> for AddressDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultisigAddress, E> {
+        context("Failed MultisigAddress deserialization", |input| {
+            self.hash_deserializer.deserialize(input)
+        })
+        .map(MultisigAddress)
+        .parse(buffer)
+    }
+}
+
 /// Info for a given address on a given cycle
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExecutionAddressCycleInfo {
@@ -729,6 +953,35 @@ mod test {
         assert_ne!(thread_addr_0, thread_addr_1);
     }
 
+    #[test]
+    fn test_multisig_address() {
+        let hash = massa_hash::Hash::compute_from("ADDR".as_bytes());
+        let multisig_addr =
+            Address::Multisig(MultisigAddress::MultisigAddressV0(MultisigAddressV0(hash)));
+        let user_addr = Address::User(UserAddress::UserAddressV0(UserAddressV0(hash)));
+        assert_ne!(multisig_addr, user_addr);
+        assert!(multisig_addr.to_string().starts_with("AM"));
+        assert_eq!(
+            Address::from_str(&multisig_addr.to_string()).unwrap(),
+            multisig_addr
+        );
+        assert_eq!(
+            multisig_addr.get_thread(THREAD_COUNT),
+            user_addr.get_thread(THREAD_COUNT)
+        );
+
+        let mut buffer = Vec::new();
+        AddressSerializer::new()
+            .serialize(&multisig_addr, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, multisig_addr.to_prefixed_bytes());
+        let (rest, deserialized) = AddressDeserializer::new()
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized, multisig_addr);
+    }
+
     #[test]
     fn test_address_serde() {
         let expected_addr =
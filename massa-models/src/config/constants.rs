@@ -209,6 +209,10 @@ pub const MAX_OPERATION_DATASTORE_ENTRY_COUNT: u64 = 128;
 pub const MAX_FUNCTION_NAME_LENGTH: u16 = u16::MAX;
 /// Maximum size of parameters in call SC
 pub const MAX_PARAMETERS_SIZE: u32 = 10_000_000;
+/// Maximum number of transfers in a `BatchTransaction` operation
+pub const MAX_BATCH_TRANSACTION_TRANSFERS: u32 = 256;
+/// Maximum number of public keys in a multisig policy
+pub const MAX_MULTISIG_PUBLIC_KEYS: u32 = 32;
 /// Maximum length of `rng_seed` in thread cycle
 pub const MAX_RNG_SEED_LENGTH: u32 = PERIODS_PER_CYCLE.saturating_mul(THREAD_COUNT as u64) as u32;
 // ***********************
@@ -33,6 +33,17 @@ use std::path::Path;
 /// 3. in path specified in `MASSA_CONFIG_OVERRIDE_PATH` environment variable (`config/config.toml` by default)
 #[inline]
 pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix: &str) -> T {
+    let s = read_massa_config(app_name, env_prefix).unwrap();
+
+    s.try_deserialize().unwrap()
+}
+
+/// Read the merged configuration sources of [`build_massa_settings`] without deserializing them,
+/// to read them again while the application is running
+pub fn read_massa_config(
+    app_name: &str,
+    env_prefix: &str,
+) -> Result<config::Config, config::ConfigError> {
     let mut builder = config::Config::builder();
     let config_path = std::env::var("MASSA_CONFIG_PATH")
         .unwrap_or_else(|_| "base_config/config.toml".to_string());
@@ -55,10 +66,7 @@ pub fn build_massa_settings<T: Deserialize<'static>>(app_name: &str, env_prefix:
         }
     }
 
-    let s = builder
+    builder
         .add_source(config::Environment::with_prefix(env_prefix))
         .build()
-        .unwrap();
-
-    s.try_deserialize().unwrap()
 }
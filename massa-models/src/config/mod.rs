@@ -68,4 +68,4 @@ pub use compact_config::CompactConfig;
 
 // Export tool to read user setting file
 mod massa_settings;
-pub use massa_settings::build_massa_settings;
+pub use massa_settings::{build_massa_settings, read_massa_config};
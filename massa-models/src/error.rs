@@ -34,6 +34,8 @@ pub enum ModelsError {
     AddressParseError(String),
     /// node id parsing error
     NodeIdParseError,
+    /// access rule parsing error: {0}
+    AccessRuleParseError(String),
     /// block id parsing error
     BlockIdParseError,
     /// operation id parsing error
@@ -62,6 +64,10 @@ pub enum ModelsError {
     OutdatedBootstrapCursor,
     /// Error raised {0}
     ErrorRaised(String),
+    /// invalid multisig: {0}
+    InvalidMultisig(String),
+    /// invalid operation envelope: {0}
+    InvalidOperationEnvelope(String),
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ModelsError {
@@ -6,6 +6,8 @@
 
 extern crate lazy_static;
 
+/// IP and node allow/deny lists
+pub mod access_list;
 /// active blocks related structures
 pub mod active_block;
 /// address related structures
@@ -40,10 +42,14 @@ pub mod execution;
 pub mod ledger;
 /// mapping grpc
 pub mod mapping_grpc;
+/// multisignature accounts
+pub mod multisig;
 /// node related structure
 pub mod node;
 /// operations
 pub mod operation;
+/// operations exchanged between offline and online machines
+pub mod operation_envelope;
 /// smart contract output events
 pub mod output_event;
 /// pre-hashed trait, for hash less hashmap/set
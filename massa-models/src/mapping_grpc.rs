@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::address::Address;
 use crate::amount::Amount;
 use crate::block::{Block, BlockGraphStatus, FilledBlock, SecureShareBlock};
 use crate::block_header::{BlockHeader, SecuredHeader};
@@ -7,11 +8,13 @@ use crate::config::CompactConfig;
 use crate::denunciation::{Denunciation, DenunciationIndex};
 use crate::endorsement::{Endorsement, SecureShareEndorsement};
 use crate::error::ModelsError;
+use crate::multisig::MultisigPolicy;
 use crate::operation::{Operation, OperationType, SecureShareOperation};
 use crate::output_event::{EventExecutionContext, SCOutputEvent};
 use crate::slot::{IndexedSlot, Slot};
 use crate::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_proto_rs::massa::model::v1 as grpc_model;
+use std::str::FromStr;
 
 //TODO check error type
 /// Converts a gRPC `grpc_model::DenunciationIndex` into a DenunciationIndex
@@ -42,6 +45,27 @@ pub fn to_denunciation_index(
     }
 }
 
+/// Converts a gRPC `grpc_model::BatchTransaction` into a BatchTransaction operation type
+pub fn to_batch_transaction(
+    value: grpc_model::BatchTransaction,
+) -> Result<OperationType, ModelsError> {
+    let transfers = value
+        .transfers
+        .into_iter()
+        .map(|transfer| {
+            let recipient_address = Address::from_str(&transfer.recipient_address)?;
+            let amount = transfer
+                .amount
+                .ok_or_else(|| ModelsError::ErrorRaised("no amount found".to_string()))?;
+            Ok((
+                recipient_address,
+                Amount::from_mantissa_scale(amount.mantissa, amount.scale)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, ModelsError>>()?;
+    Ok(OperationType::BatchTransaction { transfers })
+}
+
 impl From<Amount> for grpc_model::NativeAmount {
     fn from(value: Amount) -> Self {
         let (mantissa, scale) = value.to_mantissa_scale();
@@ -227,12 +251,60 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::CallSc(call_sc));
             }
+            OperationType::BatchTransaction { transfers } => {
+                let batch_transaction = grpc_model::BatchTransaction {
+                    transfers: transfers
+                        .into_iter()
+                        .map(|(recipient_address, amount)| grpc_model::Transaction {
+                            recipient_address: recipient_address.to_string(),
+                            amount: Some(amount.into()),
+                        })
+                        .collect(),
+                };
+                grpc_operation_type.r#type = Some(
+                    grpc_model::operation_type::Type::BatchTransaction(batch_transaction),
+                );
+            }
+            OperationType::Multisig {
+                policy,
+                signatures,
+                op,
+            } => {
+                let multisig = grpc_model::Multisig {
+                    policy: Some(policy.into()),
+                    signatures: signatures
+                        .into_iter()
+                        .map(|(index, signature)| grpc_model::MultisigSignature {
+                            index,
+                            signature: signature.to_string(),
+                        })
+                        .collect(),
+                    op: Some(Box::new((*op).into())),
+                };
+                grpc_operation_type.r#type = Some(grpc_model::operation_type::Type::Multisig(
+                    Box::new(multisig),
+                ));
+            }
         }
 
         grpc_operation_type
     }
 }
 
+impl From<MultisigPolicy> for grpc_model::MultisigPolicy {
+    fn from(policy: MultisigPolicy) -> Self {
+        grpc_model::MultisigPolicy {
+            address: policy.address().to_string(),
+            public_keys: policy
+                .public_keys
+                .iter()
+                .map(|public_key| public_key.to_string())
+                .collect(),
+            threshold: policy.threshold,
+        }
+    }
+}
+
 impl From<Operation> for grpc_model::Operation {
     fn from(op: Operation) -> Self {
         grpc_model::Operation {
@@ -251,6 +323,8 @@ impl From<OperationType> for grpc_model::OpType {
             OperationType::RollSell { .. } => grpc_model::OpType::RollSell,
             OperationType::ExecuteSC { .. } => grpc_model::OpType::ExecuteSc,
             OperationType::CallSC { .. } => grpc_model::OpType::CallSc,
+            OperationType::BatchTransaction { .. } => grpc_model::OpType::BatchTransaction,
+            OperationType::Multisig { .. } => grpc_model::OpType::Multisig,
         }
     }
 }
@@ -435,3 +509,42 @@ impl From<Denunciation> for grpc_model::Denunciation {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_batch_transaction_mapping() {
+        let transfers = vec![
+            (
+                Address::from_str("AU12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx").unwrap(),
+                Amount::from_str("1.5").unwrap(),
+            ),
+            (
+                Address::from_str("AU12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx").unwrap(),
+                Amount::from_str("0.000000001").unwrap(),
+            ),
+        ];
+        let op = OperationType::BatchTransaction {
+            transfers: transfers.clone(),
+        };
+
+        assert_eq!(
+            grpc_model::OpType::from(op.clone()),
+            grpc_model::OpType::BatchTransaction
+        );
+        let Some(grpc_model::operation_type::Type::BatchTransaction(batch_transaction)) =
+            grpc_model::OperationType::from(op).r#type
+        else {
+            panic!("expected a batch transaction");
+        };
+        assert_eq!(batch_transaction.transfers.len(), 2);
+        match to_batch_transaction(batch_transaction).unwrap() {
+            OperationType::BatchTransaction {
+                transfers: mapped_transfers,
+            } => assert_eq!(mapped_transfers, transfers),
+            _ => panic!("expected a batch transaction"),
+        }
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::address::{Address, MultisigAddress, MultisigAddressV0};
+use crate::config::MAX_MULTISIG_PUBLIC_KEYS;
+use crate::error::ModelsError;
+use massa_hash::Hash;
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
+};
+use massa_signature::{PublicKey, PublicKeyDeserializer, Signature, SignatureDeserializer};
+use nom::error::{context, ContextError, ParseError};
+use nom::multi::length_count;
+use nom::sequence::tuple;
+use nom::{IResult, Parser};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::ops::Bound::{Excluded, Included};
+
+/// Prefix of the hashed data from which multisig addresses are derived,
+/// so that they can not collide with the address of a single public key
+const MULTISIG_ADDRESS_DOMAIN: &[u8] = b"massa_multisig_address";
+
+/// Signature of a cosigner, along with the index of its public key in the policy
+pub type MultisigSignature = (u32, Signature);
+
+/// M-of-N policy from which a multisig address is derived
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigPolicy {
+    /// public keys of the cosigners. Their order defines the signature indexes
+    pub public_keys: Vec<PublicKey>,
+    /// minimal number of cosigners that must sign an operation
+    pub threshold: u32,
+}
+
+impl MultisigPolicy {
+    /// Create a new policy, checking that the threshold can be reached
+    pub fn new(public_keys: Vec<PublicKey>, threshold: u32) -> Result<Self, ModelsError> {
+        let policy = MultisigPolicy {
+            public_keys,
+            threshold,
+        };
+        policy.check()?;
+        Ok(policy)
+    }
+
+    /// Check that the key set is not empty, has no duplicates and that the threshold can be reached
+    pub fn check(&self) -> Result<(), ModelsError> {
+        if self.public_keys.is_empty() || self.public_keys.len() > MAX_MULTISIG_PUBLIC_KEYS as usize
+        {
+            return Err(ModelsError::InvalidMultisig(format!(
+                "a multisig policy must have between 1 and {} public keys, got {}",
+                MAX_MULTISIG_PUBLIC_KEYS,
+                self.public_keys.len()
+            )));
+        }
+        if self.threshold == 0 || self.threshold as usize > self.public_keys.len() {
+            return Err(ModelsError::InvalidMultisig(format!(
+                "threshold {} is not in range 1..={}",
+                self.threshold,
+                self.public_keys.len()
+            )));
+        }
+        let distinct_keys: BTreeSet<Vec<u8>> =
+            self.public_keys.iter().map(|key| key.to_bytes()).collect();
+        if distinct_keys.len() != self.public_keys.len() {
+            return Err(ModelsError::InvalidMultisig(
+                "duplicate public key in multisig policy".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Address of the multisig account
+    pub fn address(&self) -> Address {
+        let mut hash_data = Vec::new();
+        hash_data.extend(MULTISIG_ADDRESS_DOMAIN);
+        hash_data.extend(self.threshold.to_be_bytes());
+        for public_key in self.public_keys.iter() {
+            hash_data.extend(public_key.to_bytes());
+        }
+        Address::Multisig(MultisigAddress::MultisigAddressV0(MultisigAddressV0(
+            Hash::compute_from(&hash_data),
+        )))
+    }
+
+    /// Index of a public key in the policy
+    pub fn get_index(&self, public_key: &PublicKey) -> Option<u32> {
+        self.public_keys
+            .iter()
+            .position(|key| key == public_key)
+            .map(|index| index as u32)
+    }
+
+    /// Check that an operation is submitted by one of the cosigners.
+    /// The ID of a multisig operation does not depend on its submitter, so this is what
+    /// prevents anyone else from relaying it under their own signature.
+    pub fn check_submitter(&self, public_key: &PublicKey) -> Result<(), ModelsError> {
+        if self.get_index(public_key).is_none() {
+            return Err(ModelsError::InvalidMultisig(
+                "the submitter of a multisig operation must be one of its cosigners".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the signatures are from distinct cosigners of the policy and reach the threshold.
+    /// The signatures themselves are verified by `get_signature_batch`.
+    pub fn check_signature_indexes(
+        &self,
+        signatures: &[MultisigSignature],
+    ) -> Result<(), ModelsError> {
+        let mut indexes = BTreeSet::new();
+        for (index, _) in signatures {
+            if *index as usize >= self.public_keys.len() {
+                return Err(ModelsError::InvalidMultisig(format!(
+                    "signature index {} out of range",
+                    index
+                )));
+            }
+            if !indexes.insert(*index) {
+                return Err(ModelsError::InvalidMultisig(format!(
+                    "duplicate signature for index {}",
+                    index
+                )));
+            }
+        }
+        if (indexes.len() as u32) < self.threshold {
+            return Err(ModelsError::InvalidMultisig(format!(
+                "{} signatures provided but the threshold is {}",
+                indexes.len(),
+                self.threshold
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the (hash, signature, public key) tuples to give to `verify_signature_batch`.
+    /// Signatures with an invalid index are skipped: see `check_signature_indexes`.
+    pub fn get_signature_batch(
+        &self,
+        hash: &Hash,
+        signatures: &[MultisigSignature],
+    ) -> Vec<(Hash, Signature, PublicKey)> {
+        signatures
+            .iter()
+            .filter_map(|(index, signature)| {
+                self.public_keys
+                    .get(*index as usize)
+                    .map(|public_key| (*hash, *signature, *public_key))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for MultisigPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}-of-{} multisig {}",
+            self.threshold,
+            self.public_keys.len(),
+            self.address()
+        )?;
+        for (index, public_key) in self.public_keys.iter().enumerate() {
+            writeln!(f, "\t- {}: {}", index, public_key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializer for `MultisigPolicy`
+#[derive(Default)]
+pub struct MultisigPolicySerializer {
+    u32_serializer: U32VarIntSerializer,
+}
+
+impl MultisigPolicySerializer {
+    /// Creates a new `MultisigPolicySerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<MultisigPolicy> for MultisigPolicySerializer {
+    fn serialize(
+        &self,
+        value: &MultisigPolicy,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u32_serializer.serialize(&value.threshold, buffer)?;
+        let keys_count: u32 = value.public_keys.len().try_into().map_err(|_| {
+            SerializeError::NumberTooBig("could not encode multisig key count as u32".into())
+        })?;
+        self.u32_serializer.serialize(&keys_count, buffer)?;
+        for public_key in value.public_keys.iter() {
+            buffer.extend(public_key.to_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `MultisigPolicy`
+pub struct MultisigPolicyDeserializer {
+    threshold_deserializer: U32VarIntDeserializer,
+    keys_count_deserializer: U32VarIntDeserializer,
+    public_key_deserializer: PublicKeyDeserializer,
+}
+
+impl MultisigPolicyDeserializer {
+    /// Creates a new `MultisigPolicyDeserializer`
+    pub fn new() -> Self {
+        Self {
+            threshold_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_MULTISIG_PUBLIC_KEYS),
+            ),
+            keys_count_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_MULTISIG_PUBLIC_KEYS),
+            ),
+            public_key_deserializer: PublicKeyDeserializer::new(),
+        }
+    }
+}
+
+impl Default for MultisigPolicyDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<MultisigPolicy> for MultisigPolicyDeserializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_models::multisig::{MultisigPolicy, MultisigPolicyDeserializer, MultisigPolicySerializer};
+    /// use massa_serialization::{DeserializeError, Deserializer, Serializer};
+    /// use massa_signature::KeyPair;
+    ///
+    /// let public_keys = (0..3).map(|_| KeyPair::generate(0).unwrap().get_public_key()).collect();
+    /// let policy = MultisigPolicy::new(public_keys, 2).unwrap();
+    /// let mut buffer = Vec::new();
+    /// MultisigPolicySerializer::new().serialize(&policy, &mut buffer).unwrap();
+    /// let (rest, deserialized) = MultisigPolicyDeserializer::new()
+    ///     .deserialize::<DeserializeError>(&buffer)
+    ///     .unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(deserialized, policy);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultisigPolicy, E> {
+        let (rest, policy) = context(
+            "Failed MultisigPolicy deserialization",
+            tuple((
+                context("Failed threshold deserialization", |input| {
+                    self.threshold_deserializer.deserialize(input)
+                }),
+                length_count(
+                    context("Failed public key count deserialization", |input| {
+                        self.keys_count_deserializer.deserialize(input)
+                    }),
+                    context("Failed public key deserialization", |input| {
+                        self.public_key_deserializer.deserialize(input)
+                    }),
+                ),
+            )),
+        )
+        .map(|(threshold, public_keys)| MultisigPolicy {
+            public_keys,
+            threshold,
+        })
+        .parse(buffer)?;
+        policy.check().map_err(|_| {
+            nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Verify,
+            ))
+        })?;
+        Ok((rest, policy))
+    }
+}
+
+/// Serializer for a list of `MultisigSignature`
+#[derive(Default)]
+pub struct MultisigSignaturesSerializer {
+    u32_serializer: U32VarIntSerializer,
+}
+
+impl MultisigSignaturesSerializer {
+    /// Creates a new `MultisigSignaturesSerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<Vec<MultisigSignature>> for MultisigSignaturesSerializer {
+    fn serialize(
+        &self,
+        value: &Vec<MultisigSignature>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        let signatures_count: u32 = value.len().try_into().map_err(|_| {
+            SerializeError::NumberTooBig("could not encode multisig signature count as u32".into())
+        })?;
+        self.u32_serializer.serialize(&signatures_count, buffer)?;
+        for (index, signature) in value.iter() {
+            self.u32_serializer.serialize(index, buffer)?;
+            buffer.extend(signature.to_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for a list of `MultisigSignature`
+pub struct MultisigSignaturesDeserializer {
+    signatures_count_deserializer: U32VarIntDeserializer,
+    index_deserializer: U32VarIntDeserializer,
+    signature_deserializer: SignatureDeserializer,
+}
+
+impl MultisigSignaturesDeserializer {
+    /// Creates a new `MultisigSignaturesDeserializer`
+    pub fn new() -> Self {
+        Self {
+            signatures_count_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_MULTISIG_PUBLIC_KEYS),
+            ),
+            index_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Excluded(MAX_MULTISIG_PUBLIC_KEYS),
+            ),
+            signature_deserializer: SignatureDeserializer::new(),
+        }
+    }
+}
+
+impl Default for MultisigSignaturesDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<Vec<MultisigSignature>> for MultisigSignaturesDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], Vec<MultisigSignature>, E> {
+        context(
+            "Failed multisig signatures deserialization",
+            length_count(
+                context("Failed signature count deserialization", |input| {
+                    self.signatures_count_deserializer.deserialize(input)
+                }),
+                tuple((
+                    context("Failed signature index deserialization", |input| {
+                        self.index_deserializer.deserialize(input)
+                    }),
+                    context("Failed signature deserialization", |input| {
+                        self.signature_deserializer.deserialize(input)
+                    }),
+                )),
+            ),
+        )
+        .parse(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_serialization::DeserializeError;
+    use massa_signature::{verify_signature_batch, KeyPair};
+
+    #[test]
+    fn test_multisig_policy() {
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let public_keys: Vec<PublicKey> = keypairs.iter().map(|kp| kp.get_public_key()).collect();
+
+        // invalid policies
+        assert!(MultisigPolicy::new(public_keys.clone(), 0).is_err());
+        assert!(MultisigPolicy::new(public_keys.clone(), 4).is_err());
+        assert!(MultisigPolicy::new(vec![public_keys[0], public_keys[0]], 1).is_err());
+
+        let policy = MultisigPolicy::new(public_keys.clone(), 2).unwrap();
+        // the address depends on the threshold and is not the one of a single key
+        assert_ne!(
+            policy.address(),
+            MultisigPolicy::new(public_keys.clone(), 3)
+                .unwrap()
+                .address()
+        );
+        assert_ne!(
+            MultisigPolicy::new(vec![public_keys[0]], 1)
+                .unwrap()
+                .address(),
+            Address::from_public_key(&public_keys[0])
+        );
+
+        let hash = Hash::compute_from(b"operation");
+        let signatures: Vec<MultisigSignature> = vec![
+            (0, keypairs[0].sign(&hash).unwrap()),
+            (2, keypairs[2].sign(&hash).unwrap()),
+        ];
+        policy.check_signature_indexes(&signatures).unwrap();
+        verify_signature_batch(&policy.get_signature_batch(&hash, &signatures)).unwrap();
+
+        // not enough distinct signers
+        assert!(policy
+            .check_signature_indexes(&[signatures[0], signatures[0]])
+            .is_err());
+        assert!(policy.check_signature_indexes(&signatures[..1]).is_err());
+        // signature from a key at another index
+        let wrong_index = vec![signatures[0], (1, signatures[1].1)];
+        assert!(verify_signature_batch(&policy.get_signature_batch(&hash, &wrong_index)).is_err());
+
+        let mut buffer = Vec::new();
+        MultisigSignaturesSerializer::new()
+            .serialize(&signatures, &mut buffer)
+            .unwrap();
+        let (rest, deserialized) = MultisigSignaturesDeserializer::new()
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized, signatures);
+    }
+}
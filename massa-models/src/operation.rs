@@ -1,7 +1,12 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::address::AddressSerializer;
+use crate::config::MAX_BATCH_TRANSACTION_TRANSFERS;
 use crate::datastore::{Datastore, DatastoreDeserializer, DatastoreSerializer};
+use crate::multisig::{
+    MultisigPolicy, MultisigPolicyDeserializer, MultisigPolicySerializer, MultisigSignature,
+    MultisigSignaturesDeserializer, MultisigSignaturesSerializer,
+};
 use crate::prehash::{PreHashSet, PreHashed};
 use crate::secure_share::{
     Id, SecureShare, SecureShareContent, SecureShareDeserializer, SecureShareSerializer,
@@ -18,7 +23,7 @@ use massa_serialization::{
     U16VarIntSerializer, U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer,
     U64VarIntSerializer,
 };
-use massa_signature::PublicKey;
+use massa_signature::{verify_signature_batch, PublicKey, Signature};
 use nom::error::{context, ErrorKind};
 use nom::multi::length_count;
 use nom::sequence::tuple;
@@ -386,6 +391,8 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    BatchTransaction = 5,
+    Multisig = 6,
 }
 
 /// the operation as sent in the network
@@ -420,6 +427,16 @@ impl SecureShareContent for Operation {
         content_creator_pub_key: &PublicKey,
         chain_id: u64,
     ) -> Hash {
+        if matches!(self.op, OperationType::Multisig { .. }) {
+            // the ID of a multisig operation depends neither on the submitter nor on the
+            // subset of signatures: it is what the cosigners sign.
+            // The submitter must be one of the cosigners, see `verify_signature`.
+            // The same serializers already succeeded in serializing the whole content.
+            return *self
+                .compute_multisig_id(chain_id)
+                .expect("failed to serialize multisig operation content")
+                .get_hash();
+        }
         let mut hash_data = Vec::new();
         // Note: Add chain id before content hash in order to avoid replay attacks,
         //       otherwise someone can copy an operation from testnet and execute it on main net
@@ -429,6 +446,61 @@ impl SecureShareContent for Operation {
         hash_data.extend(content_serialized);
         Hash::compute_from(&hash_data)
     }
+
+    fn compute_creator_address(&self, creator_public_key: &PublicKey) -> Address {
+        match &self.op {
+            OperationType::Multisig { policy, .. } => policy.address(),
+            _ => Address::from_public_key(creator_public_key),
+        }
+    }
+
+    fn verify_signature(
+        &self,
+        public_key: &PublicKey,
+        content_hash: &Hash,
+        signature: &Signature,
+    ) -> Result<(), ModelsError> {
+        public_key.verify_signature(
+            &self.compute_signed_hash(public_key, content_hash),
+            signature,
+        )?;
+        if let OperationType::Multisig {
+            policy, signatures, ..
+        } = &self.op
+        {
+            policy.check_submitter(public_key)?;
+            policy.check_signature_indexes(signatures)?;
+            verify_signature_batch(&policy.get_signature_batch(content_hash, signatures))?;
+        }
+        Ok(())
+    }
+}
+
+impl Operation {
+    /// Data identifying a multisig operation: everything but the signatures
+    fn get_multisig_signed_content(&self) -> Result<Option<Vec<u8>>, SerializeError> {
+        let OperationType::Multisig { policy, op, .. } = &self.op else {
+            return Ok(None);
+        };
+        let mut buffer = Vec::new();
+        AmountSerializer::new().serialize(&self.fee, &mut buffer)?;
+        U64VarIntSerializer::new().serialize(&self.expire_period, &mut buffer)?;
+        MultisigPolicySerializer::new().serialize(policy, &mut buffer)?;
+        OperationTypeSerializer::new().serialize(op, &mut buffer)?;
+        Ok(Some(buffer))
+    }
+
+    /// Compute the ID of a multisig operation, which must be signed by the cosigners.
+    /// It does not depend on the signatures so it can be computed before collecting them.
+    pub fn compute_multisig_id(&self, chain_id: u64) -> Result<OperationId, ModelsError> {
+        let multisig_content = self
+            .get_multisig_signed_content()?
+            .ok_or_else(|| ModelsError::InvalidMultisig("not a multisig operation".to_string()))?;
+        let mut hash_data = Vec::new();
+        hash_data.extend(chain_id.to_be_bytes());
+        hash_data.extend(multisig_content);
+        Ok(OperationId::new(Hash::compute_from(&hash_data)))
+    }
 }
 
 /// Serializer for `Operation`
@@ -629,6 +701,30 @@ pub enum OperationType {
         /// Extra coins that are spent from the caller's balance and transferred to the target
         coins: Amount,
     },
+    /// transfer coins from sender to several recipients, all transfers succeed or none does
+    BatchTransaction {
+        /// list of (recipient address, amount)
+        transfers: Vec<(Address, Amount)>,
+    },
+    /// operation emitted by a multisig address
+    Multisig {
+        /// key set and threshold from which the emitting address is derived
+        policy: MultisigPolicy,
+        /// signatures of the operation ID by the cosigners
+        signatures: Vec<MultisigSignature>,
+        /// emitted operation, can not be a multisig operation itself
+        op: Box<OperationType>,
+    },
+}
+
+impl OperationType {
+    /// Get the operation that is actually executed: the wrapped one for multisig operations
+    pub fn get_emitted_op(&self) -> &OperationType {
+        match self {
+            OperationType::Multisig { op, .. } => op.get_emitted_op(),
+            op => op,
+        }
+    }
 }
 
 impl std::fmt::Display for OperationType {
@@ -674,6 +770,22 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- max_gas:{}", max_gas)?;
                 writeln!(f, "\t- coins:{}", coins)?;
             }
+            OperationType::BatchTransaction { transfers } => {
+                writeln!(f, "BatchTransaction:")?;
+                for (recipient_address, amount) in transfers {
+                    writeln!(f, "\t- Recipient:{}", recipient_address)?;
+                    writeln!(f, "\t  Amount:{}", amount)?;
+                }
+            }
+            OperationType::Multisig {
+                policy,
+                signatures,
+                op,
+            } => {
+                write!(f, "Multisig: {}", policy)?;
+                writeln!(f, "\t- signatures:{}", signatures.len())?;
+                write!(f, "{}", op)?;
+            }
         }
         Ok(())
     }
@@ -688,6 +800,8 @@ pub struct OperationTypeSerializer {
     address_serializer: AddressSerializer,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     datastore_serializer: DatastoreSerializer,
+    multisig_policy_serializer: MultisigPolicySerializer,
+    multisig_signatures_serializer: MultisigSignaturesSerializer,
 }
 
 impl OperationTypeSerializer {
@@ -701,6 +815,8 @@ impl OperationTypeSerializer {
             address_serializer: AddressSerializer::new(),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             datastore_serializer: DatastoreSerializer::new(),
+            multisig_policy_serializer: MultisigPolicySerializer::new(),
+            multisig_signatures_serializer: MultisigSignaturesSerializer::new(),
         }
     }
 }
@@ -781,6 +897,33 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.vec_u8_serializer.serialize(param, buffer)?;
             }
+            OperationType::BatchTransaction { transfers } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::BatchTransaction), buffer)?;
+                let transfers_len: u32 = transfers.len().try_into().map_err(|_| {
+                    SerializeError::NumberTooBig(
+                        "could not encode BatchTransaction transfers length as u32".into(),
+                    )
+                })?;
+                self.u32_serializer.serialize(&transfers_len, buffer)?;
+                for (recipient_address, amount) in transfers {
+                    self.address_serializer
+                        .serialize(recipient_address, buffer)?;
+                    self.amount_serializer.serialize(amount, buffer)?;
+                }
+            }
+            OperationType::Multisig {
+                policy,
+                signatures,
+                op,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::Multisig), buffer)?;
+                self.multisig_policy_serializer.serialize(policy, buffer)?;
+                self.serialize(op, buffer)?;
+                self.multisig_signatures_serializer
+                    .serialize(signatures, buffer)?;
+            }
         }
         Ok(())
     }
@@ -797,6 +940,9 @@ pub struct OperationTypeDeserializer {
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
     parameter_deserializer: VecU8Deserializer,
     datastore_deserializer: DatastoreDeserializer,
+    transfers_length_deserializer: U32VarIntDeserializer,
+    multisig_policy_deserializer: MultisigPolicyDeserializer,
+    multisig_signatures_deserializer: MultisigSignaturesDeserializer,
 }
 
 impl OperationTypeDeserializer {
@@ -835,6 +981,12 @@ impl OperationTypeDeserializer {
                 max_op_datastore_key_length,
                 max_op_datastore_value_length,
             ),
+            transfers_length_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_BATCH_TRANSACTION_TRANSFERS),
+            ),
+            multisig_policy_deserializer: MultisigPolicyDeserializer::new(),
+            multisig_signatures_deserializer: MultisigSignaturesDeserializer::new(),
         }
     }
 }
@@ -969,6 +1121,56 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::BatchTransaction => context(
+                    "Failed BatchTransaction deserialization",
+                    length_count(
+                        context("Failed transfers length deserialization", |input| {
+                            self.transfers_length_deserializer.deserialize(input)
+                        }),
+                        tuple((
+                            context("Failed recipient_address deserialization", |input| {
+                                self.address_deserializer.deserialize(input)
+                            }),
+                            context("Failed amount deserialization", |input| {
+                                self.amount_deserializer.deserialize(input)
+                            }),
+                        )),
+                    ),
+                )
+                .map(|transfers| OperationType::BatchTransaction { transfers })
+                .parse(input),
+                OperationTypeId::Multisig => {
+                    let (rest, (policy, op, signatures)) = context(
+                        "Failed Multisig deserialization",
+                        tuple((
+                            context("Failed policy deserialization", |input| {
+                                self.multisig_policy_deserializer.deserialize(input)
+                            }),
+                            context("Failed op deserialization", |input| self.deserialize(input)),
+                            context("Failed signatures deserialization", |input| {
+                                self.multisig_signatures_deserializer.deserialize(input)
+                            }),
+                        )),
+                    )
+                    .parse(input)?;
+                    // multisig operations can not be nested and must reach their threshold
+                    if matches!(op, OperationType::Multisig { .. })
+                        || policy.check_signature_indexes(&signatures).is_err()
+                    {
+                        return Err(nom::Err::Error(ParseError::from_error_kind(
+                            input,
+                            nom::error::ErrorKind::Verify,
+                        )));
+                    }
+                    Ok((
+                        rest,
+                        OperationType::Multisig {
+                            policy,
+                            signatures,
+                            op: Box::new(op),
+                        },
+                    ))
+                }
             }
         })
         .parse(buffer)
@@ -991,12 +1193,18 @@ impl SecureShareOperation {
     /// base_operation_gas_cost comes from the configuration and
     /// is the cost of a basic operation (BASE_OPERATION_GAS_COST)
     pub fn get_gas_usage(&self, base_operation_gas_cost: u64, sp_compilation_cost: u64) -> u64 {
-        match &self.content.op {
+        match self.content.op.get_emitted_op() {
             OperationType::ExecuteSC { max_gas, .. } => max_gas.saturating_add(sp_compilation_cost),
             OperationType::CallSC { max_gas, .. } => *max_gas,
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
             OperationType::Transaction { .. } => 0,
+            // every transfer of the batch costs as much as a basic operation
+            OperationType::BatchTransaction { transfers } => {
+                base_operation_gas_cost.saturating_mul(transfers.len().saturating_sub(1) as u64)
+            }
+            // never returned by `get_emitted_op`
+            OperationType::Multisig { .. } => 0,
         }
         .saturating_add(base_operation_gas_cost)
     }
@@ -1004,9 +1212,8 @@ impl SecureShareOperation {
     /// get the addresses that are involved in this operation from a ledger point of view
     pub fn get_ledger_involved_addresses(&self) -> PreHashSet<Address> {
         let mut res = PreHashSet::<Address>::default();
-        let emitter_address = Address::from_public_key(&self.content_creator_pub_key);
-        res.insert(emitter_address);
-        match &self.content.op {
+        res.insert(self.content_creator_address);
+        match self.content.op.get_emitted_op() {
             OperationType::Transaction {
                 recipient_address, ..
             } => {
@@ -1018,6 +1225,14 @@ impl SecureShareOperation {
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
             }
+            OperationType::BatchTransaction { transfers } => {
+                res.extend(
+                    transfers
+                        .iter()
+                        .map(|(recipient_address, _)| *recipient_address),
+                );
+            }
+            OperationType::Multisig { .. } => {}
         }
         res
     }
@@ -1025,12 +1240,18 @@ impl SecureShareOperation {
     /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
     pub fn get_max_spending(&self, roll_price: Amount) -> Amount {
         // compute the max amount of coins spent outside of the fees
-        let max_non_fee_seq_spending = match &self.content.op {
+        let max_non_fee_seq_spending = match self.content.op.get_emitted_op() {
             OperationType::Transaction { amount, .. } => *amount,
             OperationType::RollBuy { roll_count } => roll_price.saturating_mul_u64(*roll_count),
             OperationType::RollSell { .. } => Amount::zero(),
             OperationType::ExecuteSC { max_coins, .. } => *max_coins,
             OperationType::CallSC { coins, .. } => *coins,
+            OperationType::BatchTransaction { transfers } => {
+                transfers.iter().fold(Amount::zero(), |total, (_, amount)| {
+                    total.saturating_add(*amount)
+                })
+            }
+            OperationType::Multisig { .. } => Amount::zero(),
         };
 
         // add all fees and return
@@ -1040,16 +1261,18 @@ impl SecureShareOperation {
     /// get the addresses that are involved in this operation from a rolls point of view
     pub fn get_roll_involved_addresses(&self) -> Result<PreHashSet<Address>, ModelsError> {
         let mut res = PreHashSet::<Address>::default();
-        match self.content.op {
+        match self.content.op.get_emitted_op() {
             OperationType::Transaction { .. } => {}
             OperationType::RollBuy { .. } => {
-                res.insert(Address::from_public_key(&self.content_creator_pub_key));
+                res.insert(self.content_creator_address);
             }
             OperationType::RollSell { .. } => {
-                res.insert(Address::from_public_key(&self.content_creator_pub_key));
+                res.insert(self.content_creator_address);
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            OperationType::BatchTransaction { .. } => {}
+            OperationType::Multisig { .. } => {}
         }
         Ok(res)
     }
@@ -1909,4 +2132,185 @@ mod tests {
         assert_eq!(orig_operation.fee.to_string(), res_operation["fee"]);
         assert_eq!(orig_operation.expire_period, res_operation["expire_period"]);
     }
+
+    #[test]
+    #[serial]
+    fn test_batch_transaction() {
+        let sender_keypair = KeyPair::generate(0).unwrap();
+        let recv_address_1 =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let recv_address_2 =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        let op = OperationType::BatchTransaction {
+            transfers: vec![
+                (recv_address_1, Amount::from_str("1.5").unwrap()),
+                (recv_address_2, Amount::from_str("2").unwrap()),
+            ],
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op, &mut ser_type)
+            .unwrap();
+        let op_type_deserializer = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        );
+        let (rest, res_type) = op_type_deserializer
+            .deserialize::<DeserializeError>(&ser_type)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(res_type, op);
+
+        let content = Operation {
+            fee: Amount::from_str("20").unwrap(),
+            op,
+            expire_period: 50,
+        };
+        let op = Operation::new_verifiable(
+            content,
+            OperationSerializer::new(),
+            &sender_keypair,
+            *CHAINID,
+        )
+        .unwrap();
+        assert_eq!(op.get_gas_usage(10, 1000), 20);
+        assert_eq!(
+            op.get_max_spending(Amount::from_str("100").unwrap()),
+            Amount::from_str("23.5").unwrap()
+        );
+        assert_eq!(
+            op.get_ledger_involved_addresses(),
+            PreHashSet::from_iter([op.content_creator_address, recv_address_1, recv_address_2])
+        );
+
+        // empty batches and batches above the limit are rejected
+        for transfers_count in [0, MAX_BATCH_TRANSACTION_TRANSFERS as usize + 1] {
+            let op = OperationType::BatchTransaction {
+                transfers: vec![(recv_address_1, Amount::from_str("1").unwrap()); transfers_count],
+            };
+            let mut ser_type = Vec::new();
+            OperationTypeSerializer::new()
+                .serialize(&op, &mut ser_type)
+                .unwrap();
+            assert!(op_type_deserializer
+                .deserialize::<DeserializeError>(&ser_type)
+                .is_err());
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_multisig() {
+        let cosigners: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let policy =
+            MultisigPolicy::new(cosigners.iter().map(|kp| kp.get_public_key()).collect(), 2)
+                .unwrap();
+        let recipient_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+        // draft without signatures, its ID is signed offline by the cosigners
+        let mut content = Operation {
+            fee: Amount::from_str("1").unwrap(),
+            expire_period: 50,
+            op: OperationType::Multisig {
+                policy: policy.clone(),
+                signatures: vec![],
+                op: Box::new(OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str("10").unwrap(),
+                }),
+            },
+        };
+        let multisig_id = content.compute_multisig_id(*CHAINID).unwrap();
+        if let OperationType::Multisig { signatures, .. } = &mut content.op {
+            signatures.push((0, cosigners[0].sign(multisig_id.get_hash()).unwrap()));
+            signatures.push((2, cosigners[2].sign(multisig_id.get_hash()).unwrap()));
+        }
+
+        // submitted by any cosigner on behalf of the multisig address
+        let op: SecureShareOperation = Operation::new_verifiable(
+            content.clone(),
+            OperationSerializer::new(),
+            &cosigners[1],
+            *CHAINID,
+        )
+        .unwrap();
+        assert_eq!(op.id, multisig_id);
+        assert_eq!(op.content_creator_address, policy.address());
+        op.verify_signature().unwrap();
+        assert_eq!(
+            op.get_ledger_involved_addresses(),
+            PreHashSet::from_iter([policy.address(), recipient_address])
+        );
+
+        let mut ser_op = Vec::new();
+        SecureShareSerializer::new()
+            .serialize(&op, &mut ser_op)
+            .unwrap();
+        let op_deserializer = SecureShareDeserializer::new(
+            OperationDeserializer::new(
+                MAX_DATASTORE_VALUE_LENGTH,
+                MAX_FUNCTION_NAME_LENGTH,
+                MAX_PARAMETERS_SIZE,
+                MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+                MAX_OPERATION_DATASTORE_KEY_LENGTH,
+                MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+            ),
+            *CHAINID,
+        );
+        let (_, res_op): (&[u8], SecureShareOperation) = op_deserializer
+            .deserialize::<DeserializeError>(&ser_op)
+            .unwrap();
+        assert_eq!(res_op, op);
+
+        // a signature by the wrong cosigner is rejected
+        let mut forged_content = content.clone();
+        if let OperationType::Multisig { signatures, .. } = &mut forged_content.op {
+            signatures[1] = (1, cosigners[2].sign(multisig_id.get_hash()).unwrap());
+        }
+        let forged_op: SecureShareOperation = Operation::new_verifiable(
+            forged_content,
+            OperationSerializer::new(),
+            &cosigners[1],
+            *CHAINID,
+        )
+        .unwrap();
+        assert!(forged_op.verify_signature().is_err());
+
+        // only the cosigners can submit the operation
+        let outsider_op: SecureShareOperation = Operation::new_verifiable(
+            content.clone(),
+            OperationSerializer::new(),
+            &KeyPair::generate(0).unwrap(),
+            *CHAINID,
+        )
+        .unwrap();
+        assert_eq!(outsider_op.id, multisig_id);
+        assert!(outsider_op.verify_signature().is_err());
+
+        // operations below the threshold can not be deserialized
+        let mut partial_content = content;
+        if let OperationType::Multisig { signatures, .. } = &mut partial_content.op {
+            signatures.truncate(1);
+        }
+        let partial_op: SecureShareOperation = Operation::new_verifiable(
+            partial_content,
+            OperationSerializer::new(),
+            &cosigners[1],
+            *CHAINID,
+        )
+        .unwrap();
+        let mut ser_op = Vec::new();
+        SecureShareSerializer::new()
+            .serialize(&partial_op, &mut ser_op)
+            .unwrap();
+        assert!(op_deserializer
+            .deserialize::<DeserializeError>(&ser_op)
+            .is_err());
+    }
 }
@@ -0,0 +1,446 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Envelopes carrying an operation between the machines that build, sign and broadcast it,
+//! so that the signing machine never has to reach the network.
+//!
+//! Envelope bytes (varints are unsigned LEB128):
+//! - version: u32 varint, currently 0
+//! - kind: u32 varint, 0 for an unsigned operation, 1 for a signed one
+//! - chain ID: u64 varint, chain on which the operation is valid
+//! - unsigned operation: address of the expected signer (`AddressSerializer`)
+//!   followed by the operation content (`OperationSerializer`)
+//! - signed operation: signature, public key of the signer and operation content,
+//!   as sent to the network (`SecureShareSerializer`)
+//!
+//! As text, an envelope is either `OP` followed by the base58check encoding of its bytes,
+//! or `0x` followed by their hexadecimal encoding.
+
+use crate::address::{Address, AddressDeserializer, AddressSerializer};
+use crate::config::{
+    MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+};
+use crate::error::ModelsError;
+use crate::operation::{
+    Operation, OperationDeserializer, OperationSerializer, OperationType, SecureShareOperation,
+};
+use crate::secure_share::{SecureShareContent, SecureShareDeserializer, SecureShareSerializer};
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U32VarIntDeserializer,
+    U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use massa_signature::KeyPair;
+use nom::error::{context, ContextError, ParseError};
+use nom::IResult;
+use std::ops::Bound::Included;
+use std::str::FromStr;
+
+/// Prefix of the base58check text form of an envelope
+const OPERATION_ENVELOPE_PREFIX: &str = "OP";
+/// Prefix of the hexadecimal text form of an envelope
+const OPERATION_ENVELOPE_HEX_PREFIX: &str = "0x";
+/// Version of the envelope format
+const OPERATION_ENVELOPE_VERSION: u32 = 0;
+const UNSIGNED_KIND: u32 = 0;
+const SIGNED_KIND: u32 = 1;
+
+/// Operation travelling between the machines that build, sign and broadcast it
+#[derive(Debug, Clone)]
+pub enum OperationEnvelope {
+    /// Operation waiting to be signed
+    Unsigned {
+        /// chain on which the operation is valid
+        chain_id: u64,
+        /// address whose key is expected to sign the operation
+        signer_address: Address,
+        /// operation content
+        operation: Operation,
+    },
+    /// Operation ready to be broadcast
+    Signed {
+        /// chain on which the operation is valid
+        chain_id: u64,
+        /// signed operation
+        operation: SecureShareOperation,
+    },
+}
+
+impl OperationEnvelope {
+    /// Chain on which the operation is valid
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            OperationEnvelope::Unsigned { chain_id, .. }
+            | OperationEnvelope::Signed { chain_id, .. } => *chain_id,
+        }
+    }
+
+    /// Sign an unsigned operation with the key of its expected signer.
+    /// The expected signer of a multisig operation is the multisig address,
+    /// in which case the operation is signed by the key of one of its cosigners.
+    pub fn sign(self, keypair: &KeyPair) -> Result<OperationEnvelope, ModelsError> {
+        let OperationEnvelope::Unsigned {
+            chain_id,
+            signer_address,
+            operation,
+        } = self
+        else {
+            return Err(ModelsError::InvalidOperationEnvelope(
+                "the operation is already signed".to_string(),
+            ));
+        };
+        let public_key = keypair.get_public_key();
+        let is_expected_signer = match &operation.op {
+            OperationType::Multisig { policy, .. } => {
+                signer_address == policy.address() && policy.get_index(&public_key).is_some()
+            }
+            _ => Address::from_public_key(&public_key) == signer_address,
+        };
+        if !is_expected_signer {
+            return Err(ModelsError::InvalidOperationEnvelope(format!(
+                "the operation must be signed by {}",
+                signer_address
+            )));
+        }
+        Ok(OperationEnvelope::Signed {
+            chain_id,
+            operation: Operation::new_verifiable(
+                operation,
+                OperationSerializer::new(),
+                keypair,
+                chain_id,
+            )?,
+        })
+    }
+
+    /// Base58check text form of the envelope
+    pub fn to_bs58_check(&self) -> Result<String, ModelsError> {
+        Ok(format!(
+            "{}{}",
+            OPERATION_ENVELOPE_PREFIX,
+            bs58::encode(self.to_bytes()?).with_check().into_string()
+        ))
+    }
+
+    /// Hexadecimal text form of the envelope
+    pub fn to_hex(&self) -> Result<String, ModelsError> {
+        let mut res = OPERATION_ENVELOPE_HEX_PREFIX.to_string();
+        for byte in self.to_bytes()? {
+            res.push_str(&format!("{:02x}", byte));
+        }
+        Ok(res)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, ModelsError> {
+        let mut buffer = Vec::new();
+        OperationEnvelopeSerializer::new().serialize(self, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ModelsError> {
+        let (rest, envelope) = OperationEnvelopeDeserializer::new()
+            .deserialize::<DeserializeError>(bytes)
+            .map_err(|err| ModelsError::InvalidOperationEnvelope(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(ModelsError::InvalidOperationEnvelope(
+                "trailing bytes after the operation".to_string(),
+            ));
+        }
+        Ok(envelope)
+    }
+}
+
+impl FromStr for OperationEnvelope {
+    type Err = ModelsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bytes = if let Some(hex) = s.strip_prefix(OPERATION_ENVELOPE_HEX_PREFIX) {
+            if hex.len() % 2 != 0 || !hex.is_ascii() {
+                return Err(ModelsError::InvalidOperationEnvelope(
+                    "invalid hexadecimal string".to_string(),
+                ));
+            }
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|err| ModelsError::InvalidOperationEnvelope(err.to_string()))?
+        } else if let Some(bs58) = s.strip_prefix(OPERATION_ENVELOPE_PREFIX) {
+            bs58::decode(bs58)
+                .with_check(None)
+                .into_vec()
+                .map_err(|err| ModelsError::InvalidOperationEnvelope(err.to_string()))?
+        } else {
+            return Err(ModelsError::WrongPrefix(
+                OPERATION_ENVELOPE_PREFIX.to_string(),
+                s.chars().take(2).collect(),
+            ));
+        };
+        OperationEnvelope::from_bytes(&bytes)
+    }
+}
+
+/// Serializer for `OperationEnvelope`
+#[derive(Default)]
+pub struct OperationEnvelopeSerializer {
+    u32_serializer: U32VarIntSerializer,
+    u64_serializer: U64VarIntSerializer,
+    address_serializer: AddressSerializer,
+    operation_serializer: OperationSerializer,
+    secure_share_serializer: SecureShareSerializer,
+}
+
+impl OperationEnvelopeSerializer {
+    /// Creates a new `OperationEnvelopeSerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+            address_serializer: AddressSerializer::new(),
+            operation_serializer: OperationSerializer::new(),
+            secure_share_serializer: SecureShareSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<OperationEnvelope> for OperationEnvelopeSerializer {
+    fn serialize(
+        &self,
+        value: &OperationEnvelope,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u32_serializer
+            .serialize(&OPERATION_ENVELOPE_VERSION, buffer)?;
+        match value {
+            OperationEnvelope::Unsigned {
+                chain_id,
+                signer_address,
+                operation,
+            } => {
+                self.u32_serializer.serialize(&UNSIGNED_KIND, buffer)?;
+                self.u64_serializer.serialize(chain_id, buffer)?;
+                self.address_serializer.serialize(signer_address, buffer)?;
+                self.operation_serializer.serialize(operation, buffer)?;
+            }
+            OperationEnvelope::Signed {
+                chain_id,
+                operation,
+            } => {
+                self.u32_serializer.serialize(&SIGNED_KIND, buffer)?;
+                self.u64_serializer.serialize(chain_id, buffer)?;
+                self.secure_share_serializer.serialize(operation, buffer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `OperationEnvelope`
+pub struct OperationEnvelopeDeserializer {
+    version_deserializer: U32VarIntDeserializer,
+    kind_deserializer: U32VarIntDeserializer,
+    chain_id_deserializer: U64VarIntDeserializer,
+    address_deserializer: AddressDeserializer,
+}
+
+impl OperationEnvelopeDeserializer {
+    /// Creates a new `OperationEnvelopeDeserializer`
+    pub fn new() -> Self {
+        Self {
+            version_deserializer: U32VarIntDeserializer::new(
+                Included(OPERATION_ENVELOPE_VERSION),
+                Included(OPERATION_ENVELOPE_VERSION),
+            ),
+            kind_deserializer: U32VarIntDeserializer::new(
+                Included(UNSIGNED_KIND),
+                Included(SIGNED_KIND),
+            ),
+            chain_id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            address_deserializer: AddressDeserializer::new(),
+        }
+    }
+
+    fn operation_deserializer() -> OperationDeserializer {
+        OperationDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+    }
+}
+
+impl Default for OperationEnvelopeDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<OperationEnvelope> for OperationEnvelopeDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], OperationEnvelope, E> {
+        let (rest, _version) = context("Failed version deserialization", |input| {
+            self.version_deserializer.deserialize(input)
+        })(buffer)?;
+        let (rest, kind) = context("Failed kind deserialization", |input| {
+            self.kind_deserializer.deserialize(input)
+        })(rest)?;
+        let (rest, chain_id) = context("Failed chain_id deserialization", |input| {
+            self.chain_id_deserializer.deserialize(input)
+        })(rest)?;
+        match kind {
+            UNSIGNED_KIND => {
+                let (rest, signer_address) =
+                    context("Failed signer_address deserialization", |input| {
+                        self.address_deserializer.deserialize(input)
+                    })(rest)?;
+                let (rest, operation) = context("Failed operation deserialization", |input| {
+                    Self::operation_deserializer().deserialize(input)
+                })(rest)?;
+                Ok((
+                    rest,
+                    OperationEnvelope::Unsigned {
+                        chain_id,
+                        signer_address,
+                        operation,
+                    },
+                ))
+            }
+            SIGNED_KIND => {
+                // the operation ID depends on the chain it was signed for
+                let (rest, operation) =
+                    context("Failed signed operation deserialization", |input| {
+                        SecureShareDeserializer::new(Self::operation_deserializer(), chain_id)
+                            .deserialize(input)
+                    })(rest)?;
+                Ok((
+                    rest,
+                    OperationEnvelope::Signed {
+                        chain_id,
+                        operation,
+                    },
+                ))
+            }
+            _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Fail,
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+    use crate::multisig::MultisigPolicy;
+
+    #[test]
+    fn test_operation_envelope() {
+        let sender_keypair = KeyPair::generate(0).unwrap();
+        let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+        let recv_keypair = KeyPair::generate(0).unwrap();
+        let chain_id = 77;
+
+        let unsigned = OperationEnvelope::Unsigned {
+            chain_id,
+            signer_address: sender_address,
+            operation: Operation {
+                fee: Amount::from_str("0.01").unwrap(),
+                expire_period: 50,
+                op: OperationType::Transaction {
+                    recipient_address: Address::from_public_key(&recv_keypair.get_public_key()),
+                    amount: Amount::from_str("300").unwrap(),
+                },
+            },
+        };
+
+        // both text forms carry the same envelope
+        for text in [
+            unsigned.to_bs58_check().unwrap(),
+            unsigned.to_hex().unwrap(),
+        ] {
+            let OperationEnvelope::Unsigned {
+                chain_id: res_chain_id,
+                signer_address,
+                operation,
+            } = OperationEnvelope::from_str(&text).unwrap()
+            else {
+                panic!("expected an unsigned operation");
+            };
+            assert_eq!(res_chain_id, chain_id);
+            assert_eq!(signer_address, sender_address);
+            assert_eq!(operation.expire_period, 50);
+        }
+
+        // only the expected signer can sign
+        assert!(unsigned.clone().sign(&recv_keypair).is_err());
+        let signed = unsigned.sign(&sender_keypair).unwrap();
+        assert!(signed.clone().sign(&sender_keypair).is_err());
+        let OperationEnvelope::Signed { operation, .. } = &signed else {
+            panic!("expected a signed operation");
+        };
+
+        let OperationEnvelope::Signed {
+            chain_id: res_chain_id,
+            operation: res_operation,
+        } = OperationEnvelope::from_str(&signed.to_bs58_check().unwrap()).unwrap()
+        else {
+            panic!("expected a signed operation");
+        };
+        assert_eq!(res_chain_id, chain_id);
+        assert_eq!(res_operation.id, operation.id);
+        assert_eq!(res_operation.content_creator_address, sender_address);
+        res_operation.verify_signature().unwrap();
+
+        // corrupted or foreign strings are rejected
+        let text = signed.to_bs58_check().unwrap();
+        assert!(OperationEnvelope::from_str(&text[..text.len() - 1]).is_err());
+        assert!(OperationEnvelope::from_str(&sender_address.to_string()).is_err());
+        assert!(OperationEnvelope::from_str("0x0").is_err());
+    }
+
+    #[test]
+    fn test_operation_envelope_multisig() {
+        let cosigners: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate(0).unwrap()).collect();
+        let outsider = KeyPair::generate(0).unwrap();
+        let policy = MultisigPolicy::new(
+            cosigners.iter().map(|key| key.get_public_key()).collect(),
+            2,
+        )
+        .unwrap();
+        let operation = Operation {
+            fee: Amount::from_str("0.01").unwrap(),
+            expire_period: 50,
+            op: OperationType::Multisig {
+                policy: policy.clone(),
+                signatures: Vec::new(),
+                op: Box::new(OperationType::RollBuy { roll_count: 1 }),
+            },
+        };
+
+        // the key address of a cosigner is not the emitter of a multisig operation
+        let by_cosigner_address = OperationEnvelope::Unsigned {
+            chain_id: 77,
+            signer_address: Address::from_public_key(&cosigners[0].get_public_key()),
+            operation: operation.clone(),
+        };
+        assert!(by_cosigner_address.sign(&cosigners[0]).is_err());
+
+        let unsigned = OperationEnvelope::Unsigned {
+            chain_id: 77,
+            signer_address: policy.address(),
+            operation,
+        };
+        assert!(unsigned.clone().sign(&outsider).is_err());
+        let OperationEnvelope::Signed { operation, .. } = unsigned.sign(&cosigners[1]).unwrap()
+        else {
+            panic!("expected a signed operation");
+        };
+        assert_eq!(operation.content_creator_address, policy.address());
+    }
+}
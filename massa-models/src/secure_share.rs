@@ -86,7 +86,7 @@ where
         content_serializer.serialize(&self, &mut content_serialized)?;
         let public_key = keypair.get_public_key();
         let hash = Self::compute_hash(&self, &content_serialized, &public_key, chain_id);
-        let creator_address = Address::from_public_key(&public_key);
+        let creator_address = self.compute_creator_address(&public_key);
         Ok(SecureShare {
             signature: self.sign(keypair, &hash)?,
             content_creator_pub_key: public_key,
@@ -110,6 +110,11 @@ where
         Hash::compute_from(&hash_data)
     }
 
+    /// Compute the address on behalf of which the content was created
+    fn compute_creator_address(&self, creator_public_key: &PublicKey) -> Address {
+        Address::from_public_key(creator_public_key)
+    }
+
     /// Compute hash used for signature
     fn compute_signed_hash(&self, _public_key: &PublicKey, content_hash: &Hash) -> Hash {
         *content_hash
@@ -169,7 +174,7 @@ where
             // Avoid getting the rest of the data in the serialized data
             serialized_data[..serialized_data.len() - rest.len()].to_vec()
         };
-        let creator_address = Address::from_public_key(&creator_public_key);
+        let creator_address = content.compute_creator_address(&creator_public_key);
         let hash = Self::compute_hash(&content, &content_serialized, &creator_public_key, chain_id);
 
         Ok((
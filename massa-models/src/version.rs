@@ -193,6 +193,11 @@ impl Version {
     pub fn is_compatible(&self, other: &Version) -> bool {
         self.instance == other.instance && self.major == other.major
     }
+
+    /// minor version, the only part that may differ between compatible versions
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
 }
 
 impl fmt::Display for Version {
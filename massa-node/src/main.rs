@@ -7,6 +7,7 @@ extern crate massa_logging;
 
 #[cfg(feature = "op_spammer")]
 use crate::operation_injector::start_operation_injector;
+use crate::reload::{read_node_config, NodeConfigReloader};
 use crate::settings::SETTINGS;
 use crate::survey::MassaSurvey;
 
@@ -16,24 +17,28 @@ use crossbeam_channel::TryRecvError;
 use dialoguer::Password;
 use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
 use massa_api_exports::config::APIConfig;
+use massa_api_exports::rate_limit::RateLimiter;
+use massa_api_exports::reload::ConfigReloader;
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
-    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
-    DefaultConnector,
+    get_state, resync_state, start_bootstrap_server, BootstrapConfig, BootstrapManager,
+    BootstrapTcpListener, DefaultConnector,
 };
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::events::ConsensusEvent;
 use massa_consensus_exports::{
-    ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusManager,
+    bootstrapable_graph::BootstrapableGraph, ConsensusBroadcasts, ConsensusChannels,
+    ConsensusConfig, ConsensusController, ConsensusManager,
 };
 use massa_consensus_worker::start_consensus_worker;
 use massa_db_exports::{MassaDBConfig, MassaDBController};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
-    ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
+    ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionManager, GasCosts,
+    StorageCostsConstants,
 };
 use massa_execution_worker::start_execution_worker;
 #[cfg(all(
@@ -44,6 +49,8 @@ use massa_execution_worker::start_execution_worker;
 use massa_execution_worker::storage_backend::FileStorageBackend;
 #[cfg(all(feature = "dump-block", feature = "db_storage_backend"))]
 use massa_execution_worker::storage_backend::RocksDBStorageBackend;
+#[cfg(feature = "dump-block")]
+use massa_execution_worker::storage_backend::StorageBackend;
 
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
 use massa_factory_worker::start_factory;
@@ -53,7 +60,8 @@ use massa_grpc::server::{MassaPrivateGrpc, MassaPublicGrpc};
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
-use massa_metrics::{MassaMetrics, MetricsStopper};
+use massa_logging::subscriber::{init_subscriber, LogFilterHandle, LoggingConfig};
+use massa_metrics::{health::HealthConfig, MassaMetrics, MetricsStopper};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::config::constants::{
@@ -108,9 +116,9 @@ use massa_wallet::Wallet;
 use num::rational::Ratio;
 use parking_lot::RwLock;
 use settings::GrpcSettings;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
 use std::time::Duration;
 use std::{path::Path, process, sync::Arc};
@@ -118,10 +126,12 @@ use std::{path::Path, process, sync::Arc};
 use survey::MassaSurveyStopper;
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
+mod reload;
+#[cfg(feature = "replay")]
+mod replay;
 mod settings;
 mod survey;
 
@@ -129,6 +139,8 @@ async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
+    log_filter: LogFilterHandle,
+    config_keys: BTreeMap<String, String>,
 ) -> (
     MassaReceiver<ConsensusEvent>,
     Option<BootstrapManager>,
@@ -145,6 +157,8 @@ async fn launch(
     Option<massa_grpc::server::StopHandle>,
     MetricsStopper,
     MassaSurveyStopper,
+    ResyncContext,
+    Arc<NodeConfigReloader>,
 ) {
     let now = MassaTime::now();
 
@@ -158,56 +172,7 @@ async fn launch(
     let shared_storage: Storage = Storage::create_root();
 
     // init final state
-    let ledger_config = LedgerConfig {
-        thread_count: THREAD_COUNT,
-        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
-        max_key_length: MAX_DATASTORE_KEY_LENGTH,
-        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
-    };
-    let async_pool_config = AsyncPoolConfig {
-        max_length: MAX_ASYNC_POOL_LENGTH,
-        thread_count: THREAD_COUNT,
-        max_function_length: MAX_FUNCTION_NAME_LENGTH,
-        max_function_params_length: MAX_PARAMETERS_SIZE as u64,
-        max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
-    };
-    let pos_config = PoSConfig {
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        thread_count: THREAD_COUNT,
-        cycle_history_length: POS_SAVED_CYCLES,
-        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
-        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
-        max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-        initial_deferred_credits_path: SETTINGS.ledger.initial_deferred_credits_path.clone(),
-    };
-    let executed_ops_config = ExecutedOpsConfig {
-        thread_count: THREAD_COUNT,
-        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
-    };
-    let executed_denunciations_config = ExecutedDenunciationsConfig {
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
-    };
-    let final_state_config = FinalStateConfig {
-        ledger_config: ledger_config.clone(),
-        async_pool_config,
-        pos_config,
-        executed_ops_config,
-        executed_denunciations_config,
-        final_history_length: SETTINGS.ledger.final_history_length,
-        thread_count: THREAD_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        initial_seed_string: INITIAL_DRAW_SEED.into(),
-        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
-        endorsement_count: ENDORSEMENT_COUNT,
-        max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        ledger_backup_periods_interval: SETTINGS.ledger.ledger_backup_periods_interval,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-    };
+    let final_state_config = final_state_config();
 
     // Start massa metrics
     let (massa_metrics, metrics_stopper) = MassaMetrics::new(
@@ -216,6 +181,13 @@ async fn launch(
         THREAD_COUNT,
         SETTINGS.metrics.tick_delay.to_duration(),
     );
+    massa_metrics.health().set_config(HealthConfig {
+        thread_count: THREAD_COUNT,
+        max_final_slot_lag: SETTINGS.metrics.health_max_final_slot_lag,
+        max_candidate_slot_lag: SETTINGS.metrics.health_max_candidate_slot_lag,
+        min_connected_peers: SETTINGS.metrics.health_min_connected_peers,
+        need_sync_grace_period: SETTINGS.metrics.health_need_sync_grace_period.to_duration(),
+    });
 
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
@@ -232,40 +204,20 @@ async fn launch(
         }
     }
 
-    let db_config = MassaDBConfig {
-        path: SETTINGS.ledger.disk_ledger_path.clone(),
-        max_history_length: SETTINGS.ledger.final_history_length,
-        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
-        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into().unwrap(),
-        thread_count: THREAD_COUNT,
-        max_ledger_backups: SETTINGS.ledger.max_ledger_backups,
-    };
+    let db_config = db_config();
     let db = Arc::new(RwLock::new(
         Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
     ));
 
     // Create final ledger
-    let ledger = FinalLedger::new(ledger_config.clone(), db.clone());
+    let ledger = FinalLedger::new(final_state_config.ledger_config.clone(), db.clone());
 
     // launch selector worker
-    let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
-        max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
-        channel_size: CHANNEL_SIZE,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
-    })
-    .expect("could not start selector worker");
+    let (selector_manager, selector_controller) =
+        start_selector_worker(selector_config()).expect("could not start selector worker");
 
     // Creates an empty default store
-    let mip_stats_config = MipStatsConfig {
-        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-        warn_announced_version_ratio: Ratio::new(
-            u64::from(SETTINGS.versioning.mip_stats_warn_announced_version),
-            100,
-        ),
-    };
+    let mip_stats_config = mip_stats_config();
     // Ratio::new_raw(*SETTINGS.versioning.warn_announced_version_ratio, 100),
 
     // Create final state, either from a snapshot, or from scratch
@@ -339,6 +291,10 @@ async fn launch(
         per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
         ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
         rate_limit: SETTINGS.bootstrap.rate_limit,
+        priority_reserved_slots: SETTINGS.bootstrap.priority_reserved_slots,
+        max_bootstrap_queue_length: SETTINGS.bootstrap.max_bootstrap_queue_length,
+        keypair_file: SETTINGS.protocol.keypair_file.clone(),
+        parallel_bootstrap_servers: SETTINGS.bootstrap.parallel_bootstrap_servers,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -395,6 +351,7 @@ async fn launch(
         // TODO: Bootstrap again instead of panicking
         panic!("critical: db is not valid after bootstrap");
     }
+    massa_metrics.health().set_bootstrapped(true);
 
     if args.restart_from_snapshot_at_period.is_none() {
         final_state.write().recompute_caches();
@@ -445,15 +402,6 @@ async fn launch(
         }
     }
 
-    // Storage costs constants
-    let storage_costs_constants = StorageCostsConstants {
-        ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
-        ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
-        ledger_entry_datastore_base_cost: LEDGER_COST_PER_BYTE
-            .checked_mul_u64(LEDGER_ENTRY_DATASTORE_BASE_SIZE as u64)
-            .expect("Overflow when creating constant ledger_entry_datastore_base_size"),
-    };
-
     // gas costs
     let gas_costs = GasCosts::new(
         SETTINGS.execution.abi_gas_costs_file.clone(),
@@ -470,55 +418,11 @@ async fn launch(
     }
 
     // launch execution module
-    let execution_config = ExecutionConfig {
-        max_final_events: SETTINGS.execution.max_final_events,
-        readonly_queue_length: SETTINGS.execution.readonly_queue_length,
-        cursor_delay: SETTINGS.execution.cursor_delay,
-        max_async_gas: MAX_ASYNC_GAS,
-        async_msg_cst_gas_cost: ASYNC_MSG_CST_GAS_COST,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
-        roll_price: ROLL_PRICE,
-        thread_count: THREAD_COUNT,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        block_reward: BLOCK_REWARD,
-        endorsement_count: ENDORSEMENT_COUNT as u64,
-        operation_validity_period: OPERATION_VALIDITY_PERIODS,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
-        max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
-        max_bytecode_size: MAX_BYTECODE_LENGTH,
-        max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
-        storage_costs_constants,
-        max_read_only_gas: SETTINGS.execution.max_read_only_gas,
-        gas_costs: gas_costs.clone(),
-        base_operation_gas_cost: BASE_OPERATION_GAS_COST,
-        last_start_period: final_state.read().get_last_start_period(),
-        hd_cache_path: SETTINGS.execution.hd_cache_path.clone(),
-        lru_cache_size: SETTINGS.execution.lru_cache_size,
-        hd_cache_size: SETTINGS.execution.hd_cache_size,
-        snip_amount: SETTINGS.execution.snip_amount,
-        roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        broadcast_enabled: SETTINGS.api.enable_broadcast,
-        broadcast_slot_execution_output_channel_capacity: SETTINGS
-            .execution
-            .broadcast_slot_execution_output_channel_capacity,
-        max_event_size: MAX_EVENT_DATA_SIZE,
-        max_function_length: MAX_FUNCTION_NAME_LENGTH,
-        max_parameter_length: MAX_PARAMETERS_SIZE,
-        chain_id: *CHAINID,
-        #[cfg(feature = "execution-trace")]
-        broadcast_traces_enabled: true,
-        #[cfg(not(feature = "execution-trace"))]
-        broadcast_traces_enabled: false,
-        broadcast_slot_execution_traces_channel_capacity: SETTINGS
-            .execution
-            .broadcast_slot_execution_traces_channel_capacity,
-        max_execution_traces_slot_limit: SETTINGS.execution.execution_traces_limit,
+    let execution_config = execution_config(
+        final_state.read().get_last_start_period(),
+        gas_costs.clone(),
         block_dump_folder_path,
-    };
+    );
 
     let execution_channels = ExecutionChannels {
         slot_execution_output_sender: broadcast::channel(
@@ -532,21 +436,9 @@ async fn launch(
         .0,
     };
 
-    cfg_if! {
-        if #[cfg(all(feature = "dump-block", feature = "db_storage_backend"))] {
-            let block_storage_backend = Arc::new(RwLock::new(
-                RocksDBStorageBackend::new(
-                execution_config.block_dump_folder_path.clone(), SETTINGS.block_dump.max_blocks),
-            ));
-        } else if #[cfg(all(feature = "dump-block", feature = "file_storage_backend"))] {
-            let block_storage_backend = Arc::new(RwLock::new(
-                FileStorageBackend::new(
-                execution_config.block_dump_folder_path.clone(), SETTINGS.block_dump.max_blocks),
-            ));
-        } else if #[cfg(feature = "dump-block")] {
-            compile_error!("feature dump-block requise either db_storage_backend or file_storage_backend");
-        }
-    }
+    #[cfg(feature = "dump-block")]
+    let block_storage_backend =
+        block_storage_backend(execution_config.block_dump_folder_path.clone());
 
     let (execution_manager, execution_controller) = start_execution_worker(
         execution_config,
@@ -604,8 +496,12 @@ async fn launch(
         },
         selector: selector_controller.clone(),
         execution_controller: execution_controller.clone(),
+        mip_store: mip_store.clone(),
     };
 
+    // read by the APIs, updated along with the pool on a configuration reload
+    let minimal_fees = Arc::new(RwLock::new(pool_config.minimal_fees));
+
     let (pool_manager, pool_controller) = start_pool_controller(
         pool_config,
         &shared_storage,
@@ -653,6 +549,8 @@ async fn launch(
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
         max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+        peers_whitelist_path: SETTINGS.protocol.peers_whitelist_path.clone(),
+        peers_blacklist_path: SETTINGS.protocol.peers_blacklist_path.clone(),
         listeners,
         keypair_file: SETTINGS.protocol.keypair_file.clone(),
         max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
@@ -739,6 +637,9 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_block_status_channel_capacity: SETTINGS
+            .consensus
+            .broadcast_block_status_channel_capacity,
         last_start_period: final_state.read().get_last_start_period(),
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
@@ -764,6 +665,10 @@ async fn launch(
                 consensus_config.broadcast_filled_blocks_channel_capacity,
             )
             .0,
+            block_status_sender: broadcast::channel(
+                consensus_config.broadcast_block_status_channel_capacity,
+            )
+            .0,
         },
     };
 
@@ -819,6 +724,14 @@ async fn launch(
         mip_store.clone(),
     );
 
+    let resync_context = ResyncContext {
+        bootstrap_config: bootstrap_config.clone(),
+        final_state: final_state.clone(),
+        consensus_controller: consensus_controller.clone(),
+        execution_controller: execution_controller.clone(),
+        massa_metrics: massa_metrics.clone(),
+    };
+
     let bootstrap_manager = bootstrap_config.listen_addr.map(|addr| {
         let (listener_stopper, listener) =
             BootstrapTcpListener::create(&addr).unwrap_or_else(|_| {
@@ -878,9 +791,17 @@ async fn launch(
         last_start_period: final_state.read().get_last_start_period(),
         chain_id: *CHAINID,
         deferred_credits_delta: SETTINGS.api.deferred_credits_delta,
-        minimal_fees: SETTINGS.pool.minimal_fees,
+        minimal_fees: minimal_fees.clone(),
     };
 
+    // shared by the public JSON-RPC and gRPC APIs so that a client has a single budget
+    let rate_limiter = SETTINGS.api.rate_limit.enabled.then(|| {
+        Arc::new(RateLimiter::new(
+            SETTINGS.api.rate_limit.clone(),
+            massa_metrics.clone(),
+        ))
+    });
+
     // spawn Massa API
     let api = API::<ApiV2>::new(
         consensus_controller.clone(),
@@ -911,7 +832,7 @@ async fn launch(
             &SETTINGS.grpc.public,
             keypair.clone(),
             &final_state,
-            SETTINGS.pool.minimal_fees,
+            minimal_fees.clone(),
         );
 
         let grpc_public_api = MassaPublicGrpc {
@@ -931,6 +852,7 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            rate_limiter: rate_limiter.clone(),
         };
 
         // Spawn gRPC PUBLIC API
@@ -952,7 +874,7 @@ async fn launch(
             &SETTINGS.grpc.private,
             keypair.clone(),
             &final_state,
-            SETTINGS.pool.minimal_fees,
+            minimal_fees.clone(),
         );
 
         let bs_white_black_list = bootstrap_manager
@@ -972,6 +894,7 @@ async fn launch(
             stop_cv: sig_int_toggled.clone(),
             node_wallet: node_wallet.clone(),
             bs_white_black_list,
+            log_filter: log_filter.clone(),
         };
 
         // Spawn gRPC PRIVATE API
@@ -999,13 +922,26 @@ async fn launch(
         args.nb_op,
     );
 
+    // applies the changes of the configuration files, on SIGHUP or through the private API
+    let config_reloader = Arc::new(NodeConfigReloader::new(
+        config_keys,
+        pool_controller.clone(),
+        minimal_fees,
+        protocol_controller.clone(),
+        rate_limiter.clone(),
+        log_filter.clone(),
+    ));
+
     // spawn private API
     let api_private = API::<Private>::new(
+        consensus_controller.clone(),
         protocol_controller.clone(),
         execution_controller.clone(),
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        log_filter,
+        config_reloader.clone(),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -1029,6 +965,7 @@ async fn launch(
         node_id,
         shared_storage.clone(),
         mip_store.clone(),
+        rate_limiter,
     );
     let api_public_handle = api_public
         .serve(&SETTINGS.api.bind_public, &api_config)
@@ -1043,6 +980,7 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
         execution_controller,
         pool_controller,
+        protocol_controller,
         massa_metrics,
         (
             api_config.thread_count,
@@ -1098,16 +1036,116 @@ async fn launch(
         grpc_public_handle,
         metrics_stopper,
         massa_survey_stopper,
+        resync_context,
+        config_reloader,
     )
 }
 
+/// Handles on the modules involved in an in-place resynchronization
+struct ResyncContext {
+    bootstrap_config: BootstrapConfig,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    consensus_controller: Box<dyn ConsensusController>,
+    execution_controller: Box<dyn ExecutionController>,
+    massa_metrics: MassaMetrics,
+}
+
+/// Resynchronizes the node after a desynchronization without stopping its modules.
+/// Execution is suspended while the missing part of the final state is fetched from the bootstrap servers,
+/// then consensus restarts from the received final blocks and resumes execution on top of the new final state.
+///
+/// On failure, execution is resumed on top of the local final state if it is still valid,
+/// otherwise it stays suspended until the node is restarted.
+async fn resync_in_place(
+    context: &ResyncContext,
+    sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), BootstrapError> {
+    context.massa_metrics.health().set_bootstrapped(false);
+
+    // suspending execution, fetching the state and recomputing its caches are blocking
+    let bootstrap_config = context.bootstrap_config.clone();
+    let final_state = context.final_state.clone();
+    let execution_controller = context.execution_controller.clone_box();
+    let massa_metrics = context.massa_metrics.clone();
+    let resync_result = tokio::task::spawn_blocking(move || {
+        execution_controller.suspend();
+        resync_final_state(
+            &bootstrap_config,
+            final_state,
+            sig_int_toggled,
+            massa_metrics,
+        )
+    })
+    .await
+    .unwrap_or_else(|err| {
+        Err(BootstrapError::GeneralError(format!(
+            "resynchronization task failed: {}",
+            err
+        )))
+    });
+
+    match resync_result {
+        Ok(graph) => {
+            context.consensus_controller.resync(graph);
+            context.massa_metrics.health().set_bootstrapped(true);
+            Ok(())
+        }
+        Err(err) => {
+            if context.final_state.read().is_db_valid() {
+                context.execution_controller.resume_from_final_state();
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Fetches the changes missing from the local final state and recomputes its caches.
+///
+/// # Returns
+/// The final blocks to restart consensus from
+fn resync_final_state(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
+    massa_metrics: MassaMetrics,
+) -> Result<BootstrapableGraph, BootstrapError> {
+    let bootstrap_state = resync_state(
+        bootstrap_config,
+        final_state.clone(),
+        DefaultConnector,
+        *VERSION,
+        *END_TIMESTAMP,
+        sig_int_toggled,
+        massa_metrics,
+    )?;
+
+    {
+        let mut final_state = final_state.write();
+        if !final_state.is_db_valid() {
+            return Err(BootstrapError::GeneralError(
+                "db is not valid after resynchronization".to_string(),
+            ));
+        }
+        final_state.recompute_caches();
+        final_state
+            .feed_missing_draws()
+            .map_err(|err| BootstrapError::GeneralError(err.to_string()))?;
+    }
+
+    bootstrap_state.graph.ok_or_else(|| {
+        BootstrapError::GeneralError(
+            "no final blocks received during resynchronization".to_string(),
+        )
+    })
+}
+
 // Get the configuration of the gRPC server
 fn configure_grpc(
     name: ServiceName,
     settings: &GrpcSettings,
     keypair: KeyPair,
     final_state: &Arc<RwLock<dyn FinalStateController>>,
-    minimal_fees: Amount,
+    minimal_fees: Arc<RwLock<Amount>>,
 ) -> GrpcConfig {
     GrpcConfig {
         name,
@@ -1306,6 +1344,194 @@ struct Args {
         default_value = "10"
     )]
     dl_interval: u64,
+
+    #[cfg(feature = "replay")]
+    /// Replay the dumped final slots on top of the disk ledger instead of running the node,
+    /// up to the given period. The disk ledger is modified.
+    #[arg(long = "replay-until-period")]
+    replay_until_period: Option<u64>,
+
+    #[cfg(feature = "replay")]
+    /// Disk ledger to replay on, defaults to the configured disk ledger
+    #[arg(long = "replay-ledger")]
+    replay_ledger: Option<PathBuf>,
+}
+
+/// Configuration of the final state and of its components
+fn final_state_config() -> FinalStateConfig {
+    let ledger_config = LedgerConfig {
+        thread_count: THREAD_COUNT,
+        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
+        max_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+    };
+    let async_pool_config = AsyncPoolConfig {
+        max_length: MAX_ASYNC_POOL_LENGTH,
+        thread_count: THREAD_COUNT,
+        max_function_length: MAX_FUNCTION_NAME_LENGTH,
+        max_function_params_length: MAX_PARAMETERS_SIZE as u64,
+        max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
+    };
+    let pos_config = PoSConfig {
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        thread_count: THREAD_COUNT,
+        cycle_history_length: POS_SAVED_CYCLES,
+        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+        max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+        initial_deferred_credits_path: SETTINGS.ledger.initial_deferred_credits_path.clone(),
+    };
+    let executed_ops_config = ExecutedOpsConfig {
+        thread_count: THREAD_COUNT,
+        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+    };
+    let executed_denunciations_config = ExecutedDenunciationsConfig {
+        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+    };
+    FinalStateConfig {
+        ledger_config,
+        async_pool_config,
+        pos_config,
+        executed_ops_config,
+        executed_denunciations_config,
+        final_history_length: SETTINGS.ledger.final_history_length,
+        thread_count: THREAD_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        initial_seed_string: INITIAL_DRAW_SEED.into(),
+        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        ledger_backup_periods_interval: SETTINGS.ledger.ledger_backup_periods_interval,
+        state_hash_history_length: SETTINGS.ledger.state_hash_history_length,
+        t0: T0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+    }
+}
+
+/// Configuration of the database holding the final state
+fn db_config() -> MassaDBConfig {
+    MassaDBConfig {
+        path: SETTINGS.ledger.disk_ledger_path.clone(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
+        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into().unwrap(),
+        thread_count: THREAD_COUNT,
+        max_ledger_backups: SETTINGS.ledger.max_ledger_backups,
+    }
+}
+
+fn selector_config() -> SelectorConfig {
+    SelectorConfig {
+        max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
+        channel_size: CHANNEL_SIZE,
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+    }
+}
+
+fn mip_stats_config() -> MipStatsConfig {
+    MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new(
+            u64::from(SETTINGS.versioning.mip_stats_warn_announced_version),
+            100,
+        ),
+    }
+}
+
+/// Configuration of the execution module
+fn execution_config(
+    last_start_period: u64,
+    gas_costs: GasCosts,
+    block_dump_folder_path: PathBuf,
+) -> ExecutionConfig {
+    // Storage costs constants
+    let storage_costs_constants = StorageCostsConstants {
+        ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
+        ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
+        ledger_entry_datastore_base_cost: LEDGER_COST_PER_BYTE
+            .checked_mul_u64(LEDGER_ENTRY_DATASTORE_BASE_SIZE as u64)
+            .expect("Overflow when creating constant ledger_entry_datastore_base_size"),
+    };
+
+    ExecutionConfig {
+        max_final_events: SETTINGS.execution.max_final_events,
+        readonly_queue_length: SETTINGS.execution.readonly_queue_length,
+        readonly_thread_count: SETTINGS.execution.readonly_thread_count,
+        readonly_queue_timeout: SETTINGS.execution.readonly_queue_timeout,
+        readonly_execution_timeout: SETTINGS.execution.readonly_execution_timeout,
+        cursor_delay: SETTINGS.execution.cursor_delay,
+        max_async_gas: MAX_ASYNC_GAS,
+        async_msg_cst_gas_cost: ASYNC_MSG_CST_GAS_COST,
+        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        roll_price: ROLL_PRICE,
+        thread_count: THREAD_COUNT,
+        t0: T0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        block_reward: BLOCK_REWARD,
+        endorsement_count: ENDORSEMENT_COUNT as u64,
+        operation_validity_period: OPERATION_VALIDITY_PERIODS,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
+        max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_bytecode_size: MAX_BYTECODE_LENGTH,
+        max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
+        storage_costs_constants,
+        max_read_only_gas: SETTINGS.execution.max_read_only_gas,
+        gas_costs,
+        base_operation_gas_cost: BASE_OPERATION_GAS_COST,
+        last_start_period,
+        hd_cache_path: SETTINGS.execution.hd_cache_path.clone(),
+        lru_cache_size: SETTINGS.execution.lru_cache_size,
+        hd_cache_size: SETTINGS.execution.hd_cache_size,
+        snip_amount: SETTINGS.execution.snip_amount,
+        roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
+        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        broadcast_enabled: SETTINGS.api.enable_broadcast,
+        broadcast_slot_execution_output_channel_capacity: SETTINGS
+            .execution
+            .broadcast_slot_execution_output_channel_capacity,
+        max_event_size: MAX_EVENT_DATA_SIZE,
+        max_function_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_length: MAX_PARAMETERS_SIZE,
+        chain_id: *CHAINID,
+        #[cfg(feature = "execution-trace")]
+        broadcast_traces_enabled: true,
+        #[cfg(not(feature = "execution-trace"))]
+        broadcast_traces_enabled: false,
+        broadcast_slot_execution_traces_channel_capacity: SETTINGS
+            .execution
+            .broadcast_slot_execution_traces_channel_capacity,
+        max_execution_traces_slot_limit: SETTINGS.execution.execution_traces_limit,
+        block_dump_folder_path,
+    }
+}
+
+/// Storage of the blocks dumped by the execution module
+#[cfg(feature = "dump-block")]
+fn block_storage_backend(block_dump_folder_path: PathBuf) -> Arc<RwLock<dyn StorageBackend>> {
+    cfg_if! {
+        if #[cfg(feature = "db_storage_backend")] {
+            Arc::new(RwLock::new(RocksDBStorageBackend::new(
+                block_dump_folder_path,
+                SETTINGS.block_dump.max_blocks,
+            )))
+        } else if #[cfg(feature = "file_storage_backend")] {
+            Arc::new(RwLock::new(FileStorageBackend::new(
+                block_dump_folder_path,
+                SETTINGS.block_dump.max_blocks,
+            )))
+        } else {
+            compile_error!("feature dump-block requise either db_storage_backend or file_storage_backend");
+        }
+    }
 }
 
 /// Load wallet, asking for passwords if necessary
@@ -1355,24 +1581,15 @@ fn main() -> anyhow::Result<()> {
 
 async fn run(args: Args) -> anyhow::Result<()> {
     let mut cur_args = args;
-    use tracing_subscriber::prelude::*;
-    // spawn the console server in the background, returning a `Layer`:
-    let tracing_layer = tracing_subscriber::fmt::layer()
-        .with_filter(match SETTINGS.logging.level {
-            4 => LevelFilter::TRACE,
-            3 => LevelFilter::DEBUG,
-            2 => LevelFilter::INFO,
-            1 => LevelFilter::WARN,
-            _ => LevelFilter::ERROR,
-        })
-        .with_filter(filter_fn(|metadata| {
-            metadata.target().starts_with("massa") // ignore non-massa logs
-        }));
-    // build a `Subscriber` by combining layers with a `tracing_subscriber::Registry`:
-    tracing_subscriber::registry()
-        // add the console layer to the subscriber or default layers...
-        .with(tracing_layer)
-        .init();
+    // the log filter can be changed at runtime through the private API
+    let log_filter = init_subscriber(&LoggingConfig {
+        level: SETTINGS.logging.level,
+        filter: SETTINGS.logging.filter.clone(),
+        json: SETTINGS.logging.json,
+        file: SETTINGS.logging.file.clone(),
+        otlp_endpoint: SETTINGS.logging.otlp_endpoint.clone(),
+    })
+    .map_err(|err| anyhow::anyhow!(err))?;
 
     // Setup panic handlers,
     // and when a panic occurs,
@@ -1386,6 +1603,11 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
+    #[cfg(feature = "replay")]
+    if let Some(until_period) = cur_args.replay_until_period {
+        return replay::replay(cur_args.replay_ledger.clone(), until_period);
+    }
+
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(
         cur_args.password.clone(),
@@ -1406,6 +1628,24 @@ async fn run(args: Args) -> anyhow::Result<()> {
     })
     .expect("Error setting Ctrl-C handler");
 
+    // configuration the modules are launched with, the changes of the files are applied by reloads
+    let (_, config_keys) = read_node_config().map_err(|err| anyhow::anyhow!(err))?;
+
+    // configuration reload signal listener
+    let config_reload_requested = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let config_reload_requested = Arc::clone(&config_reload_requested);
+        let mut hangup = signal(SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                config_reload_requested.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+    let mut relaunched = false;
+
     #[cfg(feature = "resync_check")]
     let mut resync_check = Some(std::time::Instant::now() + std::time::Duration::from_secs(10));
 
@@ -1426,7 +1666,26 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+            resync_context,
+            config_reloader,
+        ) = launch(
+            &cur_args,
+            node_wallet.clone(),
+            Arc::clone(&sig_int_toggled),
+            log_filter.clone(),
+            config_keys.clone(),
+        )
+        .await;
+
+        // the modules were launched again with the startup settings
+        if relaunched {
+            if let Err(err) = config_reloader.reload() {
+                warn!(
+                    "could not apply the configuration changes after relaunch: {}",
+                    err
+                );
+            }
+        }
 
         // loop over messages
         let restart = loop {
@@ -1434,6 +1693,23 @@ async fn run(args: Args) -> anyhow::Result<()> {
             match consensus_event_receiver.try_recv() {
                 Ok(evt) => match evt {
                     ConsensusEvent::NeedSync => {
+                        resync_context.massa_metrics.health().signal_need_sync();
+                        if SETTINGS.bootstrap.resync_in_place {
+                            warn!("in response to a desynchronization, the node is going to resynchronize its state");
+                            match resync_in_place(&resync_context, Arc::clone(&sig_int_toggled))
+                                .await
+                            {
+                                Ok(()) => {
+                                    info!("the node state was resynchronized");
+                                    continue;
+                                }
+                                Err(BootstrapError::Interrupted(msg)) => {
+                                    info!("{}", msg);
+                                    break false;
+                                }
+                                Err(err) => warn!("in-place resynchronization failed: {}", err),
+                            }
+                        }
                         warn!("in response to a desynchronization, the node is going to bootstrap again");
                         break true;
                     }
@@ -1463,6 +1739,13 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 break false;
             }
 
+            if config_reload_requested.swap(false, Ordering::Relaxed) {
+                info!("hangup signal received, reloading the configuration");
+                if let Err(err) = config_reloader.reload() {
+                    warn!("could not reload the configuration: {}", err);
+                }
+            }
+
             // Elements of the system that involve stopping and restarting should be checked by forcing a relaunch.
             // This check allows the system to start up as normal, wait 10s, then force a relaunch. If Things take too long
             // to shutdown, or does not allow for a clean relaunch, this feature flag can expose those issues.
@@ -1501,6 +1784,8 @@ async fn run(args: Args) -> anyhow::Result<()> {
         }
         // If we restart because of a desync, then we do not want to restart from a snapshot
         cur_args.restart_from_snapshot_at_period = None;
+        relaunched = true;
     }
+    massa_logging::otel::shutdown();
     Ok(())
 }
@@ -0,0 +1,294 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Live reload of the node configuration, on SIGHUP or through the private API.
+//!
+//! The configuration files are read again and compared key by key with the configuration in effect.
+//! Changed keys of the safe-to-change subset are applied to the running modules,
+//! the other ones are only reported: they take effect after a restart of the node.
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use massa_api_exports::rate_limit::RateLimiter;
+use massa_api_exports::reload::{ConfigReloadReport, ConfigReloader};
+use massa_logging::subscriber::{level_directives, LogFilterHandle};
+use massa_models::amount::Amount;
+use massa_models::config::read_massa_config;
+use massa_pool_exports::PoolController;
+use massa_protocol_exports::ProtocolController;
+use parking_lot::{Mutex, RwLock};
+use tracing::info;
+
+use crate::settings::Settings;
+
+/// Reads the configuration sources of the node, returning the settings and the value of every key
+pub fn read_node_config() -> Result<(Settings, BTreeMap<String, String>), String> {
+    let config = read_massa_config("massa-node", "MASSA_NODE")
+        .map_err(|err| format!("could not read the configuration: {}", err))?;
+    let mut keys = BTreeMap::new();
+    flatten_keys(
+        "",
+        config
+            .collect()
+            .map_err(|err| format!("could not read the configuration: {}", err))?,
+        &mut keys,
+    );
+    let settings = config
+        .try_deserialize()
+        .map_err(|err| format!("invalid configuration: {}", err))?;
+    Ok((settings, keys))
+}
+
+fn flatten_keys(
+    prefix: &str,
+    table: config::Map<String, config::Value>,
+    keys: &mut BTreeMap<String, String>,
+) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value.clone().into_table() {
+            Ok(table) => flatten_keys(&key, table, keys),
+            Err(_) => {
+                keys.insert(key, value.to_string());
+            }
+        }
+    }
+}
+
+/// Kind of change a key of the configuration supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiveKey {
+    MinimalFees,
+    PeersCategories,
+    RateLimit,
+    Logging,
+}
+
+fn live_key(key: &str) -> Option<LiveKey> {
+    match key {
+        "pool.minimal_fees" => Some(LiveKey::MinimalFees),
+        "logging.level" | "logging.filter" => Some(LiveKey::Logging),
+        // `enabled` decides whether the rate limiter exists at all
+        "api.rate_limit.enabled" => None,
+        _ if key.starts_with("api.rate_limit.") => Some(LiveKey::RateLimit),
+        // the inbound limits are enforced by the network layer, configured at startup
+        _ if key.starts_with("protocol.peers_categories.")
+            && (key.ends_with(".target_out_connections")
+                || key.ends_with(".allow_local_peers")) =>
+        {
+            Some(LiveKey::PeersCategories)
+        }
+        _ => None,
+    }
+}
+
+/// Applies the changes of the configuration files to the modules of a running node.
+///
+/// The modules are configured from the settings read at startup when they are launched again
+/// after a desynchronization: the reload has to be done again to apply the changes to them.
+pub struct NodeConfigReloader {
+    /// value of every key of the configuration in effect
+    current: Mutex<BTreeMap<String, String>>,
+    pool_controller: Box<dyn PoolController>,
+    /// minimal fees read by the APIs
+    minimal_fees: Arc<RwLock<Amount>>,
+    protocol_controller: Box<dyn ProtocolController>,
+    /// `None` if rate limiting is disabled
+    rate_limiter: Option<Arc<RateLimiter>>,
+    log_filter: LogFilterHandle,
+}
+
+impl NodeConfigReloader {
+    /// `current` is the value of every key of the configuration the modules were launched with
+    pub fn new(
+        current: BTreeMap<String, String>,
+        pool_controller: Box<dyn PoolController>,
+        minimal_fees: Arc<RwLock<Amount>>,
+        protocol_controller: Box<dyn ProtocolController>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        log_filter: LogFilterHandle,
+    ) -> Self {
+        NodeConfigReloader {
+            current: Mutex::new(current),
+            pool_controller,
+            minimal_fees,
+            protocol_controller,
+            rate_limiter,
+            log_filter,
+        }
+    }
+
+    fn apply(&self, kind: LiveKey, settings: &Settings) -> Result<bool, String> {
+        match kind {
+            LiveKey::MinimalFees => {
+                *self.minimal_fees.write() = settings.pool.minimal_fees;
+                self.pool_controller
+                    .set_minimal_fees(settings.pool.minimal_fees);
+            }
+            LiveKey::PeersCategories => {
+                self.protocol_controller
+                    .set_peers_categories(settings.protocol.peers_categories.clone())
+                    .map_err(|err| format!("could not update the peer categories: {}", err))?;
+            }
+            LiveKey::RateLimit => match &self.rate_limiter {
+                Some(rate_limiter) => rate_limiter.set_config(settings.api.rate_limit.clone()),
+                None => return Ok(false),
+            },
+            LiveKey::Logging => {
+                let directives = settings
+                    .logging
+                    .filter
+                    .clone()
+                    .unwrap_or_else(|| level_directives(settings.logging.level));
+                self.log_filter.set_filter(&directives)?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl ConfigReloader for NodeConfigReloader {
+    fn reload(&self) -> Result<ConfigReloadReport, String> {
+        let (settings, keys) = read_node_config()?;
+        let mut current = self.current.lock();
+
+        let changed: BTreeSet<String> = current
+            .keys()
+            .chain(keys.keys())
+            .filter(|key| current.get(*key) != keys.get(*key))
+            .cloned()
+            .collect();
+
+        let mut kinds = Vec::new();
+        for key in &changed {
+            if let Some(kind) = live_key(key) {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+        // a failing kind does not prevent the other ones from being applied
+        let mut applied_kinds = Vec::new();
+        let mut failed_kinds = Vec::new();
+        for kind in kinds {
+            match self.apply(kind, &settings) {
+                Ok(true) => applied_kinds.push(kind),
+                Ok(false) => {}
+                Err(err) => failed_kinds.push((kind, err)),
+            }
+        }
+
+        let mut report = ConfigReloadReport::default();
+        for key in changed {
+            let kind = live_key(&key);
+            if let Some((_, err)) = failed_kinds
+                .iter()
+                .find(|(failed, _)| Some(*failed) == kind)
+            {
+                // kept out of `current` so that the next reload tries again
+                report.failed.push(format!("{}: {}", key, err));
+            } else if kind.is_some_and(|kind| applied_kinds.contains(&kind)) {
+                match keys.get(&key) {
+                    Some(value) => current.insert(key.clone(), value.clone()),
+                    None => current.remove(&key),
+                };
+                report.applied.push(key);
+            } else {
+                report.requires_restart.push(key);
+            }
+        }
+        info!(
+            "configuration reloaded, applied: {:?}, requires a restart: {:?}, failed: {:?}",
+            report.applied, report.requires_restart, report.failed
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{Config, File, FileFormat};
+
+    fn keys_of(toml: &str) -> BTreeMap<String, String> {
+        let table = Config::builder()
+            .add_source(File::from_str(toml, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .collect()
+            .unwrap();
+        let mut keys = BTreeMap::new();
+        flatten_keys("", table, &mut keys);
+        keys
+    }
+
+    #[test]
+    fn test_flatten_keys() {
+        let keys = keys_of(
+            r#"
+            [pool]
+            minimal_fees = "0.01"
+            [protocol.peers_categories.Bootstrap]
+            target_out_connections = 1
+            allow_local_peers = false
+            [logging]
+            level = 2
+            "#,
+        );
+        assert_eq!(
+            keys.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec![
+                "logging.level",
+                "pool.minimal_fees",
+                "protocol.peers_categories.Bootstrap.allow_local_peers",
+                "protocol.peers_categories.Bootstrap.target_out_connections",
+            ]
+        );
+        assert_eq!(keys["pool.minimal_fees"], "0.01");
+        assert_eq!(
+            keys["protocol.peers_categories.Bootstrap.target_out_connections"],
+            "1"
+        );
+    }
+
+    #[test]
+    fn test_flatten_keys_detects_changes() {
+        let before = keys_of("[pool]\nminimal_fees = \"0.01\"\nmax_operation_pool_size = 32000");
+        let after = keys_of("[pool]\nminimal_fees = \"0.02\"\nmax_operation_pool_size = 32000");
+        let changed: Vec<_> = before
+            .keys()
+            .filter(|key| before.get(*key) != after.get(*key))
+            .collect();
+        assert_eq!(changed, vec!["pool.minimal_fees"]);
+    }
+
+    #[test]
+    fn test_live_key() {
+        assert_eq!(live_key("pool.minimal_fees"), Some(LiveKey::MinimalFees));
+        assert_eq!(live_key("logging.level"), Some(LiveKey::Logging));
+        assert_eq!(live_key("logging.filter"), Some(LiveKey::Logging));
+        assert_eq!(
+            live_key("api.rate_limit.default_method_cost"),
+            Some(LiveKey::RateLimit)
+        );
+        assert_eq!(
+            live_key("protocol.peers_categories.Bootstrap.target_out_connections"),
+            Some(LiveKey::PeersCategories)
+        );
+        assert_eq!(
+            live_key("protocol.peers_categories.Bootstrap.allow_local_peers"),
+            Some(LiveKey::PeersCategories)
+        );
+        // only applied at startup
+        assert_eq!(live_key("api.rate_limit.enabled"), None);
+        assert_eq!(
+            live_key("protocol.peers_categories.Bootstrap.max_in_connections"),
+            None
+        );
+        assert_eq!(live_key("pool.max_operation_pool_size"), None);
+        assert_eq!(live_key("api.bind_public"), None);
+    }
+}
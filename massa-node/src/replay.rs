@@ -0,0 +1,151 @@
+//! Replay mode of the node.
+//!
+//! Re-executes the final slots recorded by the dump-block feature on top of a final state
+//! snapshot, with the execution code of this binary, and stops at the first slot whose
+//! final state fingerprint, execution trail hash or state changes differ from the recorded ones.
+//! The snapshot is modified in place: work on a copy of the disk ledger.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_execution_exports::{ExecutionChannels, GasCosts};
+use massa_execution_worker::replay::SlotReplayer;
+use massa_final_state::{FinalState, FinalStateController};
+use massa_ledger_worker::FinalLedger;
+use massa_metrics::MassaMetrics;
+use massa_models::config::{CHAINID, THREAD_COUNT};
+use massa_pos_worker::start_selector_worker;
+use massa_versioning::versioning::MipStore;
+use massa_wallet::Wallet;
+use parking_lot::RwLock;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::settings::SETTINGS;
+use crate::{
+    block_storage_backend, db_config, execution_config, final_state_config, mip_stats_config,
+    selector_config,
+};
+
+/// Replays the dumped final slots following the snapshot at `ledger_path`,
+/// up to the last slot of `until_period`
+pub fn replay(ledger_path: Option<PathBuf>, until_period: u64) -> anyhow::Result<()> {
+    let db_config = MassaDBConfig {
+        path: ledger_path.unwrap_or_else(|| SETTINGS.ledger.disk_ledger_path.clone()),
+        ..db_config()
+    };
+    let db = Arc::new(RwLock::new(
+        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+    ));
+
+    let final_state_config = final_state_config();
+    let ledger = FinalLedger::new(final_state_config.ledger_config.clone(), db.clone());
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(selector_config()).expect("could not start selector worker");
+    let mip_store = MipStore::try_from_db(db.clone(), mip_stats_config())
+        .map_err(|err| anyhow::anyhow!("could not load the MIP store: {}", err))?;
+
+    let final_state: Arc<RwLock<dyn FinalStateController>> = Arc::new(RwLock::new(
+        FinalState::new(
+            db,
+            final_state_config,
+            Box::new(ledger),
+            selector_controller.clone(),
+            mip_store.clone(),
+            false,
+        )
+        .map_err(|err| anyhow::anyhow!("could not load the final state: {}", err))?,
+    ));
+    final_state.write().recompute_caches();
+    final_state
+        .write()
+        .compute_initial_draws()
+        .map_err(|err| anyhow::anyhow!("could not compute initial draws: {}", err))?;
+
+    let gas_costs = GasCosts::new(
+        SETTINGS.execution.abi_gas_costs_file.clone(),
+        SETTINGS.execution.wasm_gas_costs_file.clone(),
+    )
+    .expect("Failed to load gas costs");
+    let execution_config = execution_config(
+        final_state.read().get_last_start_period(),
+        gas_costs,
+        SETTINGS.block_dump.block_dump_folder_path.clone(),
+    );
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(
+            execution_config.broadcast_slot_execution_output_channel_capacity,
+        )
+        .0,
+        #[cfg(feature = "execution-trace")]
+        slot_execution_traces_sender: broadcast::channel(
+            execution_config.broadcast_slot_execution_traces_channel_capacity,
+        )
+        .0,
+    };
+    // no staking key is involved in the replay
+    let wallet = Arc::new(RwLock::new(Wallet::new(
+        std::env::temp_dir().join("massa_replay_wallet"),
+        String::new(),
+        *CHAINID,
+    )?));
+    let (massa_metrics, _metrics_stopper) = MassaMetrics::new(
+        false,
+        SETTINGS.metrics.bind,
+        THREAD_COUNT,
+        SETTINGS.metrics.tick_delay.to_duration(),
+    );
+    let dump = block_storage_backend(execution_config.block_dump_folder_path.clone());
+
+    let mut replayer = SlotReplayer::new(
+        execution_config,
+        final_state,
+        selector_controller,
+        mip_store,
+        execution_channels,
+        wallet,
+        massa_metrics,
+        dump,
+    );
+    info!("Replaying final slots after {}", replayer.final_slot());
+
+    let result = loop {
+        if replayer.final_slot().period >= until_period
+            && replayer.final_slot().thread == THREAD_COUNT.saturating_sub(1)
+        {
+            info!("Replay reached period {} without divergence", until_period);
+            break Ok(());
+        }
+        let report = match replayer.replay_next_slot() {
+            Ok(report) => report,
+            Err(err) => break Err(anyhow::anyhow!("replay stopped: {}", err)),
+        };
+        if report.is_consistent() {
+            info!(
+                "Replayed slot {} (block {:?}): fingerprint {}",
+                report.slot, report.block_id, report.fingerprint
+            );
+            continue;
+        }
+        warn!(
+            "Divergence at slot {} (block {:?}): fingerprint {} expected {}, execution trail hash {} expected {}, first divergent key {}",
+            report.slot,
+            report.block_id,
+            report.fingerprint,
+            report.expected_fingerprint,
+            report.execution_trail_hash,
+            report.expected_execution_trail_hash,
+            report
+                .first_divergent_key
+                .as_ref()
+                .map(|key| key.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+                .unwrap_or_else(|| "none".to_string()),
+        );
+        break Err(anyhow::anyhow!("replay diverged at slot {}", report.slot));
+    };
+
+    selector_manager.stop();
+    result
+}
@@ -3,7 +3,9 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
+use massa_api_exports::rate_limit::RateLimitConfig;
 use massa_bootstrap::IpType;
+use massa_logging::subscriber::LogFileConfig;
 use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
 use massa_time::MassaTime;
@@ -17,12 +19,23 @@ lazy_static::lazy_static! {
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoggingSettings {
     pub level: usize,
+    /// filter directives in the `RUST_LOG` syntax, replacing `level` if set
+    pub filter: Option<String>,
+    /// write the logs as JSON objects
+    pub json: bool,
+    /// also write the logs to rotating files
+    pub file: Option<LogFileConfig>,
+    /// OTLP collector receiving the tracing spans, requires the `opentelemetry` feature
+    pub otlp_endpoint: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
     pub readonly_queue_length: usize,
+    pub readonly_thread_count: usize,
+    pub readonly_queue_timeout: MassaTime,
+    pub readonly_execution_timeout: MassaTime,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
     pub max_read_only_gas: u64,
@@ -52,6 +65,7 @@ pub struct LedgerSettings {
     pub initial_deferred_credits_path: Option<PathBuf>,
     pub ledger_backup_periods_interval: u64,
     pub max_ledger_backups: u64,
+    pub state_hash_history_length: usize,
 }
 
 /// Bootstrap configuration.
@@ -75,8 +89,16 @@ pub struct BootstrapSettings {
     pub per_ip_min_interval: MassaTime,
     pub ip_list_max_size: usize,
     pub rate_limit: u64,
+    /// Number of bootstrap slots reserved to the clients identified with a whitelisted node id
+    pub priority_reserved_slots: u32,
+    /// Max number of anonymous clients waiting in the bootstrap queue
+    pub max_bootstrap_queue_length: usize,
     /// Allocated time with which to manage the bootstrap process
     pub bootstrap_timeout: MassaTime,
+    /// Resynchronize the state in place instead of restarting the node when a desynchronization is detected
+    pub resync_in_place: bool,
+    /// Number of servers the final state is downloaded from at once
+    pub parallel_bootstrap_servers: usize,
 }
 
 /// Factory settings
@@ -130,6 +152,8 @@ pub struct APISettings {
     // whether to broadcast for blocks, endorsement and operations
     pub enable_broadcast: bool,
     pub deferred_credits_delta: MassaTime,
+    /// per-client rate limiting of the public JSON-RPC and gRPC APIs
+    pub rate_limit: RateLimitConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -175,6 +199,8 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// block status changes channel capacity
+    pub broadcast_block_status_channel_capacity: usize,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -192,6 +218,14 @@ pub struct MetricsSettings {
     pub bind: SocketAddr,
     /// interval at which to update metrics
     pub tick_delay: MassaTime,
+    /// the node is not ready if its final execution cursor is more than this number of slots behind the current slot
+    pub health_max_final_slot_lag: u64,
+    /// the node is not ready if its candidate execution cursor is more than this number of slots behind the current slot
+    pub health_max_candidate_slot_lag: u64,
+    /// the node is not ready with fewer connected peers
+    pub health_min_connected_peers: usize,
+    /// the node is not ready during this delay after a desynchronization was detected
+    pub health_need_sync_grace_period: MassaTime,
 }
 
 /// Protocol Configuration, read from toml user configuration file
@@ -246,6 +280,10 @@ pub struct ProtocolSettings {
     pub max_endorsements_propagation_time: MassaTime,
     /// Path for initial peers
     pub initial_peers_file: PathBuf,
+    /// Path of the peers whitelist
+    pub peers_whitelist_path: PathBuf,
+    /// Path of the peers blacklist
+    pub peers_blacklist_path: PathBuf,
     /// Keypair
     pub keypair_file: PathBuf,
     /// Ip we are bind to listen to
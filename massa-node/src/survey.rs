@@ -3,10 +3,11 @@ use std::thread::JoinHandle;
 
 use crossbeam_channel::{select, tick};
 use massa_channel::{sender::MassaSender, MassaChannel};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionController, ExecutionQueryRequest};
 use massa_metrics::MassaMetrics;
 use massa_models::{address::Address, slot::Slot, timeslots::get_latest_block_slot_at_timestamp};
 use massa_pool_exports::PoolController;
+use massa_protocol_exports::ProtocolController;
 use massa_time::MassaTime;
 use tracing::info;
 // use std::time::Duration;
@@ -43,6 +44,7 @@ impl MassaSurvey {
         tick_delay: std::time::Duration,
         execution_controller: Box<dyn ExecutionController>,
         pool_controller: Box<dyn PoolController>,
+        protocol_controller: Box<dyn ProtocolController>,
         massa_metrics: MassaMetrics,
         config: (u8, MassaTime, MassaTime, u64, u64),
     ) -> MassaSurveyStopper {
@@ -120,6 +122,22 @@ impl MassaSurvey {
                                     massa_metrics.set_current_time_period(current_slot.period);
                                 }
 
+                                {
+                                    // update the data used by the health endpoints
+                                    let now = MassaTime::now();
+                                    let state = execution_controller.query_state(ExecutionQueryRequest { requests: vec![] });
+                                    let current_slot = get_latest_block_slot_at_timestamp(config.0, config.1, config.2, now).unwrap_or(None);
+                                    massa_metrics.health().set_sync_state(
+                                        (state.final_cursor.period, state.final_cursor.thread),
+                                        (state.candidate_cursor.period, state.candidate_cursor.thread),
+                                        current_slot.map(|slot| (slot.period, slot.thread)),
+                                    );
+                                    match protocol_controller.get_stats() {
+                                        Ok((_, peers)) => massa_metrics.health().set_connected_peers(peers.len()),
+                                        Err(e) => warn!("MassaSurvey | Failed to get protocol stats: {:?}", e),
+                                    }
+                                }
+
                                 {
                                     massa_metrics.set_operations_pool(pool_controller.get_operation_count());
                                     massa_metrics.set_endorsements_pool(pool_controller.get_endorsement_count());
@@ -1,6 +1,7 @@
 use massa_execution_exports::ExecutionController;
 use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
 use massa_pos_exports::SelectorController;
+use massa_versioning::versioning::MipStore;
 
 /// channels used by the pool worker
 #[derive(Clone)]
@@ -11,6 +12,8 @@ pub struct PoolChannels {
     pub selector: Box<dyn SelectorController>,
     /// Broadcasts used by the pool worker to send new operations and endorsements
     pub broadcasts: PoolBroadcasts,
+    /// MIP store, to know which operation types are active
+    pub mip_store: MipStore,
 }
 
 /// Broadcasts used by the pool worker to send new operations and endorsements
@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    amount::Amount,
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
@@ -61,6 +62,10 @@ pub trait PoolController: Send + Sync {
 
     /// Get final cs periods (updated regularly from consensus)
     fn get_final_cs_periods(&self) -> Vec<u64>;
+
+    /// Change the minimal fee of the operations kept in the pool.
+    /// Operations paying less are removed at the next refresh.
+    fn set_minimal_fees(&self, minimal_fees: Amount);
 }
 
 /// Allow cloning `Box<dyn PoolController>`
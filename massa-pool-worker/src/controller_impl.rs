@@ -3,8 +3,9 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    amount::Amount, block_id::BlockId, denunciation::Denunciation,
+    denunciation::DenunciationPrecursor, endorsement::EndorsementId, operation::OperationId,
+    slot::Slot,
 };
 use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
@@ -231,6 +232,11 @@ impl PoolController for PoolControllerImpl {
     fn get_final_cs_periods(&self) -> Vec<u64> {
         self.last_cs_final_periods.clone()
     }
+
+    /// Change the minimal fee of the operations kept in the pool
+    fn set_minimal_fees(&self, minimal_fees: Amount) {
+        self.operation_pool.write().set_minimal_fees(minimal_fees);
+    }
 }
 
 /// Implementation of the pool manager.
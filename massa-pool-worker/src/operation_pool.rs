@@ -375,6 +375,12 @@ impl OperationPool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Change the minimal fee of the operations kept in the pool.
+    /// Operations paying less are removed at the next refresh.
+    pub(crate) fn set_minimal_fees(&mut self, minimal_fees: Amount) {
+        self.config.minimal_fees = minimal_fees;
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -391,6 +397,28 @@ impl OperationPool {
         // List all the new operations
         let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
 
+        // Drop the operations whose type is not active yet
+        {
+            let now = MassaTime::now();
+            let ops = ops_storage.read_operations();
+            new_op_ids.retain(|op_id| {
+                let op = ops
+                    .get(op_id)
+                    .expect("operation not found in storage but listed as owned");
+                let active = self
+                    .channels
+                    .mip_store
+                    .is_operation_type_active(&op.content.op, now);
+                if !active {
+                    debug!(
+                        "dropping operation {}: operation type not active yet",
+                        op_id
+                    );
+                }
+                active
+            });
+        }
+
         // If there are too many extra operations,
         // we don't want the container to fill up too much in-between refreshes so we drop any excess.
         // This is because refreshing the container is very heavy and is only called periodically.
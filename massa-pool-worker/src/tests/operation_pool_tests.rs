@@ -12,6 +12,11 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Add inactive operation types
+//! Function: [`test_add_inactive_operation_type`]
+//! Same as classic but some of the operations are batch transactions or
+//! multisig operations, which are dropped as long as their MIP is not active.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
@@ -22,10 +27,18 @@ use crate::tests::tools::OpGenerator;
 use super::tools::{
     create_some_operations, default_mock_execution_controller, pool_test, PoolTestBoilerPlate,
 };
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    config::ENDORSEMENT_COUNT,
+    multisig::MultisigPolicy,
+    operation::{OperationId, OperationType},
+    slot::Slot,
+};
 use massa_pool_exports::PoolConfig;
 use massa_pos_exports::{MockSelectorController, Selection};
-use std::{collections::BTreeMap, time::Duration};
+use massa_signature::KeyPair;
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 #[test]
 fn test_add_operation() {
@@ -136,6 +149,62 @@ fn test_add_irrelevant_operation() {
     );
 }
 
+/// Test that operations whose type is not active yet are not added.
+#[test]
+fn test_add_inactive_operation_type() {
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|_, _| Ok(BTreeMap::new()));
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            let recipient = KeyPair::generate(0).unwrap();
+            let batch_gen =
+                OpGenerator::default()
+                    .expirery(2)
+                    .op(OperationType::BatchTransaction {
+                        transfers: vec![(
+                            Address::from_public_key(&recipient.get_public_key()),
+                            Amount::from_str("1").unwrap(),
+                        )],
+                    });
+            let cosigner = KeyPair::generate(0).unwrap();
+            let multisig_gen = OpGenerator::default()
+                .expirery(2)
+                .creator(cosigner.clone())
+                .op(OperationType::Multisig {
+                    policy: MultisigPolicy::new(vec![cosigner.get_public_key()], 1).unwrap(),
+                    signatures: vec![],
+                    op: Box::new(OperationType::Transaction {
+                        recipient_address: Address::from_public_key(&recipient.get_public_key()),
+                        amount: Amount::from_str("1").unwrap(),
+                    }),
+                });
+            let op_gen = OpGenerator::default().expirery(2);
+            storage.store_operations(create_some_operations(5, &batch_gen));
+            // the ID of a multisig operation does not depend on its signature: store a single one
+            storage.store_operations(create_some_operations(1, &multisig_gen));
+            storage.store_operations(create_some_operations(5, &op_gen));
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_secs(3));
+            assert_eq!(operation_pool.get_operation_count(), 5);
+        },
+    );
+}
+
 #[test]
 fn test_pool() {
     let pool_config = PoolConfig {
@@ -6,7 +6,7 @@ use crate::start_pool_controller;
 use crossbeam_channel as _;
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
-use massa_models::config::CHAINID;
+use massa_models::config::{CHAINID, MIP_STORE_STATS_BLOCK_CONSIDERED};
 use massa_models::{
     address::Address,
     amount::Amount,
@@ -21,7 +21,9 @@ use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolControlle
 use massa_pos_exports::MockSelectorController as AutoMockSelectorController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
 use massa_wallet::test_exports::create_test_wallet;
+use num::rational::Ratio;
 use parking_lot::RwLock;
 use tokio::sync::broadcast;
 
@@ -32,6 +34,7 @@ pub(crate) struct OpGenerator {
     fee: Option<Amount>,
     amount: Option<Amount>,
     expirery: Option<u64>,
+    op: Option<OperationType>,
 }
 
 impl OpGenerator {
@@ -62,6 +65,11 @@ impl OpGenerator {
         self
     }
 
+    pub(crate) fn op(mut self, op: OperationType) -> Self {
+        self.op = Some(op);
+        self
+    }
+
     pub(crate) fn generate(&self) -> SecureShareOperation {
         let creator = self
             .creator
@@ -75,10 +83,13 @@ impl OpGenerator {
         let amount = self.amount.unwrap_or_default();
         let expirery = self.expirery.unwrap_or_default();
 
-        let op = OperationType::Transaction {
-            recipient_address: Address::from_public_key(&receiver.get_public_key()),
-            amount,
-        };
+        let op = self
+            .op
+            .clone()
+            .unwrap_or_else(|| OperationType::Transaction {
+                recipient_address: Address::from_public_key(&receiver.get_public_key()),
+                amount,
+            });
         let content = Operation {
             fee,
             op,
@@ -93,6 +104,15 @@ pub(crate) fn create_some_operations(n: usize, op_gen: &OpGenerator) -> Vec<Secu
     (0..n).map(|_| op_gen.generate()).collect()
 }
 
+/// Return an empty MIP store: no MIP is active
+pub(crate) fn create_mip_store() -> MipStore {
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    MipStore::try_from(([], mip_stats_config)).unwrap()
+}
+
 pub struct PoolTestBoilerPlate {
     pub pool_manager: Box<dyn PoolManager>,
     pub pool_controller: Box<dyn PoolController>,
@@ -123,6 +143,7 @@ impl PoolTestBoilerPlate {
                     operation_sender,
                 },
                 selector: selector_story,
+                mip_store: create_mip_store(),
             },
             wallet,
         );
@@ -165,6 +186,7 @@ pub fn pool_test<F>(
                 operation_sender,
             },
             selector,
+            mip_store: create_mip_store(),
         },
         wallet,
     );
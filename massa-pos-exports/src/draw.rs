@@ -1,13 +1,21 @@
-use crate::CycleDraws;
+use crate::{PosError, PosResult, Selection, SelectorConfig};
 use massa_hash::Hash;
 use massa_models::{address::Address, slot::Slot};
-use massa_pos_exports::{PosError, PosResult, Selection, SelectorConfig};
 use rand::{distributions::Distribution, SeedableRng};
 use rand_distr::WeightedAliasIndex;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use std::collections::{BTreeMap, HashMap};
 use tracing::debug;
 
+/// Draws for a cycle, used in selector cache
+#[derive(Debug)]
+pub struct CycleDraws {
+    /// cycle number
+    pub cycle: u64,
+    /// cache of draws
+    pub draws: HashMap<Slot, Selection>,
+}
+
 /// Draws block and creators for a given cycle.
 /// Then prune the `cache` pointer if max cache is exceeded.
 /// It uses BTreeMap to store the address <->rolls mapping.
@@ -25,7 +33,7 @@ use tracing::debug;
 /// - An inconsistency error is thrown if nobody has rolls
 ///
 /// Otherwise, the draws return an empty success.
-pub(crate) fn perform_draws(
+pub fn perform_draws(
     cfg: &SelectorConfig,
     cycle: u64,
     lookback_rolls: BTreeMap<Address, u64>,
@@ -0,0 +1,35 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::collections::BTreeMap;
+
+use massa_hash::Hash;
+use massa_models::address::Address;
+
+/// Inputs of the selector draws of a cycle.
+/// The draws are a deterministic function of these inputs, so anyone can reproduce them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawInputs {
+    /// drawn cycle
+    pub cycle: u64,
+    /// roll counts at look back (`cycle - 3`), or the initial rolls for the first cycles
+    pub lookback_rolls: BTreeMap<Address, u64>,
+    /// RNG seed at look back (`cycle - 2`), or the initial seed for the first cycles
+    pub lookback_seed: Hash,
+}
+
+impl DrawInputs {
+    /// Hash of the roll distribution, computed over the (address, roll count) pairs in address order
+    pub fn roll_distribution_hash(&self) -> Hash {
+        roll_distribution_hash(&self.lookback_rolls)
+    }
+}
+
+/// Hash of a roll distribution, computed over the (address, roll count) pairs in address order
+pub fn roll_distribution_hash(rolls: &BTreeMap<Address, u64>) -> Hash {
+    let mut bytes = Vec::new();
+    for (address, roll_count) in rolls {
+        bytes.extend(address.to_prefixed_bytes());
+        bytes.extend(roll_count.to_be_bytes());
+    }
+    Hash::compute_from(&bytes)
+}
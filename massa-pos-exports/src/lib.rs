@@ -11,6 +11,8 @@ mod config;
 mod controller_traits;
 mod cycle_info;
 mod deferred_credits;
+mod draw;
+mod draw_inputs;
 mod error;
 mod pos_changes;
 mod pos_final_state;
@@ -22,6 +24,8 @@ pub use controller_traits::{MockSelectorController, MockSelectorControllerWrappe
 pub use controller_traits::{Selection, SelectorController, SelectorManager};
 pub use cycle_info::*;
 pub use deferred_credits::*;
+pub use draw::*;
+pub use draw_inputs::*;
 pub use error::*;
 pub use pos_changes::*;
 pub use pos_final_state::*;
@@ -3,7 +3,7 @@ use crate::{
     DeferredCreditsSerializer, PoSChanges, PosError, PosResult, ProductionStats,
     SelectorController,
 };
-use crate::{DeferredCredits, PoSConfig};
+use crate::{DeferredCredits, DrawInputs, PoSConfig};
 use bitvec::vec::BitVec;
 use massa_db_exports::{
     DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
@@ -400,6 +400,42 @@ impl PoSFinalState {
         Ok(())
     }
 
+    /// Sends to the selector the draw inputs that became available since its latest draw.
+    /// Used when the final state was resynchronized without being finalized slot by slot.
+    pub fn feed_missing_draws(&mut self) -> PosResult<()> {
+        let last_drawn_cycle = self.selector.as_mut().wait_for_draws(0)?;
+
+        let mut max_cycle = None;
+        for hist_item in self.cycle_history_cache.iter() {
+            if !hist_item.1 {
+                break;
+            }
+            let draw_cycle = hist_item.0.checked_add(2).ok_or_else(|| {
+                PosError::OverflowError("cycle overflow in feed_missing_draws".into())
+            })?;
+            if draw_cycle <= last_drawn_cycle {
+                continue;
+            }
+            // the selector requires continuous draws: check before feeding it
+            let expected_cycle = max_cycle.unwrap_or(last_drawn_cycle).checked_add(1);
+            if Some(draw_cycle) != expected_cycle {
+                return Err(PosError::ContainerInconsistency(format!(
+                    "cannot feed cycle {} to the selector after cycle {}",
+                    draw_cycle,
+                    max_cycle.unwrap_or(last_drawn_cycle)
+                )));
+            }
+            self.feed_selector(draw_cycle)?;
+            max_cycle = Some(draw_cycle);
+        }
+
+        // wait for all fed cycles to be drawn
+        if let Some(wait_cycle) = max_cycle {
+            self.selector.as_mut().wait_for_draws(wait_cycle)?;
+        }
+        Ok(())
+    }
+
     /// Technical specification of `apply_changes_to_batch`:
     ///
     /// set `self.last_final_slot` = C
@@ -541,6 +577,16 @@ impl PoSFinalState {
 
     /// Feeds the selector targeting a given draw cycle
     pub fn feed_selector(&self, draw_cycle: u64) -> PosResult<()> {
+        let inputs = self.get_draw_inputs(draw_cycle)?;
+
+        // feed selector
+        self.selector
+            .as_ref()
+            .feed_cycle(draw_cycle, inputs.lookback_rolls, inputs.lookback_seed)
+    }
+
+    /// Gets the inputs of the draws of a given cycle, as long as its look back cycles are in the cycle history
+    pub fn get_draw_inputs(&self, draw_cycle: u64) -> PosResult<DrawInputs> {
         // get roll lookback
 
         let (lookback_rolls, lookback_state_hash) = match draw_cycle.checked_sub(3) {
@@ -596,10 +642,11 @@ impl PoSFinalState {
             None => self.initial_seeds[draw_cycle as usize],
         };
 
-        // feed selector
-        self.selector
-            .as_ref()
-            .feed_cycle(draw_cycle, lookback_rolls, lookback_seed)
+        Ok(DrawInputs {
+            cycle: draw_cycle,
+            lookback_rolls,
+            lookback_seed,
+        })
     }
 
     /// Feeds the selector targeting a given draw cycle
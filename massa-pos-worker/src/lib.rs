@@ -4,16 +4,15 @@
 mod tests;
 
 mod controller;
-mod draw;
 mod worker;
 
 use massa_hash::Hash;
-use massa_models::{address::Address, slot::Slot};
-use massa_pos_exports::{PosResult, Selection};
+use massa_models::address::Address;
+use massa_pos_exports::{CycleDraws, PosResult};
 
 use parking_lot::{Condvar, Mutex, RwLock, RwLockReadGuard};
 use std::{
-    collections::{BTreeMap, HashMap, VecDeque},
+    collections::{BTreeMap, VecDeque},
     sync::Arc,
 };
 
@@ -76,15 +75,6 @@ impl DrawCache {
     }
 }
 
-/// Draws for a cycle, used in selector cache
-#[derive(Debug)]
-pub(crate) struct CycleDraws {
-    /// cycle number
-    pub cycle: u64,
-    /// cache of draws
-    pub draws: HashMap<Slot, Selection>,
-}
-
 /// Structure of the shared pointer to the computed draws, or error if the draw system failed.
 pub(crate) type DrawCachePtr = Arc<(RwLockCondvar, RwLock<PosResult<DrawCache>>)>;
 
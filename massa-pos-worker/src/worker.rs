@@ -2,8 +2,6 @@
 
 use crate::controller::SelectorControllerImpl;
 use crate::controller::SelectorManagerImpl;
-use crate::draw::perform_draws;
-use crate::CycleDraws;
 use crate::DrawCache;
 use crate::RwLockCondvar;
 use crate::{Command, DrawCachePtr};
@@ -12,6 +10,7 @@ use massa_pos_exports::PosResult;
 use massa_pos_exports::SelectorConfig;
 use massa_pos_exports::SelectorController;
 use massa_pos_exports::SelectorManager;
+use massa_pos_exports::{perform_draws, CycleDraws};
 use parking_lot::RwLock;
 use std::collections::VecDeque;
 use std::sync::mpsc::sync_channel;
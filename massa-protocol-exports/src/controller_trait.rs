@@ -5,8 +5,11 @@ use std::net::SocketAddr;
 
 use crate::error::ProtocolError;
 use crate::BootstrapPeers;
+use crate::PeerCategoryInfo;
+use crate::PeerDetails;
 
 use crate::PeerId;
+use massa_models::access_list::{AccessEntry, AccessTarget};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
@@ -16,6 +19,24 @@ use peernet::peer::PeerConnectionType;
 #[cfg(feature = "test-exports")]
 use std::sync::{Arc, RwLock};
 
+/// Peers allow or deny list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PeerListType {
+    /// if present, only the peers whose IP or id is listed are accepted
+    Whitelist,
+    /// the peers whose IP or id is listed are refused
+    Blacklist,
+}
+
+impl std::fmt::Display for PeerListType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerListType::Whitelist => write!(f, "peers whitelist"),
+            PeerListType::Blacklist => write!(f, "peers blacklist"),
+        }
+    }
+}
+
 #[cfg_attr(feature = "test-exports", mockall_wrap::wrap, mockall::automock)]
 pub trait ProtocolController: Send + Sync {
     /// Perform all operations needed to stop the ProtocolController
@@ -75,12 +96,44 @@ pub trait ProtocolController: Send + Sync {
     /// Get a list of peers to be sent to someone that bootstrap to us
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError>;
 
+    /// Get the diagnostics of the connected peers and of the peers known by the peer database
+    fn get_peers_details(&self) -> Result<Vec<PeerDetails>, ProtocolError>;
+
     /// Ban a list of Peer Id
     fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the entries of a peers list, None if the list is not set
+    fn get_peers_list(
+        &self,
+        list_type: PeerListType,
+    ) -> Result<Option<Vec<AccessEntry>>, ProtocolError>;
+
+    /// Add IP ranges or peers to a peers list, creating it if needed.
+    /// Connected peers that are not allowed anymore are disconnected.
+    fn add_to_peers_list(
+        &self,
+        list_type: PeerListType,
+        entries: Vec<AccessEntry>,
+    ) -> Result<(), ProtocolError>;
+
+    /// Remove IP ranges or peers from a peers list.
+    /// Connected peers that are not allowed anymore are disconnected.
+    fn remove_from_peers_list(
+        &self,
+        list_type: PeerListType,
+        targets: Vec<AccessTarget>,
+    ) -> Result<(), ProtocolError>;
+
+    /// Replace the peer categories used to choose the outgoing connections.
+    /// The IPs of each category are kept, new categories start without IP.
+    fn set_peers_categories(
+        &self,
+        categories: HashMap<String, PeerCategoryInfo>,
+    ) -> Result<(), ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
@@ -1,14 +1,16 @@
 mod bootstrap_peers;
 mod controller_trait;
 mod error;
+mod peer_details;
 mod peer_id;
 mod settings;
 
 pub use bootstrap_peers::{
     BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, PeerData,
 };
-pub use controller_trait::{ProtocolController, ProtocolManager};
+pub use controller_trait::{PeerListType, ProtocolController, ProtocolManager};
 pub use error::ProtocolError;
+pub use peer_details::{PeerDetails, PeerState};
 pub use peer_id::{PeerId, PeerIdDeserializer, PeerIdSerializer};
 pub use peernet::peer::PeerConnectionType;
 pub use peernet::transports::TransportType;
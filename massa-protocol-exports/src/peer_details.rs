@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::SocketAddr;
+
+use massa_models::version::Version;
+use massa_time::MassaTime;
+use peernet::peer::PeerConnectionType;
+
+use crate::PeerId;
+
+/// State of a peer in the peer database
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum PeerState {
+    /// banned, connections are refused
+    Banned,
+    /// handshake in progress
+    InHandshake,
+    /// last handshake failed
+    HandshakeFailed,
+    /// last handshake succeeded
+    Trusted,
+}
+
+impl std::fmt::Display for PeerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerState::Banned => write!(f, "banned"),
+            PeerState::InHandshake => write!(f, "in handshake"),
+            PeerState::HandshakeFailed => write!(f, "handshake failed"),
+            PeerState::Trusted => write!(f, "trusted"),
+        }
+    }
+}
+
+/// Diagnostics of a peer, connected or known by the peer database
+#[derive(Clone, Debug)]
+pub struct PeerDetails {
+    /// id of the peer
+    pub peer_id: PeerId,
+    /// address and direction of the connection, `None` if not connected
+    pub connection: Option<(SocketAddr, PeerConnectionType)>,
+    /// listeners of its last announcement
+    pub listeners: Vec<SocketAddr>,
+    /// category of the connection
+    pub category: Option<String>,
+    /// time of the last successful handshake
+    pub connected_since: Option<MassaTime>,
+    /// bytes received on the current connection
+    pub bytes_received: u64,
+    /// bytes sent on the current connection
+    pub bytes_sent: u64,
+    /// block headers received since the connection
+    pub block_announcements: u64,
+    /// operation ids announced since the connection
+    pub operation_announcements: u64,
+    /// endorsements received since the connection
+    pub endorsement_announcements: u64,
+    /// block data requests it answered since the connection
+    pub asks_answered: u64,
+    /// block data requests it did not answer in time since the connection
+    pub asks_timed_out: u64,
+    /// state in the peer database, `None` if unknown
+    pub state: Option<PeerState>,
+    /// last handshake error
+    pub last_error: Option<String>,
+    /// version announced in its last handshake
+    pub version: Option<Version>,
+}
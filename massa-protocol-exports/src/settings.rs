@@ -11,7 +11,7 @@ use massa_time::MassaTime;
 use peernet::transports::TransportType;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub struct PeerCategoryInfo {
     pub allow_local_peers: bool,
     pub target_out_connections: usize,
@@ -28,6 +28,10 @@ pub struct ProtocolConfig {
     pub listeners: HashMap<SocketAddr, TransportType>,
     /// initial peers path
     pub initial_peers: PathBuf,
+    /// path of the peers whitelist: if the file exists, only the listed IP ranges and peers are accepted
+    pub peers_whitelist_path: PathBuf,
+    /// path of the peers blacklist: if the file exists, the listed IP ranges and peers are refused
+    pub peers_blacklist_path: PathBuf,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// Max known blocks we keep during their propagation
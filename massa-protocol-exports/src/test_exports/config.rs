@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use crate::{settings::PeerCategoryInfo, ProtocolConfig};
 use massa_models::config::{CHAINID, ENDORSEMENT_COUNT, MAX_MESSAGE_SIZE};
@@ -48,6 +48,8 @@ impl Default for ProtocolConfig {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            peers_whitelist_path: PathBuf::from("peers_whitelist.json"),
+            peers_blacklist_path: PathBuf::from("peers_blacklist.json"),
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -7,18 +7,25 @@ use massa_metrics::MassaMetrics;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{
+    PeerCategoryInfo, PeerDetails, PeerId, ProtocolConfig, ProtocolError,
+};
 use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use peernet::peer::PeerConnectionType;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::{collections::HashMap, net::IpAddr};
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::IpAddr,
+};
 use std::{thread::JoinHandle, time::Duration};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 use crate::handlers::peer_handler::models::ConnectionMetadata;
+use crate::peer_activity::{PeerActivity, SharedPeersActivity};
 use crate::{
     handlers::peer_handler::models::{InitialPeers, PeerState, SharedPeerDB},
     ip::to_canonical,
@@ -32,7 +39,7 @@ use crate::{
         operation_handler::{cache::OperationCache, OperationHandler},
         peer_handler::models::PeerMessageTuple,
     },
-    wrap_network::NetworkController,
+    wrap_network::{ActiveConnectionsTrait, NetworkController},
 };
 
 // protocol-connectivity
@@ -49,6 +56,10 @@ pub enum ConnectivityCommand {
             HashMap<PeerId, (SocketAddr, PeerConnectionType)>,
         )>,
     },
+    SetPeersCategories(HashMap<String, PeerCategoryInfo>),
+    GetPeersDetails {
+        responder: MassaSender<Vec<PeerDetails>>,
+    },
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -76,10 +87,11 @@ pub(crate) fn start_connectivity_thread(
     ),
     initial_peers: InitialPeers,
     peer_db: SharedPeerDB,
+    peers_activity: SharedPeersActivity,
     storage: Storage,
     protocol_channels: ProtocolChannels,
     messages_handler: MessagesHandler,
-    peer_categories: HashMap<String, (Vec<IpAddr>, PeerCategoryInfo)>,
+    mut peer_categories: HashMap<String, (Vec<IpAddr>, PeerCategoryInfo)>,
     _default_category: PeerCategoryInfo,
     config: ProtocolConfig,
     mip_store: MipStore,
@@ -149,7 +161,9 @@ pub(crate) fn start_connectivity_thread(
                 sender_operations_propagation_ext.clone(),
                 protocol_channels.operation_handler_propagation.1.clone(),
                 peer_management_handler.sender.command_sender.clone(),
+                peers_activity.clone(),
                 massa_metrics.clone(),
+                mip_store.clone(),
             );
             let mut endorsement_handler = EndorsementHandler::new(
                 pool_controller.clone(),
@@ -164,6 +178,7 @@ pub(crate) fn start_connectivity_thread(
                 sender_endorsements_propagation_ext.clone(),
                 protocol_channels.endorsement_handler_propagation.1.clone(),
                 peer_management_handler.sender.command_sender.clone(),
+                peers_activity.clone(),
                 massa_metrics.clone(),
             );
             let mut block_handler = BlockHandler::new(
@@ -185,6 +200,7 @@ pub(crate) fn start_connectivity_thread(
                 block_cache,
                 storage.clone_without_refs(),
                 mip_store,
+                peers_activity.clone(),
                 massa_metrics.clone(),
             );
 
@@ -233,6 +249,18 @@ pub(crate) fn start_connectivity_thread(
                                 }).collect();
                                 responder.try_send((stats, peers)).unwrap_or_else(|_| warn!("Failed to send stats to responder"));
                             }
+                            Ok(ConnectivityCommand::SetPeersCategories(categories)) => {
+                                // the IPs of a category come from the initial peers, only its limits change
+                                peer_categories = categories.into_iter().map(|(name, infos)| {
+                                    let ips = peer_categories.remove(&name).map(|(ips, _)| ips).unwrap_or_default();
+                                    (name, (ips, infos))
+                                }).collect();
+                                info!("Peer categories updated: {:?}", peer_categories.keys().collect::<Vec<_>>());
+                            }
+                            Ok(ConnectivityCommand::GetPeersDetails { responder }) => {
+                                let details = get_peers_details(network_controller.get_active_connections().as_ref(), &peer_db, &peers_activity);
+                                responder.try_send(details).unwrap_or_else(|_| warn!("Failed to send peers details to responder"));
+                            }
                             Err(_) => {
                                 warn!("Channel to connectivity thread is closed. Stopping the protocol");
                                 break;
@@ -249,6 +277,9 @@ pub(crate) fn start_connectivity_thread(
                         let peer_db_read = peer_db.read();
                         massa_metrics.set_known_peers(peer_db_read.get_known_peer_count() as usize);
                         massa_metrics.set_banned_peers(peer_db_read.get_banned_peer_count() as usize);
+                        // forget the activity of the peers neither connected nor known anymore
+                        let peers_connected = active_conn.get_peer_ids_connected();
+                        peers_activity.retain(|peer_id| peers_connected.contains(peer_id) || peer_db_read.get_peers().contains_key(peer_id));
                     },
                     recv(tick_try_connect) -> _ => {
                         let active_conn = network_controller.get_active_connections();
@@ -422,3 +453,148 @@ fn try_connect_peer(
     }
     conn_res
 }
+
+// Gather the diagnostics of the connected peers and of the peers known by the peer database
+fn get_peers_details(
+    active_connections: &dyn ActiveConnectionsTrait,
+    peer_db: &SharedPeerDB,
+    peers_activity: &SharedPeersActivity,
+) -> Vec<PeerDetails> {
+    let peers_connected = active_connections.get_peers_connected();
+    let bandwidth = active_connections.get_peers_connections_bandwidth();
+    let peer_db_read = peer_db.read();
+    let peers_activity_read = peers_activity.peers();
+
+    // peers failing their first handshake are only known by their activity, until the next metrics tick forgets them
+    let peer_ids: BTreeSet<PeerId> = peers_connected
+        .keys()
+        .chain(peer_db_read.get_peers().keys())
+        .chain(peers_activity_read.keys())
+        .copied()
+        .collect();
+    peer_ids
+        .into_iter()
+        .map(|peer_id| {
+            let connection = peers_connected.get(&peer_id);
+            let peer_info = peer_db_read.get_peers().get(&peer_id);
+            let activity = peers_activity_read.get(&peer_id);
+            let count = |counter: fn(&PeerActivity) -> &AtomicU64| {
+                activity.map_or(0, |activity| counter(activity).load(Ordering::Relaxed))
+            };
+            let (bytes_sent, bytes_received) = bandwidth
+                .get(&peer_id.to_string())
+                .copied()
+                .unwrap_or_default();
+            PeerDetails {
+                peer_id,
+                connection: connection.map(|(addr, connection_type, _)| (*addr, *connection_type)),
+                listeners: peer_info
+                    .and_then(|info| info.last_announce.as_ref())
+                    .map(|announce| announce.listeners.keys().copied().collect())
+                    .unwrap_or_default(),
+                category: connection.and_then(|(_, _, category)| category.clone()),
+                // the handshake time of a previous connection is meaningless
+                connected_since: connection
+                    .and(activity.and_then(|activity| activity.connected_since)),
+                bytes_received,
+                bytes_sent,
+                block_announcements: count(|activity| &activity.block_announcements),
+                operation_announcements: count(|activity| &activity.operation_announcements),
+                endorsement_announcements: count(|activity| &activity.endorsement_announcements),
+                asks_answered: count(|activity| &activity.asks_answered),
+                asks_timed_out: count(|activity| &activity.asks_timed_out),
+                state: peer_info.map(|info| info.state),
+                last_error: activity.and_then(|activity| activity.last_error.clone()),
+                version: activity.and_then(|activity| activity.version),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::peer_handler::models::{PeerDB, PeerInfo};
+    use crate::peer_activity::PeersActivity;
+    use crate::wrap_network::MockActiveConnectionsTrait;
+    use crate::wrap_peer_db::PeerDBTrait;
+    use massa_signature::KeyPair;
+
+    fn peer_id() -> PeerId {
+        PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    #[test]
+    fn test_get_peers_details() {
+        let connected = peer_id();
+        let known = peer_id();
+        let failed = peer_id();
+        let addr: SocketAddr = "127.0.0.1:31245".parse().unwrap();
+
+        let mut active_connections = MockActiveConnectionsTrait::new();
+        active_connections
+            .expect_get_peers_connected()
+            .returning(move || {
+                HashMap::from([(
+                    connected,
+                    (addr, PeerConnectionType::OUT, Some("Bootstrap".to_string())),
+                )])
+            });
+        active_connections
+            .expect_get_peers_connections_bandwidth()
+            .returning(move || HashMap::from([(connected.to_string(), (10, 20))]));
+
+        let mut peer_db = PeerDB::default();
+        for peer_id in [connected, known] {
+            peer_db.get_peers_mut().insert(
+                peer_id,
+                PeerInfo {
+                    last_announce: None,
+                    state: PeerState::Trusted,
+                },
+            );
+        }
+        let peer_db: SharedPeerDB = Arc::new(RwLock::new(peer_db));
+
+        let peers_activity: SharedPeersActivity = Arc::new(PeersActivity::default());
+        peers_activity.connected(&connected);
+        peers_activity.count(&connected, 2, |activity| &activity.block_announcements);
+        // the handshake time of the previous connection of a peer is not shown
+        peers_activity.connected(&known);
+        peers_activity.update(&failed, |activity| {
+            activity.last_error = Some("handshake timed out".to_string())
+        });
+
+        let details = get_peers_details(&active_connections, &peer_db, &peers_activity);
+        assert_eq!(details.len(), 3);
+        let details: HashMap<PeerId, PeerDetails> = details
+            .into_iter()
+            .map(|details| (details.peer_id, details))
+            .collect();
+
+        let connected_details = &details[&connected];
+        assert_eq!(
+            connected_details.connection,
+            Some((addr, PeerConnectionType::OUT))
+        );
+        assert_eq!(connected_details.category.as_deref(), Some("Bootstrap"));
+        assert!(connected_details.connected_since.is_some());
+        assert_eq!(connected_details.bytes_sent, 10);
+        assert_eq!(connected_details.bytes_received, 20);
+        assert_eq!(connected_details.block_announcements, 2);
+        assert_eq!(connected_details.state, Some(PeerState::Trusted));
+
+        let known_details = &details[&known];
+        assert!(known_details.connection.is_none());
+        assert!(known_details.connected_since.is_none());
+        assert_eq!(known_details.state, Some(PeerState::Trusted));
+
+        let failed_details = &details[&failed];
+        assert!(failed_details.connection.is_none());
+        assert!(failed_details.state.is_none());
+        assert_eq!(
+            failed_details.last_error.as_deref(),
+            Some("handshake timed out")
+        );
+    }
+}
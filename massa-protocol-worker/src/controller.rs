@@ -2,12 +2,16 @@ use std::{collections::HashMap, net::SocketAddr, time::Duration};
 
 use massa_channel::{sender::MassaSender, MassaChannel};
 use massa_models::{
+    access_list::{AccessEntry, AccessTarget},
     block_header::SecuredHeader,
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BootstrapPeers, PeerCategoryInfo, PeerDetails, PeerId, PeerListType, ProtocolController,
+    ProtocolError,
+};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
 
@@ -156,6 +160,20 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("get_stats command receive error".into()))
     }
 
+    fn get_peers_details(&self) -> Result<Vec<PeerDetails>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers_details".to_string(), Some(1));
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::GetPeersDetails { responder: sender })
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_peers_details command send error".into())
+            })?;
+        receiver.recv_timeout(Duration::from_secs(10)).map_err(|_| {
+            ProtocolError::ChannelError("get_peers_details command receive error".into())
+        })
+    }
+
     fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError> {
         self.sender_peer_management_thread
             .as_ref()
@@ -172,6 +190,63 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn get_peers_list(
+        &self,
+        list_type: PeerListType,
+    ) -> Result<Option<Vec<AccessEntry>>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers_list".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetPeersList {
+                list_type,
+                responder: sender,
+            })
+            .map_err(|_| ProtocolError::ChannelError("get_peers_list command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_peers_list command receive error".into()))
+    }
+
+    fn add_to_peers_list(
+        &self,
+        list_type: PeerListType,
+        entries: Vec<AccessEntry>,
+    ) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::AddToPeersList { list_type, entries })
+            .map_err(|_| ProtocolError::ChannelError("add_to_peers_list command send error".into()))
+    }
+
+    fn remove_from_peers_list(
+        &self,
+        list_type: PeerListType,
+        targets: Vec<AccessTarget>,
+    ) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::RemoveFromPeersList { list_type, targets })
+            .map_err(|_| {
+                ProtocolError::ChannelError("remove_from_peers_list command send error".into())
+            })
+    }
+
+    fn set_peers_categories(
+        &self,
+        categories: HashMap<String, PeerCategoryInfo>,
+    ) -> Result<(), ProtocolError> {
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::SetPeersCategories(categories))
+            .map_err(|_| {
+                ProtocolError::ChannelError("set_peers_categories command send error".into())
+            })
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
@@ -9,7 +9,7 @@ use massa_protocol_exports::ProtocolConfig;
 use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 
-use crate::wrap_network::ActiveConnectionsTrait;
+use crate::{peer_activity::SharedPeersActivity, wrap_network::ActiveConnectionsTrait};
 
 use self::{
     cache::SharedBlockCache, commands_propagation::BlockHandlerPropagationCommand,
@@ -66,6 +66,7 @@ impl BlockHandler {
         cache: SharedBlockCache,
         storage: Storage,
         mip_store: MipStore,
+        peers_activity: SharedPeersActivity,
         massa_metrics: MassaMetrics,
     ) -> Self {
         let block_retrieval_thread = start_retrieval_thread(
@@ -85,6 +86,7 @@ impl BlockHandler {
             cache.clone(),
             storage.clone_without_refs(),
             mip_store,
+            peers_activity,
             massa_metrics,
         );
         let block_propagation_thread = start_propagation_thread(
@@ -17,6 +17,7 @@ use crate::{
         peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
     },
     messages::{Message, MessagesSerializer},
+    peer_activity::SharedPeersActivity,
     wrap_network::ActiveConnectionsTrait,
 };
 use crossbeam::{
@@ -110,6 +111,7 @@ pub struct RetrievalThread {
     config: ProtocolConfig,
     storage: Storage,
     mip_store: MipStore,
+    peers_activity: SharedPeersActivity,
     massa_metrics: MassaMetrics,
     operation_id_serializer: OperationIdSerializer,
 }
@@ -156,10 +158,14 @@ impl RetrievalThread {
                                     self.on_ask_for_block_info_received(peer_id, block_id, block_info);
                                 }
                                 BlockMessage::DataResponse{block_id, block_info} => {
+                                   if self.asked_blocks.get(&peer_id).is_some_and(|asked| asked.contains_key(&block_id)) {
+                                       self.peers_activity.count(&peer_id, 1, |activity| &activity.asks_answered);
+                                   }
                                    self.on_block_info_received(peer_id, block_id, block_info);
                                    self.update_block_retrieval();
                                 }
                                 BlockMessage::Header(header) => {
+                                    self.peers_activity.count(&peer_id, 1, |activity| &activity.block_announcements);
                                     self.on_block_header_received(peer_id, header);
                                     self.update_block_retrieval();
                                 }
@@ -426,6 +432,9 @@ impl RetrievalThread {
         debug!("received header {} from {}", header.id, from_peer_id);
 
         let block_id = header.id;
+        let _span =
+            massa_logging::otel::object_span("block", &block_id, "protocol.header_received")
+                .entered();
 
         // Check header and update knowledge info
         let is_new = match self.note_header_from_peer(&header, &from_peer_id) {
@@ -971,6 +980,8 @@ impl RetrievalThread {
                     .expect("could not compute block ask expiry");
                 if expiry <= now {
                     // the block has been asked for the block data a long time agp and did not respond
+                    self.peers_activity
+                        .count(peer_id, 1, |activity| &activity.asks_timed_out);
 
                     // we mark this peer as not knowing this block
                     self.cache
@@ -1250,7 +1261,9 @@ impl RetrievalThread {
         // add block to storage and claim ref
         block_storage.store_block(signed_block);
 
-        // Send to consensus
+        // Send to consensus, which follows the block from the span entered here
+        let _span = massa_logging::otel::object_span("block", block_id, "protocol.block_retrieved")
+            .entered();
         self.consensus_controller
             .register_block(*block_id, slot, block_storage, false);
 
@@ -1277,6 +1290,7 @@ pub fn start_retrieval_thread(
     cache: SharedBlockCache,
     storage: Storage,
     mip_store: MipStore,
+    peers_activity: SharedPeersActivity,
     massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     let block_message_serializer =
@@ -1305,6 +1319,7 @@ pub fn start_retrieval_thread(
                 config,
                 storage,
                 mip_store,
+                peers_activity,
                 massa_metrics,
                 operation_id_serializer: OperationIdSerializer::new(),
             };
@@ -7,7 +7,7 @@ use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolConfig;
 use massa_storage::Storage;
 
-use crate::wrap_network::ActiveConnectionsTrait;
+use crate::{peer_activity::SharedPeersActivity, wrap_network::ActiveConnectionsTrait};
 
 use self::{
     cache::SharedEndorsementCache, commands_propagation::EndorsementHandlerPropagationCommand,
@@ -53,6 +53,7 @@ impl EndorsementHandler {
         local_sender: MassaSender<EndorsementHandlerPropagationCommand>,
         local_receiver: MassaReceiver<EndorsementHandlerPropagationCommand>,
         sender_peer_cmd: MassaSender<PeerManagementCmd>,
+        peers_activity: SharedPeersActivity,
         massa_metrics: MassaMetrics,
     ) -> Self {
         let endorsement_retrieval_thread = start_retrieval_thread(
@@ -65,6 +66,7 @@ impl EndorsementHandler {
             pool_controller,
             config.clone(),
             storage.clone_without_refs(),
+            peers_activity,
             massa_metrics,
         );
 
@@ -23,6 +23,7 @@ use crate::{
         endorsement_handler::messages::EndorsementMessage,
         peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
     },
+    peer_activity::SharedPeersActivity,
     sig_verifier::verify_sigs_batch,
 };
 
@@ -47,6 +48,7 @@ pub struct RetrievalThread {
     config: ProtocolConfig,
     storage: Storage,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    peers_activity: SharedPeersActivity,
     metrics: MassaMetrics,
     endorsement_message_deserializer: EndorsementMessageDeserializer,
 }
@@ -122,6 +124,10 @@ impl RetrievalThread {
         match message {
             EndorsementMessage::Endorsements(endorsements) => {
                 debug!("Received endorsement message: Endorsement from {}", peer_id);
+                self.peers_activity
+                    .count(&peer_id, endorsements.len() as u64, |activity| {
+                        &activity.endorsement_announcements
+                    });
                 if let Err(err) = note_endorsements_from_peer(
                     endorsements,
                     &peer_id,
@@ -296,6 +302,7 @@ pub fn start_retrieval_thread(
     pool_controller: Box<dyn PoolController>,
     config: ProtocolConfig,
     storage: Storage,
+    peers_activity: SharedPeersActivity,
     metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     let endorsement_message_deserializer =
@@ -318,6 +325,7 @@ pub fn start_retrieval_thread(
                 pool_controller,
                 config,
                 storage,
+                peers_activity,
                 metrics,
                 endorsement_message_deserializer,
             };
@@ -5,8 +5,9 @@ use massa_metrics::MassaMetrics;
 use massa_pool_exports::PoolController;
 use massa_protocol_exports::ProtocolConfig;
 use massa_storage::Storage;
+use massa_versioning::versioning::MipStore;
 
-use crate::wrap_network::ActiveConnectionsTrait;
+use crate::{peer_activity::SharedPeersActivity, wrap_network::ActiveConnectionsTrait};
 
 use self::{
     cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
@@ -51,7 +52,9 @@ impl OperationHandler {
         local_sender: MassaSender<OperationHandlerPropagationCommand>,
         local_receiver: MassaReceiver<OperationHandlerPropagationCommand>,
         peer_cmd_sender: MassaSender<PeerManagementCmd>,
+        peers_activity: SharedPeersActivity,
         massa_metrics: MassaMetrics,
+        mip_store: MipStore,
     ) -> Self {
         let operation_retrieval_thread = start_retrieval_thread(
             receiver_network,
@@ -63,6 +66,7 @@ impl OperationHandler {
             receiver_retrieval_ext,
             local_sender.clone(),
             peer_cmd_sender,
+            peers_activity,
             massa_metrics.clone(),
         );
 
@@ -73,6 +77,7 @@ impl OperationHandler {
             cache,
             storage.clone_without_refs(),
             massa_metrics,
+            mip_store,
         );
         Self {
             operation_retrieval_thread: Some((sender_retrieval_ext, operation_retrieval_thread)),
@@ -12,6 +12,8 @@ use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
 use massa_protocol_exports::ProtocolError;
 use massa_storage::Storage;
+use massa_time::MassaTime;
+use massa_versioning::versioning::MipStore;
 use tracing::{debug, info, log::warn};
 
 use crate::{
@@ -39,6 +41,7 @@ struct PropagationThread {
     cache: SharedOperationCache,
     operation_message_serializer: MessagesSerializer,
     _massa_metrics: MassaMetrics,
+    mip_store: MipStore,
 }
 
 impl PropagationThread {
@@ -50,7 +53,34 @@ impl PropagationThread {
             match self.internal_receiver.recv_deadline(batch_deadline) {
                 Ok(internal_message) => {
                     match internal_message {
-                        OperationHandlerPropagationCommand::PropagateOperations(operations) => {
+                        OperationHandlerPropagationCommand::PropagateOperations(mut operations) => {
+                            // Do not propagate operations whose type is not active yet.
+                            {
+                                let now = MassaTime::now();
+                                let inactive_ops: PreHashSet<OperationId> = {
+                                    let ops = operations.read_operations();
+                                    operations
+                                        .get_op_refs()
+                                        .iter()
+                                        .filter(|op_id| {
+                                            ops.get(op_id).is_some_and(|op| {
+                                                !self
+                                                    .mip_store
+                                                    .is_operation_type_active(&op.content.op, now)
+                                            })
+                                        })
+                                        .copied()
+                                        .collect()
+                                };
+                                if !inactive_ops.is_empty() {
+                                    debug!(
+                                        "not propagating {} operations: operation type not active yet",
+                                        inactive_ops.len()
+                                    );
+                                    operations.drop_operation_refs(&inactive_ops);
+                                }
+                            }
+
                             // Note operations as checked.
                             {
                                 let mut cache_write = self.cache.write();
@@ -208,6 +238,7 @@ pub fn start_propagation_thread(
     cache: SharedOperationCache,
     op_storage: Storage,
     massa_metrics: MassaMetrics,
+    mip_store: MipStore,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
         .name(THREAD_NAME.to_string())
@@ -227,6 +258,7 @@ pub fn start_propagation_thread(
                 config,
                 cache,
                 _massa_metrics: massa_metrics,
+                mip_store,
                 operation_message_serializer: MessagesSerializer::new()
                     .with_operation_message_serializer(OperationMessageSerializer::new()),
             };
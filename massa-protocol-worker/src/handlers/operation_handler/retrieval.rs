@@ -5,7 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
-    operation::{OperationPrefixId, OperationPrefixIds, SecureShareOperation},
+    operation::{OperationPrefixId, OperationPrefixIds, OperationType, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     secure_share::Id,
     slot::Slot,
@@ -22,6 +22,7 @@ use schnellru::{ByLength, LruMap};
 use crate::{
     handlers::peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
     messages::MessagesSerializer,
+    peer_activity::SharedPeersActivity,
     sig_verifier::verify_sigs_batch,
     wrap_network::ActiveConnectionsTrait,
 };
@@ -64,6 +65,7 @@ pub struct RetrievalThread {
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     operation_message_serializer: MessagesSerializer,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    peers_activity: SharedPeersActivity,
     _massa_metrics: MassaMetrics,
 }
 
@@ -122,6 +124,7 @@ impl RetrievalThread {
                                 }
                                 OperationMessage::OperationsAnnouncement(announcement) => {
                                     debug!("Received operation message: OperationsAnnouncement from {}", peer_id);
+                                    self.peers_activity.count(&peer_id, announcement.len() as u64, |activity| &activity.operation_announcements);
                                     if let Err(err) =
                                         self.on_operations_announcements_received(announcement, &peer_id)
                                     {
@@ -409,6 +412,18 @@ pub(crate) fn note_operations_from_peer(
             )));
         };
 
+        // quit if a multisig op is not submitted by one of its cosigners
+        if let OperationType::Multisig { policy, .. } = &operation.content.op {
+            policy
+                .check_submitter(&operation.content_creator_pub_key)
+                .map_err(|err| {
+                    ProtocolError::InvalidOperationError(format!(
+                        "Operation {}: {}",
+                        operation.id, err
+                    ))
+                })?;
+        }
+
         // add to new operations
         new_operations.insert(operation.id, operation);
     }
@@ -426,7 +441,17 @@ pub(crate) fn note_operations_from_peer(
     verify_sigs_batch(
         &new_operations
             .iter()
-            .map(|(op_id, op)| (*op_id.get_hash(), op.signature, op.content_creator_pub_key))
+            .flat_map(|(op_id, op)| {
+                let mut sigs = vec![(*op_id.get_hash(), op.signature, op.content_creator_pub_key)];
+                // cosigner signatures of multisig operations, whose indexes were checked at deserialization
+                if let OperationType::Multisig {
+                    policy, signatures, ..
+                } = &op.content.op
+                {
+                    sigs.extend(policy.get_signature_batch(op_id.get_hash(), signatures));
+                }
+                sigs
+            })
             .collect::<Vec<_>>(),
     )?;
 
@@ -479,6 +504,7 @@ pub fn start_retrieval_thread(
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     internal_sender: MassaSender<OperationHandlerPropagationCommand>,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    peers_activity: SharedPeersActivity,
     massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
@@ -503,6 +529,7 @@ pub fn start_retrieval_thread(
                     .with_operation_message_serializer(OperationMessageSerializer::new()),
                 op_batch_buffer: VecDeque::new(),
                 peer_cmd_sender,
+                peers_activity,
                 _massa_metrics: massa_metrics,
             };
             retrieval_thread.run();
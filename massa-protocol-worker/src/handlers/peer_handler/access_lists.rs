@@ -0,0 +1,191 @@
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use massa_models::{
+    access_list::{AccessEntry, AccessList, AccessTarget},
+    node::NodeId,
+};
+use massa_protocol_exports::{PeerId, PeerListType, ProtocolError};
+use massa_time::MassaTime;
+
+/// Allow and deny lists of the peers, persisted as JSON files.
+/// A peer is refused if its IP or id is blacklisted,
+/// or if there is a whitelist and neither its IP nor its id is whitelisted.
+#[derive(Default, Clone, Debug)]
+pub struct PeerAccessLists {
+    whitelist: Option<AccessList>,
+    blacklist: Option<AccessList>,
+    whitelist_path: PathBuf,
+    blacklist_path: PathBuf,
+}
+
+impl PeerAccessLists {
+    /// Loads the lists from their files, a missing file means there is no list
+    pub fn load(whitelist_path: &Path, blacklist_path: &Path) -> Result<Self, ProtocolError> {
+        Ok(PeerAccessLists {
+            whitelist: load_list(whitelist_path)?,
+            blacklist: load_list(blacklist_path)?,
+            whitelist_path: whitelist_path.to_path_buf(),
+            blacklist_path: blacklist_path.to_path_buf(),
+        })
+    }
+
+    /// Whether the peer `peer_id` connecting from `ip` is accepted
+    pub fn is_allowed(&self, ip: &IpAddr, peer_id: &PeerId) -> bool {
+        let now = MassaTime::now();
+        let node_id = NodeId::new(peer_id.get_public_key());
+        if let Some(blacklist) = &self.blacklist {
+            if blacklist.contains_ip(ip, now) || blacklist.contains_node(&node_id, now) {
+                return false;
+            }
+        }
+        self.whitelist.as_ref().map_or(true, |whitelist| {
+            whitelist.contains_ip(ip, now) || whitelist.contains_node(&node_id, now)
+        })
+    }
+
+    /// Entries of a list, None if it is not set
+    pub fn get(&self, list_type: PeerListType) -> Option<Vec<AccessEntry>> {
+        self.list(list_type).as_ref().map(AccessList::entries)
+    }
+
+    /// Adds entries to a list, creating it if needed, and saves it
+    pub fn add(
+        &mut self,
+        list_type: PeerListType,
+        entries: Vec<AccessEntry>,
+    ) -> Result<(), ProtocolError> {
+        let list = self
+            .list_mut(list_type)
+            .get_or_insert_with(AccessList::default);
+        entries.into_iter().for_each(|entry| list.insert(entry));
+        self.save(list_type)
+    }
+
+    /// Removes IP ranges or peers from a list and saves it.
+    /// A list left empty is deleted: an empty whitelist would refuse every peer.
+    pub fn remove(
+        &mut self,
+        list_type: PeerListType,
+        targets: Vec<AccessTarget>,
+    ) -> Result<(), ProtocolError> {
+        let Some(list) = self.list_mut(list_type) else {
+            return Ok(());
+        };
+        for target in targets {
+            list.remove(&target);
+        }
+        if list.is_empty() {
+            *self.list_mut(list_type) = None;
+            return match std::fs::remove_file(self.path(list_type)) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            };
+        }
+        self.save(list_type)
+    }
+
+    fn list(&self, list_type: PeerListType) -> &Option<AccessList> {
+        match list_type {
+            PeerListType::Whitelist => &self.whitelist,
+            PeerListType::Blacklist => &self.blacklist,
+        }
+    }
+
+    fn list_mut(&mut self, list_type: PeerListType) -> &mut Option<AccessList> {
+        match list_type {
+            PeerListType::Whitelist => &mut self.whitelist,
+            PeerListType::Blacklist => &mut self.blacklist,
+        }
+    }
+
+    fn path(&self, list_type: PeerListType) -> &Path {
+        match list_type {
+            PeerListType::Whitelist => &self.whitelist_path,
+            PeerListType::Blacklist => &self.blacklist_path,
+        }
+    }
+
+    fn save(&self, list_type: PeerListType) -> Result<(), ProtocolError> {
+        if let Some(list) = self.list(list_type) {
+            std::fs::write(self.path(list_type), serde_json::to_string_pretty(list)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn load_list(path: &Path) -> Result<Option<AccessList>, ProtocolError> {
+    match std::fs::read_to_string(path) {
+        Ok(list) => Ok(Some(serde_json::from_str(&list)?)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_peer_access_lists() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lists = PeerAccessLists::load(
+            &dir.path().join("whitelist.json"),
+            &dir.path().join("blacklist.json"),
+        )
+        .unwrap();
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other_peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let ip = IpAddr::from_str("10.1.2.3").unwrap();
+        let other_ip = IpAddr::from_str("192.168.0.1").unwrap();
+        assert!(lists.is_allowed(&ip, &peer_id));
+
+        lists
+            .add(
+                PeerListType::Whitelist,
+                vec![
+                    AccessEntry::from_str("10.0.0.0/8").unwrap(),
+                    AccessEntry::from_str(&peer_id.to_string()).unwrap(),
+                ],
+            )
+            .unwrap();
+        assert!(lists.is_allowed(&ip, &other_peer_id));
+        assert!(lists.is_allowed(&other_ip, &peer_id));
+        assert!(!lists.is_allowed(&other_ip, &other_peer_id));
+
+        lists
+            .add(
+                PeerListType::Blacklist,
+                vec![AccessEntry::from_str("10.1.2.0/24").unwrap()],
+            )
+            .unwrap();
+        assert!(!lists.is_allowed(&ip, &peer_id));
+
+        // the lists are saved
+        let mut lists = PeerAccessLists::load(
+            &dir.path().join("whitelist.json"),
+            &dir.path().join("blacklist.json"),
+        )
+        .unwrap();
+        assert_eq!(lists.get(PeerListType::Whitelist).unwrap().len(), 2);
+        assert!(!lists.is_allowed(&ip, &peer_id));
+
+        // emptying the whitelist deletes it instead of refusing every peer
+        lists
+            .remove(
+                PeerListType::Whitelist,
+                vec![
+                    AccessTarget::from_str("10.0.0.0/8").unwrap(),
+                    AccessTarget::from_str(&peer_id.to_string()).unwrap(),
+                ],
+            )
+            .unwrap();
+        assert!(lists.get(PeerListType::Whitelist).is_none());
+        assert!(!dir.path().join("whitelist.json").exists());
+        assert!(lists.is_allowed(&other_ip, &other_peer_id));
+    }
+}
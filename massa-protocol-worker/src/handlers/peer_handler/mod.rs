@@ -28,6 +28,7 @@ use tracing::{debug, error, info, warn};
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
 use crate::messages::{Message, MessagesHandler, MessagesSerializer};
+use crate::peer_activity::SharedPeersActivity;
 use crate::wrap_network::ActiveConnectionsTrait;
 
 use self::models::PeerInfo;
@@ -49,6 +50,7 @@ use self::{
 /// This file contains the definition of the peer management handler
 /// This handler is here to check that announcements we receive are valid and
 /// that all the endpoints we received are active.
+pub mod access_lists;
 mod announcement;
 mod messages;
 pub mod models;
@@ -151,6 +153,24 @@ impl PeerManagementHandler {
                                     peer_db.write().unban_peer(&peer_id);
                                 }
                             },
+                             Ok(PeerManagementCmd::GetPeersList { list_type, responder }) => {
+                                let list = peer_db.read().get_access_lists().get(list_type);
+                                if let Err(err) = responder.try_send(list) {
+                                    warn!("error sending {}: {:?}", list_type, err);
+                                }
+                             },
+                             Ok(PeerManagementCmd::AddToPeersList { list_type, entries }) => {
+                                if let Err(err) = peer_db.write().get_access_lists_mut().add(list_type, entries) {
+                                    warn!("error when adding to the {}: {}", list_type, err);
+                                }
+                                disconnect_refused_peers(&peer_db, &mut active_connections);
+                             },
+                             Ok(PeerManagementCmd::RemoveFromPeersList { list_type, targets }) => {
+                                if let Err(err) = peer_db.write().get_access_lists_mut().remove(list_type, targets) {
+                                    warn!("error when removing from the {}: {}", list_type, err);
+                                }
+                                disconnect_refused_peers(&peer_db, &mut active_connections);
+                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
                                 // Add myself
@@ -261,6 +281,30 @@ impl PeerManagementHandler {
     }
 }
 
+/// Disconnects the connected peers refused by the peers white/black lists
+fn disconnect_refused_peers(
+    peer_db: &SharedPeerDB,
+    active_connections: &mut Box<dyn ActiveConnectionsTrait>,
+) {
+    let refused: Vec<PeerId> = {
+        let peer_db_read = peer_db.read();
+        let access_lists = peer_db_read.get_access_lists();
+        active_connections
+            .get_peers_connected()
+            .into_iter()
+            .filter(|(peer_id, (addr, _, _))| !access_lists.is_allowed(&addr.ip(), peer_id))
+            .map(|(peer_id, _)| peer_id)
+            .collect()
+    };
+    for peer_id in refused {
+        info!(
+            "Disconnecting peer {} refused by the peers white/black lists",
+            peer_id
+        );
+        active_connections.shutdown_connection(&peer_id);
+    }
+}
+
 #[derive(Clone)]
 pub struct MassaHandshake {
     pub announcement_serializer: AnnouncementSerializer,
@@ -269,15 +313,21 @@ pub struct MassaHandshake {
     pub version_deserializer: VersionDeserializer,
     pub config: ProtocolConfig,
     pub peer_db: SharedPeerDB,
+    pub peers_activity: SharedPeersActivity,
     peer_mngt_msg_serializer: MessagesSerializer,
     peer_id_serializer: PeerIdSerializer,
     peer_id_deserializer: PeerIdDeserializer,
 }
 
 impl MassaHandshake {
-    pub fn new(peer_db: SharedPeerDB, config: ProtocolConfig) -> Self {
+    pub fn new(
+        peer_db: SharedPeerDB,
+        peers_activity: SharedPeersActivity,
+        config: ProtocolConfig,
+    ) -> Self {
         Self {
             peer_db,
+            peers_activity,
             announcement_serializer: AnnouncementSerializer::new(),
             announcement_deserializer: AnnouncementDeserializer::new(
                 AnnouncementDeserializerArgs {
@@ -370,6 +420,20 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     debug!("Banned peer tried to connect: {:?}", peer_id);
                 }
             }
+            // the peer id is only trusted once it signs our random bytes below, so a spoofed id fails there
+            if !peer_db_read
+                .get_access_lists()
+                .is_allowed(&addr.ip(), &peer_id)
+            {
+                debug!("Peer refused by the peers white/black lists: {:?}", peer_id);
+                return Err(PeerNetError::HandshakeError.error(
+                    "Massa Handshake",
+                    Some(format!(
+                        "Peer {} is refused by the peers white/black lists",
+                        peer_id
+                    )),
+                ));
+            }
         }
 
         let res = {
@@ -392,6 +456,8 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                         Some(format!("Failed to deserialize version: {}", err)),
                     )
                 })?;
+            self.peers_activity
+                .update(&peer_id, |activity| activity.version = Some(version));
             if !self.config.version.is_compatible(&version) {
                 return Err(PeerNetError::HandshakeError.error(
                     "Massa Handshake",
@@ -512,6 +578,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
             match &res {
                 Ok((peer_id, Some(announcement))) => {
                     info!("Peer connected: {:?}", peer_id);
+                    self.peers_activity.connected(peer_id);
                     peer_db_write.set_try_connect_success_or_insert(&addr);
                     peer_db_write
                         .get_peers_mut()
@@ -534,12 +601,19 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                             info.state = PeerState::HandshakeFailed;
                         });
                     peer_db_write.set_try_connect_failure_or_insert(&addr);
+                    self.peers_activity.update(&peer_id, |activity| {
+                        activity.last_error =
+                            Some("Distant peer don't have slot for us.".to_string())
+                    });
                     return Err(PeerNetError::HandshakeError.error(
                         "Massa Handshake",
                         Some("Distant peer don't have slot for us.".to_string()),
                     ));
                 }
-                Err(_) => {
+                Err(err) => {
+                    self.peers_activity.update(&peer_id, |activity| {
+                        activity.last_error = Some(format!("{:?}", err))
+                    });
                     peer_db_write.set_try_connect_failure_or_insert(&addr);
                     peer_db_write
                         .get_peers_mut()
@@ -642,7 +716,11 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake = super::MassaHandshake::new(
+            shared_peer_db,
+            Default::default(),
+            ProtocolConfig::default(),
+        );
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -703,7 +781,11 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake = super::MassaHandshake::new(
+            shared_peer_db,
+            Default::default(),
+            ProtocolConfig::default(),
+        );
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -748,7 +830,11 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake = super::MassaHandshake::new(
+            shared_peer_db,
+            Default::default(),
+            ProtocolConfig::default(),
+        );
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -1,5 +1,6 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId};
+use massa_models::access_list::{AccessEntry, AccessTarget};
+use massa_protocol_exports::{BootstrapPeers, PeerId, PeerListType};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
@@ -13,8 +14,11 @@ use tracing::info;
 
 use crate::wrap_peer_db::PeerDBTrait;
 
+use super::access_lists::PeerAccessLists;
 use super::announcement::Announcement;
 
+pub use massa_protocol_exports::PeerState;
+
 const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000;
 
 pub type InitialPeers = HashMap<PeerId, HashMap<SocketAddr, TransportType>>;
@@ -155,6 +159,8 @@ pub struct PeerDB {
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// peers allow and deny lists
+    pub access_lists: PeerAccessLists,
 }
 
 pub type SharedPeerDB = Arc<RwLock<dyn PeerDBTrait>>;
@@ -167,15 +173,6 @@ pub struct PeerInfo {
     pub state: PeerState,
 }
 
-#[warn(dead_code)]
-#[derive(Eq, PartialEq, Clone, Debug)]
-pub enum PeerState {
-    Banned,
-    InHandshake,
-    HandshakeFailed,
-    Trusted,
-}
-
 #[derive(Clone)]
 pub enum PeerManagementCmd {
     Ban(Vec<PeerId>),
@@ -183,6 +180,18 @@ pub enum PeerManagementCmd {
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    GetPeersList {
+        list_type: PeerListType,
+        responder: MassaSender<Option<Vec<AccessEntry>>>,
+    },
+    AddToPeersList {
+        list_type: PeerListType,
+        entries: Vec<AccessEntry>,
+    },
+    RemoveFromPeersList {
+        list_type: PeerListType,
+        targets: Vec<AccessTarget>,
+    },
     Stop,
 }
 
@@ -352,4 +361,12 @@ impl PeerDBTrait for PeerDB {
     fn get_tested_addresses(&self) -> &HashMap<SocketAddr, MassaTime> {
         &self.tested_addresses
     }
+
+    fn get_access_lists(&self) -> &PeerAccessLists {
+        &self.access_lists
+    }
+
+    fn get_access_lists_mut(&mut self) -> &mut PeerAccessLists {
+        &mut self.access_lists
+    }
 }
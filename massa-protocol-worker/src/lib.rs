@@ -5,6 +5,7 @@ mod handlers;
 mod ip;
 mod manager;
 mod messages;
+mod peer_activity;
 mod sig_verifier;
 mod worker;
 mod wrap_network;
@@ -0,0 +1,115 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use massa_models::version::Version;
+use massa_protocol_exports::PeerId;
+use massa_time::MassaTime;
+use parking_lot::{RwLock, RwLockReadGuard};
+
+/// Activity of a peer, the counters are reset on each successful handshake
+#[derive(Default, Debug)]
+pub struct PeerActivity {
+    /// time of the last successful handshake
+    pub connected_since: Option<MassaTime>,
+    /// version announced in the last handshake
+    pub version: Option<Version>,
+    /// last handshake error
+    pub last_error: Option<String>,
+    pub block_announcements: AtomicU64,
+    pub operation_announcements: AtomicU64,
+    pub endorsement_announcements: AtomicU64,
+    pub asks_answered: AtomicU64,
+    pub asks_timed_out: AtomicU64,
+}
+
+/// Activity of the known peers, shared by the handlers.
+/// The counters are atomics: counting a message only takes the write lock the first time its peer is seen.
+#[derive(Default)]
+pub struct PeersActivity {
+    peers: RwLock<HashMap<PeerId, PeerActivity>>,
+}
+
+impl PeersActivity {
+    /// Add `count` to a counter of a peer
+    pub fn count(
+        &self,
+        peer_id: &PeerId,
+        count: u64,
+        counter: impl Fn(&PeerActivity) -> &AtomicU64,
+    ) {
+        if let Some(activity) = self.peers.read().get(peer_id) {
+            counter(activity).fetch_add(count, Ordering::Relaxed);
+            return;
+        }
+        counter(self.peers.write().entry(*peer_id).or_default())
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Update the handshake data of a peer, inserting an empty activity if unknown
+    pub fn update(&self, peer_id: &PeerId, f: impl FnOnce(&mut PeerActivity)) {
+        f(self.peers.write().entry(*peer_id).or_default());
+    }
+
+    /// Record a successful handshake, resetting the counters of the previous connection
+    pub fn connected(&self, peer_id: &PeerId) {
+        self.update(peer_id, |activity| {
+            *activity = PeerActivity {
+                connected_since: Some(MassaTime::now()),
+                version: activity.version.take(),
+                last_error: activity.last_error.take(),
+                ..Default::default()
+            };
+        });
+    }
+
+    /// Forget the peers not matching the predicate
+    pub fn retain(&self, keep: impl Fn(&PeerId) -> bool) {
+        self.peers.write().retain(|peer_id, _| keep(peer_id));
+    }
+
+    /// Read the activity of all the peers
+    pub fn peers(&self) -> RwLockReadGuard<'_, HashMap<PeerId, PeerActivity>> {
+        self.peers.read()
+    }
+}
+
+pub type SharedPeersActivity = Arc<PeersActivity>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_connected_resets_counters() {
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let peers_activity = PeersActivity::default();
+        let version = "SAND.1.0".parse().unwrap();
+        peers_activity.update(&peer_id, |activity| {
+            activity.version = Some(version);
+            activity.last_error = Some("handshake timed out".to_string());
+        });
+        peers_activity.count(&peer_id, 3, |activity| &activity.block_announcements);
+        peers_activity.count(&peer_id, 2, |activity| &activity.asks_timed_out);
+        assert_eq!(
+            peers_activity.peers()[&peer_id]
+                .block_announcements
+                .load(Ordering::Relaxed),
+            3
+        );
+
+        peers_activity.connected(&peer_id);
+        let peers = peers_activity.peers();
+        let activity = &peers[&peer_id];
+        assert!(activity.connected_since.is_some());
+        assert_eq!(activity.version, Some(version));
+        assert_eq!(activity.last_error.as_deref(), Some("handshake timed out"));
+        assert_eq!(activity.block_announcements.load(Ordering::Relaxed), 0);
+        assert_eq!(activity.asks_timed_out.load(Ordering::Relaxed), 0);
+    }
+}
@@ -245,6 +245,7 @@ pub fn start_protocol_controller_with_mock_network(
         (sender_peers, receiver_peers),
         HashMap::default(),
         peer_db,
+        Default::default(),
         storage,
         channels,
         message_handlers.clone(),
@@ -43,6 +43,7 @@ use crate::{
             commands_retrieval::OperationHandlerRetrievalCommand,
         },
         peer_handler::{
+            access_lists::PeerAccessLists,
             models::{PeerDB, PeerManagementCmd},
             MassaHandshake,
         },
@@ -50,6 +51,7 @@ use crate::{
     ip::to_canonical,
     manager::ProtocolManagerImpl,
     messages::MessagesHandler,
+    peer_activity::SharedPeersActivity,
     wrap_network::NetworkControllerImpl,
 };
 
@@ -188,7 +190,14 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = Arc::new(RwLock::new(PeerDB {
+        access_lists: PeerAccessLists::load(
+            &config.peers_whitelist_path,
+            &config.peers_blacklist_path,
+        )?,
+        ..Default::default()
+    }));
+    let peers_activity: SharedPeersActivity = Default::default();
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -238,7 +247,7 @@ pub fn start_protocol_controller(
     };
 
     let mut peernet_config = PeerNetConfiguration::default(
-        MassaHandshake::new(peer_db.clone(), config.clone()),
+        MassaHandshake::new(peer_db.clone(), peers_activity.clone(), config.clone()),
         message_handlers.clone(),
         Context {
             our_keypair: keypair.clone(),
@@ -330,6 +339,7 @@ pub fn start_protocol_controller(
         (sender_peers, receiver_peers),
         initial_peers,
         peer_db,
+        peers_activity,
         storage,
         protocol_channels,
         message_handlers,
@@ -1,4 +1,7 @@
-use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerInfo};
+use crate::handlers::peer_handler::{
+    access_lists::PeerAccessLists,
+    models::{ConnectionMetadata, PeerInfo},
+};
 use std::{
     collections::{HashMap, HashSet},
     net::SocketAddr,
@@ -35,6 +38,8 @@ pub trait PeerDBTrait: Send + Sync {
     fn get_peers_in_test(&self) -> &HashSet<SocketAddr>;
     fn insert_tested_address(&mut self, addr: &SocketAddr, time: massa_time::MassaTime);
     fn get_tested_addresses(&self) -> &HashMap<SocketAddr, massa_time::MassaTime>;
+    fn get_access_lists(&self) -> &PeerAccessLists;
+    fn get_access_lists_mut(&mut self) -> &mut PeerAccessLists;
 }
 
 impl Clone for Box<dyn PeerDBTrait> {
@@ -0,0 +1,292 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Deployment of smart contracts through a deployer contract.
+//!
+//! An `ExecuteSC` operation runs the deployer bytecode, which reads the contracts to create from the
+//! operation datastore, creates them, calls their `constructor` function and emits one event per
+//! created contract. The datastore layout is the one expected by the standard deployer
+//! (`deployer.wasm` of massa-sc-toolkit and massa-web3), see [`deployer_datastore`].
+
+use std::time::Duration;
+
+use jsonrpsee::core::RpcResult;
+use massa_api_exports::{
+    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyResult},
+    operation::OperationInfo,
+};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    datastore::{Datastore, DatastoreSerializer},
+    execution::EventFilter,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+};
+use massa_serialization::Serializer;
+
+use crate::{to_error_obj, RpcClient};
+
+/// Prefix of the events emitted by the deployer for each created contract
+pub const DEPLOYED_EVENT_PREFIX: &str = "Contract deployed at address: ";
+
+/// Contract created by the deployer
+#[derive(Debug, Clone)]
+pub struct ContractToDeploy {
+    /// bytecode of the contract
+    pub bytecode: Vec<u8>,
+    /// arguments of its `constructor` function, serialized with the `Args` encoding of massa-as-sdk
+    pub constructor_args: Vec<u8>,
+    /// coins sent to its `constructor` function
+    pub coins: Amount,
+}
+
+/// Builds the operation datastore read by the deployer:
+/// - key `[0]`: number of contracts, u64 little endian
+/// - key `i` (u64 little endian, from 1): bytecode of the i-th contract
+/// - key `i` followed by the serialized byte array `[0]`: arguments of its constructor, if any
+/// - key `i` followed by the serialized byte array `[1]`: coins sent to its constructor (raw u64 little endian), if any
+pub fn deployer_datastore(contracts: &[ContractToDeploy]) -> Datastore {
+    let mut datastore = Datastore::new();
+    datastore.insert(vec![0], (contracts.len() as u64).to_le_bytes().to_vec());
+    for (index, contract) in contracts.iter().enumerate() {
+        let key = (index as u64 + 1).to_le_bytes().to_vec();
+        let sub_key = |tag: u8| {
+            let mut sub_key = key.clone();
+            // byte arrays are serialized with their length as u32 little endian
+            sub_key.extend(1u32.to_le_bytes());
+            sub_key.push(tag);
+            sub_key
+        };
+        if !contract.constructor_args.is_empty() {
+            datastore.insert(sub_key(0), contract.constructor_args.clone());
+        }
+        if contract.coins > Amount::zero() {
+            datastore.insert(sub_key(1), contract.coins.to_raw().to_le_bytes().to_vec());
+        }
+        datastore.insert(key, contract.bytecode.clone());
+    }
+    datastore
+}
+
+/// Addresses of the contracts created by the deployer, in creation order
+pub fn deployed_addresses(events: &[SCOutputEvent]) -> Vec<Address> {
+    events
+        .iter()
+        .filter_map(|event| event.data.strip_prefix(DEPLOYED_EVENT_PREFIX))
+        .filter_map(|address| address.trim().parse::<Address>().ok())
+        .collect()
+}
+
+/// Costs of a deployment, estimated by a read-only execution
+#[derive(Debug, Clone)]
+pub struct DeploymentEstimate {
+    /// gas used by the deployer
+    pub gas_cost: u64,
+    /// coins spent by the caller to store the contracts and their data
+    pub storage_cost: Amount,
+    /// coins sent to the constructors
+    pub constructor_coins: Amount,
+    /// addresses the contracts would be created at in the read-only execution
+    pub addresses: Vec<Address>,
+}
+
+/// Computes the costs of a deployment from the read-only execution of the deployer by `caller`,
+/// whose balance was `balance` before the execution
+fn deployment_estimate(
+    caller: Address,
+    balance: Amount,
+    contracts: &[ContractToDeploy],
+    response: ExecuteReadOnlyResponse,
+) -> RpcResult<DeploymentEstimate> {
+    if let ReadOnlyResult::Error(e) = response.result {
+        return Err(to_error_obj(format!("deployment would fail: {}", e)));
+    }
+
+    let constructor_coins = contracts.iter().fold(Amount::zero(), |sum, contract| {
+        sum.saturating_add(contract.coins)
+    });
+    let final_balance = response
+        .state_changes
+        .ledger_changes
+        .get_balance_or_else(&caller, || Some(balance))
+        .unwrap_or_default();
+    Ok(DeploymentEstimate {
+        gas_cost: response.gas_cost,
+        storage_cost: balance
+            .saturating_sub(final_balance)
+            .saturating_sub(constructor_coins),
+        constructor_coins,
+        addresses: deployed_addresses(&Vec::from(response.output_events)),
+    })
+}
+
+impl RpcClient {
+    /// Estimates the gas and storage costs of deploying `contracts` from `caller`,
+    /// by running the deployer in a read-only execution with at most `max_gas`
+    pub async fn estimate_deployment(
+        &self,
+        caller: Address,
+        deployer: Vec<u8>,
+        contracts: &[ContractToDeploy],
+        max_gas: u64,
+    ) -> RpcResult<DeploymentEstimate> {
+        let mut operation_datastore = Vec::new();
+        DatastoreSerializer::new()
+            .serialize(&deployer_datastore(contracts), &mut operation_datastore)
+            .map_err(|e| to_error_obj(e.to_string()))?;
+        let balance = self
+            .get_addresses(vec![caller])
+            .await?
+            .first()
+            .map(|info| info.candidate_balance)
+            .ok_or_else(|| to_error_obj(format!("address {} not found", caller)))?;
+
+        let response = self
+            .execute_read_only_bytecode(ReadOnlyBytecodeExecution {
+                max_gas,
+                bytecode: deployer,
+                address: Some(caller),
+                operation_datastore: Some(operation_datastore),
+                fee: None,
+            })
+            .await?;
+        deployment_estimate(caller, balance, contracts, response)
+    }
+
+    /// Polls the node every `interval` until the operation is final, at most during `timeout`
+    pub async fn wait_for_final_operation(
+        &self,
+        operation_id: OperationId,
+        interval: Duration,
+        timeout: Duration,
+    ) -> RpcResult<OperationInfo> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(info) = self.get_operations(vec![operation_id]).await?.pop() {
+                if info.is_operation_final == Some(true) {
+                    return Ok(info);
+                }
+            }
+            if tokio::time::Instant::now() + interval > deadline {
+                return Err(to_error_obj(format!(
+                    "operation {} is not final after {:?}",
+                    operation_id, timeout
+                )));
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Gets the final events of an operation and the addresses of the contracts it deployed
+    pub async fn get_deployed_addresses(
+        &self,
+        operation_id: OperationId,
+    ) -> RpcResult<(Vec<SCOutputEvent>, Vec<Address>)> {
+        let events = self
+            .get_filtered_sc_output_event(EventFilter {
+                original_operation_id: Some(operation_id),
+                is_final: Some(true),
+                ..Default::default()
+            })
+            .await?;
+        let addresses = deployed_addresses(&events);
+        Ok((events, addresses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::{output_event::EventExecutionContext, slot::Slot};
+    use std::collections::VecDeque;
+    use std::str::FromStr;
+
+    fn index_key(index: u64) -> Vec<u8> {
+        index.to_le_bytes().to_vec()
+    }
+
+    fn sub_key(index: u64, tag: u8) -> Vec<u8> {
+        let mut key = index_key(index);
+        key.extend([1, 0, 0, 0, tag]);
+        key
+    }
+
+    fn event(data: String) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(1, 0),
+                block: None,
+                read_only: true,
+                index_in_slot: 0,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data,
+        }
+    }
+
+    #[test]
+    fn test_deployer_datastore() {
+        let coins = Amount::from_str("1.5").unwrap();
+        let datastore = deployer_datastore(&[
+            ContractToDeploy {
+                bytecode: vec![1, 2, 3],
+                constructor_args: vec![],
+                coins: Amount::zero(),
+            },
+            ContractToDeploy {
+                bytecode: vec![4, 5],
+                constructor_args: vec![6, 7],
+                coins,
+            },
+        ]);
+
+        let expected = Datastore::from([
+            (vec![0], 2u64.to_le_bytes().to_vec()),
+            (index_key(1), vec![1, 2, 3]),
+            (index_key(2), vec![4, 5]),
+            (sub_key(2, 0), vec![6, 7]),
+            (sub_key(2, 1), coins.to_raw().to_le_bytes().to_vec()),
+        ]);
+        assert_eq!(datastore, expected);
+    }
+
+    #[test]
+    fn test_deployment_estimate() {
+        let caller =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let deployed =
+            Address::from_str("AS12fZLkHnLED3okr8Lduyty7dz9ZKkd24xMCc2JJWPcdmfn2eUEx").unwrap();
+        let balance = Amount::from_str("100").unwrap();
+        let contracts = [ContractToDeploy {
+            bytecode: vec![1],
+            constructor_args: vec![],
+            coins: Amount::from_str("2").unwrap(),
+        }];
+        let mut response = ExecuteReadOnlyResponse {
+            executed_at: Slot::new(1, 0),
+            result: ReadOnlyResult::Ok(vec![]),
+            output_events: VecDeque::from([
+                event("constructor called".to_string()),
+                event(format!("{}{}", DEPLOYED_EVENT_PREFIX, deployed)),
+            ]),
+            gas_cost: 1_000_000,
+            state_changes: Default::default(),
+        };
+        // 2 coins sent to the constructor and 0.5 spent on storage
+        response
+            .state_changes
+            .ledger_changes
+            .set_balance(caller, Amount::from_str("97.5").unwrap());
+
+        let estimate = deployment_estimate(caller, balance, &contracts, response.clone()).unwrap();
+        assert_eq!(estimate.gas_cost, 1_000_000);
+        assert_eq!(estimate.constructor_coins, Amount::from_str("2").unwrap());
+        assert_eq!(estimate.storage_cost, Amount::from_str("0.5").unwrap());
+        assert_eq!(estimate.addresses, vec![deployed]);
+
+        response.result = ReadOnlyResult::Error("out of gas".to_string());
+        assert!(deployment_estimate(caller, balance, &contracts, response).is_err());
+    }
+}
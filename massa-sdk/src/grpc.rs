@@ -0,0 +1,880 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Typed client for the public gRPC API of a node.
+//!
+//! Wraps the generated `PublicServiceClient` and converts requests and responses
+//! to and from `massa_models` types. Streams are wrapped so that they transparently
+//! reconnect when the connection to the node is lost.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use massa_api_exports::page::PageRequest;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::block_id::BlockId;
+use massa_models::config::THREAD_COUNT;
+use massa_models::operation::{OperationId, SecureShareOperation};
+use massa_models::secure_share::SecureShareSerializer;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::api::v1::execution_query_request_item::RequestItem;
+use massa_proto_rs::massa::api::v1::execution_query_response_item::ResponseItem;
+use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_serialization::Serializer;
+use thiserror::Error;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity};
+
+use crate::cert_manager::gen_signed_cert;
+
+/// Error of the typed gRPC client
+#[derive(Error, Debug)]
+pub enum GrpcClientError {
+    /// Url error
+    #[error("Invalid grpc url: {0}")]
+    Url(#[from] http::uri::InvalidUri),
+    /// Transport error
+    #[error("grpc transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+    /// Error status returned by the node
+    #[error("grpc status error: {0}")]
+    Status(#[from] tonic::Status),
+    /// Error reported by the node inside a response
+    #[error("node error {code}: {message}")]
+    Node {
+        /// error code
+        code: i32,
+        /// error message
+        message: String,
+    },
+    /// The response could not be converted to a model type
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+    /// Certificate error
+    #[error("certificate error: {0}")]
+    Certificate(String),
+}
+
+/// TLS settings used to reach a node exposing its gRPC API over TLS or mTLS
+#[derive(Debug, Clone)]
+pub struct GrpcTlsConfig {
+    /// PEM of the certificate authority that signed the server certificate
+    pub ca_certificate: String,
+    /// PEM certificate and private key presented to the server (mTLS only)
+    pub client_identity: Option<(String, String)>,
+    /// domain name expected in the server certificate
+    pub domain_name: Option<String>,
+}
+
+impl GrpcTlsConfig {
+    /// Loads the TLS settings from PEM files, as written by the node when it generates its certificates
+    pub fn from_files(
+        ca_certificate_path: &Path,
+        client_certificate_path: Option<&Path>,
+        client_private_key_path: Option<&Path>,
+    ) -> Result<Self, GrpcClientError> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|err| {
+                GrpcClientError::Certificate(format!("cannot read {}: {}", path.display(), err))
+            })
+        };
+        let client_identity = match (client_certificate_path, client_private_key_path) {
+            (Some(cert), Some(key)) => Some((read(cert)?, read(key)?)),
+            (None, None) => None,
+            _ => {
+                return Err(GrpcClientError::Certificate(
+                    "client certificate and private key must be provided together".to_string(),
+                ))
+            }
+        };
+        Ok(GrpcTlsConfig {
+            ca_certificate: read(ca_certificate_path)?,
+            client_identity,
+            domain_name: None,
+        })
+    }
+
+    /// Builds mTLS settings with a fresh client certificate signed by `ca`
+    pub fn with_generated_identity(
+        ca: &rcgen::Certificate,
+        subject_alt_names: Vec<String>,
+    ) -> Result<Self, GrpcClientError> {
+        let ca_certificate = ca
+            .serialize_pem()
+            .map_err(|err| GrpcClientError::Certificate(err.to_string()))?;
+        let identity = gen_signed_cert(ca, subject_alt_names)
+            .map_err(|err| GrpcClientError::Certificate(err.to_string()))?;
+        Ok(GrpcTlsConfig {
+            ca_certificate,
+            client_identity: Some(identity),
+            domain_name: None,
+        })
+    }
+
+    fn to_tonic(&self) -> ClientTlsConfig {
+        let mut tls = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(self.ca_certificate.as_bytes()));
+        if let Some((cert, key)) = &self.client_identity {
+            tls = tls.identity(Identity::from_pem(cert.as_bytes(), key.as_bytes()));
+        }
+        if let Some(domain_name) = &self.domain_name {
+            tls = tls.domain_name(domain_name.clone());
+        }
+        tls
+    }
+}
+
+/// Configuration of the typed gRPC client
+#[derive(Debug, Clone)]
+pub struct GrpcClientConfig {
+    /// address of the public gRPC API, e.g. `grpc://127.0.0.1:33037`
+    pub url: String,
+    /// TLS settings, plain text when `None`
+    pub tls: Option<GrpcTlsConfig>,
+    /// delay between two reconnection attempts of a stream
+    pub reconnect_delay: Duration,
+    /// maximum number of consecutive reconnection attempts of a stream, unlimited when `None`
+    pub max_reconnect_attempts: Option<u32>,
+    /// maximum number of items (addresses, ids...) sent in a single request,
+    /// bigger requests are split into several calls
+    pub max_items_per_request: usize,
+}
+
+impl GrpcClientConfig {
+    /// Default configuration to reach `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        GrpcClientConfig {
+            url: url.into(),
+            tls: None,
+            reconnect_delay: Duration::from_secs(1),
+            max_reconnect_attempts: None,
+            max_items_per_request: 50,
+        }
+    }
+}
+
+/// An operation found by `search_operations`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationSearchResult {
+    /// operation id
+    pub id: OperationId,
+    /// thread of the operation
+    pub thread: u8,
+    /// blocks containing the operation
+    pub block_ids: Vec<BlockId>,
+}
+
+/// Execution status of an operation, as returned by `get_operations_execution_status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationExecutionStatus {
+    /// the operation was executed successfully
+    Success,
+    /// the operation was executed and failed
+    Failure,
+    /// the operation was not executed: it is still executable or has expired
+    ExecutableOrExpired,
+}
+
+/// A slot execution output received from a stream
+#[derive(Debug, Clone)]
+pub struct ReceivedSlotExecutionOutput {
+    /// executed slot
+    pub slot: Slot,
+    /// block executed at that slot, `None` for a miss
+    pub block_id: Option<BlockId>,
+    /// true if the slot is final, false if it is only a candidate
+    pub is_final: bool,
+    /// raw execution output
+    pub output: grpc_model::ExecutionOutput,
+}
+
+/// Event emitted by a smart contract, as received from a stream
+#[derive(Debug, Clone)]
+pub struct ReceivedEvent {
+    /// call stack of the emitter, the emitter being the last address
+    pub call_stack: Vec<Address>,
+    /// operation at the origin of the event
+    pub origin_operation_id: Option<OperationId>,
+    /// true if the execution emitting the event failed
+    pub is_failure: bool,
+    /// event data
+    pub data: String,
+}
+
+/// Coin transfers of a slot, as received from a stream
+#[derive(Debug, Clone)]
+pub struct ReceivedSlotTransfers {
+    /// executed slot
+    pub slot: Slot,
+    /// transfers made by the operations and asynchronous messages of the slot
+    pub transfers: Vec<grpc_api::TransferInfo>,
+}
+
+impl ReceivedSlotExecutionOutput {
+    /// Balances set by the slot, `None` for the deleted addresses
+    pub fn balance_changes(&self) -> Vec<(Address, Option<Amount>)> {
+        let Some(state_changes) = &self.output.state_changes else {
+            return Vec::new();
+        };
+        state_changes
+            .ledger_changes
+            .iter()
+            .filter_map(|change| {
+                let address = Address::from_str(&change.address).ok()?;
+                let balance = match change.value.as_ref()?.entry.as_ref() {
+                    Some(grpc_model::ledger_change_value::Entry::CreatedEntry(entry)) => {
+                        Some(to_amount(entry.balance.as_ref()?)?)
+                    }
+                    Some(grpc_model::ledger_change_value::Entry::UpdatedEntry(update)) => {
+                        match update.balance.as_ref()?.change.as_ref()? {
+                            grpc_model::set_or_keep_balance::Change::Set(amount) => {
+                                Some(to_amount(amount)?)
+                            }
+                            grpc_model::set_or_keep_balance::Change::Keep(_) => return None,
+                        }
+                    }
+                    None => None,
+                };
+                Some((address, balance))
+            })
+            .collect()
+    }
+
+    /// Operations executed in the slot, with `true` if their execution succeeded
+    pub fn executed_operations(&self) -> Vec<(OperationId, bool)> {
+        let Some(state_changes) = &self.output.state_changes else {
+            return Vec::new();
+        };
+        state_changes
+            .executed_ops_changes
+            .iter()
+            .filter_map(|change| {
+                let operation_id = OperationId::from_str(&change.operation_id).ok()?;
+                let success = change.value.as_ref()?.status
+                    == grpc_model::OperationExecutionStatus::Success as i32;
+                Some((operation_id, success))
+            })
+            .collect()
+    }
+
+    /// Events emitted by the smart contracts executed in the slot
+    pub fn events(&self) -> Vec<ReceivedEvent> {
+        self.output
+            .events
+            .iter()
+            .map(|event| {
+                let context = event.context.as_ref();
+                ReceivedEvent {
+                    call_stack: context
+                        .map(|context| {
+                            context
+                                .call_stack
+                                .iter()
+                                .filter_map(|address| Address::from_str(address).ok())
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    origin_operation_id: context
+                        .and_then(|context| context.origin_operation_id.as_deref())
+                        .and_then(|id| OperationId::from_str(id).ok()),
+                    is_failure: context.is_some_and(|context| context.is_failure),
+                    data: String::from_utf8_lossy(&event.data).into_owned(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn to_amount(amount: &grpc_model::NativeAmount) -> Option<Amount> {
+    Amount::from_mantissa_scale(amount.mantissa, amount.scale).ok()
+}
+
+/// Typed client of the public gRPC API
+#[derive(Clone)]
+pub struct GrpcClient {
+    client: PublicServiceClient<Channel>,
+    config: GrpcClientConfig,
+}
+
+impl GrpcClient {
+    /// Connects to the node described by `config`
+    pub async fn connect(config: GrpcClientConfig) -> Result<GrpcClient, GrpcClientError> {
+        let mut endpoint = Channel::from_shared(config.url.clone())?;
+        if let Some(tls) = &config.tls {
+            endpoint = endpoint.tls_config(tls.to_tonic())?;
+        }
+        let channel = endpoint.connect().await?;
+        Ok(GrpcClient {
+            client: PublicServiceClient::new(channel),
+            config,
+        })
+    }
+
+    /// Wraps an already connected client, `config.url` being only used in messages
+    pub fn from_service_client(
+        client: PublicServiceClient<Channel>,
+        config: GrpcClientConfig,
+    ) -> GrpcClient {
+        GrpcClient { client, config }
+    }
+
+    /// Underlying generated client, for the calls that are not wrapped
+    pub fn inner(&self) -> PublicServiceClient<Channel> {
+        self.client.clone()
+    }
+
+    /// Gets the status of the node
+    pub async fn get_status(&self) -> Result<grpc_model::PublicStatus, GrpcClientError> {
+        self.client
+            .clone()
+            .get_status(grpc_api::GetStatusRequest {})
+            .await?
+            .into_inner()
+            .status
+            .ok_or_else(|| GrpcClientError::InvalidResponse("missing status".to_string()))
+    }
+
+    /// Gets the final or candidate balances of `addresses`
+    pub async fn get_balances(
+        &self,
+        addresses: &[Address],
+        is_final: bool,
+    ) -> Result<Vec<Amount>, GrpcClientError> {
+        let items = self
+            .query_state(addresses, |address| {
+                if is_final {
+                    RequestItem::AddressBalanceFinal(grpc_api::AddressBalanceFinal { address })
+                } else {
+                    RequestItem::AddressBalanceCandidate(grpc_api::AddressBalanceCandidate {
+                        address,
+                    })
+                }
+            })
+            .await?;
+        items
+            .into_iter()
+            .map(|item| match item {
+                ResponseItem::Amount(amount) => {
+                    Amount::from_mantissa_scale(amount.mantissa, amount.scale)
+                        .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))
+                }
+                other => Err(unexpected_item(other)),
+            })
+            .collect()
+    }
+
+    /// Gets the final or candidate roll counts of `addresses`
+    pub async fn get_roll_counts(
+        &self,
+        addresses: &[Address],
+        is_final: bool,
+    ) -> Result<Vec<u64>, GrpcClientError> {
+        let items = self
+            .query_state(addresses, |address| {
+                if is_final {
+                    RequestItem::AddressRollsFinal(grpc_api::AddressRollsFinal { address })
+                } else {
+                    RequestItem::AddressRollsCandidate(grpc_api::AddressRollsCandidate { address })
+                }
+            })
+            .await?;
+        items
+            .into_iter()
+            .map(|item| match item {
+                ResponseItem::RollCount(count) => Ok(count),
+                other => Err(unexpected_item(other)),
+            })
+            .collect()
+    }
+
+    /// Gets the final or candidate execution status of `operation_ids`
+    pub async fn get_operations_execution_status(
+        &self,
+        operation_ids: &[OperationId],
+        is_final: bool,
+    ) -> Result<Vec<OperationExecutionStatus>, GrpcClientError> {
+        let items = self
+            .query_state(operation_ids, |operation_id| {
+                if is_final {
+                    RequestItem::OpExecutionStatusFinal(grpc_api::OpExecutionStatusFinal {
+                        operation_id,
+                    })
+                } else {
+                    RequestItem::OpExecutionStatusCandidate(grpc_api::OpExecutionStatusCandidate {
+                        operation_id,
+                    })
+                }
+            })
+            .await?;
+        items
+            .into_iter()
+            .map(|item| match item {
+                ResponseItem::ExecutionStatus(status) => {
+                    match grpc_api::ExecutionQueryExecutionStatus::try_from(status) {
+                        Ok(grpc_api::ExecutionQueryExecutionStatus::AlreadyExecutedWithSuccess) => {
+                            Ok(OperationExecutionStatus::Success)
+                        }
+                        Ok(grpc_api::ExecutionQueryExecutionStatus::AlreadyExecutedWithFailure) => {
+                            Ok(OperationExecutionStatus::Failure)
+                        }
+                        Ok(grpc_api::ExecutionQueryExecutionStatus::ExecutableOrExpired) => {
+                            Ok(OperationExecutionStatus::ExecutableOrExpired)
+                        }
+                        _ => Err(GrpcClientError::InvalidResponse(format!(
+                            "unknown execution status {}",
+                            status
+                        ))),
+                    }
+                }
+                other => Err(unexpected_item(other)),
+            })
+            .collect()
+    }
+
+    /// Gets one page of the final or candidate datastore keys of `address` starting with `prefix`.
+    ///
+    /// `page_request.offset` is the index of the page, of `page_request.limit` keys each.
+    /// Only the keys of the page are transferred: a page shorter than the limit is the last one.
+    pub async fn get_datastore_keys_page(
+        &self,
+        address: &Address,
+        prefix: Vec<u8>,
+        is_final: bool,
+        page_request: PageRequest,
+    ) -> Result<Vec<Vec<u8>>, GrpcClientError> {
+        let offset = Some(page_request.offset.saturating_mul(page_request.limit) as u64);
+        let limit = Some(page_request.limit as u64);
+        let items = self
+            .query_state(std::slice::from_ref(address), |address| {
+                if is_final {
+                    RequestItem::AddressDatastoreKeysFinal(grpc_api::AddressDatastoreKeysFinal {
+                        address,
+                        prefix: prefix.clone(),
+                        offset,
+                        limit,
+                    })
+                } else {
+                    RequestItem::AddressDatastoreKeysCandidate(
+                        grpc_api::AddressDatastoreKeysCandidate {
+                            address,
+                            prefix: prefix.clone(),
+                            offset,
+                            limit,
+                        },
+                    )
+                }
+            })
+            .await?;
+        match items.into_iter().next() {
+            Some(ResponseItem::VecBytes(keys)) => Ok(keys.items),
+            Some(other) => Err(unexpected_item(other)),
+            None => Err(GrpcClientError::InvalidResponse(
+                "missing datastore keys".to_string(),
+            )),
+        }
+    }
+
+    /// Searches the operations created by `addresses`.
+    /// Large address lists are split into several requests.
+    pub async fn search_operations_by_addresses(
+        &self,
+        addresses: &[Address],
+    ) -> Result<Vec<OperationSearchResult>, GrpcClientError> {
+        let mut results = Vec::new();
+        for chunk in addresses.chunks(self.config.max_items_per_request.max(1)) {
+            let filter = grpc_api::SearchOperationsFilter {
+                filter: Some(grpc_api::search_operations_filter::Filter::Addresses(
+                    grpc_model::Addresses {
+                        addresses: chunk.iter().map(|a| a.to_string()).collect(),
+                    },
+                )),
+            };
+            results.extend(self.search_operations(filter).await?);
+        }
+        Ok(results)
+    }
+
+    /// Searches the operations in `operation_ids` known by the node.
+    /// Large id lists are split into several requests.
+    pub async fn search_operations_by_ids(
+        &self,
+        operation_ids: &[OperationId],
+    ) -> Result<Vec<OperationSearchResult>, GrpcClientError> {
+        let mut results = Vec::new();
+        for chunk in operation_ids.chunks(self.config.max_items_per_request.max(1)) {
+            let filter = grpc_api::SearchOperationsFilter {
+                filter: Some(grpc_api::search_operations_filter::Filter::OperationIds(
+                    grpc_model::OperationIds {
+                        operation_ids: chunk.iter().map(|id| id.to_string()).collect(),
+                    },
+                )),
+            };
+            results.extend(self.search_operations(filter).await?);
+        }
+        Ok(results)
+    }
+
+    /// Sends signed operations to the node and returns their ids
+    pub async fn send_operations(
+        &self,
+        operations: &[SecureShareOperation],
+    ) -> Result<Vec<OperationId>, GrpcClientError> {
+        let serializer = SecureShareSerializer::new();
+        let mut serialized = Vec::with_capacity(operations.len());
+        for operation in operations {
+            let mut buffer = Vec::new();
+            serializer
+                .serialize(operation, &mut buffer)
+                .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))?;
+            serialized.push(buffer);
+        }
+
+        // the node closes the stream as soon as the request side is closed:
+        // keep it open until the answer is received
+        let request = tokio_stream::iter(vec![grpc_api::SendOperationsRequest {
+            operations: serialized,
+        }])
+        .chain(tokio_stream::pending());
+        let mut responses = self
+            .client
+            .clone()
+            .send_operations(request)
+            .await?
+            .into_inner();
+        let response = responses.message().await?.ok_or_else(|| {
+            GrpcClientError::InvalidResponse("stream closed before the answer".to_string())
+        })?;
+        match response.result {
+            Some(grpc_api::send_operations_response::Result::OperationIds(ids)) => ids
+                .operation_ids
+                .iter()
+                .map(|id| {
+                    OperationId::from_str(id)
+                        .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))
+                })
+                .collect(),
+            Some(grpc_api::send_operations_response::Result::Error(err)) => {
+                Err(GrpcClientError::Node {
+                    code: err.code,
+                    message: err.message,
+                })
+            }
+            None => Err(GrpcClientError::InvalidResponse(
+                "missing send_operations result".to_string(),
+            )),
+        }
+    }
+
+    /// Streams the slot execution outputs, starting at `start_slot` if provided.
+    /// `final_only` restricts the stream to final slots.
+    ///
+    /// The stream reconnects when the connection is lost and resumes right after
+    /// the last received slot, so that no output is repeated.
+    pub fn new_slot_execution_outputs(
+        &self,
+        start_slot: Option<Slot>,
+        final_only: bool,
+    ) -> impl Stream<Item = Result<ReceivedSlotExecutionOutput, GrpcClientError>> + Send + 'static
+    {
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(config.max_items_per_request.max(1));
+
+        tokio::spawn(async move {
+            let mut start_slot = start_slot;
+            let mut attempts = 0u32;
+            loop {
+                let mut filters = Vec::new();
+                if final_only {
+                    filters.push(grpc_api::NewSlotExecutionOutputsFilter {
+                        filter: Some(grpc_api::new_slot_execution_outputs_filter::Filter::Status(
+                            grpc_model::ExecutionOutputStatus::Final as i32,
+                        )),
+                    });
+                }
+                if let Some(slot) = start_slot {
+                    filters.push(grpc_api::NewSlotExecutionOutputsFilter {
+                        filter: Some(
+                            grpc_api::new_slot_execution_outputs_filter::Filter::SlotRange(
+                                grpc_model::SlotRange {
+                                    start_slot: Some(slot.into()),
+                                    end_slot: None,
+                                },
+                            ),
+                        ),
+                    });
+                }
+                let request =
+                    tokio_stream::iter(vec![grpc_api::NewSlotExecutionOutputsRequest { filters }])
+                        .chain(tokio_stream::pending());
+
+                let error = match client.clone().new_slot_execution_outputs(request).await {
+                    Ok(response) => {
+                        let mut stream = response.into_inner();
+                        loop {
+                            match stream.message().await {
+                                Ok(Some(message)) => {
+                                    attempts = 0;
+                                    let Some(output) = message.output else {
+                                        continue;
+                                    };
+                                    let received = to_received_output(output);
+                                    if let Ok(received) = &received {
+                                        start_slot = resume_slot(start_slot, received.slot);
+                                    }
+                                    if tx.send(received).await.is_err() {
+                                        // the stream was dropped by the caller
+                                        return;
+                                    }
+                                }
+                                Ok(None) => {
+                                    break GrpcClientError::InvalidResponse(
+                                        "stream closed by the node".to_string(),
+                                    )
+                                }
+                                Err(status) => break GrpcClientError::Status(status),
+                            }
+                        }
+                    }
+                    Err(status) => GrpcClientError::Status(status),
+                };
+
+                attempts = attempts.saturating_add(1);
+                if config
+                    .max_reconnect_attempts
+                    .is_some_and(|max| attempts > max)
+                {
+                    let _ = tx.send(Err(error)).await;
+                    return;
+                }
+                tracing::warn!(
+                    "slot execution outputs stream interrupted: {}, reconnecting in {:?}",
+                    error,
+                    config.reconnect_delay
+                );
+                tokio::time::sleep(config.reconnect_delay).await;
+            }
+        });
+
+        tokio_stream::wrappers::ReceiverStream::new(rx)
+    }
+
+    /// Streams the coin transfers of each executed slot.
+    /// `final_only` restricts the stream to final slots, otherwise only candidate slots are sent.
+    /// The node needs to be built with execution traces.
+    ///
+    /// The stream reconnects when the connection is lost. The node does not filter
+    /// transfers by slot, so the slots up to the last received one are skipped after
+    /// a reconnection instead of being repeated.
+    pub fn new_slot_transfers(
+        &self,
+        final_only: bool,
+    ) -> impl Stream<Item = Result<ReceivedSlotTransfers, GrpcClientError>> + Send + 'static {
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(config.max_items_per_request.max(1));
+        let finality_level = if final_only {
+            grpc_api::FinalityLevel::Final
+        } else {
+            grpc_api::FinalityLevel::Candidate
+        };
+
+        tokio::spawn(async move {
+            let mut start_slot: Option<Slot> = None;
+            let mut attempts = 0u32;
+            loop {
+                let request = tokio_stream::iter(vec![grpc_api::NewSlotTransfersRequest {
+                    finality_level: finality_level as i32,
+                }])
+                .chain(tokio_stream::pending());
+
+                let error = match client.clone().new_slot_transfers(request).await {
+                    Ok(response) => {
+                        let mut stream = response.into_inner();
+                        loop {
+                            match stream.message().await {
+                                Ok(Some(message)) => {
+                                    attempts = 0;
+                                    let Some(slot) = message.slot else {
+                                        let _ = tx
+                                            .send(Err(GrpcClientError::InvalidResponse(
+                                                "missing slot".to_string(),
+                                            )))
+                                            .await;
+                                        continue;
+                                    };
+                                    let slot: Slot = slot.into();
+                                    if start_slot.is_some_and(|start_slot| slot < start_slot) {
+                                        // already sent before the reconnection
+                                        continue;
+                                    }
+                                    start_slot = resume_slot(start_slot, slot);
+                                    let received = ReceivedSlotTransfers {
+                                        slot,
+                                        transfers: message.transfers,
+                                    };
+                                    if tx.send(Ok(received)).await.is_err() {
+                                        // the stream was dropped by the caller
+                                        return;
+                                    }
+                                }
+                                Ok(None) => {
+                                    break GrpcClientError::InvalidResponse(
+                                        "stream closed by the node".to_string(),
+                                    )
+                                }
+                                Err(status) => break GrpcClientError::Status(status),
+                            }
+                        }
+                    }
+                    Err(status) => GrpcClientError::Status(status),
+                };
+
+                attempts = attempts.saturating_add(1);
+                if config
+                    .max_reconnect_attempts
+                    .is_some_and(|max| attempts > max)
+                {
+                    let _ = tx.send(Err(error)).await;
+                    return;
+                }
+                tracing::warn!(
+                    "slot transfers stream interrupted: {}, reconnecting in {:?}",
+                    error,
+                    config.reconnect_delay
+                );
+                tokio::time::sleep(config.reconnect_delay).await;
+            }
+        });
+
+        tokio_stream::wrappers::ReceiverStream::new(rx)
+    }
+
+    async fn search_operations(
+        &self,
+        filter: grpc_api::SearchOperationsFilter,
+    ) -> Result<Vec<OperationSearchResult>, GrpcClientError> {
+        let response = self
+            .client
+            .clone()
+            .search_operations(grpc_api::SearchOperationsRequest {
+                filters: vec![filter],
+            })
+            .await?
+            .into_inner();
+        response
+            .operation_infos
+            .into_iter()
+            .map(|info| {
+                Ok(OperationSearchResult {
+                    id: OperationId::from_str(&info.id)
+                        .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))?,
+                    thread: u8::try_from(info.thread)
+                        .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))?,
+                    block_ids: info
+                        .block_ids
+                        .iter()
+                        .map(|id| {
+                            BlockId::from_str(id)
+                                .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Runs one `query_state` item per key, in chunks of `max_items_per_request`,
+    /// and returns the response items in the order of the keys
+    async fn query_state<K: ToString>(
+        &self,
+        keys: &[K],
+        to_request: impl Fn(String) -> RequestItem,
+    ) -> Result<Vec<ResponseItem>, GrpcClientError> {
+        let mut items = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(self.config.max_items_per_request.max(1)) {
+            let queries = chunk
+                .iter()
+                .map(|key| grpc_api::ExecutionQueryRequestItem {
+                    request_item: Some(to_request(key.to_string())),
+                })
+                .collect();
+            let response = self
+                .client
+                .clone()
+                .query_state(grpc_api::QueryStateRequest { queries })
+                .await?
+                .into_inner();
+            if response.responses.len() != chunk.len() {
+                return Err(GrpcClientError::InvalidResponse(format!(
+                    "expected {} query_state responses, got {}",
+                    chunk.len(),
+                    response.responses.len()
+                )));
+            }
+            for response in response.responses {
+                match response.response {
+                    Some(grpc_api::execution_query_response::Response::Result(result)) => {
+                        items.push(result.response_item.ok_or_else(|| {
+                            GrpcClientError::InvalidResponse("empty query_state item".to_string())
+                        })?);
+                    }
+                    Some(grpc_api::execution_query_response::Response::Error(err)) => {
+                        return Err(GrpcClientError::Node {
+                            code: err.code,
+                            message: err.message,
+                        })
+                    }
+                    None => {
+                        return Err(GrpcClientError::InvalidResponse(
+                            "empty query_state response".to_string(),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// Slot to resume a stream from after `received`: final outputs of older slots
+/// keep being received after candidate ones, so it never goes backwards
+fn resume_slot(start_slot: Option<Slot>, received: Slot) -> Option<Slot> {
+    match received.get_next_slot(THREAD_COUNT) {
+        Ok(next) => Some(start_slot.map_or(next, |start_slot| start_slot.max(next))),
+        Err(_) => start_slot,
+    }
+}
+
+fn unexpected_item(item: ResponseItem) -> GrpcClientError {
+    GrpcClientError::InvalidResponse(format!("unexpected query_state item: {:?}", item))
+}
+
+fn to_received_output(
+    output: grpc_model::SlotExecutionOutput,
+) -> Result<ReceivedSlotExecutionOutput, GrpcClientError> {
+    let is_final = output.status == grpc_model::ExecutionOutputStatus::Final as i32;
+    let execution_output = output
+        .execution_output
+        .ok_or_else(|| GrpcClientError::InvalidResponse("missing execution output".to_string()))?;
+    let slot: Slot = execution_output
+        .slot
+        .clone()
+        .ok_or_else(|| GrpcClientError::InvalidResponse("missing slot".to_string()))?
+        .into();
+    let block_id = execution_output
+        .block_id
+        .as_deref()
+        .map(BlockId::from_str)
+        .transpose()
+        .map_err(|err| GrpcClientError::InvalidResponse(err.to_string()))?;
+    Ok(ReceivedSlotExecutionOutput {
+        slot,
+        block_id,
+        is_final,
+        output: execution_output,
+    })
+}
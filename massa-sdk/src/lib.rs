@@ -22,14 +22,22 @@ use massa_api_exports::{
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
     endorsement::EndorsementInfo,
     execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
-    node::NodeStatus,
+    graph::BlockGraphDump,
+    ledger::LedgerStatsInfo,
+    node::{NodeStatus, PeerDetailsInfo},
     operation::{OperationInfo, OperationInput},
+    reload::ConfigReloadReport,
+    rolls::DrawInputsInfo,
+    slot::FinalStateHashesInfo,
     TimeInterval,
 };
+use massa_consensus_exports::block_status::BlockStatusEvent;
 use massa_models::secure_share::SecureShare;
 use massa_models::slot::Slot;
 use massa_models::{
+    access_list::{AccessEntry, AccessTarget},
     address::Address,
+    amount::Amount,
     block::FilledBlock,
     block_header::BlockHeader,
     block_id::BlockId,
@@ -51,6 +59,8 @@ use thiserror::Error;
 
 pub mod cert_manager;
 mod config;
+pub mod deploy;
+pub mod grpc;
 pub use config::ClientConfig;
 pub use config::HttpConfig;
 pub use config::WsConfig;
@@ -74,10 +84,14 @@ pub struct Client {
     pub private: RpcClient,
     /// grpc public client
     pub grpc_public: Option<PublicServiceClient<tonic::transport::Channel>>,
+    /// typed client sharing the connection of `grpc_public`
+    pub grpc: Option<grpc::GrpcClient>,
     /// grpc private client
     pub grpc_private: Option<PrivateServiceClient<tonic::transport::Channel>>,
     /// Chain id
     pub chain_id: u64,
+    /// HTTP configuration, also used to reach other nodes
+    pub http_config: HttpConfig,
 }
 
 impl Client {
@@ -101,7 +115,7 @@ impl Client {
         let grpc_private_url = format!("grpc://{}", grpc_private_socket_addr);
 
         // try to start grpc client (public api) and connect to the server
-        let grpc_pub_client = match tonic::transport::Channel::from_shared(grpc_public_url)?
+        let grpc_pub_client = match tonic::transport::Channel::from_shared(grpc_public_url.clone())?
             .connect()
             .await
         {
@@ -127,9 +141,16 @@ impl Client {
         Ok(Client {
             public: RpcClient::from_url(&public_url, http_config).await,
             private: RpcClient::from_url(&private_url, http_config).await,
+            grpc: grpc_pub_client.clone().map(|client| {
+                grpc::GrpcClient::from_service_client(
+                    client,
+                    grpc::GrpcClientConfig::new(grpc_public_url),
+                )
+            }),
             grpc_public: grpc_pub_client,
             grpc_private: grpc_priv_client,
             chain_id,
+            http_config: http_config.clone(),
         })
     }
 }
@@ -190,6 +211,18 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Export the block graph between two optional slots, end excluded
+    pub async fn node_export_block_graph(
+        &self,
+        start: Option<Slot>,
+        end: Option<Slot>,
+    ) -> RpcResult<BlockGraphDump> {
+        self.http_client
+            .request("node_export_block_graph", rpc_params![start, end])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Bans given ip address(es)
     /// No confirmation to expect.
     pub async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
@@ -226,32 +259,94 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node peers whitelist IP address(es).
-    pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Replaces the log filter of the node, returns the previous filter directives
+    pub async fn node_set_log_filter(&self, filter: String) -> RpcResult<String> {
+        self.http_client
+            .request("node_set_log_filter", rpc_params![filter])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the current log filter directives of the node
+    pub async fn node_get_log_filter(&self) -> RpcResult<String> {
+        self.http_client
+            .request("node_get_log_filter", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Reads the configuration files of the node again and applies the changes that are safe to make live
+    pub async fn node_reload_config(&self) -> RpcResult<ConfigReloadReport> {
+        self.http_client
+            .request("node_reload_config", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the network diagnostics of the connected peers and of the peers known by the node
+    pub async fn get_peers_details(&self) -> RpcResult<Vec<PeerDetailsInfo>> {
+        self.http_client
+            .request("get_peers_details", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns node peers whitelist entries.
+    pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
         self.http_client
             .request("node_peers_whitelist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node peers whitelist.
-    pub async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add IP ranges or node ids to node peers whitelist.
+    pub async fn node_add_to_peers_whitelist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
+        self.http_client
+            .request("node_add_to_peers_whitelist", rpc_params![entries])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Remove IP ranges or node ids from node peers whitelist.
+    pub async fn node_remove_from_peers_whitelist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
+        self.http_client
+            .request("node_remove_from_peers_whitelist", rpc_params![targets])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns node peers blacklist entries.
+    pub async fn node_peers_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
         self.http_client
-            .request("node_add_to_peers_whitelist", rpc_params![ips])
+            .request("node_peers_blacklist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to node peers whitelist.
-    pub async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add IP ranges or node ids to node peers blacklist.
+    pub async fn node_add_to_peers_blacklist(&self, entries: Vec<AccessEntry>) -> RpcResult<()> {
+        self.http_client
+            .request("node_add_to_peers_blacklist", rpc_params![entries])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Remove IP ranges or node ids from node peers blacklist.
+    pub async fn node_remove_from_peers_blacklist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_peers_whitelist", rpc_params![ips])
+            .request("node_remove_from_peers_blacklist", rpc_params![targets])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap whitelist IP address(es).
-    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Returns node bootstrap whitelist entries.
+    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<AccessEntry>> {
         self.http_client
             .request("node_bootstrap_whitelist", rpc_params![])
             .await
@@ -267,42 +362,54 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap whitelist.
-    pub async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add IP ranges or node ids to node bootstrap whitelist.
+    pub async fn node_add_to_bootstrap_whitelist(
+        &self,
+        entries: Vec<AccessEntry>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_add_to_bootstrap_whitelist", rpc_params![ips])
+            .request("node_add_to_bootstrap_whitelist", rpc_params![entries])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap whitelist.
-    pub async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Remove IP ranges or node ids from node bootstrap whitelist.
+    pub async fn node_remove_from_bootstrap_whitelist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_bootstrap_whitelist", rpc_params![ips])
+            .request("node_remove_from_bootstrap_whitelist", rpc_params![targets])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap blacklist IP address(es).
-    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
+    /// Returns node bootstrap blacklist entries.
+    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<AccessEntry>> {
         self.http_client
             .request("node_bootstrap_blacklist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap blacklist.
-    pub async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Add IP ranges or node ids to node bootstrap blacklist.
+    pub async fn node_add_to_bootstrap_blacklist(
+        &self,
+        entries: Vec<AccessEntry>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_add_to_bootstrap_blacklist", rpc_params![ips])
+            .request("node_add_to_bootstrap_blacklist", rpc_params![entries])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap blacklist.
-    pub async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    /// Remove IP ranges or node ids from node bootstrap blacklist.
+    pub async fn node_remove_from_bootstrap_blacklist(
+        &self,
+        targets: Vec<AccessTarget>,
+    ) -> RpcResult<()> {
         self.http_client
-            .request("node_remove_from_bootstrap_blacklist", rpc_params![ips])
+            .request("node_remove_from_bootstrap_blacklist", rpc_params![targets])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
@@ -329,6 +436,52 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns aggregate data about the final ledger
+    pub async fn get_ledger_stats(&self) -> RpcResult<LedgerStatsInfo> {
+        self.http_client
+            .request("get_ledger_stats", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the addresses with the highest final balances
+    pub async fn get_top_balances(&self, count: u64) -> RpcResult<Vec<(Address, Amount)>> {
+        self.http_client
+            .request("get_top_balances", rpc_params![count])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the final datastore size in bytes of each address
+    pub async fn get_datastore_sizes(&self, addresses: Vec<Address>) -> RpcResult<Vec<u64>> {
+        self.http_client
+            .request("get_datastore_sizes", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the execution trail hash and the final state fingerprint right after each final slot
+    pub async fn get_final_state_hashes(
+        &self,
+        slots: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<FinalStateHashesInfo>>> {
+        self.http_client
+            .request("get_final_state_hashes", rpc_params![slots])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the inputs of the selector draws of cycles still in the cycle history of the node
+    pub async fn get_draw_inputs(
+        &self,
+        cycles: Vec<u64>,
+    ) -> RpcResult<Vec<Option<DrawInputsInfo>>> {
+        self.http_client
+            .request("get_draw_inputs", rpc_params![cycles])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     pub(crate) async fn _get_cliques(&self) -> RpcResult<Vec<Clique>> {
         self.http_client
             .request("get_cliques", rpc_params![])
@@ -639,6 +792,23 @@ impl RpcClientV2 {
             Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
         }
     }
+
+    /// Block status changes
+    pub async fn subscribe_block_status(
+        &self,
+    ) -> Result<Subscription<BlockStatusEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_block_status",
+                    rpc_params![],
+                    "unsubscribe_block_status",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
 }
 
 fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpBackend> {
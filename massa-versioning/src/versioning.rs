@@ -21,6 +21,7 @@ use massa_models::config::MIP_STORE_STATS_BLOCK_CONSIDERED;
 use massa_models::config::VERSIONING_ACTIVATION_DELAY_MIN;
 use massa_models::config::VERSIONING_THRESHOLD_TRANSITION_ACCEPTED;
 use massa_models::error::ModelsError;
+use massa_models::operation::OperationType;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_serialization::{DeserializeError, Deserializer, SerializeError, Serializer};
@@ -45,6 +46,10 @@ pub enum MipComponent {
     Block,
     VM,
     FinalStateHashKind,
+    // BatchTransaction operations are accepted from version 1
+    BatchTransaction,
+    // Multisig operations are accepted from version 1
+    Multisig,
     #[doc(hidden)]
     #[num_enum(default)]
     __Nonexhaustive,
@@ -641,6 +646,21 @@ impl MipStore {
         guard.get_latest_component_version_at(component, ts)
     }
 
+    /// Check if the MIPs required by an operation type are active at the given timestamp
+    pub fn is_operation_type_active(&self, op: &OperationType, ts: MassaTime) -> bool {
+        if matches!(op, OperationType::Multisig { .. })
+            && self.get_latest_component_version_at(&MipComponent::Multisig, ts) < 1
+        {
+            return false;
+        }
+        match op.get_emitted_op() {
+            OperationType::BatchTransaction { .. } => {
+                self.get_latest_component_version_at(&MipComponent::BatchTransaction, ts) >= 1
+            }
+            _ => true,
+        }
+    }
+
     /// Get all versions in 'Active state' for the given MipComponent
     pub(crate) fn get_all_active_component_versions(&self, component: &MipComponent) -> Vec<u32> {
         let guard = self.0.read();